@@ -0,0 +1,771 @@
+use crate::run_reports::RunFinding;
+use crate::{
+    app_instances_dir, launcher_dir, normalize_lock_content_type, now_iso, read_index,
+    read_lockfile, Instance, LockEntry, Lockfile,
+};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use tauri::Manager;
+
+pub(crate) const ADVISORY_SCHEMA_VERSION: u32 = 1;
+pub(crate) const SECURITY_ADVISORIES_UPDATED_EVENT: &str = "security_advisories_updated";
+const IMPORTED_BUNDLE_FILE: &str = "security_advisories.json";
+const LAST_REPORT_FILE: &str = "security_advisory_report.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct AdvisoryBundle {
+    pub schema_version: u32,
+    pub revision: u64,
+    #[serde(default)]
+    pub published_at: Option<String>,
+    #[serde(default)]
+    pub advisories: Vec<Advisory>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Advisory {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub kind: String, // compromised_release | exploit | pulled_build
+    #[serde(default)]
+    pub severity: String,
+    #[serde(default)]
+    pub summary: String,
+    #[serde(default)]
+    pub affected: Vec<AdvisoryAffected>,
+    /// Known-bad file hashes keyed by algorithm (`sha1`, `sha512`).
+    #[serde(default)]
+    pub file_hashes: HashMap<String, Vec<String>>,
+    #[serde(default)]
+    pub fixed_version: Option<String>,
+    #[serde(default)]
+    pub remediation: Option<String>, // update | remove
+    #[serde(default)]
+    pub references: Vec<String>,
+}
+
+/// One affected project. An entry matches when any identifier matches and its
+/// version is listed in `versions` or falls in `[introduced, fixed)`. With no
+/// version constraints every version is affected.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct AdvisoryAffected {
+    #[serde(default)]
+    pub source: Option<String>,
+    #[serde(default)]
+    pub project_ids: Vec<String>,
+    #[serde(default)]
+    pub mod_ids: Vec<String>,
+    #[serde(default)]
+    pub versions: Vec<String>,
+    #[serde(default)]
+    pub introduced: Option<String>,
+    #[serde(default)]
+    pub fixed: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct InstanceAdvisoryFindings {
+    pub instance_id: String,
+    pub instance_name: String,
+    pub findings: Vec<RunFinding>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct AdvisoryScanReport {
+    pub bundle_revision: u64,
+    pub checked_at: String,
+    pub trigger: String, // startup | update_check | manual | import
+    pub total_findings: usize,
+    pub instances: Vec<InstanceAdvisoryFindings>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct AdvisoryStatus {
+    pub bundled_revision: u64,
+    pub active_revision: u64,
+    pub active_source: String, // bundled | imported
+    pub advisory_count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_report: Option<AdvisoryScanReport>,
+}
+
+fn bundled_advisory_bundle() -> &'static AdvisoryBundle {
+    static BUNDLE: OnceLock<AdvisoryBundle> = OnceLock::new();
+    BUNDLE.get_or_init(|| {
+        serde_json::from_str(include_str!("data/security_advisories.json")).unwrap_or_else(|err| {
+            eprintln!("parse bundled security advisories failed: {err}");
+            AdvisoryBundle {
+                schema_version: ADVISORY_SCHEMA_VERSION,
+                revision: 0,
+                published_at: None,
+                advisories: vec![],
+            }
+        })
+    })
+}
+
+pub(crate) fn parse_advisory_bundle(raw: &str) -> Result<AdvisoryBundle, String> {
+    let bundle: AdvisoryBundle =
+        serde_json::from_str(raw).map_err(|e| format!("parse advisory bundle failed: {e}"))?;
+    if bundle.schema_version != ADVISORY_SCHEMA_VERSION {
+        return Err(format!(
+            "Advisory bundle schema {} is not supported (expected {ADVISORY_SCHEMA_VERSION}).",
+            bundle.schema_version
+        ));
+    }
+    if let Some(bad) = bundle
+        .advisories
+        .iter()
+        .find(|item| item.id.trim().is_empty())
+    {
+        return Err(format!("Advisory '{}' has no id.", bad.title));
+    }
+    Ok(bundle)
+}
+
+fn imported_bundle_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(launcher_dir(app)?.join(IMPORTED_BUNDLE_FILE))
+}
+
+fn last_report_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(launcher_dir(app)?.join(LAST_REPORT_FILE))
+}
+
+fn read_imported_bundle(app: &tauri::AppHandle) -> Option<AdvisoryBundle> {
+    let raw = fs::read_to_string(imported_bundle_path(app).ok()?).ok()?;
+    match parse_advisory_bundle(&raw) {
+        Ok(bundle) => Some(bundle),
+        Err(err) => {
+            eprintln!("imported security advisories ignored: {err}");
+            None
+        }
+    }
+}
+
+/// The newest of the bundled and imported advisory files, so an app update
+/// that ships newer data supersedes an older import.
+pub(crate) fn active_advisory_bundle(app: &tauri::AppHandle) -> (AdvisoryBundle, &'static str) {
+    let bundled = bundled_advisory_bundle();
+    match read_imported_bundle(app) {
+        Some(imported) if imported.revision > bundled.revision => (imported, "imported"),
+        _ => (bundled.clone(), "bundled"),
+    }
+}
+
+/// Loose version ordering: numeric runs compare as numbers, other runs as text,
+/// and a trailing text run (`-beta`) sorts before the bare release.
+pub(crate) fn compare_loose_versions(a: &str, b: &str) -> Ordering {
+    fn parts(raw: &str) -> Vec<Result<u64, String>> {
+        let mut out = Vec::new();
+        let mut current = String::new();
+        let mut numeric = false;
+        for ch in raw.trim().trim_start_matches(['v', 'V']).chars() {
+            if !ch.is_ascii_alphanumeric() {
+                if !current.is_empty() {
+                    out.push(part(&current, numeric));
+                    current.clear();
+                }
+                continue;
+            }
+            if !current.is_empty() && ch.is_ascii_digit() != numeric {
+                out.push(part(&current, numeric));
+                current.clear();
+            }
+            numeric = ch.is_ascii_digit();
+            current.push(ch.to_ascii_lowercase());
+        }
+        if !current.is_empty() {
+            out.push(part(&current, numeric));
+        }
+        out
+    }
+    fn part(raw: &str, numeric: bool) -> Result<u64, String> {
+        if numeric {
+            raw.parse::<u64>().map_err(|_| raw.to_string())
+        } else {
+            Err(raw.to_string())
+        }
+    }
+    let left = parts(a);
+    let right = parts(b);
+    for idx in 0..left.len().max(right.len()) {
+        let ordering = match (left.get(idx), right.get(idx)) {
+            (Some(Ok(x)), Some(Ok(y))) => x.cmp(y),
+            (Some(Err(x)), Some(Err(y))) => x.cmp(y),
+            (Some(Ok(_)), Some(Err(_))) => Ordering::Greater,
+            (Some(Err(_)), Some(Ok(_))) => Ordering::Less,
+            (Some(Ok(_)), None) => Ordering::Greater,
+            (Some(Err(_)), None) => Ordering::Less,
+            (None, Some(Ok(_))) => Ordering::Less,
+            (None, Some(Err(_))) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    Ordering::Equal
+}
+
+/// Leading `1.x[.y]` Minecraft version tag, when something follows it.
+fn strip_minecraft_version(raw: &str) -> Option<&str> {
+    let rest = raw.strip_prefix("1.")?;
+    let minor_len = rest.chars().take_while(|ch| ch.is_ascii_digit()).count();
+    // Mod versions like `1.2.3-4` share the shape; modded releases start at 1.7.
+    if rest[..minor_len].parse::<u32>().ok()? < 7 {
+        return None;
+    }
+    let mut rest = &rest[minor_len..];
+    if let Some(patch) = rest.strip_prefix('.') {
+        let patch_len = patch.chars().take_while(|ch| ch.is_ascii_digit()).count();
+        if patch_len == 0 {
+            return None;
+        }
+        rest = &patch[patch_len..];
+    }
+    let rest = rest.strip_prefix(['-', '_', ' '])?;
+    rest.chars().any(|ch| ch.is_ascii_digit()).then_some(rest)
+}
+
+/// Drops a leading mod name from CurseForge display names and file names such
+/// as `EnderCore 1.12.2-0.5.77` or `EnderCore-1.12.2-0.5.77.jar`: everything
+/// before the first number that starts a word, plus a `v` right before it.
+fn strip_display_name(raw: &str) -> &str {
+    let raw = raw.strip_suffix(".jar").unwrap_or(raw);
+    if raw.starts_with(|ch: char| ch.is_ascii_digit()) {
+        return raw;
+    }
+    for (idx, ch) in raw.char_indices() {
+        if !ch.is_ascii_digit() {
+            continue;
+        }
+        let before = &raw[..idx];
+        let before = before.strip_suffix(['v', 'V']).unwrap_or(before);
+        if before.is_empty() || before.ends_with([' ', '-', '_']) {
+            return &raw[idx..];
+        }
+    }
+    raw
+}
+
+/// The mod's own version out of strings such as `1.12.2-0.5.77`,
+/// `mc1.20.1-2.3`, `forge-1.20.1-2.3`, `2.3+1.20.1` or the CurseForge display
+/// name `EnderCore 1.12.2-0.5.77`, so the mod name or the Minecraft or loader
+/// tag in front does not decide the ordering.
+fn mod_version_part(raw: &str) -> &str {
+    const TAGS: [&str; 6] = ["neoforge", "forge", "fabric", "quilt", "minecraft", "mc"];
+    let mut rest = raw.trim();
+    if let Some((head, _)) = rest.split_once('+') {
+        if head.chars().any(|ch| ch.is_ascii_digit()) {
+            rest = head;
+        }
+    }
+    rest = strip_display_name(rest);
+    loop {
+        let before = rest.len();
+        for tag in TAGS {
+            let Some(head) = rest.get(..tag.len()) else {
+                continue;
+            };
+            if head.eq_ignore_ascii_case(tag) {
+                let after = rest[tag.len()..].trim_start_matches(['-', '_', ' ']);
+                if after.starts_with(|ch: char| ch.is_ascii_digit()) {
+                    rest = after;
+                    break;
+                }
+            }
+        }
+        if let Some(after) = strip_minecraft_version(rest) {
+            rest = after;
+        }
+        if rest.len() == before {
+            return rest;
+        }
+    }
+}
+
+fn version_is_affected(affected: &AdvisoryAffected, version: &str) -> bool {
+    let version = version.trim();
+    if affected.versions.is_empty() && affected.introduced.is_none() && affected.fixed.is_none() {
+        return true;
+    }
+    if affected.versions.iter().any(|item| {
+        item.trim().eq_ignore_ascii_case(version)
+            || mod_version_part(item).eq_ignore_ascii_case(mod_version_part(version))
+    }) {
+        return true;
+    }
+    if affected.introduced.is_none() && affected.fixed.is_none() {
+        return false;
+    }
+    // Placeholder versions such as "local-file" carry no ordering information.
+    if !version.chars().any(|ch| ch.is_ascii_digit()) {
+        return false;
+    }
+    let version = mod_version_part(version);
+    let after_introduced = affected
+        .introduced
+        .as_deref()
+        .map(|start| compare_loose_versions(version, mod_version_part(start)) != Ordering::Less)
+        .unwrap_or(true);
+    let before_fixed = affected
+        .fixed
+        .as_deref()
+        .map(|end| compare_loose_versions(version, mod_version_part(end)) == Ordering::Less)
+        .unwrap_or(true);
+    after_introduced && before_fixed
+}
+
+fn normalize_identifier(raw: &str) -> String {
+    raw.chars()
+        .filter(|ch| ch.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_lowercase()
+}
+
+fn entry_mod_ids(entry: &LockEntry) -> Vec<String> {
+    entry
+        .local_analysis
+        .as_ref()
+        .map(|analysis| {
+            analysis
+                .mod_ids
+                .iter()
+                .map(|id| normalize_identifier(id))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Returns how an entry is identified by `affected`, if it is.
+fn identify_entry(affected: &AdvisoryAffected, entry: &LockEntry) -> Option<String> {
+    let source_ok = affected
+        .source
+        .as_deref()
+        .map(|source| source.trim().eq_ignore_ascii_case(entry.source.trim()))
+        .unwrap_or(true);
+    if source_ok
+        && affected
+            .project_ids
+            .iter()
+            .any(|id| id.trim().eq_ignore_ascii_case(entry.project_id.trim()))
+    {
+        return Some(format!("project {}:{}", entry.source, entry.project_id));
+    }
+    let known_ids = entry_mod_ids(entry);
+    let filename = normalize_identifier(&entry.filename);
+    for mod_id in &affected.mod_ids {
+        let wanted = normalize_identifier(mod_id);
+        if wanted.is_empty() {
+            continue;
+        }
+        if known_ids.contains(&wanted) {
+            return Some(format!("mod id {mod_id}"));
+        }
+        if known_ids.is_empty() && filename.starts_with(&wanted) {
+            return Some(format!("file name {}", entry.filename));
+        }
+    }
+    None
+}
+
+fn hash_match(advisory: &Advisory, entry: &LockEntry) -> Option<String> {
+    for (algorithm, bad_hashes) in &advisory.file_hashes {
+        let Some(actual) = entry.hashes.get(&algorithm.to_ascii_lowercase()) else {
+            continue;
+        };
+        if bad_hashes
+            .iter()
+            .any(|bad| bad.trim().eq_ignore_ascii_case(actual.trim()))
+        {
+            return Some(format!("{algorithm} {actual}"));
+        }
+    }
+    None
+}
+
+fn advisory_finding(
+    advisory: &Advisory,
+    entry: &LockEntry,
+    evidence: Vec<String>,
+    exact: bool,
+) -> RunFinding {
+    let remove = advisory
+        .remediation
+        .as_deref()
+        .map(|value| value.eq_ignore_ascii_case("remove"))
+        .unwrap_or(false)
+        || advisory.fixed_version.is_none();
+    let likely_fix = if remove {
+        format!("Remove {} from this instance.", entry.name)
+    } else {
+        format!(
+            "Update {} to {} or newer, or remove it.",
+            entry.name,
+            advisory.fixed_version.as_deref().unwrap_or_default()
+        )
+    };
+    let severity = if advisory.severity.trim().is_empty() {
+        "unknown"
+    } else {
+        advisory.severity.trim()
+    };
+    RunFinding {
+        id: format!("advisory:{}", advisory.id),
+        category: "security".to_string(),
+        title: advisory.title.clone(),
+        explanation: format!(
+            "{} ({} severity advisory {}; installed: {} {}.)",
+            advisory.summary.trim(),
+            severity,
+            advisory.id,
+            entry.name,
+            entry.version_number
+        ),
+        confidence: if exact { 0.99 } else { 0.85 },
+        evidence: evidence
+            .into_iter()
+            .chain(advisory.references.iter().take(2).cloned())
+            .collect(),
+        likely_fix: Some(likely_fix),
+        mod_id: Some(entry.name.clone()),
+        file_path: Some(format!(
+            "{}/{}",
+            normalize_lock_content_type(&entry.content_type),
+            entry.filename
+        )),
+    }
+}
+
+/// Matches every lock entry against every advisory. Hash hits are exact;
+/// project/mod-id hits also require the installed version to be affected.
+pub(crate) fn match_lockfile(bundle: &AdvisoryBundle, lock: &Lockfile) -> Vec<RunFinding> {
+    let mut out = Vec::new();
+    for entry in &lock.entries {
+        for advisory in &bundle.advisories {
+            if let Some(hash) = hash_match(advisory, entry) {
+                out.push(advisory_finding(
+                    advisory,
+                    entry,
+                    vec![format!("File hash matches a known-bad build ({hash}).")],
+                    true,
+                ));
+                continue;
+            }
+            let hit = advisory.affected.iter().find_map(|affected| {
+                let how = identify_entry(affected, entry)?;
+                version_is_affected(affected, &entry.version_number).then_some(how)
+            });
+            if let Some(how) = hit {
+                out.push(advisory_finding(
+                    advisory,
+                    entry,
+                    vec![format!(
+                        "Matched by {how}; version {} is in the affected range.",
+                        entry.version_number
+                    )],
+                    false,
+                ));
+            }
+        }
+    }
+    out.sort_by(|a, b| a.id.cmp(&b.id).then_with(|| a.mod_id.cmp(&b.mod_id)));
+    out
+}
+
+/// Serializes read-modify-write of the stored report between concurrent
+/// update checks and scans.
+fn last_report_lock() -> &'static Mutex<()> {
+    static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+    LOCK.get_or_init(|| Mutex::new(()))
+}
+
+pub(crate) fn read_last_advisory_report(app: &tauri::AppHandle) -> Option<AdvisoryScanReport> {
+    let raw = fs::read_to_string(last_report_path(app).ok()?).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+fn write_last_advisory_report(
+    app: &tauri::AppHandle,
+    report: &AdvisoryScanReport,
+) -> Result<(), String> {
+    let path = last_report_path(app)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("mkdir launcher dir failed: {e}"))?;
+    }
+    let raw = serde_json::to_string_pretty(report)
+        .map_err(|e| format!("serialize advisory report failed: {e}"))?;
+    fs::write(&path, raw).map_err(|e| format!("write advisory report failed: {e}"))
+}
+
+/// Matches the active bundle against every instance, stores the report and
+/// notifies the UI when anything matched.
+pub(crate) fn scan_all_instances(
+    app: &tauri::AppHandle,
+    trigger: &str,
+) -> Result<AdvisoryScanReport, String> {
+    let (bundle, _) = active_advisory_bundle(app);
+    let instances_dir = app_instances_dir(app)?;
+    let index = read_index(&instances_dir)?;
+    let mut instances = Vec::new();
+    for instance in index.instances {
+        let Ok(lock) = read_lockfile(&instances_dir, &instance.id) else {
+            continue;
+        };
+        let findings = match_lockfile(&bundle, &lock);
+        if !findings.is_empty() {
+            instances.push(InstanceAdvisoryFindings {
+                instance_id: instance.id,
+                instance_name: instance.name,
+                findings,
+            });
+        }
+    }
+    let report = AdvisoryScanReport {
+        bundle_revision: bundle.revision,
+        checked_at: now_iso(),
+        trigger: trigger.to_string(),
+        total_findings: instances.iter().map(|item| item.findings.len()).sum(),
+        instances,
+    };
+    {
+        let _guard = last_report_lock()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        write_last_advisory_report(app, &report)?;
+    }
+    if report.total_findings > 0 {
+        let _ = app.emit_all(SECURITY_ADVISORIES_UPDATED_EVENT, &report);
+    }
+    Ok(report)
+}
+
+/// Matches one instance during an update check and folds the result into the
+/// stored report so the startup summary stays current.
+pub(crate) fn refresh_instance_findings(
+    app: &tauri::AppHandle,
+    instance: &Instance,
+    lock: &Lockfile,
+) -> Vec<RunFinding> {
+    let (bundle, _) = active_advisory_bundle(app);
+    let findings = match_lockfile(&bundle, lock);
+    let _guard = last_report_lock()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let mut report = read_last_advisory_report(app).unwrap_or(AdvisoryScanReport {
+        bundle_revision: bundle.revision,
+        checked_at: now_iso(),
+        trigger: "update_check".to_string(),
+        total_findings: 0,
+        instances: vec![],
+    });
+    report
+        .instances
+        .retain(|item| item.instance_id != instance.id);
+    if !findings.is_empty() {
+        report.instances.push(InstanceAdvisoryFindings {
+            instance_id: instance.id.clone(),
+            instance_name: instance.name.clone(),
+            findings: findings.clone(),
+        });
+    }
+    report.total_findings = report
+        .instances
+        .iter()
+        .map(|item| item.findings.len())
+        .sum();
+    if let Err(err) = write_last_advisory_report(app, &report) {
+        eprintln!("advisory report update failed: {err}");
+    }
+    findings
+}
+
+/// Installs a newer advisory bundle read from disk. Older or equal revisions
+/// are rejected so an import can never roll the data back.
+pub(crate) fn import_advisory_bundle(
+    app: &tauri::AppHandle,
+    raw: &str,
+) -> Result<AdvisoryStatus, String> {
+    let bundle = parse_advisory_bundle(raw)?;
+    let (active, _) = active_advisory_bundle(app);
+    if bundle.revision <= active.revision {
+        return Err(format!(
+            "Advisory bundle revision {} is not newer than the active revision {}.",
+            bundle.revision, active.revision
+        ));
+    }
+    let path = imported_bundle_path(app)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("mkdir launcher dir failed: {e}"))?;
+    }
+    let serialized = serde_json::to_string_pretty(&bundle)
+        .map_err(|e| format!("serialize advisory bundle failed: {e}"))?;
+    fs::write(&path, serialized).map_err(|e| format!("write advisory bundle failed: {e}"))?;
+    scan_all_instances(app, "import")?;
+    Ok(advisory_status(app))
+}
+
+pub(crate) fn advisory_status(app: &tauri::AppHandle) -> AdvisoryStatus {
+    let (bundle, source) = active_advisory_bundle(app);
+    AdvisoryStatus {
+        bundled_revision: bundled_advisory_bundle().revision,
+        active_revision: bundle.revision,
+        active_source: source.to_string(),
+        advisory_count: bundle.advisories.len(),
+        published_at: bundle.published_at.clone(),
+        last_report: read_last_advisory_report(app),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LocalModAnalysis;
+
+    fn entry(name: &str, filename: &str, version: &str) -> LockEntry {
+        LockEntry {
+            source: "curseforge".to_string(),
+            project_id: "cf:231868".to_string(),
+            version_id: "cf_file:1".to_string(),
+            name: name.to_string(),
+            version_number: version.to_string(),
+            filename: filename.to_string(),
+            content_type: "mods".to_string(),
+            target_scope: "instance".to_string(),
+            target_worlds: vec![],
+            pinned_version: None,
+            enabled: true,
+            hashes: HashMap::new(),
+            provider_candidates: vec![],
+            local_analysis: None,
+            security_scan: None,
+            url_update: None,
+            github_channel: None,
+        }
+    }
+
+    #[test]
+    fn bundled_advisories_parse() {
+        let bundle = bundled_advisory_bundle();
+        assert_eq!(bundle.schema_version, ADVISORY_SCHEMA_VERSION);
+        assert!(bundle.revision > 0);
+        assert!(!bundle.advisories.is_empty());
+    }
+
+    #[test]
+    fn compares_loose_versions() {
+        assert_eq!(
+            compare_loose_versions("1.12.2-0.5.76", "1.12.2-0.5.77"),
+            Ordering::Less
+        );
+        assert_eq!(compare_loose_versions("v2.0", "2.0"), Ordering::Equal);
+        assert_eq!(compare_loose_versions("2.0-beta.1", "2.0"), Ordering::Less);
+        assert_eq!(
+            compare_loose_versions("0.10.0.71", "0.9.3"),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn ranges_ignore_minecraft_and_loader_prefixes() {
+        assert_eq!(mod_version_part("1.12.2-0.5.77"), "0.5.77");
+        assert_eq!(mod_version_part("mc1.20.1-2.3"), "2.3");
+        assert_eq!(mod_version_part("forge-1.20.1-2.3"), "2.3");
+        assert_eq!(mod_version_part("1.20.1-forge-2.3"), "2.3");
+        assert_eq!(mod_version_part("2.3+1.20.1"), "2.3");
+        assert_eq!(mod_version_part("1.2.3-4"), "1.2.3-4");
+        assert_eq!(mod_version_part("1.4.2"), "1.4.2");
+        assert_eq!(mod_version_part("EnderCore 1.12.2-0.5.77"), "0.5.77");
+        assert_eq!(mod_version_part("EnderCore-1.12.2-0.5.77.jar"), "0.5.77");
+        assert_eq!(mod_version_part("Example Mod v2.3"), "2.3");
+
+        let affected = AdvisoryAffected {
+            source: None,
+            project_ids: vec![],
+            mod_ids: vec![],
+            versions: vec![],
+            introduced: None,
+            fixed: Some("1.12.2-0.5.77".to_string()),
+        };
+        assert!(version_is_affected(&affected, "1.12.2-0.5.76"));
+        assert!(version_is_affected(&affected, "0.5.76"));
+        assert!(!version_is_affected(&affected, "0.5.80"));
+        assert!(!version_is_affected(&affected, "1.12.2-0.5.77"));
+        assert!(version_is_affected(&affected, "EnderCore 1.12.2-0.5.76"));
+        assert!(!version_is_affected(&affected, "EnderCore 1.12.2-0.5.77"));
+    }
+
+    #[test]
+    fn matches_version_ranges_and_hashes() {
+        let bundle = parse_advisory_bundle(
+            r#"{
+              "schema_version": 1,
+              "revision": 7,
+              "advisories": [
+                {
+                  "id": "T-1",
+                  "title": "Exploit in Example",
+                  "severity": "high",
+                  "summary": "Example lets servers run code.",
+                  "affected": [{ "mod_ids": ["example-mod"], "introduced": "1.0", "fixed": "1.4.2" }],
+                  "fixed_version": "1.4.2",
+                  "remediation": "update"
+                },
+                {
+                  "id": "T-2",
+                  "title": "Compromised build",
+                  "summary": "A re-uploaded jar contained a stage-0 loader.",
+                  "file_hashes": { "sha1": ["ABCDEF"] },
+                  "remediation": "remove"
+                }
+              ]
+            }"#,
+        )
+        .expect("parse bundle");
+
+        let mut vulnerable = entry("Example Mod", "example-mod-1.3.0.jar", "1.3.0");
+        vulnerable
+            .hashes
+            .insert("sha1".to_string(), "abcdef".to_string());
+        let mut fixed = entry("Example Mod", "Example.jar", "1.4.2");
+        fixed.local_analysis = Some(LocalModAnalysis {
+            loader_hints: vec![],
+            mod_ids: vec!["example_mod".to_string()],
+            required_dependencies: vec![],
+            warnings: vec![],
+            suggestions: vec![],
+            scanned_at: "now".to_string(),
+        });
+        let local = entry("example-mod", "example-mod.jar", "local-file");
+        let lock = Lockfile {
+            version: 2,
+            entries: vec![vulnerable, fixed, local],
+        };
+
+        let findings = match_lockfile(&bundle, &lock);
+        let ids = findings
+            .iter()
+            .map(|item| item.id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["advisory:T-1", "advisory:T-2"]);
+        assert_eq!(
+            findings[0].likely_fix.as_deref(),
+            Some("Update Example Mod to 1.4.2 or newer, or remove it.")
+        );
+        assert_eq!(findings[1].confidence, 0.99);
+        assert_eq!(
+            findings[1].likely_fix.as_deref(),
+            Some("Remove Example Mod from this instance.")
+        );
+
+        let unsupported = parse_advisory_bundle(r#"{ "schema_version": 2, "revision": 9 }"#);
+        assert!(unsupported.is_err());
+    }
+}
@@ -0,0 +1,232 @@
+//! Reader and writer for Anvil region files (`r.<x>.<z>.mca`).
+//!
+//! A region holds up to 32×32 chunks. The first 4 KiB sector maps each
+//! chunk to its sectors (3-byte offset, 1-byte count), the second holds
+//! per-chunk timestamps, and each chunk is stored as a 4-byte length, a
+//! compression byte and the compressed NBT. Chunk payloads are kept as
+//! stored, so writing a region back never recompresses anything.
+
+use crate::nbt::{read_nbt, NbtTag};
+
+pub(crate) const SECTOR_BYTES: usize = 4096;
+pub(crate) const REGION_CHUNKS: usize = 1024;
+const HEADER_BYTES: usize = SECTOR_BYTES * 2;
+/// Flag on the compression byte marking a chunk stored in a `.mcc` file.
+const EXTERNAL_FLAG: u8 = 0x80;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RegionChunk {
+    /// Position inside the region, `x + z * 32`.
+    pub index: usize,
+    pub timestamp: u32,
+    pub compression: u8,
+    /// Compressed chunk body, without the length and compression prefix.
+    pub data: Vec<u8>,
+}
+
+impl RegionChunk {
+    /// Chunk x/z relative to the region's corner.
+    pub(crate) fn local_pos(&self) -> (i32, i32) {
+        ((self.index % 32) as i32, (self.index / 32) as i32)
+    }
+
+    /// The body lives in a separate `c.<x>.<z>.mcc` file next to the region.
+    pub(crate) fn is_external(&self) -> bool {
+        self.compression & EXTERNAL_FLAG != 0
+    }
+
+    /// Parses the chunk NBT. gzip, zlib and uncompressed chunks are read;
+    /// LZ4 and external chunks are reported as unsupported.
+    pub(crate) fn nbt(&self) -> Result<NbtTag, String> {
+        match self.compression {
+            1..=3 => read_nbt(&self.data).map(|(_, root)| root),
+            other => Err(format!("unsupported chunk compression {other}")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct RegionFile {
+    /// Present chunks, ordered by index.
+    pub chunks: Vec<RegionChunk>,
+}
+
+/// Region coordinates from an `r.<x>.<z>.mca` file name.
+pub(crate) fn region_coords(file_name: &str) -> Option<(i32, i32)> {
+    let mut parts = file_name
+        .strip_prefix("r.")?
+        .strip_suffix(".mca")?
+        .split('.');
+    let x = parts.next()?.parse().ok()?;
+    let z = parts.next()?.parse().ok()?;
+    parts.next().is_none().then_some((x, z))
+}
+
+/// Parses a region file. Header entries pointing outside the file or into
+/// the header are treated as absent rather than failing the whole region,
+/// matching how the game recovers from them.
+pub(crate) fn read_region(bytes: &[u8]) -> Result<RegionFile, String> {
+    if bytes.is_empty() {
+        return Ok(RegionFile::default());
+    }
+    if bytes.len() < HEADER_BYTES {
+        return Err("region file is shorter than its header".to_string());
+    }
+    let mut chunks = Vec::new();
+    for index in 0..REGION_CHUNKS {
+        let loc = &bytes[index * 4..index * 4 + 4];
+        let sector = u32::from_be_bytes([0, loc[0], loc[1], loc[2]]) as usize;
+        let sectors = usize::from(loc[3]);
+        if sector == 0 && sectors == 0 {
+            continue;
+        }
+        let start = sector * SECTOR_BYTES;
+        if sector < 2 || sectors == 0 || start + 5 > bytes.len() {
+            continue;
+        }
+        let len = u32::from_be_bytes([
+            bytes[start],
+            bytes[start + 1],
+            bytes[start + 2],
+            bytes[start + 3],
+        ]) as usize;
+        if len == 0 || start + 4 + len > bytes.len() || 4 + len > sectors * SECTOR_BYTES {
+            continue;
+        }
+        let stamp = SECTOR_BYTES + index * 4;
+        chunks.push(RegionChunk {
+            index,
+            timestamp: u32::from_be_bytes([
+                bytes[stamp],
+                bytes[stamp + 1],
+                bytes[stamp + 2],
+                bytes[stamp + 3],
+            ]),
+            compression: bytes[start + 4],
+            data: bytes[start + 5..start + 4 + len].to_vec(),
+        });
+    }
+    Ok(RegionFile { chunks })
+}
+
+impl RegionFile {
+    /// Serializes the region with chunks packed back to back from sector 2,
+    /// which drops the free sectors the game leaves behind.
+    pub(crate) fn to_bytes(&self) -> Result<Vec<u8>, String> {
+        let mut out = vec![0u8; HEADER_BYTES];
+        for chunk in &self.chunks {
+            if chunk.index >= REGION_CHUNKS {
+                return Err(format!("chunk index {} is out of range", chunk.index));
+            }
+            let len = chunk.data.len() + 1;
+            let sectors = (4 + len + SECTOR_BYTES - 1) / SECTOR_BYTES;
+            if sectors > usize::from(u8::MAX) {
+                return Err(format!(
+                    "chunk {} is too large to store inline",
+                    chunk.index
+                ));
+            }
+            let sector = out.len() / SECTOR_BYTES;
+            if sector > 0x00ff_ffff {
+                return Err("region file is too large".to_string());
+            }
+            let sector_bytes = (sector as u32).to_be_bytes();
+            out[chunk.index * 4..chunk.index * 4 + 4].copy_from_slice(&[
+                sector_bytes[1],
+                sector_bytes[2],
+                sector_bytes[3],
+                sectors as u8,
+            ]);
+            let stamp = SECTOR_BYTES + chunk.index * 4;
+            out[stamp..stamp + 4].copy_from_slice(&chunk.timestamp.to_be_bytes());
+            out.extend_from_slice(&(len as u32).to_be_bytes());
+            out.push(chunk.compression);
+            out.extend_from_slice(&chunk.data);
+            out.resize((sector + sectors) * SECTOR_BYTES, 0);
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod encode {
+    use super::{RegionChunk, RegionFile};
+    use crate::nbt::encode::{compound, document, gzip};
+    use crate::nbt::NbtTag;
+
+    /// Gzip chunk at `(x, z)` inside its region with the given `InhabitedTime`.
+    pub(crate) fn chunk(x: usize, z: usize, inhabited: i64) -> RegionChunk {
+        let root = compound(vec![
+            ("DataVersion", NbtTag::Int(3700)),
+            ("xPos", NbtTag::Int(x as i32)),
+            ("zPos", NbtTag::Int(z as i32)),
+            ("InhabitedTime", NbtTag::Long(inhabited)),
+        ]);
+        RegionChunk {
+            index: x + z * 32,
+            timestamp: 1_700_000_000,
+            compression: 1,
+            data: gzip(&document("", &root)),
+        }
+    }
+
+    pub(crate) fn region(chunks: Vec<RegionChunk>) -> Vec<u8> {
+        RegionFile { chunks }.to_bytes().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::encode::{chunk, region};
+    use super::*;
+
+    #[test]
+    fn round_trips_chunks_and_compacts_free_sectors() {
+        let mut big = chunk(31, 31, 5);
+        big.data
+            .extend(std::iter::repeat(7u8).take(SECTOR_BYTES * 2));
+        let bytes = region(vec![chunk(0, 0, 10), chunk(3, 1, 20), big.clone()]);
+        assert_eq!(bytes.len() % SECTOR_BYTES, 0);
+        let parsed = read_region(&bytes).unwrap();
+        assert_eq!(parsed.chunks.len(), 3);
+        assert_eq!(parsed.chunks[1].local_pos(), (3, 1));
+        assert_eq!(parsed.chunks[2], big);
+        let nbt = parsed.chunks[1].nbt().unwrap();
+        assert_eq!(nbt.get("InhabitedTime").and_then(NbtTag::as_i64), Some(20));
+
+        // Leave a hole the way the game does after a chunk moves, then compact.
+        let mut holey = bytes.clone();
+        holey[..4].copy_from_slice(&[0, 0, 0, 0]);
+        let compacted = read_region(&holey).unwrap().to_bytes().unwrap();
+        assert!(compacted.len() < bytes.len());
+        assert_eq!(read_region(&compacted).unwrap().chunks.len(), 2);
+    }
+
+    #[test]
+    fn skips_bad_header_entries_and_parses_names() {
+        let mut bytes = region(vec![chunk(1, 0, 1)]);
+        // Point chunk 0 past the end of the file and chunk 2 into the header.
+        bytes[..4].copy_from_slice(&[0, 0, 99, 1]);
+        bytes[8..12].copy_from_slice(&[0, 0, 1, 1]);
+        let parsed = read_region(&bytes).unwrap();
+        assert_eq!(parsed.chunks.len(), 1);
+        assert_eq!(parsed.chunks[0].index, 1);
+        assert!(read_region(&bytes[..100]).is_err());
+        assert!(read_region(&[]).unwrap().chunks.is_empty());
+
+        let lz4 = RegionChunk {
+            compression: 4,
+            ..chunk(0, 0, 0)
+        };
+        assert!(lz4.nbt().is_err());
+        assert!(RegionChunk {
+            compression: 0x82,
+            ..chunk(0, 0, 0)
+        }
+        .is_external());
+
+        assert_eq!(region_coords("r.-1.2.mca"), Some((-1, 2)));
+        assert_eq!(region_coords("r.0.0.mcc"), None);
+        assert_eq!(region_coords("r.a.0.mca"), None);
+    }
+}
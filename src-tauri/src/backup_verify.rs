@@ -0,0 +1,431 @@
+//! Integrity checks for snapshots and world backups.
+//!
+//! Zip archives are read end to end so the zip reader checks every entry's
+//! CRC; manifest backups re-hash each blob they reference. World backups are
+//! also compared with the file and byte counts recorded in `WorldBackupMeta`.
+//! The outcome is written back into each backup's metadata, which flags
+//! damaged backups in the UI and keeps them out of "restore latest".
+
+use crate::content_store::{BlobCheck, ContentStore};
+use crate::snapshot_store::{object_store_for, read_manifest, SnapshotManifest};
+use crate::world_backup_store::{
+    backup_roots, world_backup_destinations_for, world_backup_lock, world_backup_objects,
+};
+use crate::{
+    app_instances_dir, created_at_sort_key, instance_dir_for_instance, launcher_dir,
+    list_snapshots, list_world_backups, now_iso, read_index, read_launcher_settings,
+    read_snapshot_meta, read_world_backup_meta, snapshot_content_zip_path, snapshot_lock_path,
+    snapshots_dir, world_backup_zip_path, world_backups_dir, write_snapshot_meta,
+    write_world_backup_meta, Instance, Lockfile, WorldBackupMeta,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+use tauri::Manager;
+
+pub(crate) const BACKUPS_VERIFIED_EVENT: &str = "backups_verified";
+pub(crate) const BACKUP_KIND_SNAPSHOT: &str = "snapshot";
+pub(crate) const BACKUP_KIND_WORLD: &str = "world_backup";
+pub(crate) const DEFAULT_BACKUP_VERIFY_INTERVAL_HOURS: u32 = 168;
+const LAST_REPORT_FILE: &str = "backup_verify_report.json";
+const SCHEDULE_POLL_SECS: u64 = 30 * 60;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct CorruptBackup {
+    pub instance_id: String,
+    pub instance_name: String,
+    /// `snapshot` or `world_backup`.
+    pub kind: String,
+    pub id: String,
+    /// `local` or the destination path holding the damaged copy.
+    pub location: String,
+    pub problem: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct BackupVerifyReport {
+    /// Time of the last run that covered every instance.
+    pub checked_at: String,
+    pub trigger: String,
+    pub snapshots_checked: usize,
+    pub world_backups_checked: usize,
+    #[serde(default)]
+    pub corrupt: Vec<CorruptBackup>,
+}
+
+fn last_report_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(launcher_dir(app)?.join(LAST_REPORT_FILE))
+}
+
+pub(crate) fn read_last_backup_verify_report(app: &tauri::AppHandle) -> Option<BackupVerifyReport> {
+    let raw = fs::read_to_string(last_report_path(app).ok()?).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+fn write_last_backup_verify_report(
+    app: &tauri::AppHandle,
+    report: &BackupVerifyReport,
+) -> Result<(), String> {
+    let path = last_report_path(app)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("mkdir launcher dir failed: {e}"))?;
+    }
+    let raw = serde_json::to_string_pretty(report)
+        .map_err(|e| format!("serialize backup verify report failed: {e}"))?;
+    fs::write(&path, raw).map_err(|e| format!("write backup verify report failed: {e}"))
+}
+
+/// Reads every file entry so each CRC is checked. Returns files and bytes.
+fn check_zip(path: &Path) -> Result<(usize, u64), String> {
+    let file = fs::File::open(path).map_err(|e| format!("archive is unreadable: {e}"))?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("archive is unreadable: {e}"))?;
+    let mut files = 0usize;
+    let mut bytes = 0u64;
+    for index in 0..archive.len() {
+        let mut entry = archive
+            .by_index(index)
+            .map_err(|e| format!("archive entry {index} is unreadable: {e}"))?;
+        if entry.is_dir() {
+            continue;
+        }
+        let name = entry.name().to_string();
+        let read = io::copy(&mut entry, &mut io::sink())
+            .map_err(|e| format!("archive entry '{name}' is damaged: {e}"))?;
+        files += 1;
+        bytes = bytes.saturating_add(read);
+    }
+    Ok((files, bytes))
+}
+
+/// Re-hashes every blob the manifest references. Returns files and bytes.
+/// Nothing is removed: the store may be an external destination, and a blob
+/// that could not be read this time may be fine the next.
+fn check_manifest(
+    manifest: &SnapshotManifest,
+    store: &ContentStore,
+) -> Result<(usize, u64), String> {
+    let mut checked = HashMap::new();
+    let damaged = manifest
+        .files
+        .iter()
+        .filter_map(|file| {
+            let problem = match checked
+                .entry(file.sha512.as_str())
+                .or_insert_with(|| store.check(&file.sha512))
+            {
+                BlobCheck::Intact if store.blob_len(&file.sha512) == Some(file.size) => {
+                    return None
+                }
+                BlobCheck::Intact => "wrong size".to_string(),
+                BlobCheck::Missing => "missing".to_string(),
+                BlobCheck::Damaged => "damaged".to_string(),
+                BlobCheck::Unreadable(err) => err.clone(),
+            };
+            Some(format!("{} ({problem})", file.path))
+        })
+        .collect::<Vec<_>>();
+    if !damaged.is_empty() {
+        return Err(format!(
+            "{} file(s) missing or damaged: {}",
+            damaged.len(),
+            damaged.join(", ")
+        ));
+    }
+    let bytes = manifest
+        .files
+        .iter()
+        .fold(0u64, |sum, file| sum.saturating_add(file.size));
+    Ok((manifest.files.len(), bytes))
+}
+
+/// Checks a world backup stored under `root` (an instance folder or a
+/// destination standing in for one).
+pub(crate) fn check_world_backup(
+    root: &Path,
+    backup_dir: &Path,
+    meta: &WorldBackupMeta,
+) -> Result<(), String> {
+    let (files, bytes) = match read_manifest(backup_dir) {
+        Some(manifest) => check_manifest(&manifest, &world_backup_objects(root))?,
+        None => check_zip(&world_backup_zip_path(backup_dir))?,
+    };
+    if files != meta.files_count || bytes != meta.total_bytes {
+        return Err(format!(
+            "holds {files} file(s) and {bytes} bytes but {} file(s) and {} bytes were recorded",
+            meta.files_count, meta.total_bytes
+        ));
+    }
+    Ok(())
+}
+
+pub(crate) fn check_snapshot(instance_dir: &Path, snapshot_dir: &Path) -> Result<(), String> {
+    let raw = fs::read_to_string(snapshot_lock_path(snapshot_dir))
+        .map_err(|e| format!("lockfile is unreadable: {e}"))?;
+    serde_json::from_str::<Lockfile>(&raw).map_err(|e| format!("lockfile is damaged: {e}"))?;
+    if let Some(manifest) = read_manifest(snapshot_dir) {
+        check_manifest(&manifest, &object_store_for(instance_dir))?;
+        return Ok(());
+    }
+    let zip_path = snapshot_content_zip_path(snapshot_dir);
+    if zip_path.exists() {
+        check_zip(&zip_path)?;
+    }
+    Ok(())
+}
+
+/// Checks every snapshot and every world backup copy of one instance and
+/// records the outcome in their metadata. Returns the counts checked and the
+/// damaged backups found.
+pub(crate) fn verify_instance_backups(
+    instance_dir: &Path,
+    instance: &Instance,
+    destinations: &[crate::world_backup_store::WorldBackupDestination],
+) -> (usize, usize, Vec<CorruptBackup>) {
+    let mut corrupt = Vec::new();
+    let mut flag = |kind: &str, id: &str, location: &str, problem: &str| {
+        corrupt.push(CorruptBackup {
+            instance_id: instance.id.clone(),
+            instance_name: instance.name.clone(),
+            kind: kind.to_string(),
+            id: id.to_string(),
+            location: location.to_string(),
+            problem: problem.to_string(),
+        });
+    };
+
+    // Metas are re-read after each check and only the verification fields are
+    // written back, so edits made meanwhile survive. A backup deleted while it
+    // was being checked is skipped rather than reported as damaged.
+    let mut snapshots_checked = 0usize;
+    for meta in list_snapshots(instance_dir).unwrap_or_default() {
+        let dir = snapshots_dir(instance_dir).join(&meta.id);
+        let result = check_snapshot(instance_dir, &dir);
+        let Ok(mut current) = read_snapshot_meta(&dir) else {
+            continue;
+        };
+        if let Err(problem) = &result {
+            flag(
+                BACKUP_KIND_SNAPSHOT,
+                &meta.id,
+                crate::world_backup_store::LOCAL_LOCATION,
+                problem,
+            );
+        }
+        current.verified_at = Some(now_iso());
+        current.integrity_error = result.err();
+        let _ = write_snapshot_meta(&dir, &current);
+        snapshots_checked += 1;
+    }
+
+    let mut world_backups_checked = 0usize;
+    for (location, root) in backup_roots(instance_dir, &instance.id, destinations) {
+        let lock = world_backup_lock(&root);
+        let Ok(_guard) = lock.lock() else {
+            continue;
+        };
+        for meta in list_world_backups(&root).unwrap_or_default() {
+            let dir = world_backups_dir(&root).join(&meta.id);
+            let result = check_world_backup(&root, &dir, &meta);
+            let Ok(mut current) = read_world_backup_meta(&dir) else {
+                continue;
+            };
+            if let Err(problem) = &result {
+                flag(BACKUP_KIND_WORLD, &meta.id, &location, problem);
+            }
+            current.verified_at = Some(now_iso());
+            current.integrity_error = result.err();
+            let _ = write_world_backup_meta(&dir, &current);
+            world_backups_checked += 1;
+        }
+    }
+    (snapshots_checked, world_backups_checked, corrupt)
+}
+
+/// Verifies the backups of one instance, or of every instance when
+/// `instance_id` is `None`, and stores the report. A single-instance run
+/// only replaces that instance's findings in the stored report.
+pub(crate) fn verify_backups(
+    app: &tauri::AppHandle,
+    instance_id: Option<&str>,
+    trigger: &str,
+) -> Result<BackupVerifyReport, String> {
+    let instances_dir = app_instances_dir(app)?;
+    let index = read_index(&instances_dir)?;
+    let launcher_destinations = read_launcher_settings(app)?.world_backup_destinations;
+    let mut snapshots_checked = 0usize;
+    let mut world_backups_checked = 0usize;
+    let mut corrupt = Vec::new();
+    for instance in index
+        .instances
+        .iter()
+        .filter(|inst| instance_id.map_or(true, |wanted| inst.id == wanted))
+    {
+        let instance_dir = instance_dir_for_instance(&instances_dir, instance);
+        let destinations = world_backup_destinations_for(
+            &launcher_destinations,
+            &instance.settings.world_backup_destinations,
+        );
+        let (snapshots, worlds, found) =
+            verify_instance_backups(&instance_dir, instance, &destinations);
+        snapshots_checked += snapshots;
+        world_backups_checked += worlds;
+        corrupt.extend(found);
+    }
+
+    let report = match (instance_id, read_last_backup_verify_report(app)) {
+        (Some(wanted), Some(mut previous)) => {
+            previous.corrupt.retain(|item| item.instance_id != wanted);
+            previous.corrupt.extend(corrupt);
+            previous
+        }
+        _ => BackupVerifyReport {
+            checked_at: now_iso(),
+            trigger: trigger.to_string(),
+            snapshots_checked,
+            world_backups_checked,
+            corrupt,
+        },
+    };
+    write_last_backup_verify_report(app, &report)?;
+    if !report.corrupt.is_empty() {
+        let _ = app.emit_all(BACKUPS_VERIFIED_EVENT, &report);
+    }
+    Ok(report)
+}
+
+fn verify_due(last: Option<&BackupVerifyReport>, interval_hours: u32, now: i64) -> bool {
+    let Some(last) = last else {
+        return true;
+    };
+    let checked = created_at_sort_key(&last.checked_at);
+    checked <= 0 || now.saturating_sub(checked) >= i64::from(interval_hours) * 3600
+}
+
+/// Background loop that re-verifies everything once the configured interval
+/// has passed since the last full run. An interval of 0 turns it off.
+pub(crate) fn run_verify_schedule(app: tauri::AppHandle) {
+    loop {
+        let interval = read_launcher_settings(&app)
+            .map(|settings| settings.backup_verify_interval_hours)
+            .unwrap_or(0);
+        let last = read_last_backup_verify_report(&app);
+        if interval > 0 && verify_due(last.as_ref(), interval, chrono::Utc::now().timestamp()) {
+            if let Err(err) = verify_backups(&app, None, "scheduled") {
+                eprintln!("scheduled backup verification warning: {err}");
+            }
+        }
+        thread::sleep(Duration::from_secs(SCHEDULE_POLL_SECS));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_root;
+    use crate::world_backup_store::{
+        create_incremental_world_backup, WORLD_BACKUP_FORMAT_INCREMENTAL, WORLD_BACKUP_FORMAT_ZIP,
+    };
+    use std::io::Write;
+
+    fn meta(id: &str, format: &str, files_count: usize, total_bytes: u64) -> WorldBackupMeta {
+        WorldBackupMeta {
+            id: id.to_string(),
+            world_id: "World".to_string(),
+            created_at: "2026-04-01T10:00:00Z".to_string(),
+            reason: "test".to_string(),
+            files_count,
+            total_bytes,
+            format: format.to_string(),
+            new_bytes: 0,
+            verified_at: None,
+            integrity_error: None,
+        }
+    }
+
+    #[test]
+    fn zip_backups_are_checked_against_crcs_and_recorded_counts() {
+        let root = temp_root("zip");
+        let backup = world_backups_dir(&root).join("wb_zip");
+        fs::create_dir_all(&backup).unwrap();
+        let zip_path = world_backup_zip_path(&backup);
+        let mut zip = zip::ZipWriter::new(fs::File::create(&zip_path).unwrap());
+        let opts =
+            zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Stored);
+        zip.start_file("level.dat", opts).unwrap();
+        zip.write_all(b"level-data-payload").unwrap();
+        zip.finish().unwrap();
+
+        let good = meta("wb_zip", WORLD_BACKUP_FORMAT_ZIP, 1, 18);
+        assert!(check_world_backup(&root, &backup, &good).is_ok());
+        let miscounted = meta("wb_zip", WORLD_BACKUP_FORMAT_ZIP, 2, 18);
+        assert!(check_world_backup(&root, &backup, &miscounted).is_err());
+
+        // Flip a byte of the stored payload so only the CRC can notice.
+        let mut raw = fs::read(&zip_path).unwrap();
+        let at = raw
+            .windows(10)
+            .position(|window| window == b"level-data")
+            .expect("payload offset");
+        raw[at] ^= 0x20;
+        fs::write(&zip_path, raw).unwrap();
+        let err = check_world_backup(&root, &backup, &good).unwrap_err();
+        assert!(err.contains("damaged"), "{err}");
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn incremental_backups_fail_when_a_blob_is_damaged() {
+        let root = temp_root("incremental");
+        let world = root.join("saves").join("World");
+        fs::create_dir_all(world.join("region")).unwrap();
+        fs::write(world.join("level.dat"), b"level").unwrap();
+        fs::write(world.join("region").join("r.0.0.mca"), [5u8; 512]).unwrap();
+        let backup = world_backups_dir(&root).join("wb_inc");
+        let stats = create_incremental_world_backup(&root, "World", &world, &backup).unwrap();
+        let recorded = meta(
+            "wb_inc",
+            WORLD_BACKUP_FORMAT_INCREMENTAL,
+            stats.files_count,
+            stats.total_bytes,
+        );
+        assert!(check_world_backup(&root, &backup, &recorded).is_ok());
+
+        let manifest = read_manifest(&backup).unwrap();
+        let region = manifest
+            .files
+            .iter()
+            .find(|file| file.path == "region/r.0.0.mca")
+            .unwrap();
+        let blob = world_backups_dir(&root)
+            .join("objects")
+            .join("sha512")
+            .join(&region.sha512[..2])
+            .join(&region.sha512);
+        fs::write(&blob, [6u8; 512]).unwrap();
+        let err = check_world_backup(&root, &backup, &recorded).unwrap_err();
+        assert!(err.contains("region/r.0.0.mca (damaged)"), "{err}");
+        // Verification only reports; the damaged blob is still there to inspect.
+        assert!(blob.exists());
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn schedule_runs_once_the_interval_has_passed() {
+        let report = BackupVerifyReport {
+            checked_at: "2026-04-01T00:00:00Z".to_string(),
+            trigger: "manual".to_string(),
+            snapshots_checked: 0,
+            world_backups_checked: 0,
+            corrupt: Vec::new(),
+        };
+        let checked = created_at_sort_key(&report.checked_at);
+        assert!(verify_due(None, 24, checked));
+        assert!(!verify_due(Some(&report), 24, checked + 3600));
+        assert!(verify_due(Some(&report), 24, checked + 24 * 3600));
+    }
+}
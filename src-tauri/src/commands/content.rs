@@ -1,6 +1,11 @@
 #[allow(unused_imports)]
 pub(crate) use super::impls::{
-    check_instance_content_updates, check_modrinth_updates, import_local_mod_file,
-    install_curseforge_mod, install_modrinth_mod, preview_modrinth_install,
-    resolve_local_mod_sources, update_all_instance_content, update_all_modrinth_mods,
+    acknowledge_installed_mod_security, check_instance_content_updates, check_modrinth_updates,
+    dismiss_curseforge_handoff_item, get_security_advisory_status, import_local_mod_file,
+    import_security_advisory_bundle, install_curseforge_mod, install_maven_content,
+    install_modrinth_mod, install_url_content, list_content_watchers,
+    list_curseforge_handoff_items, preview_modrinth_install, redownload_modified_lock_entries,
+    resolve_local_mod_sources, run_security_advisory_scan, scan_curseforge_handoff_folder,
+    scan_instance_content_security, set_instance_content_watcher, update_all_instance_content,
+    update_all_modrinth_mods, verify_instance_lockfile,
 };
@@ -7,7 +7,7 @@ use std::fs::{self, File};
 use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
 use std::process::Stdio;
-use std::sync::{Arc, Mutex, OnceLock};
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
 use std::thread;
 use std::time::{Duration, Instant, SystemTime};
 use tauri::Manager;
@@ -60,7 +60,7 @@ fn create_instance_snapshot_with_event_best_effort(
     }
 }
 
-const DISCOVER_PROVIDER_SOURCES: [&str; 3] = ["modrinth", "curseforge", "github"];
+const DISCOVER_PROVIDER_SOURCES: [&str; 4] = ["modrinth", "curseforge", "github", "team"];
 
 fn create_preinstall_snapshot_with_event_best_effort(
     app: &tauri::AppHandle,
@@ -71,13 +71,82 @@ fn create_preinstall_snapshot_with_event_best_effort(
     create_instance_snapshot_with_event_best_effort(app, instances_dir, instance_id, reason);
 }
 
-fn instance_mutation_lock(instance_id: &str) -> Arc<Mutex<()>> {
-    static LOCKS: OnceLock<Mutex<HashMap<String, Arc<Mutex<()>>>>> = OnceLock::new();
+/// Serializes lockfile read-modify-write for one instance. Every command that
+/// writes the lockfile, and the content watcher, hold it. Re-entrant on the
+/// holding thread, so a locked command may call into another one. The holder
+/// also holds off content store garbage collection, since blobs it puts are
+/// only referenced once the lockfile is written.
+#[derive(Default)]
+pub(crate) struct InstanceMutationLock {
+    owner: Mutex<Option<(thread::ThreadId, usize)>>,
+    released: Condvar,
+    store_use: Mutex<Option<content_store::StoreUse>>,
+}
+
+pub(crate) struct InstanceMutationGuard<'a> {
+    lock: &'a InstanceMutationLock,
+}
+
+impl InstanceMutationLock {
+    pub(crate) fn lock(&self) -> Result<InstanceMutationGuard<'_>, String> {
+        let me = thread::current().id();
+        let mut owner = self
+            .owner
+            .lock()
+            .map_err(|_| "instance mutation lock poisoned".to_string())?;
+        loop {
+            match owner.as_mut() {
+                None => {
+                    *owner = Some((me, 1));
+                    drop(owner);
+                    let store_use = content_store::hold_configured_store();
+                    if let Ok(mut slot) = self.store_use.lock() {
+                        *slot = store_use;
+                    }
+                    return Ok(InstanceMutationGuard { lock: self });
+                }
+                Some((holder, depth)) if *holder == me => {
+                    *depth += 1;
+                    break;
+                }
+                Some(_) => {
+                    owner = self
+                        .released
+                        .wait(owner)
+                        .map_err(|_| "instance mutation lock poisoned".to_string())?;
+                }
+            }
+        }
+        Ok(InstanceMutationGuard { lock: self })
+    }
+}
+
+impl Drop for InstanceMutationGuard<'_> {
+    fn drop(&mut self) {
+        let mut owner = match self.lock.owner.lock() {
+            Ok(owner) => owner,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        if let Some((_, depth)) = owner.as_mut() {
+            *depth -= 1;
+            if *depth == 0 {
+                if let Ok(mut slot) = self.lock.store_use.lock() {
+                    slot.take();
+                }
+                *owner = None;
+                self.lock.released.notify_all();
+            }
+        }
+    }
+}
+
+pub(crate) fn instance_mutation_lock(instance_id: &str) -> Arc<InstanceMutationLock> {
+    static LOCKS: OnceLock<Mutex<HashMap<String, Arc<InstanceMutationLock>>>> = OnceLock::new();
     let locks = LOCKS.get_or_init(|| Mutex::new(HashMap::new()));
     let mut guard = locks.lock().expect("instance mutation lock registry");
     guard
         .entry(instance_id.trim().to_string())
-        .or_insert_with(|| Arc::new(Mutex::new(())))
+        .or_default()
         .clone()
 }
 
@@ -805,12 +874,20 @@ pub(crate) fn pick_external_open_path_grants(
             }
             ("Modpack archive", &["mrpack", "zip"])
         }
-        EXTERNAL_PATH_PURPOSE_PRESETS_IMPORT | EXTERNAL_PATH_PURPOSE_MODPACK_SPEC_IMPORT => {
+        EXTERNAL_PATH_PURPOSE_PRESETS_IMPORT
+        | EXTERNAL_PATH_PURPOSE_MODPACK_SPEC_IMPORT
+        | EXTERNAL_PATH_PURPOSE_ADVISORY_BUNDLE_IMPORT => {
             if wants_multiple {
                 return Err("JSON import picker only supports a single file.".to_string());
             }
             ("JSON", &["json"])
         }
+        EXTERNAL_PATH_PURPOSE_WORLD_ARCHIVE_IMPORT => {
+            if wants_multiple {
+                return Err("World archive picker only supports a single file.".to_string());
+            }
+            ("World archive", &["zip"])
+        }
         EXTERNAL_PATH_PURPOSE_LOCAL_MOD_IMPORT | EXTERNAL_PATH_PURPOSE_MODPACK_LOCAL_JAR_IMPORT => {
             external_local_content_filter(args.content_type.as_deref().unwrap_or("mods"))?
         }
@@ -904,9 +981,9 @@ pub(crate) fn pick_external_save_path_grant(
         EXTERNAL_PATH_PURPOSE_PRESETS_EXPORT | EXTERNAL_PATH_PURPOSE_MODPACK_SPEC_EXPORT => {
             ("JSON", &["json"])
         }
-        EXTERNAL_PATH_PURPOSE_INSTANCE_MODS_EXPORT | EXTERNAL_PATH_PURPOSE_SUPPORT_BUNDLE_EXPORT => {
-            ("Zip archive", &["zip"])
-        }
+        EXTERNAL_PATH_PURPOSE_INSTANCE_MODS_EXPORT
+        | EXTERNAL_PATH_PURPOSE_SUPPORT_BUNDLE_EXPORT
+        | EXTERNAL_PATH_PURPOSE_WORLD_ARCHIVE_EXPORT => ("Zip archive", &["zip"]),
         _ => return Err("Unsupported save-picker purpose".to_string()),
     };
     let Some(path) = pick_save_file_dialog(args.suggested_name.as_deref(), filter_name, extensions)? else {
@@ -1075,7 +1152,30 @@ pub(crate) fn set_launcher_settings(
     if let Some(level) = args.discord_presence_detail_level {
         settings.discord_presence_detail_level = normalize_discord_presence_detail_level(&level);
     }
+    if let Some(verify) = args.verify_lock_hashes_before_launch {
+        settings.verify_lock_hashes_before_launch = verify;
+    }
+    if let Some(location) = args.team_repository {
+        settings.team_repository = location.trim().to_string();
+        crate::team_repo::set_configured_location(&settings.team_repository);
+    }
+    let handoff_dir_changed = args.curseforge_handoff_dir.is_some();
+    if let Some(folder) = args.curseforge_handoff_dir {
+        settings.curseforge_handoff_dir = folder.trim().to_string();
+    }
+    if let Some(destinations) = args.world_backup_destinations {
+        settings.world_backup_destinations =
+            crate::world_backup_store::normalize_world_backup_destinations(destinations);
+    }
+    if let Some(hours) = args.backup_verify_interval_hours {
+        settings.backup_verify_interval_hours = hours.min(24 * 90);
+    }
     write_launcher_settings(&app, &settings)?;
+    if handoff_dir_changed {
+        if let Err(err) = crate::curseforge_handoff::restart_handoff_watcher(&app) {
+            eprintln!("curseforge handoff watcher restart failed: {err}");
+        }
+    }
     Ok(settings)
 }
 
@@ -1813,6 +1913,8 @@ pub(crate) fn rollback_instance(
     state: tauri::State<AppState>,
     args: RollbackInstanceArgs,
 ) -> Result<RollbackResult, String> {
+    let mutation_lock = instance_mutation_lock(&args.instance_id);
+    let _guard = mutation_lock.lock()?;
     let instances_dir = app_instances_dir(&app)?;
     let _ = find_instance(&instances_dir, &args.instance_id)?;
     {
@@ -1842,8 +1944,8 @@ pub(crate) fn rollback_instance(
     } else {
         snapshots
             .into_iter()
-            .next()
-            .ok_or_else(|| "No snapshots found for this instance".to_string())?
+            .find(|s| s.integrity_error.is_none())
+            .ok_or_else(|| "No intact snapshot found for this instance".to_string())?
     };
 
     let snapshot_dir = snapshots_dir(&instance_dir).join(&selected.id);
@@ -1853,7 +1955,7 @@ pub(crate) fn rollback_instance(
         serde_json::from_str(&lock_raw).map_err(|e| format!("parse snapshot lock failed: {e}"))?;
 
     let restored_files =
-        restore_instance_content_zip(&snapshot_content_zip_path(&snapshot_dir), &instance_dir)?;
+        crate::snapshot_store::restore_snapshot_content(&snapshot_dir, &instance_dir)?;
     write_lockfile(&instances_dir, &args.instance_id, &lock)?;
     log_instance_event_best_effort(
         &app,
@@ -1873,6 +1975,130 @@ pub(crate) fn rollback_instance(
     })
 }
 
+#[tauri::command]
+pub(crate) fn update_instance_snapshot(
+    app: tauri::AppHandle,
+    args: UpdateInstanceSnapshotArgs,
+) -> Result<SnapshotMeta, String> {
+    let instances_dir = app_instances_dir(&app)?;
+    let _ = find_instance(&instances_dir, &args.instance_id)?;
+    let instance_dir = instance_dir_for_id(&instances_dir, &args.instance_id)?;
+    update_snapshot_meta(
+        &instance_dir,
+        &args.snapshot_id,
+        args.label.as_deref(),
+        args.pinned,
+    )
+}
+
+#[tauri::command]
+pub(crate) async fn diff_instance_snapshots(
+    app: tauri::AppHandle,
+    args: DiffInstanceSnapshotsArgs,
+) -> Result<crate::snapshot_diff::SnapshotDiff, String> {
+    run_blocking_task("diff instance snapshots", move || {
+        let instances_dir = app_instances_dir(&app)?;
+        let _ = find_instance(&instances_dir, &args.instance_id)?;
+        let instance_dir = instance_dir_for_id(&instances_dir, &args.instance_id)?;
+        let from =
+            crate::snapshot_diff::load_snapshot_state(&instance_dir, &args.from_snapshot_id)?;
+        let to_id = args
+            .to_snapshot_id
+            .clone()
+            .filter(|id| !id.trim().is_empty())
+            .unwrap_or_else(|| crate::snapshot_diff::CURRENT_STATE.to_string());
+        let to = if to_id == crate::snapshot_diff::CURRENT_STATE {
+            let lock = read_lockfile(&instances_dir, &args.instance_id)?;
+            crate::snapshot_diff::current_state(&instance_dir, lock)?
+        } else {
+            crate::snapshot_diff::load_snapshot_state(&instance_dir, &to_id)?
+        };
+        let (lock_changes, file_changes) = crate::snapshot_diff::diff_content_states(&from, &to);
+        Ok(crate::snapshot_diff::SnapshotDiff {
+            instance_id: args.instance_id,
+            from: args.from_snapshot_id,
+            to: to_id,
+            lock_changes,
+            file_changes,
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) fn restore_instance_snapshot_selection(
+    app: tauri::AppHandle,
+    state: tauri::State<AppState>,
+    args: RestoreInstanceSnapshotSelectionArgs,
+) -> Result<crate::snapshot_diff::SelectiveRestoreResult, String> {
+    if args.entry_keys.is_empty() && args.paths.is_empty() {
+        return Err("Choose at least one file or lock entry to restore.".to_string());
+    }
+    let instances_dir = app_instances_dir(&app)?;
+    let _ = find_instance(&instances_dir, &args.instance_id)?;
+    {
+        let guard = state
+            .running
+            .lock()
+            .map_err(|_| "lock running instances failed".to_string())?;
+        if guard
+            .values()
+            .any(|entry| entry.meta.instance_id == args.instance_id)
+        {
+            return Err(
+                "Stop the running Minecraft session before restoring from a snapshot.".to_string(),
+            );
+        }
+    }
+    let mutation_lock = instance_mutation_lock(&args.instance_id);
+    let _guard = mutation_lock.lock()?;
+    let instance_dir = instance_dir_for_id(&instances_dir, &args.instance_id)?;
+    let snapshot = crate::snapshot_diff::load_snapshot_state(&instance_dir, &args.snapshot_id)?;
+    let current_lock = read_lockfile(&instances_dir, &args.instance_id)?;
+    let plan = crate::snapshot_diff::plan_selective_restore(
+        &instance_dir,
+        &snapshot,
+        &current_lock,
+        &args.entry_keys,
+        &args.paths,
+    )?;
+    let store = crate::snapshot_store::object_store_for(&instance_dir);
+    crate::snapshot_diff::verify_selection_plan(&plan, &store)?;
+
+    let undo = create_instance_snapshot(
+        &instances_dir,
+        &args.instance_id,
+        "before-selective-restore",
+    )?;
+    let (restored_files, removed_files) =
+        crate::snapshot_diff::apply_selection_plan(&instance_dir, &plan, &store)?;
+    write_lockfile(&instances_dir, &args.instance_id, &plan.lock)?;
+    log_instance_event_best_effort(
+        &app,
+        &args.instance_id,
+        "snapshot_selective_restore",
+        format!(
+            "Restored {} lock entr{} and {} file(s) from snapshot '{}' (removed {} file(s)); undo snapshot '{}'.",
+            plan.restored_entries,
+            if plan.restored_entries == 1 { "y" } else { "ies" },
+            restored_files,
+            args.snapshot_id,
+            removed_files,
+            undo.id
+        ),
+    );
+
+    Ok(crate::snapshot_diff::SelectiveRestoreResult {
+        snapshot_id: args.snapshot_id,
+        undo_snapshot_id: undo.id,
+        restored_entries: plan.restored_entries,
+        removed_entries: plan.removed_entries,
+        restored_files,
+        removed_files,
+        message: "Selective restore complete.".to_string(),
+    })
+}
+
 #[tauri::command]
 pub(crate) async fn list_instance_worlds(
     app: tauri::AppHandle,
@@ -1884,12 +2110,39 @@ pub(crate) async fn list_instance_worlds(
     .await
 }
 
+#[tauri::command]
+pub(crate) async fn list_instance_world_backups(
+    app: tauri::AppHandle,
+    args: ListInstanceWorldBackupsArgs,
+) -> Result<Vec<crate::world_backup_store::WorldBackupListing>, String> {
+    run_blocking_task("list instance world backups", move || {
+        let instances_dir = app_instances_dir(&app)?;
+        let instance = find_instance(&instances_dir, &args.instance_id)?;
+        let instance_dir = instance_dir_for_instance(&instances_dir, &instance);
+        let destinations = crate::world_backup_store::world_backup_destinations_for(
+            &read_launcher_settings(&app)?.world_backup_destinations,
+            &instance.settings.world_backup_destinations,
+        );
+        let world_id = args.world_id.as_deref().map(str::trim);
+        let listings = crate::world_backup_store::list_world_backups_with_locations(
+            &instance_dir,
+            &instance.id,
+            &destinations,
+        );
+        Ok(listings
+            .into_iter()
+            .filter(|listing| world_id.map_or(true, |world| listing.meta.world_id == world))
+            .collect())
+    })
+    .await
+}
+
 fn list_instance_worlds_inner(
     app: tauri::AppHandle,
     args: ListInstanceWorldsArgs,
 ) -> Result<Vec<InstanceWorld>, String> {
     let instances_dir = app_instances_dir(&app)?;
-    let _ = find_instance(&instances_dir, &args.instance_id)?;
+    let instance = find_instance(&instances_dir, &args.instance_id)?;
     let instance_dir = instance_dir_for_id(&instances_dir, &args.instance_id)?;
     let saves_dir = instance_dir.join("saves");
     fs::create_dir_all(&saves_dir).map_err(|e| format!("mkdir saves failed: {e}"))?;
@@ -1901,9 +2154,11 @@ fn list_instance_worlds_inner(
         *backup_count_by_world
             .entry(meta.world_id.clone())
             .or_insert(0) += 1;
-        latest_backup_by_world
-            .entry(meta.world_id.clone())
-            .or_insert(meta);
+        if meta.integrity_error.is_none() {
+            latest_backup_by_world
+                .entry(meta.world_id.clone())
+                .or_insert(meta);
+        }
     }
 
     let mut out = Vec::new();
@@ -1919,6 +2174,7 @@ fn list_instance_worlds_inner(
             continue;
         }
         let latest = latest_backup_by_world.get(&name);
+        let metadata = crate::level_dat::read_world_metadata(&path).ok();
         out.push(InstanceWorld {
             id: name.clone(),
             name: name.clone(),
@@ -1926,6 +2182,10 @@ fn list_instance_worlds_inner(
             latest_backup_id: latest.map(|m| m.id.clone()),
             latest_backup_at: latest.map(|m| m.created_at.clone()),
             backup_count: backup_count_by_world.get(&name).copied().unwrap_or(0),
+            newer_than_instance: metadata.as_ref().is_some_and(|meta| {
+                crate::level_dat::saved_by_newer_version(meta, &instance.mc_version)
+            }),
+            metadata,
         });
     }
     out.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
@@ -2043,6 +2303,47 @@ pub(crate) async fn run_storage_cleanup(
                 continue;
             }
 
+            if action == STORAGE_ACTION_GC_CONTENT_STORE {
+                let store = content_store::ContentStore::new(
+                    launcher_dir(&app)?.join(content_store::STORE_DIR_NAME),
+                );
+                let report = store.collect_garbage(&app_instances_dir(&app)?)?;
+                reclaimed_bytes = reclaimed_bytes.saturating_add(report.reclaimed_bytes);
+                actions_run += 1;
+                messages.push(format!(
+                    "Removed {} unreferenced content store file(s) and reclaimed {}; {} still in use.",
+                    report.removed_blobs,
+                    format_storage_size_label(report.reclaimed_bytes),
+                    report.live_blobs
+                ));
+                continue;
+            }
+
+            if action == STORAGE_ACTION_DEDUPE_HARDLINKS {
+                let instances_dir = app_instances_dir(&app)?;
+                let instance_dirs = read_index(&instances_dir)?
+                    .instances
+                    .iter()
+                    .map(|inst| instance_dir_for_instance(&instances_dir, inst))
+                    .collect::<Vec<_>>();
+                let report = crate::hardlink_dedupe::dedupe_instance_content(&instance_dirs);
+                reclaimed_bytes = reclaimed_bytes.saturating_add(report.reclaimed_bytes);
+                actions_run += 1;
+                let mut message = format!(
+                    "Linked {} identical file(s) across instances and reclaimed {}.",
+                    report.linked_files,
+                    format_storage_size_label(report.reclaimed_bytes)
+                );
+                if report.failed_files > 0 {
+                    message.push_str(&format!(
+                        " {} file(s) could not be linked (in use or on another drive).",
+                        report.failed_files
+                    ));
+                }
+                messages.push(message);
+                continue;
+            }
+
             let (action_kind, action_instance_ids) = if let Some(instance_id) =
                 action.strip_prefix(&format!("{}:", STORAGE_ACTION_PRUNE_RUNTIME_SESSIONS))
             {
@@ -2081,32 +2382,48 @@ pub(crate) async fn run_storage_cleanup(
                     .ok_or_else(|| format!("Instance '{instance_id}' was not found"))?;
                 let instance_dir = instance_dir_for_instance(&instances_dir, inst);
                 let settings = normalize_instance_settings(inst.settings.clone());
+                // World backups being written must not lose blobs to the GC below.
+                let backup_lock = crate::world_backup_store::world_backup_lock(&instance_dir);
+                let _backup_guard = if action_kind == STORAGE_ACTION_PRUNE_WORLD_BACKUPS {
+                    Some(
+                        backup_lock
+                            .lock()
+                            .map_err(|_| "world backup lock poisoned".to_string())?,
+                    )
+                } else {
+                    None
+                };
                 let targets = match action_kind {
                     STORAGE_ACTION_PRUNE_RUNTIME_SESSIONS => storage_stale_runtime_session_targets(
                         &instance_dir,
                         Duration::from_secs(STALE_RUNTIME_SESSION_MAX_AGE_HOURS * 3600),
                     )?,
-                    STORAGE_ACTION_PRUNE_SNAPSHOTS => storage_snapshot_cleanup_targets(
+                    STORAGE_ACTION_PRUNE_SNAPSHOTS => snapshot_prune_targets(
                         &instance_dir,
                         settings.snapshot_retention_count as usize,
                         settings.snapshot_max_age_days as i64,
                     )?,
                     STORAGE_ACTION_PRUNE_WORLD_BACKUPS => storage_world_backup_cleanup_targets(
                         &instance_dir,
-                        settings.world_backup_retention_count as usize,
+                        crate::world_backup_store::WorldBackupRetention::from_settings(&settings),
                     )?,
                     _ => Vec::new(),
                 };
                 if targets.is_empty() {
                     continue;
                 }
-                let bytes = targets
+                let mut bytes = targets
                     .iter()
                     .map(|path| dir_total_size_bytes(path))
                     .sum::<u64>();
                 for target in targets {
                     remove_path_if_exists(&target)?;
                 }
+                if action_kind == STORAGE_ACTION_PRUNE_WORLD_BACKUPS {
+                    let gc =
+                        crate::world_backup_store::collect_world_backup_objects(&instance_dir);
+                    bytes = bytes.saturating_add(gc.reclaimed_bytes);
+                }
                 reclaimed_bytes = reclaimed_bytes.saturating_add(bytes);
                 actions_run += 1;
                 let action_label = match action_kind {
@@ -2469,7 +2786,7 @@ pub(crate) fn rollback_instance_world_backup(
     args: RollbackInstanceWorldBackupArgs,
 ) -> Result<WorldRollbackResult, String> {
     let instances_dir = app_instances_dir(&app)?;
-    let _ = find_instance(&instances_dir, &args.instance_id)?;
+    let instance = find_instance(&instances_dir, &args.instance_id)?;
     {
         let guard = state
             .running
@@ -2489,22 +2806,66 @@ pub(crate) fn rollback_instance_world_backup(
         return Err("World ID is required".to_string());
     }
     let instance_dir = instance_dir_for_id(&instances_dir, &args.instance_id)?;
-    let backups = list_world_backups(&instance_dir)?;
+    let destinations = crate::world_backup_store::world_backup_destinations_for(
+        &read_launcher_settings(&app)?.world_backup_destinations,
+        &instance.settings.world_backup_destinations,
+    );
+    let location = args
+        .location
+        .as_deref()
+        .filter(|value| !value.trim().is_empty());
+    let listings = crate::world_backup_store::list_world_backups_with_locations(
+        &instance_dir,
+        &args.instance_id,
+        &destinations,
+    );
     let selected = if let Some(backup_id) = args.backup_id.as_ref() {
-        backups
+        listings
             .into_iter()
-            .find(|b| b.world_id == world_id && b.id == *backup_id)
+            .find(|listing| {
+                listing.meta.world_id == world_id
+                    && listing.meta.id == *backup_id
+                    && location.map_or(true, |wanted| {
+                        listing
+                            .locations
+                            .iter()
+                            .chain(&listing.corrupt_locations)
+                            .any(|held| held == wanted)
+                    })
+            })
+            .map(|listing| listing.meta)
             .ok_or_else(|| "World backup not found".to_string())?
     } else {
-        backups
+        // Defaults only consider copies that passed their last integrity check.
+        let intact = listings
             .into_iter()
-            .find(|b| b.world_id == world_id)
-            .ok_or_else(|| "No world backup found for this world yet".to_string())?
+            .filter(|listing| match location {
+                Some(wanted) => listing.locations.iter().any(|held| held == wanted),
+                None => listing.restorable(),
+            })
+            .map(|listing| listing.meta)
+            .collect::<Vec<_>>();
+        if let Some(at) = args.at.as_deref() {
+            crate::world_backup_store::world_backup_at(intact, world_id, at)?
+        } else {
+            intact
+                .into_iter()
+                .find(|b| b.world_id == world_id)
+                .ok_or_else(|| "No intact world backup found for this world yet".to_string())?
+        }
     };
 
-    let backup_dir = world_backups_dir(&instance_dir).join(&selected.id);
+    let backup_root = crate::world_backup_store::locate_world_backup(
+        &instance_dir,
+        &args.instance_id,
+        &destinations,
+        &selected.id,
+        location,
+    )?;
+    let backup_dir = world_backups_dir(&backup_root).join(&selected.id);
     let world_dir = instance_dir.join("saves").join(world_id);
-    let restored_files = restore_world_backup_zip(&world_backup_zip_path(&backup_dir), &world_dir)?;
+    let restored_files =
+        crate::world_backup_store::restore_world_backup(&backup_root, &backup_dir, &world_dir)?;
     log_instance_event_best_effort(
         &app,
         &args.instance_id,
@@ -2537,6 +2898,76 @@ fn install_discover_content_inner(
         );
     }
 
+    if source == "url" || source == "maven" {
+        return Err(format!(
+            "{} content is installed by URL or coordinate, not from Discover.",
+            if source == "url" { "Direct-URL" } else { "Maven" }
+        ));
+    }
+
+    if source == "team" {
+        let instances_dir = app_instances_dir(&app)?;
+        let instance = find_instance(&instances_dir, &args.instance_id)?;
+        let instance_dir = instance_dir_for_instance(&instances_dir, &instance);
+        let mut lock = read_lockfile(&instances_dir, &args.instance_id)?;
+        let client = build_http_client()?;
+        if let Some(reason) = snapshot_reason {
+            create_preinstall_snapshot_with_event_best_effort(
+                &app,
+                &instances_dir,
+                &args.instance_id,
+                reason,
+            );
+        }
+        emit_install_progress(
+            &app,
+            InstallProgressEvent {
+                instance_id: args.instance_id.clone(),
+                project_id: args.project_id.clone(),
+                stage: "downloading".to_string(),
+                downloaded: 0,
+                total: None,
+                percent: None,
+                message: Some("Fetching from team repository…".to_string()),
+            },
+        );
+        let new_entry = crate::team_repo::install_team_content_inner(
+            &instance,
+            &instance_dir,
+            &mut lock,
+            &client,
+            &args.project_id,
+            args.project_title.as_deref(),
+            &content_type,
+            &args.target_worlds,
+        )?;
+        lock.entries.sort_by_key(|entry| entry.name.to_lowercase());
+        write_lockfile(&instances_dir, &args.instance_id, &lock)?;
+        log_instance_event_best_effort(
+            &app,
+            &args.instance_id,
+            "content_install",
+            format!(
+                "Installed {} '{}' via team repository.",
+                content_type_display_name(&content_type),
+                new_entry.name
+            ),
+        );
+        emit_install_progress(
+            &app,
+            InstallProgressEvent {
+                instance_id: args.instance_id.clone(),
+                project_id: args.project_id.clone(),
+                stage: "completed".to_string(),
+                downloaded: 1,
+                total: Some(1),
+                percent: Some(100.0),
+                message: Some("Team repository install complete".to_string()),
+            },
+        );
+        return Ok(lock_entry_to_installed(&instance_dir, &new_entry));
+    }
+
     if content_type == "mods" {
         if source == "curseforge" {
             return install_curseforge_mod_inner(
@@ -2901,6 +3332,8 @@ pub(crate) async fn install_discover_content(
     args: InstallDiscoverContentArgs,
 ) -> Result<InstalledMod, String> {
     run_blocking_task("install discover content", move || {
+        let mutation_lock = instance_mutation_lock(&args.instance_id);
+        let _guard = mutation_lock.lock()?;
         let subject = snapshot_install_subject(args.project_title.as_deref(), &args.project_id);
         let reason = format!("before-install-discover:{subject}");
         install_discover_content_inner(app, &args, Some(reason.as_str()))
@@ -2908,6 +3341,94 @@ pub(crate) async fn install_discover_content(
     .await
 }
 
+#[tauri::command]
+pub(crate) async fn install_url_content(
+    app: tauri::AppHandle,
+    args: InstallUrlContentArgs,
+) -> Result<InstalledMod, String> {
+    run_blocking_task("install url content", move || {
+        let mutation_lock = instance_mutation_lock(&args.instance_id);
+        let _guard = mutation_lock.lock()?;
+        let instances_dir = app_instances_dir(&app)?;
+        let instance = find_instance(&instances_dir, &args.instance_id)?;
+        let instance_dir = instance_dir_for_instance(&instances_dir, &instance);
+        let mut lock = read_lockfile(&instances_dir, &args.instance_id)?;
+        let client = build_http_client()?;
+        let project_id = crate::url_source::url_project_key(&args.url);
+        let subject = snapshot_install_subject(args.name.as_deref(), &project_id);
+        create_preinstall_snapshot_with_event_best_effort(
+            &app,
+            &instances_dir,
+            &args.instance_id,
+            &format!("before-install-url:{subject}"),
+        );
+        let new_entry =
+            crate::url_source::install_url_content_inner(&instance_dir, &mut lock, &client, &args)?;
+        lock.entries.sort_by_key(|entry| entry.name.to_lowercase());
+        write_lockfile(&instances_dir, &args.instance_id, &lock)?;
+        log_instance_event_best_effort(
+            &app,
+            &args.instance_id,
+            "content_install",
+            format!(
+                "Installed {} '{}' from {} (sha256 {}).",
+                content_type_display_name(&new_entry.content_type),
+                new_entry.name,
+                args.url.trim(),
+                new_entry.hashes.get("sha256").map(String::as_str).unwrap_or("?")
+            ),
+        );
+        Ok(lock_entry_to_installed(&instance_dir, &new_entry))
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn install_maven_content(
+    app: tauri::AppHandle,
+    args: InstallMavenContentArgs,
+) -> Result<InstalledMod, String> {
+    run_blocking_task("install maven content", move || {
+        let mutation_lock = instance_mutation_lock(&args.instance_id);
+        let _guard = mutation_lock.lock()?;
+        let instances_dir = app_instances_dir(&app)?;
+        let instance = find_instance(&instances_dir, &args.instance_id)?;
+        let instance_dir = instance_dir_for_instance(&instances_dir, &instance);
+        let mut lock = read_lockfile(&instances_dir, &args.instance_id)?;
+        let client = build_http_client()?;
+        let subject = snapshot_install_subject(args.name.as_deref(), &args.coordinate);
+        create_preinstall_snapshot_with_event_best_effort(
+            &app,
+            &instances_dir,
+            &args.instance_id,
+            &format!("before-install-maven:{subject}"),
+        );
+        let new_entry = crate::maven_repo::install_maven_content_inner(
+            &instance,
+            &instance_dir,
+            &mut lock,
+            &client,
+            &args,
+        )?;
+        lock.entries.sort_by_key(|entry| entry.name.to_lowercase());
+        write_lockfile(&instances_dir, &args.instance_id, &lock)?;
+        log_instance_event_best_effort(
+            &app,
+            &args.instance_id,
+            "content_install",
+            format!(
+                "Installed {} '{}' {} from Maven ({}).",
+                content_type_display_name(&new_entry.content_type),
+                new_entry.name,
+                new_entry.version_number,
+                args.repository_url.trim()
+            ),
+        );
+        Ok(lock_entry_to_installed(&instance_dir, &new_entry))
+    })
+    .await
+}
+
 #[tauri::command]
 pub(crate) fn preview_preset_apply(
     app: tauri::AppHandle,
@@ -3248,6 +3769,10 @@ fn search_discover_content_inner(
         });
     }
 
+    if selected_sources.len() == 1 && selected_sources[0] == "team" {
+        return crate::team_repo::search_team_discover(&client, &args);
+    }
+
     let mut sub = args.clone();
     sub.offset = 0;
     sub.limit = pool_limit.max(GITHUB_DISCOVER_MIN_RESULT_POOL / 2);
@@ -3255,6 +3780,7 @@ fn search_discover_content_inner(
     let include_modrinth = selected_sources.iter().any(|value| value == "modrinth");
     let include_curseforge = selected_sources.iter().any(|value| value == "curseforge");
     let include_github = selected_sources.iter().any(|value| value == "github");
+    let include_team = selected_sources.iter().any(|value| value == "team");
 
     let (modrinth_hits, modrinth_total) = if include_modrinth {
         gather_provider_hits_with_query_variants(
@@ -3282,8 +3808,18 @@ fn search_discover_content_inner(
             (Vec::new(), 0)
         };
 
+    // A missing or unreachable team repository never fails the combined search.
+    let (team_hits, team_total) = if include_team {
+        crate::team_repo::search_team_discover(&client, &sub)
+            .map(|result| (result.hits, result.total_hits))
+            .unwrap_or_default()
+    } else {
+        (Vec::new(), 0)
+    };
+
     let mut merged = modrinth_hits;
     merge_discover_hits_in_place(&mut merged, curseforge_hits, args.query.trim());
+    merge_discover_hits_in_place(&mut merged, team_hits, args.query.trim());
     let mut github_total = 0usize;
     if include_github && normalized_content_type == "mods" {
         if explicit_source_subset {
@@ -3312,6 +3848,7 @@ fn search_discover_content_inner(
     let mut total_hits = modrinth_total
         .saturating_add(curseforge_total)
         .saturating_add(github_total)
+        .saturating_add(team_total)
         .max(merged.len());
     if source == "all" && normalized_content_type == "mods" {
         total_hits = total_hits.max(merged.len());
@@ -4145,6 +4682,180 @@ fn export_instance_mods_zip_inner(
     })
 }
 
+#[tauri::command]
+pub(crate) async fn export_instance_world(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    args: ExportInstanceWorldArgs,
+) -> Result<crate::world_archive::WorldExportResult, String> {
+    let grant_path = consume_external_path_grant(
+        &state,
+        EXTERNAL_PATH_PURPOSE_WORLD_ARCHIVE_EXPORT,
+        &args.grant_id,
+    )?;
+    run_blocking_task("export instance world", move || {
+        let instances_dir = app_instances_dir(&app)?;
+        let instance = find_instance(&instances_dir, &args.instance_id)?;
+        let world_dir = world_root_dir(&instances_dir, &instance.id, &args.world_id)?;
+        let world_id = args.world_id.trim().to_string();
+        let required_mods = if args.include_mod_list {
+            crate::world_archive::required_mods_from_lock(&read_lockfile(
+                &instances_dir,
+                &instance.id,
+            )?)
+        } else {
+            Vec::new()
+        };
+        let manifest = crate::world_archive::WorldArchiveManifest {
+            format: crate::world_archive::WORLD_ARCHIVE_FORMAT,
+            world_name: world_id.clone(),
+            mc_version: Some(instance.mc_version.clone()),
+            loader: Some(instance.loader.clone()),
+            exported_at: now_iso(),
+            includes_datapacks: args.include_datapacks,
+            required_mods,
+        };
+        let files_count = crate::world_archive::export_world_archive(
+            &world_dir,
+            &grant_path,
+            args.include_datapacks,
+            &manifest,
+        )?;
+        Ok(crate::world_archive::WorldExportResult {
+            output_path: grant_path.display().to_string(),
+            world_id,
+            files_count,
+            required_mods: manifest.required_mods.len(),
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn import_instance_world(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    args: ImportInstanceWorldArgs,
+) -> Result<crate::world_archive::WorldImportResult, String> {
+    let grant_path = consume_external_path_grant(
+        &state,
+        EXTERNAL_PATH_PURPOSE_WORLD_ARCHIVE_IMPORT,
+        &args.grant_id,
+    )?;
+    run_blocking_task("import instance world", move || {
+        let instances_dir = app_instances_dir(&app)?;
+        let instance = find_instance(&instances_dir, &args.instance_id)?;
+        let instance_dir = instance_dir_for_id(&instances_dir, &instance.id)?;
+        let mut result = crate::world_archive::import_world_archive(
+            &grant_path,
+            &instance_dir.join("saves"),
+            args.name.as_deref(),
+        )?;
+        if let Some(manifest) = result.manifest.as_ref() {
+            let lock = read_lockfile(&instances_dir, &instance.id)?;
+            result.missing_mods =
+                crate::world_archive::missing_required_mods(&manifest.required_mods, &lock);
+        }
+        log_instance_event_best_effort(
+            &app,
+            &instance.id,
+            "world_import",
+            format!(
+                "Imported world '{}' ({} files){}.",
+                result.world_id,
+                result.files_count,
+                if result.missing_mods.is_empty() {
+                    String::new()
+                } else {
+                    format!("; {} required mod(s) missing", result.missing_mods.len())
+                }
+            ),
+        );
+        Ok(result)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn trim_instance_world(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    args: TrimInstanceWorldArgs,
+) -> Result<crate::world_trim::WorldTrimReport, String> {
+    {
+        let guard = state
+            .running
+            .lock()
+            .map_err(|_| "lock running instances failed".to_string())?;
+        if guard
+            .values()
+            .any(|entry| entry.meta.instance_id == args.instance_id)
+        {
+            return Err(
+                "Stop the running Minecraft session before trimming this world.".to_string(),
+            );
+        }
+    }
+    run_blocking_task("trim instance world", move || {
+        let instances_dir = app_instances_dir(&app)?;
+        let instance = find_instance(&instances_dir, &args.instance_id)?;
+        let instance_dir = instance_dir_for_id(&instances_dir, &instance.id)?;
+        let world_dir = world_root_dir(&instances_dir, &instance.id, &args.world_id)?;
+        let world_id = args.world_id.trim().to_string();
+        let options = crate::world_trim::WorldTrimOptions {
+            min_inhabited_ticks: args
+                .min_inhabited_seconds
+                .map(|secs| i64::from(secs) * 20)
+                .unwrap_or(crate::world_trim::DEFAULT_MIN_INHABITED_TICKS),
+            protect_radius_chunks: args
+                .protect_radius_chunks
+                .map(|radius| {
+                    radius.min(crate::world_trim::MAX_PROTECT_RADIUS_CHUNKS as u32) as i32
+                })
+                .unwrap_or(crate::world_trim::DEFAULT_PROTECT_RADIUS_CHUNKS),
+            dry_run: args.dry_run,
+        };
+        let backup_id = if args.dry_run {
+            None
+        } else {
+            let settings = read_launcher_settings(&app)?;
+            let instance_settings = normalize_instance_settings(instance.settings.clone());
+            let backup = create_world_backup_for_world(
+                &instance_dir,
+                &instance.id,
+                &world_id,
+                "before-trim",
+                crate::world_backup_store::WorldBackupRetention::from_settings(&instance_settings),
+                &crate::world_backup_store::world_backup_destinations_for(
+                    &settings.world_backup_destinations,
+                    &instance_settings.world_backup_destinations,
+                ),
+            )
+            .map_err(|err| format!("Backup before trimming failed; world left untouched: {err}"))?;
+            Some(backup.id)
+        };
+        let mut report = crate::world_trim::trim_world(&world_dir, &options)?;
+        report.world_id = world_id;
+        report.backup_id = backup_id;
+        if !report.dry_run {
+            log_instance_event_best_effort(
+                &app,
+                &instance.id,
+                "world_trim",
+                format!(
+                    "Trimmed world '{}': removed {} of {} chunks, reclaimed {} bytes.",
+                    report.world_id,
+                    report.chunks_removed,
+                    report.chunks_scanned,
+                    report.bytes_reclaimed
+                ),
+            );
+        }
+        Ok(report)
+    })
+    .await
+}
+
 #[tauri::command]
 pub(crate) fn list_instances(app: tauri::AppHandle) -> Result<Vec<Instance>, String> {
     let dir = app_instances_dir(&app)?;
@@ -4155,6 +4866,15 @@ pub(crate) fn list_instances(app: tauri::AppHandle) -> Result<Vec<Instance>, Str
     Ok(idx.instances)
 }
 
+/// New instances get the same content watcher that startup gives existing
+/// ones. Started after any imported files are copied in, so those are not
+/// adopted as dropped files.
+fn start_content_watcher_best_effort(app: &tauri::AppHandle, instance_id: &str) {
+    if let Err(err) = crate::content_watcher::start_instance_watcher(app, instance_id) {
+        eprintln!("content watcher for '{instance_id}' not started: {err}");
+    }
+}
+
 fn create_instance_internal(
     app: &tauri::AppHandle,
     clean_name: String,
@@ -4241,7 +4961,7 @@ pub(crate) fn create_instance(
         .as_deref()
         .map(|grant_id| consume_external_path_grant(&state, EXTERNAL_PATH_PURPOSE_INSTANCE_ICON, grant_id))
         .transpose()?;
-    create_instance_internal(
+    let instance = create_instance_internal(
         &app,
         clean_name,
         clean_mc,
@@ -4249,7 +4969,9 @@ pub(crate) fn create_instance(
         "custom".to_string(),
         icon_path,
         settings,
-    )
+    )?;
+    start_content_watcher_best_effort(&app, &instance.id);
+    Ok(instance)
 }
 
 #[tauri::command]
@@ -4290,6 +5012,7 @@ pub(crate) fn create_instance_from_modpack_file(
     if imported_files == 0 {
         warnings.push("No override files were found in the archive.".to_string());
     }
+    start_content_watcher_best_effort(&app, &instance.id);
     Ok(CreateInstanceFromModpackFileResult {
         instance,
         imported_files,
@@ -4339,6 +5062,7 @@ pub(crate) fn import_instance_from_launcher(
     let instances_dir = app_instances_dir(&app)?;
     let instance_dir = instance_dir_for_instance(&instances_dir, &instance);
     let imported_files = copy_launcher_source_into_instance(&source_path, &instance_dir)?;
+    start_content_watcher_best_effort(&app, &instance.id);
     Ok(ImportInstanceFromLauncherResult {
         instance,
         imported_files,
@@ -4509,6 +5233,10 @@ pub(crate) fn update_instance(
         != inst.settings.world_backup_interval_minutes
         || prev_inst.settings.world_backup_retention_count
             != inst.settings.world_backup_retention_count
+        || prev_inst.settings.world_backup_keep_hourly != inst.settings.world_backup_keep_hourly
+        || prev_inst.settings.world_backup_keep_daily != inst.settings.world_backup_keep_daily
+        || prev_inst.settings.world_backup_keep_weekly != inst.settings.world_backup_keep_weekly
+        || prev_inst.settings.world_backup_destinations != inst.settings.world_backup_destinations
         || prev_inst.settings.snapshot_retention_count != inst.settings.snapshot_retention_count
         || prev_inst.settings.snapshot_max_age_days != inst.settings.snapshot_max_age_days
     {
@@ -4620,6 +5348,7 @@ pub(crate) fn delete_instance(
         return Err("instance not found".into());
     }
 
+    crate::content_watcher::stop_instance_watcher(&target.id);
     let inst_dir = instance_dir_for_instance(&dir, &target);
     if inst_dir.exists() {
         fs::remove_dir_all(inst_dir).map_err(|e| format!("remove dir failed: {e}"))?;
@@ -4862,6 +5591,9 @@ fn install_modrinth_mod_inner(
                 verification_status: None,
             }],
             local_analysis: None,
+            security_scan: None,
+            url_update: None,
+            github_channel: None,
         };
         validate_lock_entry_uniqueness(&lock, &new_entry)?;
 
@@ -4972,9 +5704,7 @@ pub(crate) async fn install_modrinth_mod(
 ) -> Result<InstalledMod, String> {
     run_blocking_task("install modrinth mod", move || {
         let mutation_lock = instance_mutation_lock(&args.instance_id);
-        let _guard = mutation_lock
-            .lock()
-            .map_err(|_| "instance mutation lock poisoned".to_string())?;
+        let _guard = mutation_lock.lock()?;
         let subject = snapshot_install_subject(args.project_title.as_deref(), &args.project_id);
         let reason = format!("before-install-modrinth:{subject}");
         install_modrinth_mod_inner(app, args, Some(reason.as_str()))
@@ -4989,9 +5719,7 @@ pub(crate) async fn install_curseforge_mod(
 ) -> Result<InstalledMod, String> {
     run_blocking_task("install curseforge mod", move || {
         let mutation_lock = instance_mutation_lock(&args.instance_id);
-        let _guard = mutation_lock
-            .lock()
-            .map_err(|_| "instance mutation lock poisoned".to_string())?;
+        let _guard = mutation_lock.lock()?;
         let subject = snapshot_install_subject(args.project_title.as_deref(), &args.project_id);
         let reason = format!("before-install-curseforge:{subject}");
         install_curseforge_mod_inner(app, args, Some(reason.as_str()))
@@ -5327,17 +6055,48 @@ pub(crate) async fn import_local_mod_file(
         &args.grant_id,
     )?;
     run_blocking_task("import local mod file", move || {
-        import_local_mod_file_inner(app, args, source_path)
+        let mutation_lock = instance_mutation_lock(&args.instance_id);
+        let _guard = mutation_lock.lock()?;
+        let instance_id = args.instance_id.clone();
+        let installed = import_local_mod_file_inner(&app, args, source_path)?;
+        log_instance_event_best_effort(
+            &app,
+            &instance_id,
+            "local_import",
+            format!(
+                "Imported local {} file '{}'.",
+                content_type_display_name(&installed.content_type),
+                installed.name
+            ),
+        );
+        Ok(installed)
     })
     .await
 }
 
+/// Runs a file the content watcher found in an instance folder through the
+/// local import pipeline, so it gets identified and tracked like a picked file.
+pub(crate) fn adopt_dropped_content_file(
+    app: &tauri::AppHandle,
+    instance_id: &str,
+    content_type: &str,
+    path: PathBuf,
+) -> Result<InstalledMod, String> {
+    let args = ImportLocalModFileArgs {
+        instance_id: instance_id.to_string(),
+        grant_id: String::new(),
+        content_type: Some(content_type.to_string()),
+        target_worlds: None,
+    };
+    import_local_mod_file_inner(app, args, path)
+}
+
 fn import_local_mod_file_inner(
-    app: tauri::AppHandle,
+    app: &tauri::AppHandle,
     args: ImportLocalModFileArgs,
     source_path: PathBuf,
 ) -> Result<InstalledMod, String> {
-    let instances_dir = app_instances_dir(&app)?;
+    let instances_dir = app_instances_dir(app)?;
     let instance = find_instance(&instances_dir, &args.instance_id)?;
     let instance_dir = instance_dir_for_id(&instances_dir, &args.instance_id)?;
 
@@ -5474,6 +6233,9 @@ fn import_local_mod_file_inner(
             hashes: found.hashes,
             provider_candidates: detected_provider_candidates,
             local_analysis: local_analysis.clone(),
+            security_scan: None,
+            url_update: None,
+            github_channel: None,
         }
     } else {
         let project_id = format!(
@@ -5504,10 +6266,13 @@ fn import_local_mod_file_inner(
             hashes: HashMap::new(),
             provider_candidates: detected_provider_candidates,
             local_analysis,
+            security_scan: None,
+            url_update: None,
+            github_channel: None,
         }
     };
     validate_lock_entry_uniqueness(&lock, &new_entry)?;
-    write_download_to_content_targets(
+    new_entry.security_scan = write_download_to_content_targets(
         &instance_dir,
         &normalized_content_type,
         &safe_filename,
@@ -5520,16 +6285,6 @@ fn import_local_mod_file_inner(
     lock.entries
         .sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
     write_lockfile(&instances_dir, &args.instance_id, &lock)?;
-    log_instance_event_best_effort(
-        &app,
-        &args.instance_id,
-        "local_import",
-        format!(
-            "Imported local {} file '{}'.",
-            content_type_display_name(&normalized_content_type),
-            new_entry.name
-        ),
-    );
 
     Ok(lock_entry_to_installed(&instance_dir, &new_entry))
 }
@@ -5869,6 +6624,8 @@ pub(crate) async fn resolve_local_mod_sources(
     args: ResolveLocalModSourcesArgs,
 ) -> Result<LocalResolverResult, String> {
     run_blocking_task("resolve local mod sources", move || {
+        let mutation_lock = instance_mutation_lock(&args.instance_id);
+        let _guard = mutation_lock.lock()?;
         let mode = args.mode.unwrap_or_else(|| "missing_only".to_string());
         resolve_local_mod_sources_inner(
             &app,
@@ -5900,13 +6657,15 @@ fn check_instance_content_updates_command_inner(
     let lock = read_lockfile(&instances_dir, &args.instance_id)?;
     let client = build_http_client()?;
     let content_type_filter = normalize_update_content_type_filter(args.content_types.as_deref());
-    check_instance_content_updates_inner(
+    let mut result = check_instance_content_updates_inner(
         &client,
         &instance,
         &lock,
         UpdateScope::AllContent,
         content_type_filter.as_ref(),
-    )
+    )?;
+    result.advisories = crate::advisories::refresh_instance_findings(&app, &instance, &lock);
+    Ok(result)
 }
 
 fn canonical_curseforge_dependency_id(raw: &str) -> Option<String> {
@@ -6152,6 +6911,13 @@ fn update_all_instance_content_inner(
                 None,
             ),
             Err(fast_err) => {
+                if update.source.trim().eq_ignore_ascii_case("curseforge")
+                    && crate::curseforge_handoff::is_distribution_blocked(&fast_err)
+                {
+                    // Queued for a manual download; the slow path would only queue it again.
+                    warnings.push(format!("Skipped update '{}': {fast_err}", update.name));
+                    continue;
+                }
                 if update.source.trim().eq_ignore_ascii_case("curseforge")
                     && error_mentions_forbidden(&fast_err)
                 {
@@ -6235,6 +7001,10 @@ fn update_all_instance_content_inner(
             ),
         );
     }
+    // Match the updated versions so fixed advisories leave the stored report.
+    if let Ok(updated_lock) = read_lockfile(&instances_dir, &args.instance_id) {
+        crate::advisories::refresh_instance_findings(&app, &instance, &updated_lock);
+    }
     Ok(UpdateAllContentResult {
         checked_entries: check.checked_entries,
         updated_entries,
@@ -6250,6 +7020,8 @@ pub(crate) async fn update_all_instance_content(
     args: CheckUpdatesArgs,
 ) -> Result<UpdateAllContentResult, String> {
     run_blocking_task("update all instance content", move || {
+        let mutation_lock = instance_mutation_lock(&args.instance_id);
+        let _guard = mutation_lock.lock()?;
         update_all_instance_content_inner(app, args)
     })
     .await
@@ -6281,6 +7053,7 @@ fn check_modrinth_updates_inner(
         UpdateScope::ModrinthModsOnly,
         None,
     )?;
+    crate::advisories::refresh_instance_findings(&app, &instance, &lock);
     Ok(content_updates_to_modrinth_result(content))
 }
 
@@ -6290,6 +7063,8 @@ pub(crate) async fn update_all_modrinth_mods(
     args: CheckUpdatesArgs,
 ) -> Result<UpdateAllResult, String> {
     run_blocking_task("update all modrinth mods", move || {
+        let mutation_lock = instance_mutation_lock(&args.instance_id);
+        let _guard = mutation_lock.lock()?;
         update_all_modrinth_mods_inner(app, args)
     })
     .await
@@ -6354,6 +7129,10 @@ fn update_all_modrinth_mods_inner(
             format!("Updated {} mod(s) from Modrinth update-all.", updated_mods),
         );
     }
+    // Match the updated versions so fixed advisories leave the stored report.
+    if let Ok(updated_lock) = read_lockfile(&instances_dir, &args.instance_id) {
+        crate::advisories::refresh_instance_findings(&app, &instance, &updated_lock);
+    }
     Ok(UpdateAllResult {
         checked_mods: check.checked_entries,
         updated_mods,
@@ -6465,6 +7244,8 @@ pub(crate) async fn launch_quick_play_server(
             method: args.method.clone(),
             quick_play_host: Some(server.host.clone()),
             quick_play_port: Some(server.port),
+            quick_play_world: None,
+            backup_world_first: false,
         },
     )
     .await?;
@@ -6502,6 +7283,73 @@ pub(crate) async fn launch_instance(
     };
     let quick_play_port = normalize_quick_play_port(args.quick_play_port);
     let quick_play_active = quick_play_host.is_some();
+    let quick_play_world = match args.quick_play_world.as_deref().map(str::trim) {
+        Some(world) if !world.is_empty() => {
+            if quick_play_active {
+                return Err("Quick play can join a server or open a world, not both.".to_string());
+            }
+            Some(world.to_string())
+        }
+        _ => None,
+    };
+    if quick_play_world.is_some() && !supports_world_quick_play(&instance.mc_version) {
+        return Err(format!(
+            "Opening a world directly needs Minecraft 1.20 or newer; this instance runs {}.",
+            instance.mc_version
+        ));
+    }
+    ensure_mod_security_findings_acknowledged(&read_lockfile(&instances_dir, &instance.id)?)?;
+    if let Some(world) = quick_play_world.as_deref() {
+        let world_dir = world_root_dir(&instances_dir, &instance.id, world)?;
+        let newer_version = crate::level_dat::read_world_metadata(&world_dir)
+            .ok()
+            .filter(|meta| crate::level_dat::saved_by_newer_version(meta, &instance.mc_version))
+            .map(|meta| {
+                meta.version_name
+                    .unwrap_or_else(|| "a newer version".to_string())
+            });
+        if let Some(saved_by) = newer_version {
+            if !args.backup_world_first {
+                return Err(format!(
+                    "World '{}' was last saved by Minecraft {}, newer than this instance's {}. Opening it here can corrupt it; launch again with a backup first.",
+                    world, saved_by, instance.mc_version
+                ));
+            }
+            let backup = create_world_backup_for_world(
+                &app_instance_dir,
+                &instance.id,
+                world,
+                "before-downgrade",
+                crate::world_backup_store::WorldBackupRetention::from_settings(&instance_settings),
+                &crate::world_backup_store::world_backup_destinations_for(
+                    &settings.world_backup_destinations,
+                    &instance_settings.world_backup_destinations,
+                ),
+            )
+            .map_err(|err| {
+                format!(
+                    "Backup of world '{}' failed; launch skipped: {}",
+                    world, err
+                )
+            })?;
+            log_instance_event_best_effort(
+                &app,
+                &instance.id,
+                "world_backup",
+                format!(
+                    "Backed up world '{}' ({}) before opening it in Minecraft {}; it was last saved by {}.",
+                    world, backup.id, instance.mc_version, saved_by
+                ),
+            );
+        }
+    }
+    let launch_snapshot_id = match launch_content_snapshot(&instances_dir, &instance.id) {
+        Ok(id) => Some(id),
+        Err(err) => {
+            eprintln!("launch snapshot failed for '{}': {err}", instance.id);
+            None
+        }
+    };
     clear_launch_cancel_request(&state, &instance.id)?;
     if let Err(err) = mark_instance_launch_triggered(&instances_dir, &instance.id) {
         eprintln!(
@@ -6587,6 +7435,7 @@ pub(crate) async fn launch_instance(
                 } else {
                     None
                 },
+                quick_play_world.as_deref(),
             )?;
             clear_launch_cancel_request(&state, &instance.id)?;
 
@@ -6933,6 +7782,8 @@ pub(crate) async fn launch_instance(
             if let Some(host) = quick_play_host.as_ref() {
                 command.arg("--server").arg(host);
                 command.arg("--port").arg(quick_play_port.to_string());
+            } else if let Some(world) = quick_play_world.as_ref() {
+                command.arg("--quickPlaySingleplayer").arg(world);
             }
             command.stdout(Stdio::from(launch_log_file));
             command.stderr(Stdio::from(launch_log_file_err));
@@ -6979,6 +7830,7 @@ pub(crate) async fn launch_instance(
                 &launch_id,
                 pid,
                 use_isolated_runtime_session,
+                launch_snapshot_id.clone(),
             ) {
                 eprintln!(
                     "playtime active session registration failed for '{}': {}",
@@ -6990,9 +7842,13 @@ pub(crate) async fn launch_instance(
             let close_launcher_on_exit = instance_settings.close_launcher_on_game_exit;
             let world_backup_interval_secs =
                 u64::from(instance_settings.world_backup_interval_minutes.clamp(5, 15)) * 60;
-            let world_backup_retention_count =
-                usize::try_from(instance_settings.world_backup_retention_count.clamp(1, 2))
-                    .unwrap_or(1);
+            let world_backup_retention =
+                crate::world_backup_store::WorldBackupRetention::from_settings(&instance_settings);
+            let world_backup_destinations =
+                crate::world_backup_store::world_backup_destinations_for(
+                    &settings.world_backup_destinations,
+                    &instance_settings.world_backup_destinations,
+                );
             let log_path_text = launch_log_path.display().to_string();
             let running_meta = RunningInstance {
                 launch_id: launch_id.clone(),
@@ -7046,7 +7902,8 @@ pub(crate) async fn launch_instance(
             let keep_launcher_open_for_thread = keep_launcher_open;
             let close_launcher_on_exit_for_thread = close_launcher_on_exit;
             let world_backup_interval_secs_for_thread = world_backup_interval_secs;
-            let world_backup_retention_count_for_thread = world_backup_retention_count;
+            let world_backup_retention_for_thread = world_backup_retention;
+            let world_backup_destinations_for_thread = world_backup_destinations;
             let run_world_backups_for_thread = !use_isolated_runtime_session;
             let runtime_session_cleanup_for_thread = runtime_session_cleanup_dir.clone();
             let app_instance_dir_for_thread = app_instance_dir.clone();
@@ -7062,7 +7919,8 @@ pub(crate) async fn launch_instance(
                             &instances_dir_for_thread,
                             &instance_id_for_thread,
                             "auto-world-backup",
-                            world_backup_retention_count_for_thread,
+                            world_backup_retention_for_thread,
+                            &world_backup_destinations_for_thread,
                         );
                         next_world_backup_at = Instant::now()
                             + Duration::from_secs(world_backup_interval_secs_for_thread);
@@ -7204,6 +8062,8 @@ pub(crate) async fn launch_instance(
                         "Native launch started (quick join {}:{}).",
                         host, quick_play_port
                     )
+                } else if let Some(world) = quick_play_world.as_ref() {
+                    format!("Native launch started (opening world '{}').", world)
                 } else {
                     "Native launch started.".to_string()
                 },
@@ -7734,6 +8594,60 @@ pub(crate) fn preflight_launch_compatibility(
             blocking: signal.blocking,
         });
     }
+    let flagged_security = lock
+        .entries
+        .iter()
+        .filter(|entry| entry.enabled && lock_entry_security_unacknowledged(entry))
+        .collect::<Vec<_>>();
+    if !flagged_security.is_empty() {
+        let preview = flagged_security
+            .iter()
+            .take(4)
+            .map(|entry| {
+                let top = entry
+                    .security_scan
+                    .as_ref()
+                    .and_then(|scan| scan.findings.first())
+                    .map(|finding| format!("{} ({})", finding.title, finding.severity))
+                    .unwrap_or_default();
+                format!("{}: {}", entry.name, top)
+            })
+            .collect::<Vec<_>>()
+            .join("; ");
+        items.push(LaunchCompatibilityItem {
+            code: "SECURITY_SCAN_UNACKNOWLEDGED".to_string(),
+            title: "Malware scan flagged enabled mods".to_string(),
+            message: format!(
+                "{} enabled mod(s) have scan findings that were not acknowledged: {preview}. Review and acknowledge them, or remove the mods.",
+                flagged_security.len()
+            ),
+            severity: "blocker".to_string(),
+            blocking: true,
+        });
+    }
+    if settings.verify_lock_hashes_before_launch {
+        let integrity = verify_lockfile_integrity(&args.instance_id, &instance_dir, &lock);
+        if integrity.modified > 0 {
+            let preview = integrity
+                .items
+                .iter()
+                .filter(|item| item.status == "modified")
+                .take(4)
+                .map(|item| item.filename.clone())
+                .collect::<Vec<_>>()
+                .join(", ");
+            items.push(LaunchCompatibilityItem {
+                code: "LOCKFILE_HASH_MISMATCH".to_string(),
+                title: "Installed files differ from the lockfile".to_string(),
+                message: format!(
+                    "{} enabled file(s) no longer match the hash recorded at install: {preview}. Re-download them from their provider or turn off pre-launch hash verification.",
+                    integrity.modified
+                ),
+                severity: "blocker".to_string(),
+                blocking: true,
+            });
+        }
+    }
     if missing_enabled_mods > 0 {
         items.push(LaunchCompatibilityItem {
             code: "MISSING_ENABLED_MOD_FILES".to_string(),
@@ -7854,6 +8768,71 @@ pub(crate) fn preflight_launch_compatibility(
         });
     }
 
+    let mixin_conflicts = crate::jar_analysis::mixins::detect_mixin_conflicts(
+        &crate::jar_analysis::mixins::collect_enabled_mod_mixins(&lock, &instance_dir),
+    );
+    let shared_mixin_classes = mixin_conflicts
+        .iter()
+        .map(|conflict| conflict.class_name.as_str())
+        .collect::<HashSet<_>>();
+    if !shared_mixin_classes.is_empty() {
+        let preview = mixin_conflicts
+            .iter()
+            .filter(|conflict| conflict.method.is_none())
+            .take(3)
+            .map(|conflict| format!("{} [{}]", conflict.class_name, conflict.mods.join(", ")))
+            .collect::<Vec<_>>();
+        items.push(LaunchCompatibilityItem {
+            code: "MIXIN_SHARED_TARGETS".to_string(),
+            title: "Several mods modify the same classes".to_string(),
+            message: if preview.is_empty() {
+                format!(
+                    "{} class(es) are targeted by mixins from more than one enabled mod.",
+                    shared_mixin_classes.len()
+                )
+            } else {
+                format!(
+                    "{} class(es) are targeted by mixins from more than one enabled mod, e.g. {}.",
+                    shared_mixin_classes.len(),
+                    preview.join(" | ")
+                )
+            },
+            severity: "info".to_string(),
+            blocking: false,
+        });
+    }
+    let risky_mixin_methods = mixin_conflicts
+        .iter()
+        .filter(|conflict| conflict.method.is_some() && conflict.risk != "low")
+        .collect::<Vec<_>>();
+    if !risky_mixin_methods.is_empty() {
+        let preview = risky_mixin_methods
+            .iter()
+            .take(3)
+            .map(|conflict| {
+                format!(
+                    "{}.{} ({}: {}; {})",
+                    conflict.class_name,
+                    conflict.method.as_deref().unwrap_or_default(),
+                    conflict.risk,
+                    conflict.kinds.join("+"),
+                    conflict.mods.join(", ")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(" | ");
+        items.push(LaunchCompatibilityItem {
+            code: "MIXIN_METHOD_CONFLICTS".to_string(),
+            title: "Mods patch the same methods in clash-prone ways".to_string(),
+            message: format!(
+                "{} method(s) are overwritten, redirected or modified by more than one enabled mod (highest risk first): {preview}. If launch fails with a mixin error, disable one mod of the pair.",
+                risky_mixin_methods.len()
+            ),
+            severity: "warning".to_string(),
+            blocking: false,
+        });
+    }
+
     let unresolved_local_entries = lock
         .entries
         .iter()
@@ -7871,6 +8850,34 @@ pub(crate) fn preflight_launch_compatibility(
         });
     }
 
+    let newer_worlds =
+        crate::level_dat::worlds_newer_than(&instance_dir.join("saves"), &instance.mc_version);
+    if !newer_worlds.is_empty() {
+        let preview = newer_worlds
+            .iter()
+            .take(4)
+            .map(|(world, meta)| {
+                format!(
+                    "{} ({})",
+                    world,
+                    meta.version_name.as_deref().unwrap_or("unknown version")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        items.push(LaunchCompatibilityItem {
+            code: "WORLDS_NEWER_THAN_INSTANCE".to_string(),
+            title: "Worlds were saved by a newer Minecraft".to_string(),
+            message: format!(
+                "{} world(s) were last saved by a newer version than {}: {preview}. Opening them here can corrupt chunks; back them up first.",
+                newer_worlds.len(),
+                instance.mc_version
+            ),
+            severity: "warning".to_string(),
+            blocking: false,
+        });
+    }
+
     if let Ok(store) = friend_link::store::read_store(&app) {
         if let Some(session) = friend_link::store::get_session(&store, &args.instance_id) {
             if !session.pending_conflicts.is_empty() {
@@ -7911,6 +8918,7 @@ pub(crate) fn preflight_launch_compatibility(
         items,
         permissions: permission_eval.checklist,
         mic_requirement: permission_eval.mic_requirement,
+        mixin_conflicts,
     })
 }
 
@@ -8066,6 +9074,8 @@ pub(crate) fn list_installed_mods(
     app: tauri::AppHandle,
     args: ListInstalledModsArgs,
 ) -> Result<Vec<InstalledMod>, String> {
+    let mutation_lock = instance_mutation_lock(&args.instance_id);
+    let _guard = mutation_lock.lock()?;
     let instances_dir = app_instances_dir(&app)?;
     let _ = find_instance(&instances_dir, &args.instance_id)?;
     let instance_dir = instance_dir_for_id(&instances_dir, &args.instance_id)?;
@@ -8142,6 +9152,8 @@ pub(crate) fn prune_missing_installed_entries(
     app: tauri::AppHandle,
     args: PruneMissingInstalledEntriesArgs,
 ) -> Result<PruneMissingInstalledEntriesResult, String> {
+    let mutation_lock = instance_mutation_lock(&args.instance_id);
+    let _guard = mutation_lock.lock()?;
     let instances_dir = app_instances_dir(&app)?;
     let _ = find_instance(&instances_dir, &args.instance_id)?;
     let instance_dir = instance_dir_for_id(&instances_dir, &args.instance_id)?;
@@ -8192,6 +9204,8 @@ pub(crate) fn set_installed_mod_enabled(
     app: tauri::AppHandle,
     args: SetInstalledModEnabledArgs,
 ) -> Result<InstalledMod, String> {
+    let mutation_lock = instance_mutation_lock(&args.instance_id);
+    let _guard = mutation_lock.lock()?;
     let instances_dir = app_instances_dir(&app)?;
     let _ = find_instance(&instances_dir, &args.instance_id)?;
     let instance_dir = instance_dir_for_id(&instances_dir, &args.instance_id)?;
@@ -8326,6 +9340,8 @@ pub(crate) fn set_installed_mod_pin(
     app: tauri::AppHandle,
     args: SetInstalledModPinArgs,
 ) -> Result<InstalledMod, String> {
+    let mutation_lock = instance_mutation_lock(&args.instance_id);
+    let _guard = mutation_lock.lock()?;
     let instances_dir = app_instances_dir(&app)?;
     let _ = find_instance(&instances_dir, &args.instance_id)?;
     let instance_dir = instance_dir_for_id(&instances_dir, &args.instance_id)?;
@@ -8372,15 +9388,489 @@ pub(crate) fn set_installed_mod_pin(
     Ok(lock_entry_to_installed(&instance_dir, &lock.entries[idx]))
 }
 
+#[tauri::command]
+pub(crate) fn set_installed_mod_github_channel(
+    app: tauri::AppHandle,
+    args: SetInstalledModGithubChannelArgs,
+) -> Result<InstalledMod, String> {
+    let mutation_lock = instance_mutation_lock(&args.instance_id);
+    let _guard = mutation_lock.lock()?;
+    let instances_dir = app_instances_dir(&app)?;
+    let _ = find_instance(&instances_dir, &args.instance_id)?;
+    let instance_dir = instance_dir_for_id(&instances_dir, &args.instance_id)?;
+    let mut lock = read_lockfile(&instances_dir, &args.instance_id)?;
+
+    let idx = find_lock_entry_index(
+        &lock,
+        &args.version_id,
+        args.content_type.as_deref(),
+        args.filename.as_deref(),
+    )?;
+    let next_channel = github_channels::normalize_github_channel(args.channel)?;
+    let entry = &mut lock.entries[idx];
+    if !entry.source.trim().eq_ignore_ascii_case("github") {
+        return Err("Update channels are only available for GitHub content".to_string());
+    }
+    if next_channel.is_some() && normalize_lock_content_type(&entry.content_type) != "mods" {
+        return Err("GitHub update channels only apply to mods".to_string());
+    }
+    if entry.github_channel != next_channel {
+        entry.github_channel = next_channel;
+        write_lockfile(&instances_dir, &args.instance_id, &lock)?;
+        let entry = &lock.entries[idx];
+        log_instance_event_best_effort(
+            &app,
+            &args.instance_id,
+            "content_channel",
+            format!(
+                "GitHub channel for '{}' set to {}.",
+                entry.name,
+                entry
+                    .github_channel
+                    .as_ref()
+                    .map(|channel| match channel.branch.as_deref() {
+                        Some(branch) => format!("{} ({branch})", channel.kind),
+                        None => channel.kind.clone(),
+                    })
+                    .unwrap_or_else(|| "default".to_string())
+            ),
+        );
+    }
+
+    Ok(lock_entry_to_installed(&instance_dir, &lock.entries[idx]))
+}
+
+#[tauri::command]
+pub(crate) fn acknowledge_installed_mod_security(
+    app: tauri::AppHandle,
+    args: AcknowledgeInstalledModSecurityArgs,
+) -> Result<InstalledMod, String> {
+    let mutation_lock = instance_mutation_lock(&args.instance_id);
+    let _guard = mutation_lock.lock()?;
+    let instances_dir = app_instances_dir(&app)?;
+    let _ = find_instance(&instances_dir, &args.instance_id)?;
+    let instance_dir = instance_dir_for_id(&instances_dir, &args.instance_id)?;
+    let mut lock = read_lockfile(&instances_dir, &args.instance_id)?;
+
+    let idx = find_lock_entry_index(
+        &lock,
+        &args.version_id,
+        args.content_type.as_deref(),
+        args.filename.as_deref(),
+    )?;
+
+    let entry = &mut lock.entries[idx];
+    let Some(scan) = entry.security_scan.as_mut() else {
+        return Err("This entry has not been scanned.".to_string());
+    };
+    if !scan.findings.is_empty() && scan.acknowledged_at.is_none() {
+        scan.acknowledged_at = Some(now_iso());
+        let count = scan.findings.len();
+        write_lockfile(&instances_dir, &args.instance_id, &lock)?;
+        log_instance_event_best_effort(
+            &app,
+            &args.instance_id,
+            "security_ack",
+            format!(
+                "Acknowledged {count} malware scan finding(s) for '{}'.",
+                lock.entries[idx].name
+            ),
+        );
+    }
+
+    Ok(lock_entry_to_installed(&instance_dir, &lock.entries[idx]))
+}
+
+#[tauri::command]
+pub(crate) async fn scan_instance_content_security(
+    app: tauri::AppHandle,
+    args: ScanInstanceContentSecurityArgs,
+) -> Result<InstanceSecurityScanReport, String> {
+    run_blocking_task("scan instance content security", move || {
+        let mutation_lock = instance_mutation_lock(&args.instance_id);
+        let _guard = mutation_lock.lock()?;
+        let instances_dir = app_instances_dir(&app)?;
+        let _ = find_instance(&instances_dir, &args.instance_id)?;
+        let instance_dir = instance_dir_for_id(&instances_dir, &args.instance_id)?;
+        let mut lock = read_lockfile(&instances_dir, &args.instance_id)?;
+        let mut flagged = Vec::new();
+        let mut scanned_files = 0usize;
+        let mut unscanned = Vec::new();
+        let mut tracked_paths = HashSet::new();
+
+        for entry in lock.entries.iter_mut() {
+            if normalize_lock_content_type(&entry.content_type) != "mods" {
+                continue;
+            }
+            let Some(path) = local_entry_file_read_path(&instance_dir, entry)? else {
+                continue;
+            };
+            tracked_paths.insert(path.clone());
+            let bytes = match fs::read(&path) {
+                Ok(bytes) => bytes,
+                Err(err) => {
+                    unscanned.push(format!("{}: {err}", entry.filename));
+                    continue;
+                }
+            };
+            scanned_files += 1;
+            let scan =
+                carry_security_acknowledgement(entry.security_scan.as_ref(), scan_mod_jar_security(&bytes));
+            if !scan.findings.is_empty() {
+                flagged.push(InstanceSecurityScanItem {
+                    filename: entry.filename.clone(),
+                    name: entry.name.clone(),
+                    enabled: entry.enabled,
+                    tracked: true,
+                    acknowledged: scan.acknowledged_at.is_some(),
+                    findings: scan.findings.clone(),
+                });
+            }
+            entry.security_scan = Some(scan);
+        }
+
+        // Jars dropped into mods/ by hand are scanned too, but have no lock entry to
+        // carry findings, so they are only reported.
+        if let Ok(read_dir) = fs::read_dir(instance_dir.join("mods")) {
+            for item in read_dir.flatten() {
+                let path = item.path();
+                let file_name = item.file_name().to_string_lossy().to_string();
+                let lower = file_name.to_ascii_lowercase();
+                if !path.is_file()
+                    || tracked_paths.contains(&path)
+                    || !(lower.ends_with(".jar") || lower.ends_with(".jar.disabled"))
+                {
+                    continue;
+                }
+                let bytes = match fs::read(&path) {
+                    Ok(bytes) => bytes,
+                    Err(err) => {
+                        unscanned.push(format!("{file_name}: {err}"));
+                        continue;
+                    }
+                };
+                scanned_files += 1;
+                let findings = jar_analysis::malware::scan_jar_bytes(&bytes);
+                if !findings.is_empty() {
+                    flagged.push(InstanceSecurityScanItem {
+                        name: file_name.trim_end_matches(".disabled").to_string(),
+                        enabled: !lower.ends_with(".disabled"),
+                        filename: file_name,
+                        tracked: false,
+                        acknowledged: false,
+                        findings,
+                    });
+                }
+            }
+        }
+
+        write_lockfile(&instances_dir, &args.instance_id, &lock)?;
+        let unacknowledged_count = flagged.iter().filter(|item| !item.acknowledged).count();
+        log_instance_event_best_effort(
+            &app,
+            &args.instance_id,
+            "security_scan",
+            format!(
+                "Malware scan checked {scanned_files} jar(s): {} flagged, {unacknowledged_count} awaiting acknowledgement, {} unreadable.",
+                flagged.len(),
+                unscanned.len()
+            ),
+        );
+        Ok(InstanceSecurityScanReport {
+            instance_id: args.instance_id,
+            scanned_at: now_iso(),
+            scanned_files,
+            unacknowledged_count,
+            flagged,
+            unscanned,
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn verify_instance_lockfile(
+    app: tauri::AppHandle,
+    args: VerifyInstanceLockfileArgs,
+) -> Result<LockfileIntegrityReport, String> {
+    run_blocking_task("verify instance lockfile", move || {
+        let instances_dir = app_instances_dir(&app)?;
+        let _ = find_instance(&instances_dir, &args.instance_id)?;
+        let instance_dir = instance_dir_for_id(&instances_dir, &args.instance_id)?;
+        let lock = read_lockfile(&instances_dir, &args.instance_id)?;
+        let report = verify_lockfile_integrity(&args.instance_id, &instance_dir, &lock);
+        if report.modified > 0 || report.missing > 0 {
+            log_instance_event_best_effort(
+                &app,
+                &args.instance_id,
+                "integrity_check",
+                format!(
+                    "Lockfile verification checked {} entries: {} modified, {} missing, {} without a recorded hash.",
+                    report.checked_entries, report.modified, report.missing, report.no_hash
+                ),
+            );
+        }
+        Ok(report)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn redownload_modified_lock_entries(
+    app: tauri::AppHandle,
+    args: RedownloadModifiedEntriesArgs,
+) -> Result<LockEntryRedownloadResult, String> {
+    run_blocking_task("redownload modified lock entries", move || {
+        let mutation_lock = instance_mutation_lock(&args.instance_id);
+        let _guard = mutation_lock.lock()?;
+        let instances_dir = app_instances_dir(&app)?;
+        let _ = find_instance(&instances_dir, &args.instance_id)?;
+        let instance_dir = instance_dir_for_id(&instances_dir, &args.instance_id)?;
+        let mut lock = read_lockfile(&instances_dir, &args.instance_id)?;
+        let selected = args
+            .version_ids
+            .map(|ids| ids.into_iter().collect::<HashSet<_>>());
+        let client = build_http_client()?;
+        let mut repaired = Vec::new();
+        let mut warnings = Vec::new();
+
+        for entry in lock.entries.iter_mut() {
+            if !entry.enabled {
+                continue;
+            }
+            if let Some(selected) = selected.as_ref() {
+                if !selected.contains(&entry.version_id) {
+                    continue;
+                }
+            }
+            let item = verify_lock_entry_integrity(&instance_dir, entry);
+            if item.status != "modified" && item.status != "missing" {
+                continue;
+            }
+            if !item.redownloadable {
+                warnings.push(format!(
+                    "{}: source '{}' cannot be re-downloaded automatically.",
+                    entry.filename, entry.source
+                ));
+                continue;
+            }
+            let bytes = match crate::friend_link::download_launcher_lock_entry_bytes(
+                &client,
+                entry,
+                &instance_dir,
+            ) {
+                Ok(Some(bytes)) => bytes,
+                Ok(None) => {
+                    warnings.push(format!(
+                        "{}: provider did not return the pinned file.",
+                        entry.filename
+                    ));
+                    continue;
+                }
+                Err(err) => {
+                    warnings.push(format!("{}: {err}", entry.filename));
+                    continue;
+                }
+            };
+            if let Some((algorithm, expected)) = lock_entry_expected_hash(entry) {
+                if hash_bytes_hex(algorithm, &bytes) != expected {
+                    warnings.push(format!(
+                        "{}: downloaded file does not match the recorded {algorithm}.",
+                        entry.filename
+                    ));
+                    continue;
+                }
+            }
+            let scan = write_download_to_content_targets(
+                &instance_dir,
+                &entry.content_type,
+                &entry.filename,
+                &entry.target_worlds,
+                &bytes,
+            )?;
+            if let Some(scan) = scan {
+                entry.security_scan = Some(carry_security_acknowledgement(
+                    entry.security_scan.as_ref(),
+                    scan,
+                ));
+            }
+            repaired.push(entry.filename.clone());
+        }
+
+        if !repaired.is_empty() {
+            write_lockfile(&instances_dir, &args.instance_id, &lock)?;
+            log_instance_event_best_effort(
+                &app,
+                &args.instance_id,
+                "integrity_repair",
+                format!(
+                    "Re-downloaded {} file(s) that no longer matched the lockfile.",
+                    repaired.len()
+                ),
+            );
+        }
+        Ok(LockEntryRedownloadResult {
+            instance_id: args.instance_id,
+            repaired,
+            warnings,
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn rehydrate_instance_content(
+    app: tauri::AppHandle,
+    args: RehydrateInstanceContentArgs,
+) -> Result<crate::rehydrate::InstanceRehydrateReport, String> {
+    run_blocking_task("rehydrate instance content", move || {
+        let mutation_lock = instance_mutation_lock(&args.instance_id);
+        let _guard = mutation_lock.lock()?;
+        let instances_dir = app_instances_dir(&app)?;
+        let _ = find_instance(&instances_dir, &args.instance_id)?;
+        let instance_dir = instance_dir_for_id(&instances_dir, &args.instance_id)?;
+        let mut lock = read_lockfile(&instances_dir, &args.instance_id)?;
+        let client = if args.offline {
+            None
+        } else {
+            Some(build_http_client()?)
+        };
+        let store = crate::content_store::ContentStore::configured();
+        let report = crate::rehydrate::rehydrate_instance_content(
+            &args.instance_id,
+            &instance_dir,
+            &mut lock,
+            store.as_ref(),
+            client.as_ref(),
+        );
+        if report.restored > 0 {
+            write_lockfile(&instances_dir, &args.instance_id, &lock)?;
+        }
+        if report.restored > 0 || report.present < lock.entries.len() {
+            log_instance_event_best_effort(
+                &app,
+                &args.instance_id,
+                "content_rehydrate",
+                format!(
+                    "Rehydrated {} file(s) from the lockfile{}: {} need network access, {} unavailable, {} failed.",
+                    report.restored,
+                    if report.offline { " offline" } else { "" },
+                    report.needs_network,
+                    report.unavailable,
+                    report.failed
+                ),
+            );
+        }
+        Ok(report)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) fn set_instance_content_watcher(
+    app: tauri::AppHandle,
+    args: SetInstanceContentWatcherArgs,
+) -> Result<Vec<String>, String> {
+    if args.enabled {
+        crate::content_watcher::start_instance_watcher(&app, &args.instance_id)?;
+    } else {
+        crate::content_watcher::stop_instance_watcher(&args.instance_id);
+    }
+    Ok(crate::content_watcher::watched_instance_ids())
+}
+
+#[tauri::command]
+pub(crate) fn list_content_watchers() -> Vec<String> {
+    crate::content_watcher::watched_instance_ids()
+}
+
+#[tauri::command]
+pub(crate) fn list_curseforge_handoff_items(
+    app: tauri::AppHandle,
+    args: ListCurseforgeHandoffItemsArgs,
+) -> Result<Vec<crate::curseforge_handoff::CurseforgeHandoffItem>, String> {
+    crate::curseforge_handoff::list_handoff_items(&app, args.instance_id.as_deref())
+}
+
+#[tauri::command]
+pub(crate) async fn scan_curseforge_handoff_folder(
+    app: tauri::AppHandle,
+) -> Result<crate::curseforge_handoff::CurseforgeHandoffScanResult, String> {
+    run_blocking_task("scan curseforge handoff folder", move || {
+        crate::curseforge_handoff::scan_handoff_folder(&app)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) fn dismiss_curseforge_handoff_item(
+    app: tauri::AppHandle,
+    args: DismissCurseforgeHandoffItemArgs,
+) -> Result<bool, String> {
+    let instances_dir = app_instances_dir(&app)?;
+    let instance_dir = instance_dir_for_id(&instances_dir, &args.instance_id)?;
+    crate::curseforge_handoff::dismiss_handoff_item(&instance_dir, &args.project_id)
+}
+
+#[tauri::command]
+pub(crate) fn get_security_advisory_status(
+    app: tauri::AppHandle,
+) -> Result<crate::advisories::AdvisoryStatus, String> {
+    Ok(crate::advisories::advisory_status(&app))
+}
+
+#[tauri::command]
+pub(crate) async fn run_security_advisory_scan(
+    app: tauri::AppHandle,
+) -> Result<crate::advisories::AdvisoryScanReport, String> {
+    run_blocking_task("run security advisory scan", move || {
+        crate::advisories::scan_all_instances(&app, "manual")
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) fn import_security_advisory_bundle(
+    app: tauri::AppHandle,
+    state: tauri::State<AppState>,
+    args: ImportSecurityAdvisoryBundleArgs,
+) -> Result<crate::advisories::AdvisoryStatus, String> {
+    let path = consume_external_path_grant(
+        &state,
+        EXTERNAL_PATH_PURPOSE_ADVISORY_BUNDLE_IMPORT,
+        &args.grant_id,
+    )?;
+    let raw =
+        fs::read_to_string(&path).map_err(|e| format!("read advisory bundle failed: {e}"))?;
+    crate::advisories::import_advisory_bundle(&app, &raw)
+}
+
+#[tauri::command]
+pub(crate) async fn verify_backups(
+    app: tauri::AppHandle,
+    args: VerifyBackupsArgs,
+) -> Result<crate::backup_verify::BackupVerifyReport, String> {
+    run_blocking_task("verify backups", move || {
+        let instance_id = args.instance_id.as_deref().map(str::trim);
+        crate::backup_verify::verify_backups(&app, instance_id, "manual")
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) fn get_backup_verify_report(
+    app: tauri::AppHandle,
+) -> Result<Option<crate::backup_verify::BackupVerifyReport>, String> {
+    Ok(crate::backup_verify::read_last_backup_verify_report(&app))
+}
+
 #[tauri::command]
 pub(crate) fn set_installed_mod_provider(
     app: tauri::AppHandle,
     args: SetInstalledModProviderArgs,
 ) -> Result<InstalledMod, String> {
     let mutation_lock = instance_mutation_lock(&args.instance_id);
-    let _guard = mutation_lock
-        .lock()
-        .map_err(|_| "instance mutation lock poisoned".to_string())?;
+    let _guard = mutation_lock.lock()?;
     let instances_dir = app_instances_dir(&app)?;
     let _ = find_instance(&instances_dir, &args.instance_id)?;
     let instance_dir = instance_dir_for_id(&instances_dir, &args.instance_id)?;
@@ -8555,9 +10045,7 @@ pub(crate) fn attach_installed_mod_github_repo(
     args: AttachInstalledModGithubRepoArgs,
 ) -> Result<InstalledMod, String> {
     let mutation_lock = instance_mutation_lock(&args.instance_id);
-    let _guard = mutation_lock
-        .lock()
-        .map_err(|_| "instance mutation lock poisoned".to_string())?;
+    let _guard = mutation_lock.lock()?;
     let instances_dir = app_instances_dir(&app)?;
     let _instance = find_instance(&instances_dir, &args.instance_id)?;
     let instance_dir = instance_dir_for_id(&instances_dir, &args.instance_id)?;
@@ -8733,9 +10221,7 @@ pub(crate) fn remove_installed_mod(
     args: RemoveInstalledModArgs,
 ) -> Result<InstalledMod, String> {
     let mutation_lock = instance_mutation_lock(&args.instance_id);
-    let _guard = mutation_lock
-        .lock()
-        .map_err(|_| "instance mutation lock poisoned".to_string())?;
+    let _guard = mutation_lock.lock()?;
     let instances_dir = app_instances_dir(&app)?;
     let _ = find_instance(&instances_dir, &args.instance_id)?;
     let instance_dir = instance_dir_for_id(&instances_dir, &args.instance_id)?;
@@ -8851,6 +10337,9 @@ mod tests {
             hashes: HashMap::new(),
             provider_candidates: vec![],
             local_analysis: None,
+            security_scan: None,
+            url_update: None,
+            github_channel: None,
         }
     }
 
@@ -8900,6 +10389,9 @@ mod tests {
                 verification_status: None,
             }],
             local_analysis: None,
+            security_scan: None,
+            url_update: None,
+            github_channel: None,
         }
     }
 
@@ -9066,6 +10558,58 @@ mod tests {
         let _ = fs::remove_dir_all(&instance_dir);
     }
 
+    #[test]
+    fn instance_mutation_lock_is_reentrant_and_exclusive() {
+        let lock = instance_mutation_lock("inst_mutation_lock_test");
+        let outer = lock.lock().expect("outer guard");
+        let inner = lock.lock().expect("nested guard on the same thread");
+        let other = {
+            let lock = lock.clone();
+            std::thread::spawn(move || {
+                let _guard = lock.lock().expect("guard on another thread");
+                Instant::now()
+            })
+        };
+        std::thread::sleep(Duration::from_millis(50));
+        drop(inner);
+        std::thread::sleep(Duration::from_millis(50));
+        let released_at = Instant::now();
+        drop(outer);
+        assert!(other.join().expect("join") >= released_at);
+    }
+
+    #[test]
+    fn replacing_entry_with_unchanged_filename_keeps_new_file() {
+        let instance_dir = temp_path("duplicate-same-filename");
+        fs::create_dir_all(instance_dir.join("mods")).unwrap();
+        let filename = "mymod-1.0-SNAPSHOT.jar";
+
+        let mut lock = Lockfile {
+            version: 2,
+            entries: vec![sample_duplicate_mod_entry(
+                "github",
+                "gh:owner/mymod",
+                filename,
+                "My Mod",
+            )],
+        };
+        // The new build was already written over the old one at the same path.
+        fs::write(instance_dir.join("mods").join(filename), b"new build").unwrap();
+        let mut incoming =
+            sample_duplicate_mod_entry("github", "gh:owner/mymod", filename, "My Mod");
+        incoming.version_id = "gh_artifact:2".to_string();
+
+        enforce_lock_entry_uniqueness(&mut lock, &instance_dir, &mut incoming).unwrap();
+
+        assert!(lock.entries.is_empty());
+        assert_eq!(
+            fs::read(instance_dir.join("mods").join(filename)).unwrap(),
+            b"new build"
+        );
+
+        let _ = fs::remove_dir_all(&instance_dir);
+    }
+
     #[test]
     fn lower_priority_mod_source_is_blocked_by_existing_duplicate() {
         let instance_dir = temp_path("duplicate-priority-block");
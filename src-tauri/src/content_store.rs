@@ -0,0 +1,825 @@
+//! Launcher-wide content-addressed store for downloaded content files.
+//!
+//! Blobs live under `<launcher>/content_store/sha512/<ab>/<sha512>` and are
+//! shared by every instance: installed files are materialized from the store by
+//! hardlink, or by copy when the instance lives on another volume. Providers
+//! that do not publish SHA-512 (CurseForge, GitHub asset digests) find blobs via
+//! SHA-1/SHA-256 alias files. A blob stays live while any instance lockfile,
+//! snapshot lockfile or snapshot manifest references it; the rest is
+//! garbage-collected from the Storage Manager.
+
+use crate::snapshot_store::read_manifest;
+use crate::{
+    instance_dir_for_id, read_index, read_lockfile, snapshot_lock_path, snapshots_dir, Lockfile,
+};
+use sha2::Digest as _;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex, OnceLock, RwLock};
+
+pub(crate) const STORE_DIR_NAME: &str = "content_store";
+const ALIAS_ALGORITHMS: [&str; 2] = ["sha1", "sha256"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct BlobDigest {
+    pub sha1: String,
+    pub sha256: String,
+    pub sha512: String,
+    pub len: u64,
+}
+
+impl BlobDigest {
+    fn of_bytes(bytes: &[u8]) -> Self {
+        Self {
+            sha1: format!("{:x}", sha1::Sha1::digest(bytes)),
+            sha256: format!("{:x}", sha2::Sha256::digest(bytes)),
+            sha512: format!("{:x}", sha2::Sha512::digest(bytes)),
+            len: bytes.len() as u64,
+        }
+    }
+
+    pub(crate) fn of_file(path: &Path) -> Result<Self, String> {
+        let mut file =
+            fs::File::open(path).map_err(|e| format!("open '{}' failed: {e}", path.display()))?;
+        let mut sha1 = sha1::Sha1::new();
+        let mut sha256 = sha2::Sha256::new();
+        let mut sha512 = sha2::Sha512::new();
+        let mut len = 0u64;
+        let mut buf = vec![0u8; 64 * 1024];
+        loop {
+            let read = file
+                .read(&mut buf)
+                .map_err(|e| format!("read '{}' failed: {e}", path.display()))?;
+            if read == 0 {
+                break;
+            }
+            sha1.update(&buf[..read]);
+            sha256.update(&buf[..read]);
+            sha512.update(&buf[..read]);
+            len += read as u64;
+        }
+        Ok(Self {
+            sha1: format!("{:x}", sha1.finalize()),
+            sha256: format!("{:x}", sha256.finalize()),
+            sha512: format!("{:x}", sha512.finalize()),
+            len,
+        })
+    }
+
+    fn alias(&self, algorithm: &str) -> &str {
+        match algorithm {
+            "sha1" => &self.sha1,
+            _ => &self.sha256,
+        }
+    }
+}
+
+/// Outcome of re-hashing one stored blob.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum BlobCheck {
+    Intact,
+    Missing,
+    /// The bytes no longer hash to the blob's name.
+    Damaged,
+    Unreadable(String),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct StoreGcReport {
+    pub removed_blobs: usize,
+    pub reclaimed_bytes: u64,
+    pub live_blobs: usize,
+}
+
+fn configured_root() -> &'static RwLock<Option<PathBuf>> {
+    static ROOT: OnceLock<RwLock<Option<PathBuf>>> = OnceLock::new();
+    ROOT.get_or_init(|| RwLock::new(None))
+}
+
+/// Points installs at `<launcher>/content_store`. Until this is called (e.g. in
+/// tests) installs write instance files directly.
+pub(crate) fn set_store_root(launcher_dir: &Path) {
+    if let Ok(mut guard) = configured_root().write() {
+        *guard = Some(launcher_dir.join(STORE_DIR_NAME));
+    }
+}
+
+/// Store-wide lock between writers and garbage collection. Anything that puts
+/// blobs holds a [`StoreUse`] until the record referencing them (lockfile or
+/// snapshot manifest) is written; collection waits for every use to end and
+/// holds off new ones until it is done. Uses nest freely on one thread.
+#[derive(Default)]
+pub(crate) struct StoreLock {
+    state: Mutex<StoreLockState>,
+    changed: Condvar,
+}
+
+#[derive(Default)]
+struct StoreLockState {
+    users: usize,
+    collecting: bool,
+}
+
+/// Shared hold on a [`StoreLock`]; released on drop.
+pub(crate) struct StoreUse {
+    lock: Arc<StoreLock>,
+}
+
+/// Exclusive hold on a [`StoreLock`] for garbage collection.
+pub(crate) struct StoreCollection {
+    lock: Arc<StoreLock>,
+}
+
+impl StoreLock {
+    fn state(&self) -> std::sync::MutexGuard<'_, StoreLockState> {
+        match self.state.lock() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    fn wait<'a>(
+        &self,
+        state: std::sync::MutexGuard<'a, StoreLockState>,
+    ) -> std::sync::MutexGuard<'a, StoreLockState> {
+        match self.changed.wait(state) {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    pub(crate) fn begin_use(self: &Arc<Self>) -> StoreUse {
+        let mut state = self.state();
+        while state.collecting {
+            state = self.wait(state);
+        }
+        state.users += 1;
+        StoreUse { lock: self.clone() }
+    }
+
+    pub(crate) fn begin_collection(self: &Arc<Self>) -> StoreCollection {
+        let mut state = self.state();
+        while state.collecting || state.users > 0 {
+            state = self.wait(state);
+        }
+        state.collecting = true;
+        StoreCollection { lock: self.clone() }
+    }
+}
+
+impl Drop for StoreUse {
+    fn drop(&mut self) {
+        let mut state = self.lock.state();
+        state.users -= 1;
+        if state.users == 0 {
+            self.lock.changed.notify_all();
+        }
+    }
+}
+
+impl Drop for StoreCollection {
+    fn drop(&mut self) {
+        self.lock.state().collecting = false;
+        self.lock.changed.notify_all();
+    }
+}
+
+pub(crate) fn store_lock(root: &Path) -> Arc<StoreLock> {
+    static LOCKS: OnceLock<Mutex<HashMap<PathBuf, Arc<StoreLock>>>> = OnceLock::new();
+    let locks = LOCKS.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut guard = locks.lock().expect("content store lock registry");
+    guard.entry(root.to_path_buf()).or_default().clone()
+}
+
+/// Holds off garbage collection of the configured store; `None` when installs
+/// write instance files directly.
+pub(crate) fn hold_configured_store() -> Option<StoreUse> {
+    ContentStore::configured().map(|store| store.begin_use())
+}
+
+fn normalize_hex(value: &str, len: usize) -> Option<String> {
+    let value = value.trim().to_ascii_lowercase();
+    (value.len() == len && value.bytes().all(|b| b.is_ascii_hexdigit())).then_some(value)
+}
+
+fn hex_len(algorithm: &str) -> usize {
+    match algorithm {
+        "sha1" => 40,
+        "sha256" => 64,
+        _ => 128,
+    }
+}
+
+/// Hardlinks `src` to `dest`, copying when linking is not possible.
+pub(crate) fn link_or_copy(src: &Path, dest: &Path) -> Result<(), String> {
+    if fs::hard_link(src, dest).is_ok() {
+        return Ok(());
+    }
+    fs::copy(src, dest).map(|_| ()).map_err(|e| {
+        format!(
+            "copy '{}' -> '{}' failed: {e}",
+            src.display(),
+            dest.display()
+        )
+    })
+}
+
+pub(crate) struct ContentStore {
+    root: PathBuf,
+}
+
+impl ContentStore {
+    pub(crate) fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    pub(crate) fn configured() -> Option<Self> {
+        configured_root()
+            .read()
+            .ok()
+            .and_then(|guard| guard.clone())
+            .map(Self::new)
+    }
+
+    fn blob_path(&self, sha512: &str) -> Option<PathBuf> {
+        let hash = normalize_hex(sha512, 128)?;
+        Some(self.root.join("sha512").join(&hash[..2]).join(hash))
+    }
+
+    fn alias_path(&self, algorithm: &str, value: &str) -> Option<PathBuf> {
+        let value = normalize_hex(value, hex_len(algorithm))?;
+        Some(
+            self.root
+                .join("alias")
+                .join(algorithm)
+                .join(&value[..2])
+                .join(value),
+        )
+    }
+
+    /// Holds off garbage collection until the returned use is dropped.
+    pub(crate) fn begin_use(&self) -> StoreUse {
+        store_lock(&self.root).begin_use()
+    }
+
+    pub(crate) fn contains(&self, sha512: &str) -> bool {
+        self.blob_path(sha512).map(|p| p.is_file()).unwrap_or(false)
+    }
+
+    /// SHA-512 of a stored blob matching any of the provider `hashes`.
+    pub(crate) fn find(&self, hashes: &HashMap<String, String>) -> Option<String> {
+        if let Some(sha512) = hashes.get("sha512") {
+            if self.contains(sha512) {
+                return normalize_hex(sha512, 128);
+            }
+        }
+        ALIAS_ALGORITHMS.iter().find_map(|algorithm| {
+            let alias = self.alias_path(algorithm, hashes.get(*algorithm)?)?;
+            let sha512 = fs::read_to_string(alias).ok()?;
+            self.contains(&sha512).then(|| sha512.trim().to_string())
+        })
+    }
+
+    fn write_aliases(&self, digest: &BlobDigest) {
+        for algorithm in ALIAS_ALGORITHMS {
+            let Some(path) = self.alias_path(algorithm, digest.alias(algorithm)) else {
+                continue;
+            };
+            if path.exists() {
+                continue;
+            }
+            if let Some(parent) = path.parent() {
+                let _ = fs::create_dir_all(parent);
+            }
+            let _ = fs::write(path, &digest.sha512);
+        }
+    }
+
+    fn blob_target(&self, sha512: &str) -> Result<PathBuf, String> {
+        let path = self
+            .blob_path(sha512)
+            .ok_or_else(|| format!("invalid content hash '{sha512}'"))?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("mkdir '{}' failed: {e}", parent.display()))?;
+        }
+        Ok(path)
+    }
+
+    fn commit_temp(&self, temp: &Path, blob: &Path) -> Result<(), String> {
+        if blob.exists() {
+            let _ = fs::remove_file(temp);
+            return Ok(());
+        }
+        fs::rename(temp, blob).map_err(|e| {
+            let _ = fs::remove_file(temp);
+            format!("store blob '{}' failed: {e}", blob.display())
+        })
+    }
+
+    /// Drops an existing blob whose bytes no longer match its name, so the
+    /// caller stores a fresh copy instead of trusting it.
+    fn drop_if_damaged(&self, sha512: &str, blob: &Path) {
+        if blob.exists() && self.check(sha512) == BlobCheck::Damaged {
+            let _ = fs::remove_file(blob);
+        }
+    }
+
+    /// Stores `bytes`, returning their digest. A damaged copy already in the
+    /// store is replaced.
+    pub(crate) fn put_bytes(&self, bytes: &[u8]) -> Result<BlobDigest, String> {
+        let digest = BlobDigest::of_bytes(bytes);
+        let blob = self.blob_target(&digest.sha512)?;
+        self.drop_if_damaged(&digest.sha512, &blob);
+        if !blob.exists() {
+            let temp = blob.with_extension(format!("{}.tmp", std::process::id()));
+            fs::write(&temp, bytes)
+                .map_err(|e| format!("write '{}' failed: {e}", temp.display()))?;
+            self.commit_temp(&temp, &blob)?;
+        }
+        self.write_aliases(&digest);
+        Ok(digest)
+    }
+
+    /// Stores the file at `path`, linking it into the store when possible. A
+    /// damaged copy already in the store is replaced.
+    pub(crate) fn put_file(&self, path: &Path) -> Result<BlobDigest, String> {
+        let digest = BlobDigest::of_file(path)?;
+        let blob = self.blob_target(&digest.sha512)?;
+        self.drop_if_damaged(&digest.sha512, &blob);
+        if !blob.exists() {
+            let temp = blob.with_extension(format!("{}.tmp", std::process::id()));
+            let _ = fs::remove_file(&temp);
+            link_or_copy(path, &temp)?;
+            self.commit_temp(&temp, &blob)?;
+        }
+        self.write_aliases(&digest);
+        Ok(digest)
+    }
+
+    /// Stores a private copy of the file at `path`, hashing the copy so the
+    /// blob matches its name even if the source changes meanwhile. Used for
+    /// files that are rewritten in place and must never share an inode. A
+    /// damaged copy already in the store is replaced. Returns whether the blob
+    /// is new to the store.
+    pub(crate) fn put_file_copy(&self, path: &Path) -> Result<(BlobDigest, bool), String> {
+        let incoming = self.root.join("incoming");
+        fs::create_dir_all(&incoming)
+            .map_err(|e| format!("mkdir '{}' failed: {e}", incoming.display()))?;
+        let temp = incoming.join(format!("{}.tmp", uuid::Uuid::new_v4()));
+        fs::copy(path, &temp).map_err(|e| {
+            let _ = fs::remove_file(&temp);
+            format!("copy '{}' failed: {e}", path.display())
+        })?;
+        let digest = match BlobDigest::of_file(&temp) {
+            Ok(digest) => digest,
+            Err(err) => {
+                let _ = fs::remove_file(&temp);
+                return Err(err);
+            }
+        };
+        let blob = self.blob_target(&digest.sha512)?;
+        self.drop_if_damaged(&digest.sha512, &blob);
+        let fresh = !blob.exists();
+        self.commit_temp(&temp, &blob)?;
+        Ok((digest, fresh))
+    }
+
+    /// Copies the blob named `sha512` from `source` unless this store already
+    /// has it. The copy is hashed where it landed, so a transfer that went
+    /// wrong is rejected instead of being stored under the wrong name.
+    pub(crate) fn copy_blob_from(&self, source: &ContentStore, sha512: &str) -> Result<(), String> {
+        if self.contains(sha512) {
+            return Ok(());
+        }
+        let blob = source
+            .blob_path(sha512)
+            .filter(|p| p.is_file())
+            .ok_or_else(|| format!("content store has no blob {sha512}"))?;
+        let (digest, _) = self.put_file_copy(&blob)?;
+        if !digest.sha512.eq_ignore_ascii_case(sha512) {
+            return Err(format!("copied blob {sha512} failed verification"));
+        }
+        Ok(())
+    }
+
+    /// Writes a private copy of the blob to `dest`, replacing whatever is there.
+    pub(crate) fn copy_out(&self, sha512: &str, dest: &Path) -> Result<(), String> {
+        let blob = self
+            .blob_path(sha512)
+            .filter(|p| p.is_file())
+            .ok_or_else(|| format!("content store has no blob {sha512}"))?;
+        if dest.exists() {
+            fs::remove_file(dest)
+                .map_err(|e| format!("remove '{}' failed: {e}", dest.display()))?;
+        }
+        fs::copy(&blob, dest)
+            .map(|_| ())
+            .map_err(|e| format!("copy '{}' failed: {e}", dest.display()))
+    }
+
+    /// Size of the stored blob named `sha512`.
+    pub(crate) fn blob_len(&self, sha512: &str) -> Option<u64> {
+        let blob = self.blob_path(sha512)?;
+        fs::metadata(blob).ok().map(|meta| meta.len())
+    }
+
+    /// Places the blob at `dest`, replacing whatever is there. The blob is
+    /// re-verified first, like [`ContentStore::stage`], so a damaged copy is
+    /// dropped instead of being linked into an instance.
+    pub(crate) fn materialize(&self, sha512: &str, dest: &Path) -> Result<(), String> {
+        if !self.verify(sha512) {
+            return Err(format!("content store has no intact blob {sha512}"));
+        }
+        let blob = self
+            .blob_path(sha512)
+            .ok_or_else(|| format!("content store has no blob {sha512}"))?;
+        if dest.exists() {
+            fs::remove_file(dest)
+                .map_err(|e| format!("remove '{}' failed: {e}", dest.display()))?;
+        }
+        link_or_copy(&blob, dest)
+    }
+
+    /// Re-hashes the blob named `sha512` without changing the store.
+    pub(crate) fn check(&self, sha512: &str) -> BlobCheck {
+        let Some(blob) = self.blob_path(sha512).filter(|p| p.is_file()) else {
+            return BlobCheck::Missing;
+        };
+        match BlobDigest::of_file(&blob) {
+            Ok(digest) if digest.sha512.eq_ignore_ascii_case(sha512) => BlobCheck::Intact,
+            Ok(_) => BlobCheck::Damaged,
+            Err(err) => BlobCheck::Unreadable(err),
+        }
+    }
+
+    /// Whether the blob named `sha512` is present and still matches its name.
+    /// Blobs whose bytes no longer match are dropped from the store; blobs
+    /// that merely could not be read are left alone.
+    pub(crate) fn verify(&self, sha512: &str) -> bool {
+        match self.check(sha512) {
+            BlobCheck::Intact => true,
+            BlobCheck::Damaged => {
+                if let Some(blob) = self.blob_path(sha512) {
+                    let _ = fs::remove_file(blob);
+                }
+                false
+            }
+            BlobCheck::Missing | BlobCheck::Unreadable(_) => false,
+        }
+    }
+
+    /// Copies a stored blob matching `hashes` to `dest` after re-verifying it.
+    /// Blobs that no longer match their name are dropped from the store.
+    pub(crate) fn stage(
+        &self,
+        hashes: &HashMap<String, String>,
+        dest: &Path,
+    ) -> Option<BlobDigest> {
+        let sha512 = self.find(hashes)?;
+        let blob = self.blob_path(&sha512)?;
+        let digest = BlobDigest::of_file(&blob).ok()?;
+        if digest.sha512 != sha512 {
+            let _ = fs::remove_file(&blob);
+            return None;
+        }
+        let _ = fs::remove_file(dest);
+        link_or_copy(&blob, dest).ok()?;
+        Some(digest)
+    }
+
+    /// Bytes of a stored blob matching `hashes`, verified against its name.
+    pub(crate) fn read(&self, hashes: &HashMap<String, String>) -> Option<Vec<u8>> {
+        let sha512 = self.find(hashes)?;
+        let blob = self.blob_path(&sha512)?;
+        let bytes = fs::read(&blob).ok()?;
+        if BlobDigest::of_bytes(&bytes).sha512 != sha512 {
+            let _ = fs::remove_file(&blob);
+            return None;
+        }
+        Some(bytes)
+    }
+
+    fn add_lock_refs(&self, lock: &Lockfile, counts: &mut HashMap<String, usize>) {
+        for entry in &lock.entries {
+            if let Some(sha512) = self.find(&entry.hashes) {
+                *counts.entry(sha512).or_insert(0) += 1;
+            }
+        }
+    }
+
+    /// Reference count per stored blob across every instance lockfile and
+    /// snapshot lockfile.
+    pub(crate) fn reference_counts(
+        &self,
+        instances_dir: &Path,
+    ) -> Result<HashMap<String, usize>, String> {
+        let mut counts = HashMap::new();
+        for instance in read_index(instances_dir)?.instances {
+            let lock = read_lockfile(instances_dir, &instance.id)?;
+            self.add_lock_refs(&lock, &mut counts);
+            let Ok(instance_dir) = instance_dir_for_id(instances_dir, &instance.id) else {
+                continue;
+            };
+            let Ok(snapshots) = fs::read_dir(snapshots_dir(&instance_dir)) else {
+                continue;
+            };
+            for snapshot in snapshots.flatten() {
+                if let Some(manifest) = read_manifest(&snapshot.path()) {
+                    for file in manifest.files {
+                        *counts.entry(file.sha512).or_insert(0) += 1;
+                    }
+                }
+                let Ok(raw) = fs::read_to_string(snapshot_lock_path(&snapshot.path())) else {
+                    continue;
+                };
+                if let Ok(lock) = serde_json::from_str::<Lockfile>(&raw) {
+                    self.add_lock_refs(&lock, &mut counts);
+                }
+            }
+        }
+        Ok(counts)
+    }
+
+    fn blobs(&self) -> Vec<(String, PathBuf, fs::Metadata)> {
+        let mut out = Vec::new();
+        let Ok(shards) = fs::read_dir(self.root.join("sha512")) else {
+            return out;
+        };
+        for shard in shards.flatten() {
+            let Ok(files) = fs::read_dir(shard.path()) else {
+                continue;
+            };
+            for file in files.flatten() {
+                let name = file.file_name().to_string_lossy().to_string();
+                // In-flight `<hash>.<pid>.tmp` files are not blobs yet.
+                if normalize_hex(&name, 128).as_deref() != Some(name.as_str()) {
+                    continue;
+                }
+                let Ok(meta) = file.metadata() else {
+                    continue;
+                };
+                if meta.is_file() {
+                    out.push((name, file.path(), meta));
+                }
+            }
+        }
+        out
+    }
+
+    /// Unreferenced blobs with the bytes removing each would free. A blob that
+    /// is still hardlinked elsewhere frees nothing.
+    fn garbage(&self, live: &HashMap<String, usize>) -> Vec<(PathBuf, u64)> {
+        self.blobs()
+            .into_iter()
+            .filter(|(name, _, _)| !live.contains_key(name))
+            .map(|(_, path, meta)| {
+                let freed = if link_count(&path, &meta) > 1 {
+                    0
+                } else {
+                    meta.len()
+                };
+                (path, freed)
+            })
+            .collect()
+    }
+
+    /// Number of unreferenced blobs and the bytes collecting them would free.
+    pub(crate) fn pending_garbage(&self, instances_dir: &Path) -> Result<(usize, u64), String> {
+        let live = self.reference_counts(instances_dir)?;
+        let garbage = self.garbage(&live);
+        Ok((garbage.len(), garbage.iter().map(|(_, bytes)| *bytes).sum()))
+    }
+
+    /// Removes unreferenced blobs and the aliases pointing at them. Waits for
+    /// in-flight writers, whose blobs may not be referenced yet.
+    pub(crate) fn collect_garbage(&self, instances_dir: &Path) -> Result<StoreGcReport, String> {
+        let _collection = store_lock(&self.root).begin_collection();
+        let live = self.reference_counts(instances_dir)?;
+        Ok(self.remove_unreferenced(&live))
+    }
+
+    /// Removes every blob missing from `live`, for stores whose references
+    /// are tracked by the caller.
+    pub(crate) fn remove_unreferenced(&self, live: &HashMap<String, usize>) -> StoreGcReport {
+        let mut report = StoreGcReport {
+            live_blobs: live.len(),
+            ..StoreGcReport::default()
+        };
+        for (path, freed) in self.garbage(live) {
+            if fs::remove_file(&path).is_ok() {
+                report.removed_blobs += 1;
+                report.reclaimed_bytes = report.reclaimed_bytes.saturating_add(freed);
+            }
+        }
+        self.prune_aliases();
+        report
+    }
+
+    fn prune_aliases(&self) {
+        let remaining = self
+            .blobs()
+            .into_iter()
+            .map(|(name, _, _)| name)
+            .collect::<HashSet<_>>();
+        for algorithm in ALIAS_ALGORITHMS {
+            let Ok(shards) = fs::read_dir(self.root.join("alias").join(algorithm)) else {
+                continue;
+            };
+            for shard in shards.flatten() {
+                let Ok(files) = fs::read_dir(shard.path()) else {
+                    continue;
+                };
+                for file in files.flatten() {
+                    let target = fs::read_to_string(file.path()).unwrap_or_default();
+                    if !remaining.contains(target.trim()) {
+                        let _ = fs::remove_file(file.path());
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Number of hardlinks to the file at `path`, whose metadata is `meta`.
+#[cfg(unix)]
+pub(crate) fn link_count(_path: &Path, meta: &fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    meta.nlink()
+}
+
+#[cfg(windows)]
+pub(crate) fn link_count(path: &Path, _meta: &fs::Metadata) -> u64 {
+    windows_file_information(path)
+        .map(|info| u64::from(info.nNumberOfLinks))
+        .unwrap_or(1)
+}
+
+#[cfg(not(any(unix, windows)))]
+pub(crate) fn link_count(_path: &Path, _meta: &fs::Metadata) -> u64 {
+    1
+}
+
+/// Link count, volume and file index of `path`. `std` only exposes these on
+/// nightly, so they come from `GetFileInformationByHandle`. The handle is
+/// opened without read access, so files in use by the game still answer.
+#[cfg(windows)]
+pub(crate) fn windows_file_information(
+    path: &Path,
+) -> Option<windows_sys::Win32::Storage::FileSystem::BY_HANDLE_FILE_INFORMATION> {
+    use std::os::windows::fs::OpenOptionsExt;
+    use std::os::windows::io::AsRawHandle;
+    use windows_sys::Win32::Storage::FileSystem::{
+        GetFileInformationByHandle, BY_HANDLE_FILE_INFORMATION,
+    };
+
+    let file = fs::OpenOptions::new().access_mode(0).open(path).ok()?;
+    // SAFETY: the struct is plain old data, so all zeroes is a valid value.
+    let mut info: BY_HANDLE_FILE_INFORMATION = unsafe { std::mem::zeroed() };
+    // SAFETY: `file` keeps the handle open for the duration of the call.
+    let ok = unsafe { GetFileInformationByHandle(file.as_raw_handle() as isize, &mut info) };
+    (ok != 0).then_some(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_root;
+
+    #[test]
+    fn stored_bytes_are_found_by_any_published_hash() {
+        let root = temp_root("find");
+        let store = ContentStore::new(root.join("store"));
+        let digest = store.put_bytes(b"mod jar bytes").expect("put bytes");
+
+        for (algorithm, value) in [
+            ("sha512", digest.sha512.clone()),
+            ("sha1", digest.sha1.clone()),
+            ("sha256", digest.sha256.to_ascii_uppercase()),
+        ] {
+            let hashes = HashMap::from([(algorithm.to_string(), value)]);
+            assert_eq!(store.find(&hashes), Some(digest.sha512.clone()));
+        }
+        let staged = root.join("staged.part");
+        let hashes = HashMap::from([("sha1".to_string(), digest.sha1.clone())]);
+        assert_eq!(store.stage(&hashes, &staged), Some(digest.clone()));
+        assert_eq!(fs::read(&staged).expect("read staged"), b"mod jar bytes");
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn materialized_files_share_the_stored_blob() {
+        let root = temp_root("materialize");
+        let store = ContentStore::new(root.join("store"));
+        let staged = root.join("download.part");
+        fs::write(&staged, b"shared content").expect("write staged");
+        let digest = store.put_file(&staged).expect("put file");
+
+        let first = root.join("a").join("mod.jar");
+        let second = root.join("b").join("mod.jar");
+        for dest in [&first, &second] {
+            fs::create_dir_all(dest.parent().unwrap()).expect("mkdir");
+            store
+                .materialize(&digest.sha512, dest)
+                .expect("materialize");
+            assert_eq!(fs::read(dest).expect("read"), b"shared content");
+        }
+        #[cfg(any(unix, windows))]
+        {
+            let blob = store.blob_path(&digest.sha512).unwrap();
+            let meta = fs::metadata(&blob).unwrap();
+            // Staged file, store blob and both instance copies.
+            assert_eq!(link_count(&blob, &meta), 4);
+        }
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn corrupt_blobs_are_dropped_instead_of_served() {
+        let root = temp_root("corrupt");
+        let store = ContentStore::new(root.join("store"));
+        let digest = store.put_bytes(b"original").expect("put bytes");
+        fs::write(store.blob_path(&digest.sha512).unwrap(), b"tampered").expect("tamper");
+
+        let hashes = HashMap::from([("sha512".to_string(), digest.sha512.clone())]);
+        assert_eq!(store.read(&hashes), None);
+        assert!(!store.contains(&digest.sha512));
+
+        // Storing the same bytes again replaces a damaged blob, and damaged
+        // blobs are never linked out.
+        let blob = store.blob_path(&digest.sha512).unwrap();
+        store.put_bytes(b"original").expect("put again");
+        fs::write(&blob, b"tampered").expect("tamper");
+        store.put_bytes(b"original").expect("put repaired");
+        assert_eq!(fs::read(&blob).expect("read blob"), b"original");
+        fs::write(&blob, b"tampered").expect("tamper");
+        let source = root.join("original.dat");
+        fs::write(&source, b"original").expect("write source");
+        let (_, fresh) = store.put_file_copy(&source).expect("put copy repaired");
+        assert!(fresh);
+        assert_eq!(fs::read(&blob).expect("read blob"), b"original");
+        fs::write(&blob, b"tampered").expect("tamper");
+        let dest = root.join("mod.jar");
+        assert!(store.materialize(&digest.sha512, &dest).is_err());
+        assert!(!dest.exists());
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn garbage_collection_keeps_referenced_blobs() {
+        let root = temp_root("gc");
+        let store = ContentStore::new(root.join("store"));
+        let kept = store.put_bytes(b"referenced").expect("put kept");
+        let dropped = store.put_bytes(b"orphaned").expect("put dropped");
+        let live = HashMap::from([(kept.sha512.clone(), 1usize)]);
+        let in_flight = store
+            .blob_path(&dropped.sha512)
+            .unwrap()
+            .with_extension("4242.tmp");
+        fs::write(&in_flight, b"partial").expect("write in-flight temp");
+
+        let garbage = store.garbage(&live);
+        assert_eq!(garbage.len(), 1);
+        assert_eq!(garbage[0].1, b"orphaned".len() as u64);
+        for (path, _) in garbage {
+            fs::remove_file(path).expect("remove garbage");
+        }
+        store.prune_aliases();
+        assert!(in_flight.exists());
+        assert!(store.contains(&kept.sha512));
+        assert!(!store.contains(&dropped.sha512));
+        let hashes = HashMap::from([("sha1".to_string(), dropped.sha1.clone())]);
+        assert_eq!(store.find(&hashes), None);
+        assert!(store
+            .alias_path("sha1", &kept.sha1)
+            .map(|p| p.exists())
+            .unwrap_or(false));
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn garbage_collection_waits_for_writers() {
+        let root = temp_root("gc-lock");
+        let store = ContentStore::new(root.join("store"));
+        let writer = store.begin_use();
+        let nested = store.begin_use();
+        let (done_tx, done_rx) = std::sync::mpsc::channel();
+        let lock = store_lock(&store.root);
+        let collector = std::thread::spawn(move || {
+            let _collection = lock.begin_collection();
+            done_tx.send(()).expect("signal collection");
+        });
+        drop(nested);
+        assert!(done_rx
+            .recv_timeout(std::time::Duration::from_millis(100))
+            .is_err());
+        drop(writer);
+        done_rx
+            .recv_timeout(std::time::Duration::from_secs(5))
+            .expect("collection runs once writers finish");
+        collector.join().expect("collector thread");
+        let _ = fs::remove_dir_all(root);
+    }
+}
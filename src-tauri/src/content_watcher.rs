@@ -0,0 +1,502 @@
+use crate::{
+    app_instances_dir, find_instance, instance_dir_for_id, normalize_lock_content_type, read_index,
+    read_lockfile, write_lockfile, LockEntry, Lockfile,
+};
+use notify::event::{ModifyKind, RenameMode};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::Duration;
+
+/// Instance folders whose files are adopted into the lockfile. Datapacks live
+/// per world and are left to the explicit import flow.
+const WATCHED_CONTENT_DIRS: [(&str, &str); 3] = [
+    ("mods", "mods"),
+    ("resourcepacks", "resourcepacks"),
+    ("shaderpacks", "shaderpacks"),
+];
+/// Quiet period before a burst of events is handled, so copies finish and the
+/// launcher's own install/update writes have landed in the lockfile.
+const EVENT_DEBOUNCE: Duration = Duration::from_millis(1500);
+
+fn watcher_registry() -> &'static Mutex<HashMap<String, RecommendedWatcher>> {
+    static REGISTRY: OnceLock<Mutex<HashMap<String, RecommendedWatcher>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Filesystem changes collected over one debounce window.
+#[derive(Debug, Default)]
+pub(crate) struct ContentEventBatch {
+    pub created: BTreeSet<PathBuf>,
+    pub removed: BTreeSet<PathBuf>,
+    pub renamed: Vec<(PathBuf, PathBuf)>,
+}
+
+impl ContentEventBatch {
+    fn push(&mut self, event: Event) {
+        match event.kind {
+            EventKind::Create(_) => self.created.extend(event.paths),
+            EventKind::Remove(_) => self.removed.extend(event.paths),
+            EventKind::Modify(ModifyKind::Name(RenameMode::Both)) if event.paths.len() == 2 => {
+                self.renamed
+                    .push((event.paths[0].clone(), event.paths[1].clone()));
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::From)) => {
+                self.removed.extend(event.paths)
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::To)) => self.created.extend(event.paths),
+            EventKind::Modify(ModifyKind::Name(_)) => {
+                for path in event.paths {
+                    if path.exists() {
+                        self.created.insert(path);
+                    } else {
+                        self.removed.insert(path);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.created.is_empty() && self.removed.is_empty() && self.renamed.is_empty()
+    }
+}
+
+/// What a batch did to the lockfile, plus the untracked files left to import.
+#[derive(Debug, Default)]
+pub(crate) struct ContentWatchOutcome {
+    pub renamed: Vec<(String, String)>,
+    pub toggled: Vec<(String, bool)>,
+    pub removed: Vec<String>,
+    pub adopt: Vec<(String, PathBuf)>,
+}
+
+impl ContentWatchOutcome {
+    pub fn lock_changed(&self) -> bool {
+        !self.renamed.is_empty() || !self.toggled.is_empty() || !self.removed.is_empty()
+    }
+}
+
+/// Content type and enabled file name for a path directly inside a watched
+/// folder; the `.disabled` suffix is reported separately.
+fn classify_content_path(instance_dir: &Path, path: &Path) -> Option<(&'static str, String, bool)> {
+    let parent = path.parent()?;
+    if parent.parent()? != instance_dir {
+        return None;
+    }
+    let dir_name = parent.file_name()?.to_str()?;
+    let content_type = WATCHED_CONTENT_DIRS
+        .iter()
+        .find(|(dir, _)| *dir == dir_name)
+        .map(|(_, content_type)| *content_type)?;
+    let file_name = path.file_name()?.to_str()?;
+    if file_name.starts_with('.') {
+        return None;
+    }
+    let (base, enabled) = match file_name.strip_suffix(".disabled") {
+        Some(base) => (base, false),
+        None => (file_name, true),
+    };
+    let ext = Path::new(base)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("");
+    if !crate::local_file_extension_allowed(content_type, ext) {
+        return None;
+    }
+    Some((content_type, base.to_string(), enabled))
+}
+
+fn find_entry_index(lock: &Lockfile, content_type: &str, filename: &str) -> Option<usize> {
+    lock.entries.iter().position(|entry: &LockEntry| {
+        entry.filename == filename
+            && normalize_lock_content_type(&entry.content_type) == content_type
+    })
+}
+
+/// Applies renames and removals to `lock` and lists new untracked files.
+/// Paths are re-checked on disk, so stale or self-inflicted events are no-ops.
+pub(crate) fn reconcile_content_events(
+    instance_dir: &Path,
+    lock: &mut Lockfile,
+    batch: &ContentEventBatch,
+) -> ContentWatchOutcome {
+    let mut outcome = ContentWatchOutcome::default();
+    let mut created = batch.created.clone();
+    let mut removed = batch.removed.clone();
+
+    for (from, to) in &batch.renamed {
+        removed.remove(from);
+        let Some((to_type, to_name, to_enabled)) = classify_content_path(instance_dir, to) else {
+            removed.insert(from.clone());
+            continue;
+        };
+        let Some((from_type, from_name, _)) = classify_content_path(instance_dir, from) else {
+            created.insert(to.clone());
+            continue;
+        };
+        if !to.exists() {
+            continue;
+        }
+        let Some(idx) = find_entry_index(lock, from_type, &from_name)
+            .filter(|_| from_type == to_type)
+            .filter(|_| {
+                find_entry_index(lock, to_type, &to_name).is_none() || from_name == to_name
+            })
+        else {
+            created.insert(to.clone());
+            continue;
+        };
+        let entry = &mut lock.entries[idx];
+        if from_name != to_name {
+            outcome.renamed.push((from_name, to_name.clone()));
+            entry.filename = to_name;
+        }
+        if entry.enabled != to_enabled {
+            entry.enabled = to_enabled;
+            outcome.toggled.push((entry.filename.clone(), to_enabled));
+        }
+    }
+
+    for path in &removed {
+        let Some((content_type, name, _)) = classify_content_path(instance_dir, path) else {
+            continue;
+        };
+        let (enabled_path, disabled_path) =
+            crate::content_paths_for_type(instance_dir, content_type, &name);
+        if enabled_path.exists() || disabled_path.exists() {
+            continue;
+        }
+        if let Some(idx) = find_entry_index(lock, content_type, &name) {
+            lock.entries.remove(idx);
+            outcome.removed.push(name);
+        }
+    }
+
+    for path in &created {
+        let Some((content_type, name, enabled)) = classify_content_path(instance_dir, path) else {
+            continue;
+        };
+        if !enabled || !path.is_file() || crate::sanitize_filename(&name) != name {
+            continue;
+        }
+        if find_entry_index(lock, content_type, &name).is_none() {
+            outcome.adopt.push((content_type.to_string(), path.clone()));
+        }
+    }
+    outcome
+}
+
+fn log_event(app: &tauri::AppHandle, instance_id: &str, kind: &str, summary: String) {
+    if let Err(err) = crate::run_reports::log_instance_event(app, instance_id, kind, &summary) {
+        eprintln!("content watcher history write failed for '{instance_id}' [{kind}]: {err}");
+    }
+}
+
+fn watch_new_content_dirs(instance_id: &str, instance_dir: &Path, batch: &mut ContentEventBatch) {
+    let new_dirs = batch
+        .created
+        .iter()
+        .filter(|path| {
+            path.parent() == Some(instance_dir)
+                && path.is_dir()
+                && path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| WATCHED_CONTENT_DIRS.iter().any(|(dir, _)| *dir == name))
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    if new_dirs.is_empty() {
+        return;
+    }
+    if let Ok(mut registry) = watcher_registry().lock() {
+        if let Some(watcher) = registry.get_mut(instance_id) {
+            for dir in &new_dirs {
+                let _ = watcher.watch(dir, RecursiveMode::NonRecursive);
+            }
+        }
+    }
+    // Files copied in together with the folder arrive before the watch exists.
+    for dir in new_dirs {
+        if let Ok(read_dir) = std::fs::read_dir(&dir) {
+            batch
+                .created
+                .extend(read_dir.flatten().map(|item| item.path()));
+        }
+    }
+}
+
+fn handle_batch(
+    app: &tauri::AppHandle,
+    instance_id: &str,
+    instance_dir: &Path,
+    mut batch: ContentEventBatch,
+) -> Result<(), String> {
+    watch_new_content_dirs(instance_id, instance_dir, &mut batch);
+    let instances_dir = app_instances_dir(app)?;
+    if find_instance(&instances_dir, instance_id).is_err() {
+        return Ok(());
+    }
+    // Every command that writes the lockfile holds this lock; hold it across
+    // the read-modify-write and the adopt imports below.
+    let mutation_lock = crate::commands::impls::instance_mutation_lock(instance_id);
+    let _guard = mutation_lock.lock()?;
+    let mut lock = read_lockfile(&instances_dir, instance_id)?;
+    let outcome = reconcile_content_events(instance_dir, &mut lock, &batch);
+    if outcome.lock_changed() {
+        write_lockfile(&instances_dir, instance_id, &lock)?;
+    }
+    for (from, to) in &outcome.renamed {
+        log_event(
+            app,
+            instance_id,
+            "watcher_renamed",
+            format!("Tracked rename of '{from}' to '{to}' made outside the launcher."),
+        );
+    }
+    for (name, enabled) in &outcome.toggled {
+        log_event(
+            app,
+            instance_id,
+            "watcher_toggled",
+            format!(
+                "'{name}' was {} outside the launcher.",
+                if *enabled { "enabled" } else { "disabled" }
+            ),
+        );
+    }
+    for name in &outcome.removed {
+        log_event(
+            app,
+            instance_id,
+            "watcher_removed",
+            format!("Removed '{name}' from the lockfile after its file was deleted."),
+        );
+    }
+    for (content_type, path) in outcome.adopt {
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        match crate::commands::impls::adopt_dropped_content_file(
+            app,
+            instance_id,
+            &content_type,
+            path,
+        ) {
+            Ok(installed) => log_event(
+                app,
+                instance_id,
+                "watcher_adopted",
+                format!(
+                    "Adopted '{}' dropped into {content_type}/ (source: {}).",
+                    installed.filename, installed.source
+                ),
+            ),
+            Err(err) => log_event(
+                app,
+                instance_id,
+                "watcher_adopt_failed",
+                format!("Could not adopt '{file_name}' dropped into {content_type}/: {err}"),
+            ),
+        }
+    }
+    Ok(())
+}
+
+fn run_event_loop(
+    app: tauri::AppHandle,
+    instance_id: String,
+    instance_dir: PathBuf,
+    rx: Receiver<notify::Result<Event>>,
+) {
+    // Exits once the watcher is dropped from the registry and the channel closes.
+    while let Ok(first) = rx.recv() {
+        let mut batch = ContentEventBatch::default();
+        if let Ok(event) = first {
+            batch.push(event);
+        }
+        let mut closed = false;
+        loop {
+            match rx.recv_timeout(EVENT_DEBOUNCE) {
+                Ok(Ok(event)) => batch.push(event),
+                Ok(Err(_)) => {}
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => {
+                    closed = true;
+                    break;
+                }
+            }
+        }
+        if !closed && !batch.is_empty() {
+            if let Err(err) = handle_batch(&app, &instance_id, &instance_dir, batch) {
+                eprintln!("content watcher for '{instance_id}' failed: {err}");
+            }
+        }
+        if closed {
+            break;
+        }
+    }
+}
+
+/// Starts watching an instance's content folders. Returns false when a
+/// watcher is already running for it.
+pub(crate) fn start_instance_watcher(
+    app: &tauri::AppHandle,
+    instance_id: &str,
+) -> Result<bool, String> {
+    let mut registry = watcher_registry()
+        .lock()
+        .map_err(|_| "content watcher registry lock poisoned".to_string())?;
+    if registry.contains_key(instance_id) {
+        return Ok(false);
+    }
+    let instances_dir = app_instances_dir(app)?;
+    let _ = find_instance(&instances_dir, instance_id)?;
+    let instance_dir = instance_dir_for_id(&instances_dir, instance_id)?;
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)
+        .map_err(|e| format!("create content watcher failed: {e}"))?;
+    watcher
+        .watch(&instance_dir, RecursiveMode::NonRecursive)
+        .map_err(|e| format!("watch '{}' failed: {e}", instance_dir.display()))?;
+    for (dir, _) in WATCHED_CONTENT_DIRS {
+        let path = instance_dir.join(dir);
+        if path.is_dir() {
+            watcher
+                .watch(&path, RecursiveMode::NonRecursive)
+                .map_err(|e| format!("watch '{}' failed: {e}", path.display()))?;
+        }
+    }
+    registry.insert(instance_id.to_string(), watcher);
+    let app = app.clone();
+    let id = instance_id.to_string();
+    thread::spawn(move || run_event_loop(app, id, instance_dir, rx));
+    Ok(true)
+}
+
+pub(crate) fn stop_instance_watcher(instance_id: &str) -> bool {
+    watcher_registry()
+        .lock()
+        .map(|mut registry| registry.remove(instance_id).is_some())
+        .unwrap_or(false)
+}
+
+pub(crate) fn watched_instance_ids() -> Vec<String> {
+    let mut ids = watcher_registry()
+        .lock()
+        .map(|registry| registry.keys().cloned().collect::<Vec<_>>())
+        .unwrap_or_default();
+    ids.sort();
+    ids
+}
+
+pub(crate) fn start_all_instance_watchers(app: &tauri::AppHandle) -> Result<(), String> {
+    let instances_dir = app_instances_dir(app)?;
+    for instance in read_index(&instances_dir)?.instances {
+        if let Err(err) = start_instance_watcher(app, &instance.id) {
+            eprintln!("content watcher for '{}' not started: {err}", instance.id);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LockEntry;
+    use std::collections::HashMap;
+    use std::fs;
+
+    fn local_entry(filename: &str, enabled: bool) -> LockEntry {
+        LockEntry {
+            source: "local".to_string(),
+            project_id: format!("local:mods:{filename}"),
+            version_id: format!("local_{filename}"),
+            name: filename.to_string(),
+            version_number: "local-file".to_string(),
+            filename: filename.to_string(),
+            content_type: "mods".to_string(),
+            target_scope: "instance".to_string(),
+            target_worlds: vec![],
+            pinned_version: None,
+            enabled,
+            hashes: HashMap::new(),
+            provider_candidates: vec![],
+            local_analysis: None,
+            security_scan: None,
+            url_update: None,
+            github_channel: None,
+        }
+    }
+
+    #[test]
+    fn reconciles_drops_renames_toggles_and_deletes() {
+        let root =
+            std::env::temp_dir().join(format!("openjar-content-watcher-{}", uuid::Uuid::new_v4()));
+        let mods = root.join("mods");
+        fs::create_dir_all(&mods).expect("create mods dir");
+        for name in [
+            "dropped.jar",
+            "renamed-new.jar",
+            "toggled.jar.disabled",
+            "tracked.jar",
+        ] {
+            fs::write(mods.join(name), b"jar").expect("write jar");
+        }
+        fs::write(mods.join("notes.txt"), b"txt").expect("write txt");
+
+        let mut lock = Lockfile {
+            version: 2,
+            entries: vec![
+                local_entry("renamed-old.jar", true),
+                local_entry("toggled.jar", true),
+                local_entry("deleted.jar", true),
+                local_entry("tracked.jar", true),
+            ],
+        };
+        let batch = ContentEventBatch {
+            created: [
+                mods.join("dropped.jar"),
+                mods.join("notes.txt"),
+                mods.join("tracked.jar"),
+            ]
+            .into_iter()
+            .collect(),
+            removed: [mods.join("deleted.jar")].into_iter().collect(),
+            renamed: vec![
+                (mods.join("renamed-old.jar"), mods.join("renamed-new.jar")),
+                (mods.join("toggled.jar"), mods.join("toggled.jar.disabled")),
+            ],
+        };
+
+        let outcome = reconcile_content_events(&root, &mut lock, &batch);
+
+        assert_eq!(
+            outcome.adopt,
+            vec![("mods".to_string(), mods.join("dropped.jar"))]
+        );
+        assert_eq!(
+            outcome.renamed,
+            vec![("renamed-old.jar".to_string(), "renamed-new.jar".to_string())]
+        );
+        assert_eq!(outcome.toggled, vec![("toggled.jar".to_string(), false)]);
+        assert_eq!(outcome.removed, vec!["deleted.jar".to_string()]);
+        let filenames = lock
+            .entries
+            .iter()
+            .map(|entry| entry.filename.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            filenames,
+            vec!["renamed-new.jar", "toggled.jar", "tracked.jar"]
+        );
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,725 @@
+//! Manual download handoff for CurseForge files whose authors disabled
+//! third-party distribution.
+//!
+//! Blocked files are queued per instance in `curseforge_handoff.json` with
+//! their project page. A watcher on the downloads folder (the system Downloads
+//! directory unless configured) identifies files by the CurseForge murmur2
+//! fingerprint, moves each match into the instance and completes its lock
+//! entry.
+
+use crate::{
+    app_instances_dir, carry_security_acknowledgement, curseforge_fingerprint_candidates,
+    hash_bytes_hex, instance_dir_for_instance, normalize_lock_content_type,
+    normalize_target_worlds_for_datapack, now_iso, parse_cf_hashes, read_index,
+    read_launcher_settings, read_lockfile, remove_replaced_entries_for_content,
+    resolve_curseforge_file_download_url, sanitize_filename, sha512_hex,
+    write_download_to_content_targets, write_lockfile, CurseforgeFile, CurseforgeMod, LockEntry,
+    Lockfile, ProviderCandidate,
+};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+use std::time::Duration;
+
+const HANDOFF_FILE_NAME: &str = "curseforge_handoff.json";
+/// Browsers write to a temporary name and rename when done; wait for that.
+const EVENT_DEBOUNCE: Duration = Duration::from_millis(2000);
+const PARTIAL_DOWNLOAD_SUFFIXES: [&str; 5] =
+    [".part", ".crdownload", ".download", ".tmp", ".partial"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct CurseforgeHandoffItem {
+    #[serde(default)]
+    pub instance_id: String,
+    pub project_id: String,
+    pub file_id: i64,
+    pub name: String,
+    pub filename: String,
+    #[serde(default)]
+    pub version_number: String,
+    pub content_type: String,
+    #[serde(default)]
+    pub target_worlds: Vec<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub fingerprint: Option<u32>,
+    #[serde(default)]
+    pub file_length: Option<u64>,
+    #[serde(default)]
+    pub hashes: HashMap<String, String>,
+    pub project_url: String,
+    #[serde(default)]
+    pub download_page_url: Option<String>,
+    pub queued_at: String,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct CurseforgeHandoffScanResult {
+    pub folder: Option<String>,
+    pub completed: Vec<String>,
+    pub remaining: usize,
+    pub warnings: Vec<String>,
+}
+
+/// Whether a download-url failure means the author disabled third-party
+/// downloads, as opposed to a transient or key problem.
+pub(crate) fn is_distribution_blocked(err: &str) -> bool {
+    let lower = err.to_ascii_lowercase();
+    lower.contains("http 403")
+        || lower.contains("third-party downloads")
+        || lower.contains("has no download url")
+}
+
+pub(crate) fn blocked_message(name: &str) -> String {
+    format!(
+        "The author of '{name}' disabled third-party downloads. It was added to CurseForge manual downloads; \
+download it from its project page and it will be moved into place automatically."
+    )
+}
+
+pub(crate) fn handoff_item_for_file(
+    project: Option<&CurseforgeMod>,
+    mod_id: i64,
+    file: &CurseforgeFile,
+    name: &str,
+    content_type: &str,
+    target_worlds: &[String],
+    enabled: bool,
+) -> CurseforgeHandoffItem {
+    let website = project
+        .and_then(|project| project.links.as_ref())
+        .map(|links| links.website_url.trim().trim_end_matches('/').to_string())
+        .filter(|url| !url.is_empty());
+    CurseforgeHandoffItem {
+        instance_id: String::new(),
+        project_id: format!("cf:{mod_id}"),
+        file_id: file.id,
+        name: name.to_string(),
+        filename: sanitize_filename(&file.file_name),
+        version_number: if file.display_name.trim().is_empty() {
+            file.file_name.clone()
+        } else {
+            file.display_name.clone()
+        },
+        content_type: normalize_lock_content_type(content_type),
+        target_worlds: target_worlds.to_vec(),
+        enabled,
+        fingerprint: file
+            .file_fingerprint
+            .and_then(|value| u32::try_from(value).ok()),
+        file_length: file.file_length.filter(|len| *len > 0),
+        hashes: parse_cf_hashes(file),
+        download_page_url: website
+            .as_ref()
+            .map(|url| format!("{url}/files/{}", file.id)),
+        project_url: website
+            .unwrap_or_else(|| format!("https://www.curseforge.com/projects/{mod_id}")),
+        queued_at: now_iso(),
+    }
+}
+
+/// Serializes read-modify-write of one instance's handoff list between
+/// installs queueing files, dismissals and folder scans.
+fn handoff_list_lock(instance_dir: &Path) -> Arc<Mutex<()>> {
+    static LOCKS: OnceLock<Mutex<HashMap<PathBuf, Arc<Mutex<()>>>>> = OnceLock::new();
+    let locks = LOCKS.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut guard = locks.lock().expect("handoff list lock registry");
+    guard
+        .entry(instance_dir.to_path_buf())
+        .or_insert_with(|| Arc::new(Mutex::new(())))
+        .clone()
+}
+
+pub(crate) fn read_handoff_items(instance_dir: &Path) -> Vec<CurseforgeHandoffItem> {
+    fs::read_to_string(instance_dir.join(HANDOFF_FILE_NAME))
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn write_handoff_items(instance_dir: &Path, items: &[CurseforgeHandoffItem]) -> Result<(), String> {
+    let path = instance_dir.join(HANDOFF_FILE_NAME);
+    if items.is_empty() {
+        if path.exists() {
+            fs::remove_file(&path).map_err(|e| format!("remove handoff list failed: {e}"))?;
+        }
+        return Ok(());
+    }
+    let raw = serde_json::to_string_pretty(items)
+        .map_err(|e| format!("serialize handoff list failed: {e}"))?;
+    fs::write(&path, raw).map_err(|e| format!("write handoff list failed: {e}"))
+}
+
+/// Adds or replaces the queued file for a project.
+pub(crate) fn queue_blocked_file(
+    instance_dir: &Path,
+    item: CurseforgeHandoffItem,
+) -> Result<(), String> {
+    let list_lock = handoff_list_lock(instance_dir);
+    let _guard = list_lock
+        .lock()
+        .map_err(|_| "handoff list lock poisoned".to_string())?;
+    let mut items = read_handoff_items(instance_dir);
+    items.retain(|existing| existing.project_id != item.project_id);
+    items.push(item);
+    write_handoff_items(instance_dir, &items)
+}
+
+/// Download URL for `file`. When its author disabled third-party downloads,
+/// the item built by `item` is queued instead and the error says so.
+pub(crate) fn resolve_download_url_or_queue(
+    client: &Client,
+    api_key: &str,
+    instance_dir: &Path,
+    mod_id: i64,
+    file: &CurseforgeFile,
+    item: impl FnOnce() -> Result<CurseforgeHandoffItem, String>,
+) -> Result<String, String> {
+    match resolve_curseforge_file_download_url(client, api_key, mod_id, file) {
+        Err(err) if is_distribution_blocked(&err) => {
+            let item = item()?;
+            let message = blocked_message(&item.name);
+            queue_blocked_file(instance_dir, item)?;
+            Err(message)
+        }
+        other => other,
+    }
+}
+
+pub(crate) fn dismiss_handoff_item(instance_dir: &Path, project_id: &str) -> Result<bool, String> {
+    let list_lock = handoff_list_lock(instance_dir);
+    let _guard = list_lock
+        .lock()
+        .map_err(|_| "handoff list lock poisoned".to_string())?;
+    let mut items = read_handoff_items(instance_dir);
+    let before = items.len();
+    items.retain(|item| item.project_id != project_id.trim());
+    write_handoff_items(instance_dir, &items)?;
+    Ok(items.len() != before)
+}
+
+fn item_matches_bytes(item: &CurseforgeHandoffItem, bytes: &[u8]) -> bool {
+    if let Some(fingerprint) = item.fingerprint {
+        return curseforge_fingerprint_candidates(bytes).contains(&fingerprint);
+    }
+    item.hashes
+        .get("sha1")
+        .map(|expected| hash_bytes_hex("sha1", bytes) == expected.trim().to_ascii_lowercase())
+        .unwrap_or(false)
+}
+
+fn looks_like_partial_download(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    PARTIAL_DOWNLOAD_SUFFIXES
+        .iter()
+        .any(|suffix| name.ends_with(suffix))
+}
+
+fn has_content_extension(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| {
+            let ext = ext.to_string_lossy().to_ascii_lowercase();
+            ext == "jar" || ext == "zip"
+        })
+        .unwrap_or(false)
+}
+
+/// Whether `path` could be the download of `item`, judged without reading
+/// it: the extension must match the queued file, and so must the size when
+/// CurseForge reported one. Without a size the browser's name has to start
+/// with the queued file's name (browsers only append " (1)" and the like).
+fn could_be_download_of(path: &Path, len: u64, item: &CurseforgeHandoffItem) -> bool {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    let expected = item.filename.to_ascii_lowercase();
+    let (expected_stem, expected_ext) = expected.rsplit_once('.').unwrap_or((&expected, ""));
+    if !expected_ext.is_empty() && !name.ends_with(&format!(".{expected_ext}")) {
+        return false;
+    }
+    match item.file_length {
+        Some(expected_len) => expected_len == len,
+        None => !expected_stem.is_empty() && name.starts_with(expected_stem),
+    }
+}
+
+/// Files in the folder that could be one of the queued downloads, filtered by
+/// name and the size CurseForge reports before anything is hashed.
+fn candidate_files(folder: &Path, items: &[CurseforgeHandoffItem]) -> Vec<PathBuf> {
+    let Ok(read) = fs::read_dir(folder) else {
+        return vec![];
+    };
+    read.filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().map(|t| t.is_file()).unwrap_or(false))
+        .filter(|entry| {
+            let path = entry.path();
+            if looks_like_partial_download(&path) || !has_content_extension(&path) {
+                return false;
+            }
+            let len = entry.metadata().map(|meta| meta.len()).unwrap_or(0);
+            items
+                .iter()
+                .any(|item| could_be_download_of(&path, len, item))
+        })
+        .map(|entry| entry.path())
+        .collect()
+}
+
+fn complete_handoff_item(
+    instance_dir: &Path,
+    lock: &mut Lockfile,
+    item: &CurseforgeHandoffItem,
+    bytes: &[u8],
+) -> Result<LockEntry, String> {
+    let worlds = if item.content_type == "datapacks" {
+        normalize_target_worlds_for_datapack(instance_dir, &item.target_worlds)?
+    } else {
+        vec![]
+    };
+    let previous = lock
+        .entries
+        .iter()
+        .find(|entry| {
+            normalize_lock_content_type(&entry.content_type) == item.content_type
+                && entry.project_id.eq_ignore_ascii_case(&item.project_id)
+        })
+        .cloned();
+    remove_replaced_entries_for_content(lock, instance_dir, &item.project_id, &item.content_type)?;
+    let security_scan = write_download_to_content_targets(
+        instance_dir,
+        &item.content_type,
+        &item.filename,
+        &worlds,
+        bytes,
+    )?;
+    if item.content_type == "mods" && !item.enabled {
+        crate::disable_mod_file(instance_dir, &item.filename)?;
+    }
+    let mut hashes = item.hashes.clone();
+    hashes.insert("sha512".to_string(), sha512_hex(bytes));
+    let version_id = format!("cf_file:{}", item.file_id);
+    let entry = LockEntry {
+        source: "curseforge".to_string(),
+        project_id: item.project_id.clone(),
+        version_id: version_id.clone(),
+        name: item.name.clone(),
+        version_number: item.version_number.clone(),
+        filename: item.filename.clone(),
+        content_type: item.content_type.clone(),
+        target_scope: if item.content_type == "datapacks" {
+            "world".to_string()
+        } else {
+            "instance".to_string()
+        },
+        target_worlds: worlds,
+        pinned_version: previous
+            .as_ref()
+            .and_then(|entry| entry.pinned_version.clone()),
+        enabled: item.enabled,
+        hashes,
+        provider_candidates: vec![ProviderCandidate {
+            source: "curseforge".to_string(),
+            project_id: item.project_id.clone(),
+            version_id,
+            name: item.name.clone(),
+            version_number: item.version_number.clone(),
+            confidence: None,
+            reason: Some("Manual download matched by CurseForge fingerprint.".to_string()),
+            verification_status: Some("verified".to_string()),
+        }],
+        local_analysis: previous
+            .as_ref()
+            .and_then(|entry| entry.local_analysis.clone()),
+        security_scan: security_scan.map(|scan| {
+            carry_security_acknowledgement(
+                previous
+                    .as_ref()
+                    .and_then(|entry| entry.security_scan.as_ref()),
+                scan,
+            )
+        }),
+        url_update: None,
+        github_channel: None,
+    };
+    lock.entries.push(entry.clone());
+    Ok(entry)
+}
+
+/// Moves every folder file matching a queued item into the instance and
+/// completes its lock entry. Matched items are removed from `items`.
+pub(crate) fn adopt_handoff_downloads(
+    folder: &Path,
+    instance_dir: &Path,
+    lock: &mut Lockfile,
+    items: &mut Vec<CurseforgeHandoffItem>,
+    warnings: &mut Vec<String>,
+) -> Vec<LockEntry> {
+    let mut completed = Vec::new();
+    for path in candidate_files(folder, items) {
+        if items.is_empty() {
+            break;
+        }
+        let Ok(bytes) = fs::read(&path) else {
+            continue;
+        };
+        let Some(idx) = items
+            .iter()
+            .position(|item| item_matches_bytes(item, &bytes))
+        else {
+            continue;
+        };
+        match complete_handoff_item(instance_dir, lock, &items[idx], &bytes) {
+            Ok(entry) => {
+                if let Err(err) = fs::remove_file(&path) {
+                    warnings.push(format!(
+                        "Installed '{}' but could not remove '{}': {err}",
+                        entry.name,
+                        path.display()
+                    ));
+                }
+                items.remove(idx);
+                completed.push(entry);
+            }
+            Err(err) => warnings.push(format!(
+                "Could not complete '{}' from '{}': {err}",
+                items[idx].name,
+                path.display()
+            )),
+        }
+    }
+    completed
+}
+
+pub(crate) fn handoff_folder(app: &tauri::AppHandle) -> Option<PathBuf> {
+    let configured = read_launcher_settings(app)
+        .map(|settings| settings.curseforge_handoff_dir.trim().to_string())
+        .unwrap_or_default();
+    if configured.is_empty() {
+        tauri::api::path::download_dir()
+    } else {
+        Some(PathBuf::from(configured))
+    }
+}
+
+pub(crate) fn list_handoff_items(
+    app: &tauri::AppHandle,
+    instance_id: Option<&str>,
+) -> Result<Vec<CurseforgeHandoffItem>, String> {
+    let instances_dir = app_instances_dir(app)?;
+    let index = read_index(&instances_dir)?;
+    let mut out = Vec::new();
+    for instance in index
+        .instances
+        .iter()
+        .filter(|instance| instance_id.map(|id| id == instance.id).unwrap_or(true))
+    {
+        let instance_dir = instance_dir_for_instance(&instances_dir, instance);
+        out.extend(
+            read_handoff_items(&instance_dir)
+                .into_iter()
+                .map(|mut item| {
+                    item.instance_id = instance.id.clone();
+                    item
+                }),
+        );
+    }
+    Ok(out)
+}
+
+/// Checks the downloads folder against every instance's queue.
+pub(crate) fn scan_handoff_folder(
+    app: &tauri::AppHandle,
+) -> Result<CurseforgeHandoffScanResult, String> {
+    let folder = handoff_folder(app);
+    let mut result = CurseforgeHandoffScanResult {
+        folder: folder.as_ref().map(|path| path.display().to_string()),
+        completed: vec![],
+        remaining: 0,
+        warnings: vec![],
+    };
+    let instances_dir = app_instances_dir(app)?;
+    let index = read_index(&instances_dir)?;
+    for instance in &index.instances {
+        let instance_dir = instance_dir_for_instance(&instances_dir, instance);
+        if read_handoff_items(&instance_dir).is_empty() {
+            continue;
+        }
+        let mutation_lock = crate::commands::impls::instance_mutation_lock(&instance.id);
+        let _mutation_guard = mutation_lock.lock()?;
+        let list_lock = handoff_list_lock(&instance_dir);
+        let _list_guard = list_lock
+            .lock()
+            .map_err(|_| "handoff list lock poisoned".to_string())?;
+        let mut items = read_handoff_items(&instance_dir);
+        if items.is_empty() {
+            continue;
+        }
+        let Some(folder) = folder.as_ref().filter(|path| path.is_dir()) else {
+            result.remaining += items.len();
+            continue;
+        };
+        let mut lock = read_lockfile(&instances_dir, &instance.id)?;
+        let completed = adopt_handoff_downloads(
+            folder,
+            &instance_dir,
+            &mut lock,
+            &mut items,
+            &mut result.warnings,
+        );
+        if !completed.is_empty() {
+            lock.entries.sort_by_key(|entry| entry.name.to_lowercase());
+            write_lockfile(&instances_dir, &instance.id, &lock)?;
+            write_handoff_items(&instance_dir, &items)?;
+            for entry in &completed {
+                let summary = format!(
+                    "Completed CurseForge manual download '{}' ({}).",
+                    entry.name, entry.filename
+                );
+                if let Err(err) = crate::run_reports::log_instance_event(
+                    app,
+                    &instance.id,
+                    "curseforge_handoff",
+                    &summary,
+                ) {
+                    eprintln!("curseforge handoff history write failed: {err}");
+                }
+                result
+                    .completed
+                    .push(format!("{}: {}", instance.name, entry.name));
+            }
+        }
+        result.remaining += items.len();
+    }
+    Ok(result)
+}
+
+fn watcher_slot() -> &'static Mutex<Option<(PathBuf, RecommendedWatcher)>> {
+    static SLOT: OnceLock<Mutex<Option<(PathBuf, RecommendedWatcher)>>> = OnceLock::new();
+    SLOT.get_or_init(|| Mutex::new(None))
+}
+
+fn run_event_loop(app: tauri::AppHandle, rx: Receiver<notify::Result<Event>>) {
+    loop {
+        let Ok(first) = rx.recv() else {
+            return;
+        };
+        let mut relevant = matches!(
+            first,
+            Ok(Event {
+                kind: EventKind::Create(_) | EventKind::Modify(_),
+                ..
+            })
+        );
+        loop {
+            match rx.recv_timeout(EVENT_DEBOUNCE) {
+                Ok(Ok(event)) => {
+                    relevant |= matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+                }
+                Ok(Err(_)) => {}
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        }
+        if relevant {
+            if let Err(err) = scan_handoff_folder(&app) {
+                eprintln!("curseforge handoff scan failed: {err}");
+            }
+        }
+    }
+}
+
+/// (Re)starts the downloads-folder watcher for the configured folder. Does
+/// nothing when that folder is already watched.
+pub(crate) fn restart_handoff_watcher(app: &tauri::AppHandle) -> Result<Option<String>, String> {
+    let Some(folder) = handoff_folder(app).filter(|path| path.is_dir()) else {
+        if let Ok(mut slot) = watcher_slot().lock() {
+            *slot = None;
+        }
+        return Ok(None);
+    };
+    let mut slot = watcher_slot()
+        .lock()
+        .map_err(|_| "handoff watcher lock poisoned".to_string())?;
+    if slot
+        .as_ref()
+        .map(|(path, _)| path == &folder)
+        .unwrap_or(false)
+    {
+        return Ok(Some(folder.display().to_string()));
+    }
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(move |event| {
+        let _ = tx.send(event);
+    })
+    .map_err(|e| format!("create downloads watcher failed: {e}"))?;
+    watcher
+        .watch(&folder, RecursiveMode::NonRecursive)
+        .map_err(|e| format!("watch '{}' failed: {e}", folder.display()))?;
+    *slot = Some((folder.clone(), watcher));
+    let loop_app = app.clone();
+    thread::spawn(move || run_event_loop(loop_app, rx));
+    Ok(Some(folder.display().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_root;
+
+    fn item(bytes: &[u8]) -> CurseforgeHandoffItem {
+        CurseforgeHandoffItem {
+            instance_id: String::new(),
+            project_id: "cf:1234".to_string(),
+            file_id: 99,
+            name: "Blocked Mod".to_string(),
+            filename: "blocked-mod-1.0.jar".to_string(),
+            version_number: "1.0".to_string(),
+            content_type: "mods".to_string(),
+            target_worlds: vec![],
+            enabled: true,
+            fingerprint: curseforge_fingerprint_candidates(bytes).first().copied(),
+            file_length: Some(bytes.len() as u64),
+            hashes: HashMap::new(),
+            project_url: "https://www.curseforge.com/minecraft/mc-mods/blocked".to_string(),
+            download_page_url: None,
+            queued_at: "now".to_string(),
+        }
+    }
+
+    #[test]
+    fn detects_distribution_blocked_errors() {
+        assert!(is_distribution_blocked(
+            "CurseForge blocked automated download URL access for this file (HTTP 403). \
+This file may disallow third-party downloads."
+        ));
+        assert!(is_distribution_blocked(
+            "CurseForge file has no download url"
+        ));
+        assert!(!is_distribution_blocked(
+            "CurseForge download-url lookup failed with status 500"
+        ));
+    }
+
+    #[test]
+    fn candidates_are_prefiltered_before_hashing() {
+        let downloads = temp_root("prefilter");
+        for name in [
+            "blocked-mod-1.0 (2).jar",
+            "unrelated.jar",
+            "blocked-mod-1.0.zip",
+            "notes.txt",
+        ] {
+            fs::write(downloads.join(name), b"contents").expect("write download");
+        }
+        let no_length = CurseforgeHandoffItem {
+            file_length: None,
+            ..item(b"contents")
+        };
+        let names = |items: &[CurseforgeHandoffItem]| {
+            let mut names = candidate_files(&downloads, items)
+                .iter()
+                .map(|path| path.file_name().unwrap().to_string_lossy().to_string())
+                .collect::<Vec<_>>();
+            names.sort();
+            names
+        };
+        assert_eq!(names(&[no_length]), vec!["blocked-mod-1.0 (2).jar"]);
+        assert_eq!(
+            names(&[item(b"contents")]),
+            vec!["blocked-mod-1.0 (2).jar", "unrelated.jar"]
+        );
+        let _ = fs::remove_dir_all(&downloads);
+    }
+
+    #[test]
+    fn adopts_matching_download_and_completes_lock_entry() {
+        let downloads = temp_root("downloads");
+        let instance_dir = temp_root("instance");
+        let bytes = b"PK\x03\x04 blocked mod jar contents".to_vec();
+        fs::write(downloads.join("blocked-mod-1.0 (1).jar"), &bytes).expect("write download");
+        fs::write(
+            downloads.join("other.jar"),
+            b"PK\x03\x04 something else entirely!!",
+        )
+        .expect("write other");
+        fs::write(downloads.join("blocked-mod-1.0.jar.crdownload"), &bytes).expect("write partial");
+
+        queue_blocked_file(&instance_dir, item(&bytes)).expect("queue");
+        let mut items = read_handoff_items(&instance_dir);
+        assert_eq!(items.len(), 1);
+
+        let mut lock = Lockfile::default();
+        let mut warnings = Vec::new();
+        let completed = adopt_handoff_downloads(
+            &downloads,
+            &instance_dir,
+            &mut lock,
+            &mut items,
+            &mut warnings,
+        );
+        assert_eq!(completed.len(), 1, "warnings: {warnings:?}");
+        assert!(items.is_empty());
+        assert_eq!(lock.entries.len(), 1);
+        assert_eq!(lock.entries[0].source, "curseforge");
+        assert_eq!(lock.entries[0].version_id, "cf_file:99");
+        assert!(lock.entries[0].hashes.contains_key("sha512"));
+        assert_eq!(
+            fs::read(instance_dir.join("mods").join("blocked-mod-1.0.jar")).expect("installed"),
+            bytes
+        );
+        assert!(!downloads.join("blocked-mod-1.0 (1).jar").exists());
+        assert!(downloads.join("other.jar").exists());
+        assert!(downloads.join("blocked-mod-1.0.jar.crdownload").exists());
+
+        let _ = fs::remove_dir_all(&downloads);
+        let _ = fs::remove_dir_all(&instance_dir);
+    }
+
+    #[test]
+    fn completing_a_replacement_keeps_the_pin() {
+        let instance_dir = temp_root("replace");
+        let bytes = b"PK\x03\x04 replacement jar".to_vec();
+        let mut lock = Lockfile::default();
+        lock.entries.push(LockEntry {
+            source: "curseforge".to_string(),
+            project_id: "cf:1234".to_string(),
+            version_id: "cf_file:98".to_string(),
+            name: "Blocked Mod".to_string(),
+            version_number: "0.9".to_string(),
+            filename: "blocked-mod-0.9.jar".to_string(),
+            content_type: "mods".to_string(),
+            target_scope: "instance".to_string(),
+            target_worlds: vec![],
+            pinned_version: Some("cf_file:99".to_string()),
+            enabled: true,
+            hashes: HashMap::new(),
+            provider_candidates: vec![],
+            local_analysis: None,
+            security_scan: None,
+            url_update: None,
+            github_channel: None,
+        });
+
+        let entry = complete_handoff_item(&instance_dir, &mut lock, &item(&bytes), &bytes)
+            .expect("complete");
+        assert_eq!(lock.entries.len(), 1);
+        assert_eq!(entry.version_id, "cf_file:99");
+        assert_eq!(entry.pinned_version.as_deref(), Some("cf_file:99"));
+        let _ = fs::remove_dir_all(&instance_dir);
+    }
+}
@@ -28,7 +28,7 @@ use rand::rngs::OsRng;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 use uuid::Uuid;
 
@@ -1805,9 +1805,13 @@ fn verify_bytes_against_entry_hashes(
     Ok(())
 }
 
+/// Fetches an entry's pinned file from its provider. CurseForge files whose
+/// author disabled third-party downloads are queued for a manual download in
+/// `instance_dir` instead.
 fn download_lock_entry_bytes_from_provider(
     client: &reqwest::blocking::Client,
     entry: &CanonicalLockEntry,
+    instance_dir: &Path,
 ) -> Result<Option<Vec<u8>>, String> {
     let source = entry.source.trim().to_ascii_lowercase();
     if source == "modrinth" {
@@ -1841,16 +1845,88 @@ fn download_lock_entry_bytes_from_provider(
             return Ok(None);
         };
         let file = crate::fetch_curseforge_file(client, &api_key, mod_id, file_id)?;
-        let url = crate::resolve_curseforge_file_download_url(client, &api_key, mod_id, &file)?;
+        let url = crate::curseforge_handoff::resolve_download_url_or_queue(
+            client,
+            &api_key,
+            instance_dir,
+            mod_id,
+            &file,
+            || {
+                Ok(crate::curseforge_handoff::handoff_item_for_file(
+                    None,
+                    mod_id,
+                    &file,
+                    &entry.name,
+                    &entry.content_type,
+                    &entry.target_worlds,
+                    entry.enabled,
+                ))
+            },
+        )?;
         let bytes =
             crate::download_bytes_with_retry(client, &url, &format!("cf:{mod_id}:{file_id}"))?;
         verify_bytes_against_entry_hashes(&bytes, entry)?;
         return Ok(Some(bytes));
     }
 
+    if source == crate::team_repo::TEAM_SOURCE {
+        let Some(bytes) = crate::team_repo::download_team_version_bytes(
+            client,
+            &entry.project_id,
+            &entry.version_id,
+        )?
+        else {
+            return Ok(None);
+        };
+        verify_bytes_against_entry_hashes(&bytes, entry)?;
+        return Ok(Some(bytes));
+    }
+
+    if source == crate::maven_repo::MAVEN_SOURCE {
+        let bytes = crate::maven_repo::download_maven_entry_bytes(
+            client,
+            &entry.project_id,
+            &entry.version_number,
+        )?;
+        verify_bytes_against_entry_hashes(&bytes, entry)?;
+        return Ok(Some(bytes));
+    }
+
+    if source == crate::url_source::URL_SOURCE {
+        let bytes = crate::url_source::download_url_entry_bytes(
+            client,
+            &entry.project_id,
+            &entry.version_number,
+            &entry.hashes,
+        )?;
+        return Ok(Some(bytes));
+    }
+
     Ok(None)
 }
 
+/// Fetches the exact pinned file for a launcher lock entry from its provider.
+pub(crate) fn download_launcher_lock_entry_bytes(
+    client: &reqwest::blocking::Client,
+    entry: &crate::LockEntry,
+    instance_dir: &Path,
+) -> Result<Option<Vec<u8>>, String> {
+    let canonical = CanonicalLockEntry {
+        source: entry.source.clone(),
+        project_id: entry.project_id.clone(),
+        version_id: entry.version_id.clone(),
+        name: entry.name.clone(),
+        version_number: entry.version_number.clone(),
+        filename: entry.filename.clone(),
+        content_type: entry.content_type.clone(),
+        target_scope: entry.target_scope.clone(),
+        target_worlds: entry.target_worlds.clone(),
+        enabled: entry.enabled,
+        hashes: entry.hashes.clone(),
+    };
+    download_lock_entry_bytes_from_provider(client, &canonical, instance_dir)
+}
+
 fn sync_lock_entry_binaries(
     instances_dir: &PathBuf,
     instance_id: &str,
@@ -1870,6 +1946,7 @@ fn sync_lock_entry_binaries(
         .map(|peer| (peer.peer_id.clone(), peer.endpoint.clone()))
         .collect::<HashMap<_, _>>();
     let provider_client = crate::build_http_client().ok();
+    let instance_dir = crate::instance_dir_for_id(instances_dir, instance_id)?;
 
     let mut failure_count = 0usize;
     for (key, entry) in lock_map {
@@ -1950,7 +2027,7 @@ fn sync_lock_entry_binaries(
 
         if !synced {
             if let Some(client) = provider_client.as_ref() {
-                match download_lock_entry_bytes_from_provider(client, entry) {
+                match download_lock_entry_bytes_from_provider(client, entry, &instance_dir) {
                     Ok(Some(bytes)) => {
                         let wrote = state::write_lock_entry_bytes(
                             instances_dir,
@@ -315,6 +315,8 @@ fn resolve_path_under_root_no_symlink(
     Ok(root.join(&normalized))
 }
 
+/// Writes `bytes` to a temp file and renames it over `path`. The rename swaps
+/// the directory entry, so other hardlinks to the old file keep their bytes.
 fn atomic_replace_file(path: &Path, bytes: &[u8], label: &str) -> Result<(), String> {
     let ext = path
         .extension()
@@ -0,0 +1,622 @@
+//! Per-entry GitHub update channels: stable releases only, releases including
+//! pre-releases, or the newest successful GitHub Actions artifact on a branch.
+//!
+//! Entries without a channel keep the default release selection (stable
+//! first, pre-releases only when nothing stable matches). Artifact versions use
+//! version ids `gh_artifact:<artifact id>`; their zip wrapper is unpacked to
+//! find the mod jar.
+
+use crate::{
+    canonical_lock_entry_name, disable_mod_file, enforce_lock_entry_uniqueness, github_api_tokens,
+    github_get_json, github_mark_token_cooldown, github_name_similarity_score, github_project_key,
+    github_release_asset_looks_like_mod_jar, github_release_sort_key,
+    github_tokens_in_request_order, hash_bytes_hex, parse_github_project_id, sanitize_filename,
+    select_github_release_with_asset, validate_lock_entry_uniqueness,
+    write_download_to_content_targets, ContentUpdateInfo, GithubRelease, GithubReleaseSelection,
+    GithubRepository, LockEntry, Lockfile, ProviderCandidate, SearchDiscoverContentArgs,
+    GITHUB_API_BASE, GITHUB_API_VERSION,
+};
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io::{Cursor, Read};
+use std::path::Path;
+
+pub(crate) const CHANNEL_STABLE: &str = "stable";
+pub(crate) const CHANNEL_PRERELEASE: &str = "prerelease";
+pub(crate) const CHANNEL_ARTIFACT: &str = "artifact";
+const ARTIFACT_VERSION_PREFIX: &str = "gh_artifact:";
+const WORKFLOW_RUNS_PER_PAGE: usize = 10;
+/// Artifact zips are held in memory; nightly mod builds are far below this.
+const MAX_ARTIFACT_JAR_BYTES: u64 = 256 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct GithubChannel {
+    /// `stable`, `prerelease` or `artifact`.
+    pub kind: String,
+    /// Branch whose workflow runs are tracked (artifact channel only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// Workflow file name or id; any workflow on the branch when empty.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workflow: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct GithubWorkflowRunsResponse {
+    #[serde(default)]
+    workflow_runs: Vec<GithubWorkflowRun>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct GithubWorkflowRun {
+    pub id: u64,
+    #[serde(default)]
+    pub head_sha: String,
+    #[serde(default)]
+    pub head_branch: Option<String>,
+    #[serde(default)]
+    pub run_number: u64,
+    #[serde(default)]
+    pub conclusion: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct GithubArtifactsResponse {
+    #[serde(default)]
+    artifacts: Vec<GithubArtifact>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct GithubArtifact {
+    pub id: u64,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub size_in_bytes: u64,
+    #[serde(default)]
+    pub expired: bool,
+    #[serde(default)]
+    pub archive_download_url: String,
+}
+
+fn non_empty(value: Option<&String>) -> Option<&str> {
+    value.map(|v| v.trim()).filter(|v| !v.is_empty())
+}
+
+fn valid_ref_text(value: &str) -> bool {
+    value
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '/'))
+        && !value.contains("..")
+}
+
+/// Validates a requested channel. `None` or an empty kind clears it.
+pub(crate) fn normalize_github_channel(
+    channel: Option<GithubChannel>,
+) -> Result<Option<GithubChannel>, String> {
+    let Some(channel) = channel else {
+        return Ok(None);
+    };
+    let kind = channel.kind.trim().to_ascii_lowercase().replace('-', "");
+    match kind.as_str() {
+        "" => Ok(None),
+        CHANNEL_STABLE | CHANNEL_PRERELEASE => Ok(Some(GithubChannel {
+            kind,
+            branch: None,
+            workflow: None,
+        })),
+        CHANNEL_ARTIFACT => {
+            let branch = non_empty(channel.branch.as_ref())
+                .ok_or_else(|| "The artifact channel needs a branch".to_string())?
+                .to_string();
+            let workflow = non_empty(channel.workflow.as_ref()).map(str::to_string);
+            if !valid_ref_text(&branch) || !workflow.as_deref().map(valid_ref_text).unwrap_or(true)
+            {
+                return Err("Branch or workflow name has invalid characters".to_string());
+            }
+            Ok(Some(GithubChannel {
+                kind,
+                branch: Some(branch),
+                workflow,
+            }))
+        }
+        _ => Err(format!(
+            "Unknown GitHub channel '{}'; use stable, prerelease or artifact",
+            channel.kind
+        )),
+    }
+}
+
+pub(crate) fn is_artifact_channel(channel: Option<&GithubChannel>) -> bool {
+    channel
+        .map(|channel| channel.kind == CHANNEL_ARTIFACT)
+        .unwrap_or(false)
+}
+
+pub(crate) fn parse_github_artifact_id(version_id: &str) -> Option<u64> {
+    version_id
+        .trim()
+        .strip_prefix(ARTIFACT_VERSION_PREFIX)
+        .and_then(|rest| rest.trim().parse::<u64>().ok())
+        .filter(|id| *id > 0)
+}
+
+/// Channel of the github entry an update replaces, so it survives the update.
+pub(crate) fn github_channel_for_update(
+    lock: &Lockfile,
+    update: &ContentUpdateInfo,
+) -> Option<GithubChannel> {
+    let update_repo = parse_github_project_id(&update.project_id).ok();
+    lock.entries
+        .iter()
+        .filter(|entry| entry.source.trim().eq_ignore_ascii_case("github"))
+        .find(|entry| {
+            (!update.current_version_id.trim().is_empty()
+                && entry.version_id.trim() == update.current_version_id.trim())
+                || (update_repo.is_some()
+                    && parse_github_project_id(&entry.project_id).ok() == update_repo)
+        })
+        .and_then(|entry| entry.github_channel.clone())
+}
+
+/// Release selection honoring the entry's channel. Artifact channels never
+/// select releases.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn select_github_release_for_channel(
+    repo: &GithubRepository,
+    releases: &[GithubRelease],
+    query: &str,
+    required_game_version: Option<&str>,
+    required_loader: Option<&str>,
+    discover_filters: Option<&SearchDiscoverContentArgs>,
+    repo_loader_hints: Option<&HashSet<String>>,
+    channel: Option<&GithubChannel>,
+) -> Option<GithubReleaseSelection> {
+    let select = |subset: &[GithubRelease]| {
+        select_github_release_with_asset(
+            repo,
+            subset,
+            query,
+            required_game_version,
+            required_loader,
+            discover_filters,
+            repo_loader_hints,
+        )
+    };
+    let Some(channel) = channel else {
+        return select(releases);
+    };
+    let (stable, prerelease): (Vec<GithubRelease>, Vec<GithubRelease>) = releases
+        .iter()
+        .cloned()
+        .partition(|release| !release.prerelease);
+    match channel.kind.as_str() {
+        CHANNEL_STABLE => select(&stable),
+        CHANNEL_PRERELEASE => {
+            // Newest of the best stable and best pre-release pick.
+            match (select(&stable), select(&prerelease)) {
+                (Some(a), Some(b)) => Some(
+                    if github_release_sort_key(&b.release) > github_release_sort_key(&a.release) {
+                        b
+                    } else {
+                        a
+                    },
+                ),
+                (a, b) => a.or(b),
+            }
+        }
+        _ => None,
+    }
+}
+
+pub(crate) fn fetch_latest_successful_run(
+    client: &Client,
+    owner: &str,
+    repo: &str,
+    channel: &GithubChannel,
+) -> Result<Option<GithubWorkflowRun>, String> {
+    let branch = non_empty(channel.branch.as_ref())
+        .ok_or_else(|| "The artifact channel needs a branch".to_string())?;
+    let scope = match non_empty(channel.workflow.as_ref()) {
+        Some(workflow) => format!("actions/workflows/{workflow}/runs"),
+        None => "actions/runs".to_string(),
+    };
+    let url = format!(
+        "{GITHUB_API_BASE}/repos/{owner}/{repo}/{scope}?branch={branch}&status=success&per_page={WORKFLOW_RUNS_PER_PAGE}"
+    );
+    let runs = github_get_json::<GithubWorkflowRunsResponse>(client, &url)?;
+    Ok(runs.workflow_runs.into_iter().find(|run| {
+        run.conclusion
+            .as_deref()
+            .map(|value| value.eq_ignore_ascii_case("success"))
+            .unwrap_or(false)
+    }))
+}
+
+fn fetch_run_artifacts(
+    client: &Client,
+    owner: &str,
+    repo: &str,
+    run_id: u64,
+) -> Result<Vec<GithubArtifact>, String> {
+    let url = format!("{GITHUB_API_BASE}/repos/{owner}/{repo}/actions/runs/{run_id}/artifacts");
+    Ok(github_get_json::<GithubArtifactsResponse>(client, &url)?.artifacts)
+}
+
+pub(crate) fn pick_artifact<'a>(
+    artifacts: &'a [GithubArtifact],
+    query_hint: &str,
+) -> Option<&'a GithubArtifact> {
+    artifacts
+        .iter()
+        .filter(|artifact| !artifact.expired && !artifact.archive_download_url.trim().is_empty())
+        .filter(|artifact| {
+            let lower = artifact.name.to_ascii_lowercase();
+            !(lower.contains("sources") || lower.contains("javadoc") || lower.contains("test"))
+        })
+        .max_by_key(|artifact| {
+            (
+                github_name_similarity_score(&artifact.name, query_hint),
+                artifact.size_in_bytes,
+            )
+        })
+}
+
+/// Artifact downloads require authentication, so this only succeeds with a
+/// configured token; tokens rotate and cool down like API requests.
+pub(crate) fn download_artifact_zip(client: &Client, url: &str) -> Result<Vec<u8>, String> {
+    let all_tokens = github_api_tokens();
+    if all_tokens.is_empty() {
+        return Err(
+            "GitHub Actions artifacts can only be downloaded with a GitHub token. Configure GitHub API auth in Settings > Advanced > GitHub API."
+                .to_string(),
+        );
+    }
+    let mut last_status = None;
+    for token in github_tokens_in_request_order(&all_tokens) {
+        let mut response = client
+            .get(url)
+            .header("Accept", "application/vnd.github+json")
+            .header("X-GitHub-Api-Version", GITHUB_API_VERSION)
+            .bearer_auth(&token)
+            .send()
+            .map_err(|e| format!("GitHub artifact download failed: {e}"))?;
+        let status = response.status();
+        if status.is_success() {
+            let mut bytes = Vec::new();
+            response
+                .copy_to(&mut bytes)
+                .map_err(|e| format!("GitHub artifact download read failed: {e}"))?;
+            return Ok(bytes);
+        }
+        if status == reqwest::StatusCode::FORBIDDEN || status == reqwest::StatusCode::UNAUTHORIZED {
+            github_mark_token_cooldown(&token, status, response.headers());
+            last_status = Some(status);
+            continue;
+        }
+        if status == reqwest::StatusCode::GONE {
+            return Err("GitHub artifact has expired".to_string());
+        }
+        return Err(format!(
+            "GitHub artifact download failed with status {status}"
+        ));
+    }
+    Err(format!(
+        "GitHub artifact download failed with status {} for every configured token",
+        last_status
+            .map(|status| status.to_string())
+            .unwrap_or_else(|| "unknown".to_string())
+    ))
+}
+
+/// Unpacks the artifact zip wrapper and returns the best matching mod jar.
+/// Only the jar's base name is used, so archive paths never reach the disk.
+pub(crate) fn extract_mod_jar_from_artifact(
+    zip_bytes: &[u8],
+    query_hint: &str,
+) -> Result<(String, Vec<u8>), String> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(zip_bytes))
+        .map_err(|e| format!("open GitHub artifact zip failed: {e}"))?;
+    let mut best: Option<(i64, usize, String)> = None;
+    for idx in 0..archive.len() {
+        let file = archive
+            .by_index(idx)
+            .map_err(|e| format!("read GitHub artifact zip failed: {e}"))?;
+        if file.is_dir() {
+            continue;
+        }
+        let base = file
+            .name()
+            .rsplit(['/', '\\'])
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        let lower = base.to_ascii_lowercase();
+        if !github_release_asset_looks_like_mod_jar(&base)
+            || lower.ends_with("-dev.jar")
+            || lower.ends_with("-api.jar")
+            || file.size() > MAX_ARTIFACT_JAR_BYTES
+        {
+            continue;
+        }
+        let score = github_name_similarity_score(&base, query_hint);
+        if best.as_ref().map(|(s, _, _)| score > *s).unwrap_or(true) {
+            best = Some((score, idx, base));
+        }
+    }
+    let (_, idx, base) = best.ok_or_else(|| "GitHub artifact contains no mod jar".to_string())?;
+    let mut file = archive
+        .by_index(idx)
+        .map_err(|e| format!("read GitHub artifact zip failed: {e}"))?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)
+        .map_err(|e| format!("extract '{base}' from GitHub artifact failed: {e}"))?;
+    let filename = sanitize_filename(&base);
+    if filename.is_empty() {
+        return Err("GitHub artifact jar name is invalid".to_string());
+    }
+    Ok((filename, bytes))
+}
+
+fn artifact_version_label(run: &GithubWorkflowRun, branch: &str) -> String {
+    let short_sha = run.head_sha.chars().take(7).collect::<String>();
+    format!("{branch}@{short_sha} (run #{})", run.run_number)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn check_github_artifact_update(
+    client: &Client,
+    entry: &LockEntry,
+    channel: &GithubChannel,
+    owner: &str,
+    repo_name: &str,
+    display_name: &str,
+    query_hint: &str,
+    current_version_id: &str,
+    warnings: &mut Vec<String>,
+) -> Result<Option<ContentUpdateInfo>, String> {
+    let project_id = github_project_key(owner, repo_name);
+    let Some(run) = fetch_latest_successful_run(client, owner, repo_name, channel)? else {
+        warnings.push(format!(
+            "No successful workflow run found for '{}' ({}) on branch {}.",
+            display_name,
+            project_id,
+            channel.branch.as_deref().unwrap_or_default()
+        ));
+        return Ok(None);
+    };
+    let artifacts = fetch_run_artifacts(client, owner, repo_name, run.id)?;
+    let Some(artifact) = pick_artifact(&artifacts, query_hint) else {
+        warnings.push(format!(
+            "The latest workflow run for '{}' ({}) has no downloadable artifact.",
+            display_name, project_id
+        ));
+        return Ok(None);
+    };
+    let latest_version_id = format!("{ARTIFACT_VERSION_PREFIX}{}", artifact.id);
+    if latest_version_id == current_version_id.trim() {
+        return Ok(None);
+    }
+    let branch = run
+        .head_branch
+        .clone()
+        .or_else(|| channel.branch.clone())
+        .unwrap_or_default();
+    Ok(Some(ContentUpdateInfo {
+        source: "github".to_string(),
+        content_type: "mods".to_string(),
+        project_id,
+        name: display_name.to_string(),
+        current_version_id: current_version_id.to_string(),
+        current_version_number: entry.version_number.clone(),
+        latest_version_id,
+        latest_version_number: artifact_version_label(&run, &branch),
+        enabled: entry.enabled,
+        target_worlds: vec![],
+        latest_file_name: None,
+        latest_download_url: Some(artifact.archive_download_url.clone()),
+        latest_hashes: HashMap::new(),
+        required_dependencies: vec![],
+        compatibility_status: Some("unverified".to_string()),
+        compatibility_notes: vec![format!(
+            "Development build from workflow run #{} ({}).",
+            run.run_number, artifact.name
+        )],
+    }))
+}
+
+/// Installs an artifact update found by [`check_github_artifact_update`],
+/// keeping the entry on its channel.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn install_github_artifact_update(
+    instance_dir: &Path,
+    lock: &mut Lockfile,
+    client: &Client,
+    update: &ContentUpdateInfo,
+    owner: &str,
+    repo_name: &str,
+    carried_pin: Option<String>,
+    channel: Option<GithubChannel>,
+) -> Result<LockEntry, String> {
+    let artifact_id = parse_github_artifact_id(&update.latest_version_id)
+        .ok_or_else(|| "Missing GitHub artifact id".to_string())?;
+    let download_url = update
+        .latest_download_url
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| {
+            format!(
+                "{GITHUB_API_BASE}/repos/{owner}/{repo_name}/actions/artifacts/{artifact_id}/zip"
+            )
+        });
+    let zip_bytes = download_artifact_zip(client, &download_url)?;
+    let query_hint = if update.name.trim().is_empty() {
+        repo_name
+    } else {
+        update.name.as_str()
+    };
+    let (filename, bytes) = extract_mod_jar_from_artifact(&zip_bytes, query_hint)?;
+    let project_key = github_project_key(owner, repo_name);
+    let fallback_name = if update.name.trim().is_empty() {
+        format!("{owner}/{repo_name}")
+    } else {
+        update.name.clone()
+    };
+    let mut new_entry = LockEntry {
+        source: "github".to_string(),
+        project_id: project_key.clone(),
+        version_id: update.latest_version_id.clone(),
+        name: canonical_lock_entry_name("mods", &filename, &fallback_name),
+        version_number: update.latest_version_number.clone(),
+        filename: filename.clone(),
+        content_type: "mods".to_string(),
+        target_scope: "instance".to_string(),
+        target_worlds: vec![],
+        pinned_version: carried_pin,
+        enabled: update.enabled,
+        hashes: ["sha512", "sha256", "sha1"]
+            .into_iter()
+            .map(|algorithm| (algorithm.to_string(), hash_bytes_hex(algorithm, &bytes)))
+            .collect(),
+        provider_candidates: vec![ProviderCandidate {
+            source: "github".to_string(),
+            project_id: project_key,
+            version_id: update.latest_version_id.clone(),
+            name: fallback_name,
+            version_number: update.latest_version_number.clone(),
+            confidence: None,
+            reason: Some("GitHub Actions artifact".to_string()),
+            verification_status: Some("verified".to_string()),
+        }],
+        local_analysis: None,
+        security_scan: None,
+        url_update: None,
+        github_channel: channel,
+    };
+    validate_lock_entry_uniqueness(lock, &new_entry)?;
+    new_entry.security_scan =
+        write_download_to_content_targets(instance_dir, "mods", &filename, &[], &bytes)?;
+    if !new_entry.enabled {
+        disable_mod_file(instance_dir, &new_entry.filename)?;
+    }
+    // The replaced build's hashes describe another file, so only this
+    // artifact's own hashes are kept after merging.
+    let hashes = new_entry.hashes.clone();
+    enforce_lock_entry_uniqueness(lock, instance_dir, &mut new_entry)?;
+    new_entry.hashes = hashes;
+    lock.entries.push(new_entry.clone());
+    Ok(new_entry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn release(id: u64, tag: &str, prerelease: bool, published_at: &str) -> GithubRelease {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "tag_name": tag,
+            "prerelease": prerelease,
+            "published_at": published_at,
+            "assets": [{
+                "name": format!("testmod-{tag}.jar"),
+                "browser_download_url": format!("https://example.invalid/{tag}.jar"),
+                "size": 1024
+            }]
+        }))
+        .expect("release")
+    }
+
+    fn repo() -> GithubRepository {
+        serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "name": "testmod",
+            "full_name": "owner/testmod",
+            "owner": { "login": "owner", "type": "User" }
+        }))
+        .expect("repo")
+    }
+
+    #[test]
+    fn channel_controls_prerelease_selection() {
+        let releases = vec![
+            release(1, "1.0.0", false, "2026-01-01T00:00:00Z"),
+            release(2, "1.1.0-beta.1", true, "2026-02-01T00:00:00Z"),
+        ];
+        let pick = |channel: Option<GithubChannel>| {
+            select_github_release_for_channel(
+                &repo(),
+                &releases,
+                "testmod",
+                None,
+                None,
+                None,
+                None,
+                channel.as_ref(),
+            )
+            .map(|selection| selection.release.id)
+        };
+        let channel = |kind: &str| {
+            normalize_github_channel(Some(GithubChannel {
+                kind: kind.to_string(),
+                branch: None,
+                workflow: None,
+            }))
+            .expect("channel")
+        };
+        assert_eq!(pick(None), Some(1));
+        assert_eq!(pick(channel("stable")), Some(1));
+        assert_eq!(pick(channel("pre-release")), Some(2));
+    }
+
+    #[test]
+    fn artifact_channel_requires_safe_branch() {
+        let artifact = |branch: Option<&str>| {
+            normalize_github_channel(Some(GithubChannel {
+                kind: "artifact".to_string(),
+                branch: branch.map(str::to_string),
+                workflow: Some("build.yml".to_string()),
+            }))
+        };
+        assert!(artifact(Some("main")).expect("valid").is_some());
+        assert!(artifact(None).is_err());
+        assert!(artifact(Some("../etc")).is_err());
+        assert!(normalize_github_channel(Some(GithubChannel {
+            kind: "nightly".to_string(),
+            branch: None,
+            workflow: None,
+        }))
+        .is_err());
+        assert_eq!(parse_github_artifact_id("gh_artifact:42"), Some(42));
+        assert_eq!(parse_github_artifact_id("gh_release:42"), None);
+    }
+
+    #[test]
+    fn unpacks_mod_jar_from_artifact_wrapper() {
+        let mut buffer = Cursor::new(Vec::new());
+        {
+            let mut zip = zip::ZipWriter::new(&mut buffer);
+            let options = zip::write::FileOptions::default();
+            for (name, body) in [
+                (
+                    "build/libs/testmod-1.2.0-sources.jar",
+                    b"sources".as_slice(),
+                ),
+                ("build/libs/testmod-1.2.0.jar", b"the mod".as_slice()),
+                ("../escape/testmod-1.2.0-dev.jar", b"dev".as_slice()),
+            ] {
+                zip.start_file(name, options).expect("start file");
+                zip.write_all(body).expect("write");
+            }
+            zip.finish().expect("finish");
+        }
+        let (filename, bytes) =
+            extract_mod_jar_from_artifact(buffer.get_ref(), "testmod").expect("extract");
+        assert_eq!(filename, "testmod-1.2.0.jar");
+        assert_eq!(bytes, b"the mod");
+    }
+}
@@ -0,0 +1,323 @@
+//! Replaces byte-identical content files across instances with hardlinks.
+//!
+//! Only mods, resource packs and shader packs are considered, and files are
+//! linked only within one filesystem. Launcher writes into instance folders go
+//! through [`detach_before_write`], so an update replaces that one file
+//! instead of editing every instance that shares it. Tools outside the
+//! launcher that edit a file in place still change every linked copy.
+
+use crate::content_store::{link_count, BlobDigest};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub(crate) const DEDUPE_CONTENT_DIRS: [&str; 3] = ["mods", "resourcepacks", "shaderpacks"];
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct DedupeReport {
+    pub linked_files: usize,
+    pub reclaimed_bytes: u64,
+    pub failed_files: usize,
+}
+
+struct ContentFile {
+    path: PathBuf,
+    len: u64,
+    device: u64,
+    identity: Option<(u64, u64)>,
+    links: u64,
+}
+
+/// `(device, inode)` for files on platforms that expose it; volume serial
+/// number and file index on Windows.
+#[cfg(unix)]
+pub(crate) fn file_identity(_path: &Path, meta: &fs::Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((meta.dev(), meta.ino()))
+}
+
+#[cfg(windows)]
+pub(crate) fn file_identity(path: &Path, _meta: &fs::Metadata) -> Option<(u64, u64)> {
+    let info = crate::content_store::windows_file_information(path)?;
+    let index = (u64::from(info.nFileIndexHigh) << 32) | u64::from(info.nFileIndexLow);
+    Some((u64::from(info.dwVolumeSerialNumber), index))
+}
+
+#[cfg(not(any(unix, windows)))]
+pub(crate) fn file_identity(_path: &Path, _meta: &fs::Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+/// Size of a file, or zero when another hardlink to it was already counted.
+pub(crate) fn unique_file_len(
+    path: &Path,
+    meta: &fs::Metadata,
+    seen: &mut HashSet<(u64, u64)>,
+) -> u64 {
+    if link_count(path, meta) > 1 {
+        if let Some(identity) = file_identity(path, meta) {
+            if !seen.insert(identity) {
+                return 0;
+            }
+        }
+    }
+    meta.len()
+}
+
+/// Unlinks `path` when it shares its data with other hardlinks, so the write
+/// that follows creates a private file rather than editing every linked copy.
+pub(crate) fn detach_before_write(path: &Path) -> Result<(), String> {
+    let Ok(meta) = fs::symlink_metadata(path) else {
+        return Ok(());
+    };
+    if meta.is_file() && link_count(path, &meta) > 1 {
+        fs::remove_file(path).map_err(|e| format!("remove '{}' failed: {e}", path.display()))?;
+    }
+    Ok(())
+}
+
+fn collect_content_files(instance_dirs: &[PathBuf]) -> Vec<ContentFile> {
+    let mut out = Vec::new();
+    for instance_dir in instance_dirs {
+        for dir in DEDUPE_CONTENT_DIRS {
+            let Ok(entries) = fs::read_dir(instance_dir.join(dir)) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let Ok(meta) = fs::symlink_metadata(&path) else {
+                    continue;
+                };
+                if !meta.is_file() || meta.len() == 0 {
+                    continue;
+                }
+                let identity = file_identity(&path, &meta);
+                let links = link_count(&path, &meta);
+                out.push(ContentFile {
+                    path,
+                    len: meta.len(),
+                    device: identity.map(|(dev, _)| dev).unwrap_or(0),
+                    identity,
+                    links,
+                });
+            }
+        }
+    }
+    out
+}
+
+fn same_file(a: &ContentFile, b: &ContentFile) -> bool {
+    a.identity.is_some() && a.identity == b.identity
+}
+
+/// Files of equal size on one device, the only ones that can be linked.
+fn size_groups(files: Vec<ContentFile>) -> Vec<Vec<ContentFile>> {
+    let mut groups: BTreeMap<(u64, u64), Vec<ContentFile>> = BTreeMap::new();
+    for file in files {
+        groups
+            .entry((file.device, file.len))
+            .or_default()
+            .push(file);
+    }
+    groups
+        .into_values()
+        .filter(|group| {
+            group
+                .iter()
+                .any(|file| group.iter().any(|other| !same_file(file, other)))
+        })
+        .collect()
+}
+
+/// Byte-identical content files, grouped per filesystem with the copy to
+/// keep first. Only files sharing a size with another file are hashed.
+fn identical_sets(instance_dirs: &[PathBuf]) -> Vec<Vec<ContentFile>> {
+    let mut sets = Vec::new();
+    for group in size_groups(collect_content_files(instance_dirs)) {
+        let mut by_hash: HashMap<String, Vec<ContentFile>> = HashMap::new();
+        for file in group {
+            if let Ok(digest) = BlobDigest::of_file(&file.path) {
+                by_hash.entry(digest.sha512).or_default().push(file);
+            }
+        }
+        for mut files in by_hash.into_values() {
+            // The most-linked copy is likely already shared with the content store.
+            files.sort_by(|a, b| b.links.cmp(&a.links).then_with(|| a.path.cmp(&b.path)));
+            sets.push(files);
+        }
+    }
+    sets
+}
+
+/// Duplicates a dedupe run would link and the bytes that would free.
+pub(crate) fn estimate_duplicates(instance_dirs: &[PathBuf]) -> (usize, u64) {
+    let mut count = 0usize;
+    let mut bytes = 0u64;
+    for files in identical_sets(instance_dirs) {
+        let Some((canonical, rest)) = files.split_first() else {
+            continue;
+        };
+        let mut seen = HashSet::new();
+        for file in rest {
+            if same_file(file, canonical) || file.identity.is_some_and(|id| !seen.insert(id)) {
+                continue;
+            }
+            count += 1;
+            if file.links <= 1 {
+                bytes = bytes.saturating_add(file.len);
+            }
+        }
+    }
+    (count, bytes)
+}
+
+/// Points `duplicate` at the data of `canonical` via a sibling temp link.
+fn replace_with_link(canonical: &Path, duplicate: &Path) -> Result<(), String> {
+    let name = duplicate
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let temp = duplicate.with_file_name(format!(".{name}.openjar-link"));
+    let _ = fs::remove_file(&temp);
+    fs::hard_link(canonical, &temp).map_err(|e| {
+        format!(
+            "link '{}' -> '{}' failed: {e}",
+            canonical.display(),
+            temp.display()
+        )
+    })?;
+    fs::rename(&temp, duplicate).map_err(|e| {
+        let _ = fs::remove_file(&temp);
+        format!("replace '{}' failed: {e}", duplicate.display())
+    })
+}
+
+/// Hashes same-size content files across `instance_dirs` and hardlinks every
+/// byte-identical copy to one canonical file per filesystem.
+pub(crate) fn dedupe_instance_content(instance_dirs: &[PathBuf]) -> DedupeReport {
+    let mut report = DedupeReport::default();
+    for files in identical_sets(instance_dirs) {
+        let Some((canonical, rest)) = files.split_first() else {
+            continue;
+        };
+        // Data is only freed once every link to a duplicate's inode is replaced.
+        let mut replaced_links: HashMap<(u64, u64), u64> = HashMap::new();
+        for file in rest {
+            if same_file(file, canonical) {
+                continue;
+            }
+            match replace_with_link(&canonical.path, &file.path) {
+                Ok(()) => {
+                    report.linked_files += 1;
+                    let freed = match file.identity {
+                        Some(id) if file.links > 1 => {
+                            let replaced = replaced_links.entry(id).or_insert(0);
+                            *replaced += 1;
+                            *replaced == file.links
+                        }
+                        _ => true,
+                    };
+                    if freed {
+                        report.reclaimed_bytes = report.reclaimed_bytes.saturating_add(file.len);
+                    }
+                }
+                Err(_) => report.failed_files += 1,
+            }
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{temp_root, write_file};
+
+    #[test]
+    fn identical_files_are_linked_and_reclaimed_once() {
+        let root = temp_root("link");
+        let dirs = vec![root.join("a"), root.join("b"), root.join("c")];
+        write_file(&dirs[0].join("mods").join("lib.jar"), b"same library");
+        write_file(&dirs[1].join("mods").join("lib-copy.jar"), b"same library");
+        write_file(
+            &dirs[2].join("resourcepacks").join("pack.zip"),
+            b"same library",
+        );
+        write_file(&dirs[2].join("mods").join("other.jar"), b"diff library");
+        write_file(&dirs[2].join("config").join("lib.jar"), b"same library");
+
+        // Names do not matter: the renamed copy and the pack are both counted.
+        assert_eq!(
+            estimate_duplicates(&dirs),
+            (2, 2 * b"same library".len() as u64)
+        );
+        let report = dedupe_instance_content(&dirs);
+        assert_eq!(report.linked_files, 2);
+        assert_eq!(report.failed_files, 0);
+        assert_eq!(report.reclaimed_bytes, 2 * b"same library".len() as u64);
+        assert_eq!(
+            fs::read(dirs[2].join("mods").join("other.jar")).expect("read"),
+            b"diff library"
+        );
+
+        let again = dedupe_instance_content(&dirs);
+        assert_eq!(again, DedupeReport::default());
+        #[cfg(any(unix, windows))]
+        {
+            let copy = dirs[1].join("mods").join("lib-copy.jar");
+            assert_eq!(link_count(&copy, &fs::metadata(&copy).unwrap()), 3);
+            // config/ is never touched.
+            let config = dirs[2].join("config").join("lib.jar");
+            assert_eq!(link_count(&config, &fs::metadata(&config).unwrap()), 1);
+        }
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn estimate_drops_to_zero_once_linked() {
+        let root = temp_root("estimate");
+        let dirs = vec![root.join("a"), root.join("b")];
+        write_file(&dirs[0].join("mods").join("lib.jar"), b"library");
+        write_file(&dirs[1].join("mods").join("lib.jar"), b"library");
+        assert_eq!(estimate_duplicates(&dirs), (1, b"library".len() as u64));
+        dedupe_instance_content(&dirs);
+        assert_eq!(estimate_duplicates(&dirs).1, 0);
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn detached_write_leaves_other_links_untouched() {
+        let root = temp_root("detach");
+        let dirs = vec![root.join("a"), root.join("b")];
+        let first = dirs[0].join("mods").join("lib.jar");
+        let second = dirs[1].join("mods").join("lib.jar");
+        write_file(&first, b"library");
+        write_file(&second, b"library");
+        dedupe_instance_content(&dirs);
+
+        detach_before_write(&second).expect("detach");
+        fs::write(&second, b"patched").expect("write patched");
+        assert_eq!(fs::read(&first).expect("read first"), b"library");
+        assert_eq!(fs::read(&second).expect("read second"), b"patched");
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[cfg(any(unix, windows))]
+    #[test]
+    fn unique_len_counts_linked_bytes_once() {
+        let root = temp_root("unique");
+        let first = root.join("a.jar");
+        write_file(&first, b"linked bytes");
+        fs::hard_link(&first, root.join("b.jar")).expect("hard link");
+        let mut seen = HashSet::new();
+        let total = ["a.jar", "b.jar"]
+            .iter()
+            .map(|name| {
+                let path = root.join(name);
+                unique_file_len(&path, &fs::metadata(&path).unwrap(), &mut seen)
+            })
+            .sum::<u64>();
+        assert_eq!(total, b"linked bytes".len() as u64);
+        let _ = fs::remove_dir_all(root);
+    }
+}
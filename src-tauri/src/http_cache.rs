@@ -0,0 +1,408 @@
+//! On-disk cache for provider metadata responses (Modrinth, CurseForge,
+//! GitHub), kept under `launcher_cache_dir` so it survives restarts.
+//!
+//! Entries are fresh for a per-endpoint TTL. Once expired they are revalidated
+//! with `If-None-Match`/`If-Modified-Since`; a 304 refreshes the entry without
+//! a body. When the network is unreachable, entries up to [`MAX_STALE`] old are
+//! served instead of failing.
+
+use crate::sha256_bytes_hex;
+use reqwest::blocking::Client;
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{OnceLock, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub(crate) const CACHE_DIR_NAME: &str = "http_metadata";
+/// Oldest response served when the provider cannot be reached.
+pub(crate) const MAX_STALE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+const DEFAULT_TTL: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedResponse {
+    url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_modified: Option<String>,
+    /// Unix seconds of the last successful fetch or revalidation.
+    fetched_at: u64,
+    body: String,
+}
+
+fn configured_root() -> &'static RwLock<Option<PathBuf>> {
+    static ROOT: OnceLock<RwLock<Option<PathBuf>>> = OnceLock::new();
+    ROOT.get_or_init(|| RwLock::new(None))
+}
+
+/// Points the shared cache at `<launcher cache>/http_metadata`. Until this is
+/// called (e.g. in tests) requests go straight to the network.
+pub(crate) fn set_cache_root(launcher_cache_dir: &Path) {
+    if let Ok(mut guard) = configured_root().write() {
+        *guard = Some(launcher_cache_dir.join(CACHE_DIR_NAME));
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// How long a response stays fresh before it is revalidated.
+pub(crate) fn ttl_for_url(url: &str) -> Duration {
+    let path = url
+        .split_once("://")
+        .map(|(_, rest)| rest)
+        .unwrap_or(url)
+        .split('?')
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let minutes = |m: u64| Duration::from_secs(m * 60);
+    if path.contains("/actions/") {
+        minutes(5)
+    } else if path.contains("/search") {
+        minutes(10)
+    } else if path.ends_with("/releases") {
+        minutes(15)
+    } else if path.contains("/readme") || path.ends_with("/description") {
+        minutes(6 * 60)
+    } else if path.contains("/version/") || path.contains("/files/") {
+        // A published version or file is immutable apart from moderation.
+        minutes(24 * 60)
+    } else if path.ends_with("/version") || path.ends_with("/files") {
+        minutes(15)
+    } else if path.contains("/project/") || path.contains("/mods/") || path.contains("/repos/") {
+        minutes(60)
+    } else {
+        DEFAULT_TTL
+    }
+}
+
+pub(crate) struct HttpMetadataCache {
+    root: PathBuf,
+}
+
+impl HttpMetadataCache {
+    pub(crate) fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    pub(crate) fn configured() -> Option<Self> {
+        configured_root()
+            .read()
+            .ok()
+            .and_then(|guard| guard.clone())
+            .map(Self::new)
+    }
+
+    fn path_for(&self, url: &str) -> PathBuf {
+        let key = sha256_bytes_hex(url.as_bytes());
+        self.root.join(&key[..2]).join(format!("{key}.json"))
+    }
+
+    fn read(&self, url: &str) -> Option<CachedResponse> {
+        let raw = fs::read_to_string(self.path_for(url)).ok()?;
+        let cached = serde_json::from_str::<CachedResponse>(&raw).ok()?;
+        (cached.url == url).then_some(cached)
+    }
+
+    fn write(&self, cached: &CachedResponse) {
+        let path = self.path_for(&cached.url);
+        let Some(parent) = path.parent() else {
+            return;
+        };
+        if fs::create_dir_all(parent).is_err() {
+            return;
+        }
+        let Ok(raw) = serde_json::to_vec(cached) else {
+            return;
+        };
+        let tmp = path.with_extension(format!("tmp-{}", uuid::Uuid::new_v4()));
+        if fs::write(&tmp, raw).is_ok() && fs::rename(&tmp, &path).is_err() {
+            let _ = fs::remove_file(&tmp);
+        }
+    }
+
+    fn age(cached: &CachedResponse) -> Duration {
+        Duration::from_secs(now_secs().saturating_sub(cached.fetched_at))
+    }
+
+    /// Body of an entry still within its TTL.
+    pub(crate) fn fresh(&self, url: &str) -> Option<String> {
+        self.read(url)
+            .filter(|cached| Self::age(cached) <= ttl_for_url(url))
+            .map(|cached| cached.body)
+    }
+
+    /// Body of an expired entry that is still acceptable while offline.
+    pub(crate) fn stale(&self, url: &str) -> Option<String> {
+        self.read(url)
+            .filter(|cached| Self::age(cached) <= MAX_STALE)
+            .map(|cached| cached.body)
+    }
+
+    /// Conditional request headers for a cached entry.
+    pub(crate) fn validators(&self, url: &str) -> Vec<(&'static str, String)> {
+        let Some(cached) = self.read(url) else {
+            return vec![];
+        };
+        let mut out = Vec::new();
+        if let Some(etag) = cached.etag {
+            out.push(("If-None-Match", etag));
+        }
+        if let Some(last_modified) = cached.last_modified {
+            out.push(("If-Modified-Since", last_modified));
+        }
+        out
+    }
+
+    pub(crate) fn store(&self, url: &str, body: &str, headers: &HeaderMap) {
+        if body.trim().is_empty() {
+            return;
+        }
+        self.write(&CachedResponse {
+            url: url.to_string(),
+            etag: header_text(headers, "etag"),
+            last_modified: header_text(headers, "last-modified"),
+            fetched_at: now_secs(),
+            body: body.to_string(),
+        });
+    }
+
+    /// Handles a 304: restarts the entry's TTL and returns the cached body.
+    pub(crate) fn revalidated(&self, url: &str, headers: &HeaderMap) -> Option<String> {
+        let mut cached = self.read(url)?;
+        cached.fetched_at = now_secs();
+        if let Some(etag) = header_text(headers, "etag") {
+            cached.etag = Some(etag);
+        }
+        if let Some(last_modified) = header_text(headers, "last-modified") {
+            cached.last_modified = Some(last_modified);
+        }
+        self.write(&cached);
+        Some(cached.body)
+    }
+
+    /// Removes entries too old to be served even offline. Returns the count.
+    pub(crate) fn prune(&self) -> usize {
+        let Ok(shards) = fs::read_dir(&self.root) else {
+            return 0;
+        };
+        let mut removed = 0usize;
+        for shard in shards.flatten() {
+            let Ok(files) = fs::read_dir(shard.path()) else {
+                continue;
+            };
+            for file in files.flatten() {
+                let path = file.path();
+                let expired = fs::read_to_string(&path)
+                    .ok()
+                    .and_then(|raw| serde_json::from_str::<CachedResponse>(&raw).ok())
+                    .map(|cached| Self::age(&cached) > MAX_STALE)
+                    .unwrap_or(true);
+                if expired && fs::remove_file(&path).is_ok() {
+                    removed += 1;
+                }
+            }
+        }
+        removed
+    }
+}
+
+fn header_text(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// GET through the shared cache. Errors read `"{error_label}: {err}"` or
+/// `"{error_label} with status {status}"`.
+pub(crate) fn get_text(
+    client: &Client,
+    url: &str,
+    headers: &[(&str, &str)],
+    error_label: &str,
+) -> Result<String, String> {
+    get_text_with(
+        HttpMetadataCache::configured().as_ref(),
+        client,
+        url,
+        headers,
+        error_label,
+    )
+}
+
+pub(crate) fn get_text_with(
+    cache: Option<&HttpMetadataCache>,
+    client: &Client,
+    url: &str,
+    headers: &[(&str, &str)],
+    error_label: &str,
+) -> Result<String, String> {
+    if let Some(body) = cache.and_then(|cache| cache.fresh(url)) {
+        return Ok(body);
+    }
+    let mut request = client.get(url);
+    for (name, value) in headers {
+        request = request.header(*name, *value);
+    }
+    for (name, value) in cache.map(|cache| cache.validators(url)).unwrap_or_default() {
+        request = request.header(name, value);
+    }
+    let response = match request.send() {
+        Ok(response) => response,
+        Err(err) => {
+            return cache
+                .and_then(|cache| cache.stale(url))
+                .ok_or_else(|| format!("{error_label}: {err}"));
+        }
+    };
+    let status = response.status();
+    if status == StatusCode::NOT_MODIFIED {
+        return cache
+            .and_then(|cache| cache.revalidated(url, response.headers()))
+            .ok_or_else(|| format!("{error_label} with status {status}"));
+    }
+    if !status.is_success() {
+        if status.is_server_error() {
+            if let Some(body) = cache.and_then(|cache| cache.stale(url)) {
+                return Ok(body);
+            }
+        }
+        return Err(format!("{error_label} with status {status}"));
+    }
+    let response_headers = response.headers().clone();
+    let body = response.text().map_err(|e| format!("{error_label}: {e}"))?;
+    if let Some(cache) = cache {
+        cache.store(url, &body, &response_headers);
+    }
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_root;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    fn temp_cache(label: &str) -> HttpMetadataCache {
+        HttpMetadataCache::new(temp_root(&format!("http-cache-{label}")))
+    }
+
+    /// Serves `{"v":1}` with an ETag and answers matching revalidations with
+    /// 304. Returns the base URL and counters for 200 and 304 responses.
+    fn etag_server() -> (String, Arc<AtomicUsize>, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+        let base = format!("http://{}", listener.local_addr().expect("addr"));
+        let full = Arc::new(AtomicUsize::new(0));
+        let not_modified = Arc::new(AtomicUsize::new(0));
+        let (full_ref, not_modified_ref) = (full.clone(), not_modified.clone());
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let mut reader = BufReader::new(&stream);
+                let mut conditional = false;
+                loop {
+                    let mut line = String::new();
+                    if reader.read_line(&mut line).unwrap_or(0) == 0 || line.trim().is_empty() {
+                        break;
+                    }
+                    if line
+                        .to_ascii_lowercase()
+                        .starts_with("if-none-match: \"v1\"")
+                    {
+                        conditional = true;
+                    }
+                }
+                let mut stream = &stream;
+                let response = if conditional {
+                    not_modified_ref.fetch_add(1, Ordering::SeqCst);
+                    "HTTP/1.1 304 Not Modified\r\nETag: \"v1\"\r\nConnection: close\r\n\r\n"
+                        .to_string()
+                } else {
+                    full_ref.fetch_add(1, Ordering::SeqCst);
+                    "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Length: 7\r\nConnection: close\r\n\r\n{\"v\":1}"
+                        .to_string()
+                };
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+        (base, full, not_modified)
+    }
+
+    fn expire(cache: &HttpMetadataCache, url: &str, age: Duration) {
+        let mut cached = cache.read(url).expect("cached entry");
+        cached.fetched_at = now_secs().saturating_sub(age.as_secs());
+        cache.write(&cached);
+    }
+
+    #[test]
+    fn revalidates_expired_entries_with_etag() {
+        let cache = temp_cache("etag");
+        let (base, full, not_modified) = etag_server();
+        let url = format!("{base}/v2/project/abc/version");
+        let client = Client::new();
+
+        let first = get_text_with(Some(&cache), &client, &url, &[], "fetch").expect("first");
+        let second = get_text_with(Some(&cache), &client, &url, &[], "fetch").expect("second");
+        assert_eq!(first, "{\"v\":1}");
+        assert_eq!(second, first);
+        assert_eq!(
+            full.load(Ordering::SeqCst),
+            1,
+            "fresh entry skips the network"
+        );
+
+        expire(&cache, &url, ttl_for_url(&url) + Duration::from_secs(5));
+        let third = get_text_with(Some(&cache), &client, &url, &[], "fetch").expect("third");
+        assert_eq!(third, first);
+        assert_eq!(full.load(Ordering::SeqCst), 1);
+        assert_eq!(not_modified.load(Ordering::SeqCst), 1);
+        assert!(cache.fresh(&url).is_some(), "304 restarts the TTL");
+        let _ = fs::remove_dir_all(&cache.root);
+    }
+
+    #[test]
+    fn serves_stale_entries_when_offline() {
+        let cache = temp_cache("offline");
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+        let url = format!(
+            "http://{}/v1/mods/1/files",
+            listener.local_addr().expect("addr")
+        );
+        drop(listener);
+        cache.store(&url, "{\"data\":[]}", &HeaderMap::new());
+        let client = Client::new();
+
+        expire(&cache, &url, Duration::from_secs(3 * 24 * 60 * 60));
+        let body = get_text_with(Some(&cache), &client, &url, &[], "lookup").expect("stale");
+        assert_eq!(body, "{\"data\":[]}");
+
+        expire(&cache, &url, MAX_STALE + Duration::from_secs(60));
+        let err = get_text_with(Some(&cache), &client, &url, &[], "lookup").expect_err("too old");
+        assert!(err.starts_with("lookup: "));
+        assert_eq!(cache.prune(), 1);
+        let _ = fs::remove_dir_all(&cache.root);
+    }
+
+    #[test]
+    fn ttls_follow_endpoint_kind() {
+        let releases = ttl_for_url("https://api.github.com/repos/a/b/releases?per_page=30");
+        let repo = ttl_for_url("https://api.github.com/repos/a/b");
+        let version = ttl_for_url("https://api.modrinth.com/v2/version/abc");
+        let runs = ttl_for_url("https://api.github.com/repos/a/b/actions/runs?branch=main");
+        assert!(runs < releases);
+        assert!(releases < repo);
+        assert!(repo < version);
+    }
+}
@@ -0,0 +1,591 @@
+//! Minimal JVM class-file reader.
+//!
+//! Only the parts needed by jar analysis are decoded: the constant pool, the
+//! class/method names and their (visible and invisible) annotations. Code
+//! attributes and everything else are skipped by length.
+
+const CLASS_MAGIC: u32 = 0xCAFE_BABE;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Constant {
+    Empty,
+    Utf8(String),
+    Integer(i32),
+    Float,
+    Long(i64),
+    Double,
+    Class(u16),
+    String(u16),
+    FieldRef(u16, u16),
+    MethodRef(u16, u16),
+    InterfaceMethodRef(u16, u16),
+    NameAndType(u16, u16),
+    MethodHandle,
+    MethodType,
+    Dynamic,
+    InvokeDynamic,
+    Module,
+    Package,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum ElementValue {
+    Int(i64),
+    String(String),
+    Enum {
+        type_name: String,
+        const_name: String,
+    },
+    Class(String),
+    Annotation(Annotation),
+    Array(Vec<ElementValue>),
+    Other,
+}
+
+impl ElementValue {
+    /// Flattens a single value or an array of values into plain strings.
+    pub(crate) fn strings(&self) -> Vec<String> {
+        match self {
+            ElementValue::String(value) | ElementValue::Class(value) => vec![value.clone()],
+            ElementValue::Array(items) => items.iter().flat_map(|item| item.strings()).collect(),
+            _ => vec![],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Annotation {
+    pub type_descriptor: String,
+    pub values: Vec<(String, ElementValue)>,
+}
+
+impl Annotation {
+    pub(crate) fn value(&self, name: &str) -> Option<&ElementValue> {
+        self.values
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct MemberInfo {
+    pub name: String,
+    pub annotations: Vec<Annotation>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ClassFile {
+    pub constant_pool: Vec<Constant>,
+    pub name: String,
+    pub super_name: Option<String>,
+    pub annotations: Vec<Annotation>,
+    pub methods: Vec<MemberInfo>,
+}
+
+impl ClassFile {
+    pub(crate) fn utf8(&self, index: u16) -> Option<&str> {
+        match self.constant_pool.get(index as usize) {
+            Some(Constant::Utf8(value)) => Some(value.as_str()),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn class_name(&self, index: u16) -> Option<&str> {
+        match self.constant_pool.get(index as usize) {
+            Some(Constant::Class(name_index)) => self.utf8(*name_index),
+            _ => None,
+        }
+    }
+
+    /// Values of `ldc`-able string constants (not every UTF-8 entry).
+    pub(crate) fn string_constants(&self) -> Vec<&str> {
+        self.constant_pool
+            .iter()
+            .filter_map(|constant| match constant {
+                Constant::String(index) => self.utf8(*index),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Every class referenced from the constant pool, in internal (slash) form.
+    pub(crate) fn referenced_classes(&self) -> Vec<&str> {
+        self.constant_pool
+            .iter()
+            .filter_map(|constant| match constant {
+                Constant::Class(index) => self.utf8(*index),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Referenced methods as `(owner, name, descriptor)` triples.
+    pub(crate) fn method_refs(&self) -> Vec<(&str, &str, &str)> {
+        self.constant_pool
+            .iter()
+            .filter_map(|constant| match constant {
+                Constant::MethodRef(class_index, nat_index)
+                | Constant::InterfaceMethodRef(class_index, nat_index) => {
+                    let owner = self.class_name(*class_index)?;
+                    let (name, descriptor) = match self.constant_pool.get(*nat_index as usize) {
+                        Some(Constant::NameAndType(name, descriptor)) => {
+                            (self.utf8(*name)?, self.utf8(*descriptor)?)
+                        }
+                        _ => return None,
+                    };
+                    Some((owner, name, descriptor))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| "class file truncated".to_string())?;
+        let out = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(out)
+    }
+
+    fn u1(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u2(&mut self) -> Result<u16, String> {
+        let raw = self.take(2)?;
+        Ok(u16::from_be_bytes([raw[0], raw[1]]))
+    }
+
+    fn u4(&mut self) -> Result<u32, String> {
+        let raw = self.take(4)?;
+        Ok(u32::from_be_bytes([raw[0], raw[1], raw[2], raw[3]]))
+    }
+}
+
+fn read_constant_pool(reader: &mut Reader<'_>) -> Result<Vec<Constant>, String> {
+    let count = reader.u2()? as usize;
+    let mut pool = Vec::with_capacity(count);
+    pool.push(Constant::Empty);
+    while pool.len() < count {
+        let tag = reader.u1()?;
+        let constant = match tag {
+            1 => {
+                let len = reader.u2()? as usize;
+                Constant::Utf8(String::from_utf8_lossy(reader.take(len)?).into_owned())
+            }
+            3 => Constant::Integer(reader.u4()? as i32),
+            4 => {
+                reader.u4()?;
+                Constant::Float
+            }
+            5 => {
+                let high = reader.u4()? as u64;
+                let low = reader.u4()? as u64;
+                Constant::Long(((high << 32) | low) as i64)
+            }
+            6 => {
+                reader.take(8)?;
+                Constant::Double
+            }
+            7 => Constant::Class(reader.u2()?),
+            8 => Constant::String(reader.u2()?),
+            9 => Constant::FieldRef(reader.u2()?, reader.u2()?),
+            10 => Constant::MethodRef(reader.u2()?, reader.u2()?),
+            11 => Constant::InterfaceMethodRef(reader.u2()?, reader.u2()?),
+            12 => Constant::NameAndType(reader.u2()?, reader.u2()?),
+            15 => {
+                reader.take(3)?;
+                Constant::MethodHandle
+            }
+            16 => {
+                reader.u2()?;
+                Constant::MethodType
+            }
+            17 => {
+                reader.take(4)?;
+                Constant::Dynamic
+            }
+            18 => {
+                reader.take(4)?;
+                Constant::InvokeDynamic
+            }
+            19 => {
+                reader.u2()?;
+                Constant::Module
+            }
+            20 => {
+                reader.u2()?;
+                Constant::Package
+            }
+            other => return Err(format!("unknown constant pool tag {other}")),
+        };
+        let wide = matches!(constant, Constant::Long(_) | Constant::Double);
+        pool.push(constant);
+        if wide {
+            pool.push(Constant::Empty);
+        }
+    }
+    Ok(pool)
+}
+
+fn pool_utf8(pool: &[Constant], index: u16) -> String {
+    match pool.get(index as usize) {
+        Some(Constant::Utf8(value)) => value.clone(),
+        _ => String::new(),
+    }
+}
+
+fn read_element_value(reader: &mut Reader<'_>, pool: &[Constant]) -> Result<ElementValue, String> {
+    let tag = reader.u1()?;
+    let value = match tag {
+        b'B' | b'C' | b'I' | b'S' | b'Z' => match pool.get(reader.u2()? as usize) {
+            Some(Constant::Integer(value)) => ElementValue::Int(*value as i64),
+            _ => ElementValue::Other,
+        },
+        b'J' => match pool.get(reader.u2()? as usize) {
+            Some(Constant::Long(value)) => ElementValue::Int(*value),
+            _ => ElementValue::Other,
+        },
+        b'D' | b'F' => {
+            reader.u2()?;
+            ElementValue::Other
+        }
+        b's' => ElementValue::String(pool_utf8(pool, reader.u2()?)),
+        b'e' => ElementValue::Enum {
+            type_name: pool_utf8(pool, reader.u2()?),
+            const_name: pool_utf8(pool, reader.u2()?),
+        },
+        b'c' => ElementValue::Class(pool_utf8(pool, reader.u2()?)),
+        b'@' => ElementValue::Annotation(read_annotation(reader, pool)?),
+        b'[' => {
+            let count = reader.u2()? as usize;
+            let mut items = Vec::with_capacity(count.min(256));
+            for _ in 0..count {
+                items.push(read_element_value(reader, pool)?);
+            }
+            ElementValue::Array(items)
+        }
+        other => {
+            return Err(format!(
+                "unknown annotation element tag '{}'",
+                other as char
+            ))
+        }
+    };
+    Ok(value)
+}
+
+fn read_annotation(reader: &mut Reader<'_>, pool: &[Constant]) -> Result<Annotation, String> {
+    let type_descriptor = pool_utf8(pool, reader.u2()?);
+    let pairs = reader.u2()? as usize;
+    let mut values = Vec::with_capacity(pairs.min(64));
+    for _ in 0..pairs {
+        let name = pool_utf8(pool, reader.u2()?);
+        values.push((name, read_element_value(reader, pool)?));
+    }
+    Ok(Annotation {
+        type_descriptor,
+        values,
+    })
+}
+
+/// Reads an attribute table, decoding annotation attributes and skipping the rest.
+fn read_attributes(reader: &mut Reader<'_>, pool: &[Constant]) -> Result<Vec<Annotation>, String> {
+    let count = reader.u2()? as usize;
+    let mut annotations = Vec::new();
+    for _ in 0..count {
+        let name = pool_utf8(pool, reader.u2()?);
+        let len = reader.u4()? as usize;
+        let body = reader.take(len)?;
+        if name != "RuntimeVisibleAnnotations" && name != "RuntimeInvisibleAnnotations" {
+            continue;
+        }
+        let mut inner = Reader {
+            bytes: body,
+            pos: 0,
+        };
+        let total = inner.u2()? as usize;
+        for _ in 0..total {
+            annotations.push(read_annotation(&mut inner, pool)?);
+        }
+    }
+    Ok(annotations)
+}
+
+fn read_members(reader: &mut Reader<'_>, pool: &[Constant]) -> Result<Vec<MemberInfo>, String> {
+    let count = reader.u2()? as usize;
+    let mut members = Vec::with_capacity(count.min(1024));
+    for _ in 0..count {
+        reader.u2()?;
+        let name = pool_utf8(pool, reader.u2()?);
+        reader.u2()?;
+        let annotations = read_attributes(reader, pool)?;
+        members.push(MemberInfo { name, annotations });
+    }
+    Ok(members)
+}
+
+pub(crate) fn parse_class_file(bytes: &[u8]) -> Result<ClassFile, String> {
+    let mut reader = Reader { bytes, pos: 0 };
+    if reader.u4()? != CLASS_MAGIC {
+        return Err("not a class file".to_string());
+    }
+    reader.u2()?;
+    reader.u2()?;
+    let constant_pool = read_constant_pool(&mut reader)?;
+    reader.u2()?;
+    let this_index = reader.u2()?;
+    let super_index = reader.u2()?;
+    let interfaces = reader.u2()? as usize;
+    reader.take(interfaces * 2)?;
+    read_members(&mut reader, &constant_pool)?;
+    let methods = read_members(&mut reader, &constant_pool)?;
+    let annotations = read_attributes(&mut reader, &constant_pool)?;
+
+    let mut class = ClassFile {
+        constant_pool,
+        name: String::new(),
+        super_name: None,
+        annotations,
+        methods,
+    };
+    class.name = class.class_name(this_index).unwrap_or_default().to_string();
+    class.super_name = class.class_name(super_index).map(str::to_string);
+    Ok(class)
+}
+
+#[cfg(test)]
+pub(crate) mod test_classes {
+    //! Hand-assembles tiny class files so analysis code can be tested without
+    //! checking compiled fixtures into the repository.
+
+    pub(crate) enum TestValue<'a> {
+        Str(&'a str),
+        Class(&'a str),
+        StrArray(&'a [&'a str]),
+        ClassArray(&'a [&'a str]),
+    }
+
+    pub(crate) struct TestAnnotation<'a> {
+        pub descriptor: &'a str,
+        pub values: Vec<(&'a str, TestValue<'a>)>,
+    }
+
+    pub(crate) struct TestMethod<'a> {
+        pub name: &'a str,
+        pub descriptor: &'a str,
+        pub annotations: Vec<TestAnnotation<'a>>,
+    }
+
+    #[derive(Default)]
+    struct Pool {
+        bytes: Vec<u8>,
+        count: u16,
+    }
+
+    impl Pool {
+        fn push(&mut self, raw: Vec<u8>) -> u16 {
+            self.count += 1;
+            self.bytes.extend(raw);
+            self.count
+        }
+
+        fn utf8(&mut self, value: &str) -> u16 {
+            let mut raw = vec![1u8];
+            raw.extend((value.len() as u16).to_be_bytes());
+            raw.extend(value.as_bytes());
+            self.push(raw)
+        }
+
+        fn class(&mut self, internal_name: &str) -> u16 {
+            let name = self.utf8(internal_name);
+            let mut raw = vec![7u8];
+            raw.extend(name.to_be_bytes());
+            self.push(raw)
+        }
+
+        fn string(&mut self, value: &str) -> u16 {
+            let utf8 = self.utf8(value);
+            let mut raw = vec![8u8];
+            raw.extend(utf8.to_be_bytes());
+            self.push(raw)
+        }
+
+        fn method_ref(&mut self, owner: &str, name: &str, descriptor: &str) -> u16 {
+            let class = self.class(owner);
+            let name = self.utf8(name);
+            let descriptor = self.utf8(descriptor);
+            let mut nat = vec![12u8];
+            nat.extend(name.to_be_bytes());
+            nat.extend(descriptor.to_be_bytes());
+            let nat = self.push(nat);
+            let mut raw = vec![10u8];
+            raw.extend(class.to_be_bytes());
+            raw.extend(nat.to_be_bytes());
+            self.push(raw)
+        }
+    }
+
+    fn encode_value(pool: &mut Pool, value: &TestValue<'_>, out: &mut Vec<u8>) {
+        match value {
+            TestValue::Str(raw) => {
+                out.push(b's');
+                out.extend(pool.utf8(raw).to_be_bytes());
+            }
+            TestValue::Class(raw) => {
+                out.push(b'c');
+                out.extend(pool.utf8(raw).to_be_bytes());
+            }
+            TestValue::StrArray(items) => {
+                out.push(b'[');
+                out.extend((items.len() as u16).to_be_bytes());
+                for item in items.iter() {
+                    encode_value(pool, &TestValue::Str(item), out);
+                }
+            }
+            TestValue::ClassArray(items) => {
+                out.push(b'[');
+                out.extend((items.len() as u16).to_be_bytes());
+                for item in items.iter() {
+                    encode_value(pool, &TestValue::Class(item), out);
+                }
+            }
+        }
+    }
+
+    fn encode_annotations(pool: &mut Pool, annotations: &[TestAnnotation<'_>]) -> Vec<u8> {
+        if annotations.is_empty() {
+            return 0u16.to_be_bytes().to_vec();
+        }
+        let mut body = (annotations.len() as u16).to_be_bytes().to_vec();
+        for annotation in annotations {
+            body.extend(pool.utf8(annotation.descriptor).to_be_bytes());
+            body.extend((annotation.values.len() as u16).to_be_bytes());
+            for (name, value) in &annotation.values {
+                body.extend(pool.utf8(name).to_be_bytes());
+                encode_value(pool, value, &mut body);
+            }
+        }
+        let mut out = 1u16.to_be_bytes().to_vec();
+        out.extend(pool.utf8("RuntimeInvisibleAnnotations").to_be_bytes());
+        out.extend((body.len() as u32).to_be_bytes());
+        out.extend(body);
+        out
+    }
+
+    /// Builds a class with the given annotations, methods, string constants
+    /// and method references (`owner`, `name`, `descriptor`).
+    pub(crate) fn build_class(
+        name: &str,
+        annotations: &[TestAnnotation<'_>],
+        methods: &[TestMethod<'_>],
+        strings: &[&str],
+        method_refs: &[(&str, &str, &str)],
+    ) -> Vec<u8> {
+        let mut pool = Pool::default();
+        let this_class = pool.class(name);
+        let super_class = pool.class("java/lang/Object");
+        for value in strings {
+            pool.string(value);
+        }
+        for (owner, method, descriptor) in method_refs {
+            pool.method_ref(owner, method, descriptor);
+        }
+        let mut tail = Vec::new();
+        tail.extend(0x0021u16.to_be_bytes());
+        tail.extend(this_class.to_be_bytes());
+        tail.extend(super_class.to_be_bytes());
+        tail.extend(0u16.to_be_bytes());
+        tail.extend(0u16.to_be_bytes());
+        tail.extend((methods.len() as u16).to_be_bytes());
+        for method in methods {
+            tail.extend(0x0001u16.to_be_bytes());
+            tail.extend(pool.utf8(method.name).to_be_bytes());
+            tail.extend(pool.utf8(method.descriptor).to_be_bytes());
+            tail.extend(encode_annotations(&mut pool, &method.annotations));
+        }
+        tail.extend(encode_annotations(&mut pool, annotations));
+
+        let mut out = Vec::new();
+        out.extend(0xCAFE_BABEu32.to_be_bytes());
+        out.extend(0u16.to_be_bytes());
+        out.extend(61u16.to_be_bytes());
+        out.extend((pool.count + 1).to_be_bytes());
+        out.extend(pool.bytes);
+        out.extend(tail);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_classes::*;
+    use super::*;
+
+    #[test]
+    fn parses_class_annotations_methods_and_references() {
+        let bytes = build_class(
+            "com/example/mixin/RendererMixin",
+            &[TestAnnotation {
+                descriptor: "Lorg/spongepowered/asm/mixin/Mixin;",
+                values: vec![(
+                    "value",
+                    TestValue::ClassArray(&["Lnet/minecraft/class_757;"]),
+                )],
+            }],
+            &[TestMethod {
+                name: "onRender",
+                descriptor: "()V",
+                annotations: vec![TestAnnotation {
+                    descriptor: "Lorg/spongepowered/asm/mixin/injection/Inject;",
+                    values: vec![("method", TestValue::StrArray(&["render"]))],
+                }],
+            }],
+            &["hello"],
+            &[(
+                "java/lang/Runtime",
+                "exec",
+                "(Ljava/lang/String;)Ljava/lang/Process;",
+            )],
+        );
+        let class = parse_class_file(&bytes).expect("parse class");
+        assert_eq!(class.name, "com/example/mixin/RendererMixin");
+        assert_eq!(class.super_name.as_deref(), Some("java/lang/Object"));
+        assert_eq!(class.annotations.len(), 1);
+        assert_eq!(
+            class.annotations[0].value("value").map(|v| v.strings()),
+            Some(vec!["Lnet/minecraft/class_757;".to_string()])
+        );
+        assert_eq!(class.methods.len(), 1);
+        assert_eq!(
+            class.methods[0].annotations[0]
+                .value("method")
+                .map(|v| v.strings()),
+            Some(vec!["render".to_string()])
+        );
+        assert_eq!(class.string_constants(), vec!["hello"]);
+        assert!(class
+            .method_refs()
+            .iter()
+            .any(|(owner, name, _)| *owner == "java/lang/Runtime" && *name == "exec"));
+    }
+
+    #[test]
+    fn rejects_truncated_or_foreign_bytes() {
+        assert!(parse_class_file(b"PK\x03\x04").is_err());
+        let bytes = build_class("a/B", &[], &[], &[], &[]);
+        assert!(parse_class_file(&bytes[..bytes.len() - 3]).is_err());
+    }
+}
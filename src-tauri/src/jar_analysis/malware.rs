@@ -0,0 +1,457 @@
+//! Static scan of mod jars for known malware and suspicious class-file patterns.
+//!
+//! Nothing is executed or decompiled: each class's constant pool is inspected for
+//! the APIs and string constants that loaders like fractureiser relied on.
+//! Jar-in-jar dependencies (`META-INF/jars/*.jar`) are scanned as well.
+
+use super::classfile::{parse_class_file, ClassFile};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::io::{Cursor, Read};
+use zip::ZipArchive;
+
+pub(crate) const SEVERITY_CRITICAL: &str = "critical";
+pub(crate) const SEVERITY_HIGH: &str = "high";
+pub(crate) const SEVERITY_MEDIUM: &str = "medium";
+
+const MAX_CLASS_BYTES: u64 = 4 * 1024 * 1024;
+const MAX_NESTED_JAR_BYTES: u64 = 64 * 1024 * 1024;
+const MAX_NESTED_JAR_DEPTH: usize = 2;
+const MAX_CLASSES_PER_SCAN: usize = 40_000;
+const MAX_REPORTED_FINDINGS: usize = 50;
+const MIN_BASE64_BLOB_LEN: usize = 512;
+
+/// Hosts, file names and markers published for fractureiser and its
+/// follow-up variants.
+const KNOWN_IOC_STRINGS: &[&str] = &[
+    "85.217.144.130",
+    "107.189.3.101",
+    "files-8ie.pages.dev",
+    "libwebgl64.jar",
+    "systemd-utility",
+    ".config/.data/lib.jar",
+    "skyrage",
+];
+
+const STARTUP_LOCATION_MARKERS: &[&str] = &[
+    "start menu\\programs\\startup",
+    "start menu/programs/startup",
+    "currentversion\\run",
+    ".config/autostart",
+    "/etc/systemd/system",
+    ".config/systemd/user",
+    "/etc/rc.local",
+    "/etc/init.d",
+    "library/launchagents",
+    "library/launchdaemons",
+    "crontab",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct MalwareFinding {
+    pub rule: String,
+    pub severity: String, // critical | high | medium
+    pub title: String,
+    pub detail: String,
+    /// Class path inside the jar; nested jars are joined with `!/`.
+    pub location: String,
+}
+
+#[derive(Debug, Default)]
+struct ClassSignals {
+    url_class_loader: bool,
+    url_host_port_ctor: bool,
+    reflective_invoke: bool,
+    define_class: bool,
+    base64_decode: bool,
+    base64_blob: bool,
+    process_exec: bool,
+    network: bool,
+    file_write: bool,
+    startup_markers: Vec<String>,
+    known_iocs: Vec<String>,
+}
+
+fn looks_like_base64_blob(value: &str) -> bool {
+    value.len() >= MIN_BASE64_BLOB_LEN
+        && value
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'/' | b'=' | b'-' | b'_'))
+}
+
+fn class_signals(class: &ClassFile) -> ClassSignals {
+    let mut signals = ClassSignals::default();
+    for name in class.referenced_classes() {
+        match name {
+            "java/net/URLClassLoader" => signals.url_class_loader = true,
+            "java/net/http/HttpClient" | "java/net/Socket" => signals.network = true,
+            _ => {}
+        }
+    }
+    for (owner, name, descriptor) in class.method_refs() {
+        match (owner, name) {
+            ("java/net/URLClassLoader", _) => signals.url_class_loader = true,
+            ("java/net/URL", "<init>")
+                if descriptor == "(Ljava/lang/String;Ljava/lang/String;ILjava/lang/String;)V" =>
+            {
+                signals.url_host_port_ctor = true
+            }
+            ("java/net/URL", "openConnection" | "openStream")
+            | ("java/net/http/HttpClient", "send" | "sendAsync") => signals.network = true,
+            ("java/lang/Class", "forName" | "getMethod" | "getDeclaredMethod")
+            | ("java/lang/ClassLoader", "loadClass")
+            | ("java/lang/reflect/Method", "invoke") => signals.reflective_invoke = true,
+            ("java/lang/ClassLoader", "defineClass")
+            | ("java/lang/invoke/MethodHandles$Lookup", "defineClass")
+            | ("java/security/SecureClassLoader", "defineClass") => signals.define_class = true,
+            ("java/util/Base64$Decoder", "decode") => signals.base64_decode = true,
+            ("java/lang/Runtime", "exec") | ("java/lang/ProcessBuilder", "start") => {
+                signals.process_exec = true
+            }
+            ("java/io/FileOutputStream", "<init>")
+            | ("java/io/FileWriter", "<init>")
+            | (
+                "java/nio/file/Files",
+                "write" | "writeString" | "copy" | "newOutputStream" | "newBufferedWriter",
+            ) => signals.file_write = true,
+            _ => {}
+        }
+    }
+    for value in class.string_constants() {
+        let lower = value.to_ascii_lowercase();
+        for ioc in KNOWN_IOC_STRINGS {
+            if lower.contains(ioc) && !signals.known_iocs.iter().any(|v| v == ioc) {
+                signals.known_iocs.push(ioc.to_string());
+            }
+        }
+        for marker in STARTUP_LOCATION_MARKERS {
+            if lower.contains(marker) && !signals.startup_markers.iter().any(|v| v == marker) {
+                signals.startup_markers.push(marker.to_string());
+            }
+        }
+        if !signals.base64_blob && looks_like_base64_blob(value) {
+            signals.base64_blob = true;
+        }
+    }
+    signals
+}
+
+fn finding(
+    rule: &str,
+    severity: &str,
+    title: &str,
+    detail: String,
+    location: &str,
+) -> MalwareFinding {
+    MalwareFinding {
+        rule: rule.to_string(),
+        severity: severity.to_string(),
+        title: title.to_string(),
+        detail,
+        location: location.to_string(),
+    }
+}
+
+fn findings_for_class(location: &str, signals: &ClassSignals) -> Vec<MalwareFinding> {
+    let mut out = Vec::new();
+    if !signals.known_iocs.is_empty() {
+        out.push(finding(
+            "known_ioc",
+            SEVERITY_CRITICAL,
+            "Known malware indicator",
+            format!(
+                "Contains strings published as malware indicators: {}.",
+                signals.known_iocs.join(", ")
+            ),
+            location,
+        ));
+    }
+    if signals.url_class_loader && signals.url_host_port_ctor && signals.reflective_invoke {
+        out.push(finding(
+            "fractureiser_loader",
+            SEVERITY_CRITICAL,
+            "fractureiser-style remote loader",
+            "Builds a URL from host and port, loads classes from it with URLClassLoader and invokes them reflectively.".to_string(),
+            location,
+        ));
+    } else if (signals.url_class_loader || signals.define_class)
+        && (signals.base64_decode || signals.base64_blob)
+    {
+        out.push(finding(
+            "encoded_class_loading",
+            SEVERITY_HIGH,
+            "Loads classes from encoded data",
+            "Combines a class loader (URLClassLoader or defineClass) with base64-encoded data, a common way to hide a payload.".to_string(),
+            location,
+        ));
+    }
+    if !signals.startup_markers.is_empty() && (signals.file_write || signals.process_exec) {
+        out.push(finding(
+            "startup_persistence",
+            SEVERITY_HIGH,
+            "Writes to an OS startup location",
+            format!(
+                "References {} and writes files or runs commands.",
+                signals.startup_markers.join(", ")
+            ),
+            location,
+        ));
+    }
+    if signals.process_exec && signals.network {
+        out.push(finding(
+            "download_and_execute",
+            SEVERITY_HIGH,
+            "Downloads and runs programs",
+            "Opens network connections and starts external processes (Runtime.exec/ProcessBuilder).".to_string(),
+            location,
+        ));
+    } else if signals.process_exec {
+        out.push(finding(
+            "process_execution",
+            SEVERITY_MEDIUM,
+            "Starts external processes",
+            "Calls Runtime.exec or ProcessBuilder.start. Some mods do this legitimately (for example to open a folder).".to_string(),
+            location,
+        ));
+    }
+    out
+}
+
+fn scan_archive(
+    bytes: &[u8],
+    prefix: &str,
+    depth: usize,
+    classes_seen: &mut usize,
+    out: &mut Vec<MalwareFinding>,
+) {
+    let Ok(mut archive) = ZipArchive::new(Cursor::new(bytes)) else {
+        return;
+    };
+    for idx in 0..archive.len() {
+        if *classes_seen >= MAX_CLASSES_PER_SCAN || out.len() >= MAX_REPORTED_FINDINGS {
+            return;
+        }
+        let Ok(file) = archive.by_index(idx) else {
+            continue;
+        };
+        if file.is_dir() {
+            continue;
+        }
+        let name = file.name().to_string();
+        let lower = name.to_ascii_lowercase();
+        let is_class = lower.ends_with(".class");
+        let is_nested_jar = lower.ends_with(".jar") && depth < MAX_NESTED_JAR_DEPTH;
+        let limit = if is_class {
+            MAX_CLASS_BYTES
+        } else if is_nested_jar {
+            MAX_NESTED_JAR_BYTES
+        } else {
+            continue;
+        };
+        if file.size() > limit {
+            continue;
+        }
+        let mut data = Vec::with_capacity(file.size() as usize);
+        if file.take(limit).read_to_end(&mut data).is_err() {
+            continue;
+        }
+        let location = format!("{prefix}{name}");
+        if is_nested_jar {
+            scan_archive(
+                &data,
+                &format!("{location}!/"),
+                depth + 1,
+                classes_seen,
+                out,
+            );
+            continue;
+        }
+        *classes_seen += 1;
+        let Ok(class) = parse_class_file(&data) else {
+            continue;
+        };
+        out.extend(findings_for_class(&location, &class_signals(&class)));
+    }
+}
+
+/// Scans every class in a jar (and its nested jars) and returns the findings,
+/// most severe first. Unreadable archives and classes yield no findings.
+pub(crate) fn scan_jar_bytes(bytes: &[u8]) -> Vec<MalwareFinding> {
+    let mut out = Vec::new();
+    let mut classes_seen = 0usize;
+    scan_archive(bytes, "", 0, &mut classes_seen, &mut out);
+    let mut seen = BTreeSet::new();
+    out.retain(|item| seen.insert((item.rule.clone(), item.location.clone())));
+    out.sort_by_key(|item| severity_rank(&item.severity));
+    out.truncate(MAX_REPORTED_FINDINGS);
+    out
+}
+
+pub(crate) fn severity_rank(severity: &str) -> u8 {
+    match severity {
+        SEVERITY_CRITICAL => 0,
+        SEVERITY_HIGH => 1,
+        SEVERITY_MEDIUM => 2,
+        _ => 3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::classfile::test_classes::*;
+    use super::*;
+    use std::io::Write;
+    use zip::write::FileOptions;
+
+    fn build_jar(files: &[(&str, Vec<u8>)]) -> Vec<u8> {
+        let mut cursor = Cursor::new(Vec::new());
+        {
+            let mut zip = zip::ZipWriter::new(&mut cursor);
+            for (name, bytes) in files {
+                zip.start_file(*name, FileOptions::default())
+                    .expect("start zip entry");
+                zip.write_all(bytes).expect("write zip entry");
+            }
+            zip.finish().expect("finish zip");
+        }
+        cursor.into_inner()
+    }
+
+    #[test]
+    fn flags_fractureiser_style_loader_in_nested_jar() {
+        let loader = build_class(
+            "dev/neko/nekoclient/Stage0",
+            &[],
+            &[],
+            &["http", "/dl"],
+            &[
+                ("java/net/URLClassLoader", "<init>", "([Ljava/net/URL;)V"),
+                (
+                    "java/net/URL",
+                    "<init>",
+                    "(Ljava/lang/String;Ljava/lang/String;ILjava/lang/String;)V",
+                ),
+                (
+                    "java/lang/ClassLoader",
+                    "loadClass",
+                    "(Ljava/lang/String;)Ljava/lang/Class;",
+                ),
+            ],
+        );
+        let inner = build_jar(&[("dev/neko/nekoclient/Stage0.class", loader)]);
+        let outer = build_jar(&[
+            ("fabric.mod.json", br#"{"id":"x"}"#.to_vec()),
+            ("META-INF/jars/lib.jar", inner),
+        ]);
+        let findings = scan_jar_bytes(&outer);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].rule, "fractureiser_loader");
+        assert_eq!(findings[0].severity, SEVERITY_CRITICAL);
+        assert_eq!(
+            findings[0].location,
+            "META-INF/jars/lib.jar!/dev/neko/nekoclient/Stage0.class"
+        );
+    }
+
+    #[test]
+    fn flags_persistence_and_iocs_but_not_plain_classes() {
+        let dropper = build_class(
+            "a/Dropper",
+            &[],
+            &[],
+            &[
+                "\\Microsoft\\Windows\\Start Menu\\Programs\\Startup\\run.bat",
+                "https://files-8ie.pages.dev/x",
+            ],
+            &[(
+                "java/nio/file/Files",
+                "write",
+                "(Ljava/nio/file/Path;[B[Ljava/nio/file/OpenOption;)Ljava/nio/file/Path;",
+            )],
+        );
+        let opener = build_class(
+            "a/Opener",
+            &[],
+            &[],
+            &["xdg-open"],
+            &[(
+                "java/lang/Runtime",
+                "exec",
+                "([Ljava/lang/String;)Ljava/lang/Process;",
+            )],
+        );
+        let plain = build_class("a/Plain", &[], &[], &["hello"], &[]);
+        let jar = build_jar(&[
+            ("a/Dropper.class", dropper),
+            ("a/Opener.class", opener),
+            ("a/Plain.class", plain),
+        ]);
+        let rules = scan_jar_bytes(&jar)
+            .into_iter()
+            .map(|item| (item.rule, item.location))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rules,
+            vec![
+                ("known_ioc".to_string(), "a/Dropper.class".to_string()),
+                (
+                    "startup_persistence".to_string(),
+                    "a/Dropper.class".to_string()
+                ),
+                (
+                    "process_execution".to_string(),
+                    "a/Opener.class".to_string()
+                ),
+            ]
+        );
+        assert!(scan_jar_bytes(b"not a zip").is_empty());
+    }
+
+    #[test]
+    fn launch_waits_for_every_finding_to_be_acknowledged() {
+        let flagged = |severity: &str| crate::LockEntry {
+            source: "modrinth".to_string(),
+            project_id: format!("proj-{severity}"),
+            version_id: format!("ver-{severity}"),
+            name: format!("{severity} mod"),
+            version_number: "1.0.0".to_string(),
+            filename: format!("{severity}.jar"),
+            content_type: "mods".to_string(),
+            target_scope: "instance".to_string(),
+            target_worlds: vec![],
+            pinned_version: None,
+            enabled: true,
+            hashes: Default::default(),
+            provider_candidates: vec![],
+            local_analysis: None,
+            security_scan: Some(crate::JarSecurityScan {
+                scanned_at: "2026-01-01T00:00:00Z".to_string(),
+                findings: vec![MalwareFinding {
+                    rule: "test-rule".to_string(),
+                    severity: severity.to_string(),
+                    title: "Test finding".to_string(),
+                    detail: String::new(),
+                    location: "a/B.class".to_string(),
+                }],
+                acknowledged_at: None,
+            }),
+            url_update: None,
+            github_channel: None,
+        };
+
+        let mut lock = crate::Lockfile {
+            version: 2,
+            entries: vec![flagged(SEVERITY_MEDIUM)],
+        };
+        let err = crate::ensure_mod_security_findings_acknowledged(&lock).unwrap_err();
+        assert!(err.contains("medium mod"), "{err}");
+
+        lock.entries[0].enabled = false;
+        assert!(crate::ensure_mod_security_findings_acknowledged(&lock).is_ok());
+        lock.entries[0].enabled = true;
+        if let Some(scan) = lock.entries[0].security_scan.as_mut() {
+            scan.acknowledged_at = Some("2026-01-02T00:00:00Z".to_string());
+        }
+        assert!(crate::ensure_mod_security_findings_acknowledged(&lock).is_ok());
+    }
+}
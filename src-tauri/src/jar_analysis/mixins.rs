@@ -0,0 +1,907 @@
+use super::classfile::{parse_class_file, ClassFile};
+use crate::{mod_paths, normalize_lock_content_type, Lockfile};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::io::{Cursor, Read, Seek};
+use std::path::Path;
+use zip::ZipArchive;
+
+const MIXIN_ANNOTATION: &str = "Lorg/spongepowered/asm/mixin/Mixin;";
+const MAX_MIXIN_CONFIGS_PER_JAR: usize = 64;
+const MAX_MIXIN_CLASSES_PER_JAR: usize = 4000;
+const MAX_MIXIN_CLASS_BYTES: u64 = 2 * 1024 * 1024;
+const MAX_REPORTED_MIXIN_CONFLICTS: usize = 200;
+const SHARED_CLASS_RISK_SCORE: u32 = 5;
+
+/// Mixin targets declared by one jar, resolved through its refmaps.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct MixinScan {
+    pub configs: Vec<String>,
+    pub targets: Vec<MixinTarget>,
+}
+
+/// A targeted method name and the injector kind used on it.
+type TargetMethod = (String, &'static str);
+
+#[derive(Debug, Clone)]
+pub(crate) struct MixinTarget {
+    pub class_name: String,
+    pub mixin_class: String,
+    pub config: String,
+    pub methods: Vec<TargetMethod>,
+}
+
+impl MixinScan {
+    fn owns_config(&self, config: &str) -> bool {
+        let wanted = config_file_name(config);
+        self.configs
+            .iter()
+            .any(|owned| config_file_name(owned).eq_ignore_ascii_case(wanted))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct EnabledModMixins {
+    /// Lockfile filename; unique per entry where display names may repeat.
+    pub key: String,
+    pub label: String,
+    pub tokens: Vec<String>,
+    pub scan: MixinScan,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct MixinConflict {
+    pub class_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    pub risk: String, // high | medium | low
+    pub risk_score: u32,
+    pub mods: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub kinds: Vec<String>,
+    /// `config:mixin class` pairs touching the class, as mixin logs print them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mixins: Vec<String>,
+}
+
+fn injector_kind(descriptor: &str) -> Option<&'static str> {
+    match descriptor {
+        "Lorg/spongepowered/asm/mixin/Overwrite;" => Some("overwrite"),
+        "Lorg/spongepowered/asm/mixin/injection/Redirect;" => Some("redirect"),
+        "Lorg/spongepowered/asm/mixin/injection/ModifyConstant;" => Some("modify_constant"),
+        "Lorg/spongepowered/asm/mixin/injection/ModifyArg;"
+        | "Lorg/spongepowered/asm/mixin/injection/ModifyArgs;" => Some("modify_arg"),
+        "Lorg/spongepowered/asm/mixin/injection/ModifyVariable;" => Some("modify_variable"),
+        "Lorg/spongepowered/asm/mixin/injection/Inject;" => Some("inject"),
+        "Lcom/llamalad7/mixinextras/injector/wrapoperation/WrapOperation;"
+        | "Lcom/llamalad7/mixinextras/injector/wrapmethod/WrapMethod;"
+        | "Lcom/llamalad7/mixinextras/injector/ModifyExpressionValue;"
+        | "Lcom/llamalad7/mixinextras/injector/ModifyReturnValue;"
+        | "Lcom/llamalad7/mixinextras/injector/ModifyReceiver;"
+        | "Lcom/llamalad7/mixinextras/injector/WrapWithCondition;"
+        | "Lcom/llamalad7/mixinextras/injector/v2/WrapWithCondition;" => Some("wrap"),
+        _ => None,
+    }
+}
+
+fn read_zip_bytes<R: Read + Seek>(archive: &mut ZipArchive<R>, path: &str) -> Option<Vec<u8>> {
+    let file = archive.by_name(path).ok()?;
+    if file.size() > MAX_MIXIN_CLASS_BYTES {
+        return None;
+    }
+    let mut out = Vec::with_capacity(file.size() as usize);
+    file.take(MAX_MIXIN_CLASS_BYTES)
+        .read_to_end(&mut out)
+        .ok()?;
+    Some(out)
+}
+
+fn read_zip_text<R: Read + Seek>(archive: &mut ZipArchive<R>, path: &str) -> Option<String> {
+    let bytes = read_zip_bytes(archive, path.trim().trim_start_matches('/'))?;
+    let text = String::from_utf8_lossy(&bytes).into_owned();
+    (!text.trim().is_empty()).then_some(text)
+}
+
+fn config_file_name(path: &str) -> &str {
+    path.rsplit(['/', '\\']).next().unwrap_or(path).trim()
+}
+
+fn push_config(out: &mut Vec<String>, raw: &str) {
+    let value = raw.trim().trim_start_matches('/');
+    if value.is_empty() || !value.to_ascii_lowercase().ends_with(".json") {
+        return;
+    }
+    if out.len() < MAX_MIXIN_CONFIGS_PER_JAR && !out.iter().any(|existing| existing == value) {
+        out.push(value.to_string());
+    }
+}
+
+fn collect_json_mixin_configs(value: Option<&serde_json::Value>, out: &mut Vec<String>) {
+    match value {
+        Some(serde_json::Value::String(path)) => push_config(out, path),
+        Some(serde_json::Value::Array(items)) => {
+            for item in items {
+                match item {
+                    serde_json::Value::String(path) => push_config(out, path),
+                    serde_json::Value::Object(map) => {
+                        if let Some(path) = map.get("config").and_then(|v| v.as_str()) {
+                            push_config(out, path);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+pub(crate) fn parse_manifest_mixin_configs(raw: &str) -> Vec<String> {
+    let mut logical_lines: Vec<String> = Vec::new();
+    for line in raw.lines() {
+        let line = line.trim_end_matches('\r');
+        if let Some(continued) = line.strip_prefix(' ') {
+            if let Some(last) = logical_lines.last_mut() {
+                last.push_str(continued);
+                continue;
+            }
+        }
+        logical_lines.push(line.to_string());
+    }
+    let mut out = Vec::new();
+    for line in logical_lines {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        if !key.trim().eq_ignore_ascii_case("MixinConfigs") {
+            continue;
+        }
+        for part in value.split(',') {
+            push_config(&mut out, part);
+        }
+    }
+    out
+}
+
+pub(crate) fn parse_mods_toml_mixin_configs(raw: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut in_mixins_table = false;
+    for line in raw.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            in_mixins_table = trimmed.replace(' ', "") == "[[mixins]]";
+            continue;
+        }
+        if !in_mixins_table {
+            continue;
+        }
+        let Some((key, value)) = trimmed.split_once('=') else {
+            continue;
+        };
+        if key.trim() == "config" {
+            push_config(&mut out, value.trim().trim_matches('"').trim_matches('\''));
+        }
+    }
+    out
+}
+
+fn discover_mixin_config_paths<R: Read + Seek>(archive: &mut ZipArchive<R>) -> Vec<String> {
+    let mut out = Vec::new();
+    if let Some(raw) = read_zip_text(archive, "fabric.mod.json") {
+        if let Ok(value) = serde_json::from_str::<serde_json::Value>(&raw) {
+            collect_json_mixin_configs(value.get("mixins"), &mut out);
+        }
+    }
+    if let Some(raw) = read_zip_text(archive, "quilt.mod.json") {
+        if let Ok(value) = serde_json::from_str::<serde_json::Value>(&raw) {
+            collect_json_mixin_configs(value.get("mixin"), &mut out);
+        }
+    }
+    if let Some(raw) = read_zip_text(archive, "META-INF/MANIFEST.MF") {
+        for path in parse_manifest_mixin_configs(&raw) {
+            push_config(&mut out, &path);
+        }
+    }
+    for toml_path in ["META-INF/mods.toml", "META-INF/neoforge.mods.toml"] {
+        if let Some(raw) = read_zip_text(archive, toml_path) {
+            for path in parse_mods_toml_mixin_configs(&raw) {
+                push_config(&mut out, &path);
+            }
+        }
+    }
+    if out.is_empty() {
+        let root_candidates = archive
+            .file_names()
+            .filter(|name| !name.contains('/'))
+            .filter(|name| {
+                let lower = name.to_ascii_lowercase();
+                lower.ends_with(".mixins.json")
+                    || (lower.starts_with("mixins.") && lower.ends_with(".json"))
+            })
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        for path in root_candidates {
+            push_config(&mut out, &path);
+        }
+    }
+    out
+}
+
+fn refmap_lookup(
+    refmap: Option<&serde_json::Value>,
+    mixin_class: &str,
+    key: &str,
+) -> Option<String> {
+    let refmap = refmap?;
+    let direct = refmap
+        .get("mappings")
+        .and_then(|mappings| mappings.get(mixin_class))
+        .and_then(|entries| entries.get(key))
+        .and_then(|value| value.as_str());
+    if let Some(found) = direct {
+        return Some(found.to_string());
+    }
+    refmap
+        .get("data")?
+        .as_object()?
+        .values()
+        .find_map(|env| env.get(mixin_class)?.get(key)?.as_str().map(str::to_string))
+}
+
+fn normalize_class_name(raw: &str) -> Option<String> {
+    let trimmed = raw.trim();
+    let inner = trimmed
+        .strip_prefix('L')
+        .and_then(|rest| rest.strip_suffix(';'))
+        .unwrap_or(trimmed);
+    if inner.is_empty() || inner.starts_with('[') {
+        return None;
+    }
+    Some(inner.replace('/', "."))
+}
+
+/// Reduces a mixin target selector (`owner;name(desc)`, `name*`, ...) to its method name.
+fn selector_method_name(selector: &str) -> Option<String> {
+    let mut rest = selector.trim();
+    if rest.starts_with('L') {
+        if let Some(idx) = rest.find(';') {
+            if rest.find('(').map(|paren| idx < paren).unwrap_or(true) {
+                rest = &rest[idx + 1..];
+            }
+        }
+    }
+    let name = rest
+        .split(['(', ':'])
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .trim_end_matches('*')
+        .trim();
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+fn mixin_targets_from_class(
+    class: &ClassFile,
+    config: &str,
+    refmap: Option<&serde_json::Value>,
+) -> Vec<MixinTarget> {
+    let Some(mixin) = class
+        .annotations
+        .iter()
+        .find(|annotation| annotation.type_descriptor == MIXIN_ANNOTATION)
+    else {
+        return vec![];
+    };
+    let mut target_classes = BTreeSet::new();
+    if let Some(value) = mixin.value("value") {
+        for descriptor in value.strings() {
+            if let Some(name) = normalize_class_name(&descriptor) {
+                target_classes.insert(name);
+            }
+        }
+    }
+    if let Some(value) = mixin.value("targets") {
+        for raw in value.strings() {
+            let mapped = refmap_lookup(refmap, &class.name, &raw).unwrap_or(raw);
+            if let Some(name) = normalize_class_name(&mapped) {
+                target_classes.insert(name);
+            }
+        }
+    }
+
+    let mut methods: Vec<(String, &'static str)> = Vec::new();
+    for method in &class.methods {
+        for annotation in &method.annotations {
+            let Some(kind) = injector_kind(&annotation.type_descriptor) else {
+                continue;
+            };
+            if kind == "overwrite" {
+                methods.push((method.name.clone(), kind));
+                continue;
+            }
+            let selectors = annotation
+                .value("method")
+                .map(|value| value.strings())
+                .unwrap_or_default();
+            for selector in selectors {
+                let mapped = refmap_lookup(refmap, &class.name, &selector).unwrap_or(selector);
+                if let Some(name) = selector_method_name(&mapped) {
+                    methods.push((name, kind));
+                }
+            }
+        }
+    }
+    methods.sort();
+    methods.dedup();
+
+    let mixin_class = class.name.replace('/', ".");
+    target_classes
+        .into_iter()
+        .map(|class_name| MixinTarget {
+            class_name,
+            mixin_class: mixin_class.clone(),
+            config: config.to_string(),
+            methods: methods.clone(),
+        })
+        .collect()
+}
+
+/// Reads every mixin config in a jar (plus its refmap) and resolves the classes
+/// and methods each mixin targets. Unreadable parts are skipped silently.
+pub(crate) fn scan_mixins_from_jar(file_bytes: &[u8]) -> MixinScan {
+    let Ok(mut archive) = ZipArchive::new(Cursor::new(file_bytes)) else {
+        return MixinScan::default();
+    };
+    let configs = discover_mixin_config_paths(&mut archive);
+    let mut targets = Vec::new();
+    let mut classes_read = 0usize;
+    'configs: for config_path in &configs {
+        let Some(raw) = read_zip_text(&mut archive, config_path) else {
+            continue;
+        };
+        let Ok(config) = serde_json::from_str::<serde_json::Value>(&raw) else {
+            continue;
+        };
+        let package = config
+            .get("package")
+            .and_then(|value| value.as_str())
+            .unwrap_or_default()
+            .trim()
+            .trim_end_matches('.')
+            .to_string();
+        let refmap = config
+            .get("refmap")
+            .and_then(|value| value.as_str())
+            .and_then(|path| read_zip_text(&mut archive, path))
+            .and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).ok());
+        for key in ["mixins", "client", "server"] {
+            let names = config
+                .get(key)
+                .and_then(|value| value.as_array())
+                .map(|items| {
+                    items
+                        .iter()
+                        .filter_map(|item| item.as_str())
+                        .map(str::to_string)
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            for name in names {
+                if classes_read >= MAX_MIXIN_CLASSES_PER_JAR {
+                    break 'configs;
+                }
+                let name = name.trim();
+                if name.is_empty() {
+                    continue;
+                }
+                let qualified = if package.is_empty() {
+                    name.to_string()
+                } else {
+                    format!("{package}.{name}")
+                };
+                let class_path = format!("{}.class", qualified.replace('.', "/"));
+                let Some(bytes) = read_zip_bytes(&mut archive, &class_path) else {
+                    continue;
+                };
+                classes_read += 1;
+                let Ok(class) = parse_class_file(&bytes) else {
+                    continue;
+                };
+                targets.extend(mixin_targets_from_class(
+                    &class,
+                    config_file_name(config_path),
+                    refmap.as_ref(),
+                ));
+            }
+        }
+    }
+    MixinScan { configs, targets }
+}
+
+fn entry_reference_tokens(entry: &crate::LockEntry) -> Vec<String> {
+    let mut tokens = BTreeSet::new();
+    if let Some(analysis) = entry.local_analysis.as_ref() {
+        for mod_id in &analysis.mod_ids {
+            tokens.insert(mod_id.trim().to_ascii_lowercase());
+        }
+    }
+    for raw in [&entry.project_id, &entry.name, &entry.filename] {
+        let lower = raw.trim().to_ascii_lowercase();
+        let stem = lower.trim_end_matches(".disabled").trim_end_matches(".jar");
+        if stem.len() >= 3 {
+            tokens.insert(stem.to_string());
+        }
+    }
+    tokens
+        .into_iter()
+        .filter(|token| !token.is_empty())
+        .collect()
+}
+
+/// Scans the mixins of every enabled mod jar in the lockfile.
+pub(crate) fn collect_enabled_mod_mixins(
+    lock: &Lockfile,
+    instance_dir: &Path,
+) -> Vec<EnabledModMixins> {
+    let mut out = Vec::new();
+    for entry in &lock.entries {
+        if !entry.enabled || normalize_lock_content_type(&entry.content_type) != "mods" {
+            continue;
+        }
+        let (enabled_path, _) = mod_paths(instance_dir, &entry.filename);
+        let Ok(bytes) = fs::read(&enabled_path) else {
+            continue;
+        };
+        let scan = scan_mixins_from_jar(&bytes);
+        if scan.targets.is_empty() {
+            continue;
+        }
+        out.push(EnabledModMixins {
+            key: entry.filename.clone(),
+            label: entry.name.trim().to_string(),
+            tokens: entry_reference_tokens(entry),
+            scan,
+        });
+    }
+    out
+}
+
+fn is_modify_kind(kind: &str) -> bool {
+    matches!(kind, "modify_constant" | "modify_arg" | "modify_variable")
+}
+
+/// How likely two injectors from different mods into the same method are to clash.
+fn pair_risk_score(left: &str, right: &str) -> u32 {
+    if left == "overwrite" && right == "overwrite" {
+        100
+    } else if left == "overwrite" || right == "overwrite" {
+        80
+    } else if left == "redirect" && right == "redirect" {
+        70
+    } else if (left == "redirect" && is_modify_kind(right))
+        || (right == "redirect" && is_modify_kind(left))
+    {
+        50
+    } else if left == right && is_modify_kind(left) {
+        40
+    } else if left == "redirect" || right == "redirect" {
+        30
+    } else if is_modify_kind(left) && is_modify_kind(right) {
+        25
+    } else if left == "inject" && right == "inject" {
+        10
+    } else {
+        15
+    }
+}
+
+fn risk_label(score: u32) -> &'static str {
+    if score >= 70 {
+        "high"
+    } else if score >= 30 {
+        "medium"
+    } else {
+        "low"
+    }
+}
+
+/// Finds classes (and, where injector data allows it, methods) that mixins
+/// from more than one enabled mod modify, ranked by clash risk.
+pub(crate) fn detect_mixin_conflicts(mods: &[EnabledModMixins]) -> Vec<MixinConflict> {
+    let names = conflict_display_names(mods);
+    let display = |keys: Vec<&String>| -> Vec<String> {
+        let mut out = keys
+            .into_iter()
+            .map(|key| names.get(key).cloned().unwrap_or_else(|| key.clone()))
+            .collect::<Vec<_>>();
+        out.sort();
+        out
+    };
+    let mut by_class: BTreeMap<String, BTreeMap<String, Vec<TargetMethod>>> = BTreeMap::new();
+    let mut mixins_by_class: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for item in mods {
+        for target in &item.scan.targets {
+            mixins_by_class
+                .entry(target.class_name.clone())
+                .or_default()
+                .insert(format!("{}:{}", target.config, target.mixin_class));
+            by_class
+                .entry(target.class_name.clone())
+                .or_default()
+                .entry(item.key.clone())
+                .or_default()
+                .extend(target.methods.iter().cloned());
+        }
+    }
+
+    let mut out = Vec::new();
+    for (class_name, per_mod) in by_class {
+        if per_mod.len() < 2 {
+            continue;
+        }
+        let mixins = mixins_by_class
+            .get(&class_name)
+            .map(|set| set.iter().cloned().collect::<Vec<_>>())
+            .unwrap_or_default();
+        let mut by_method: BTreeMap<String, BTreeMap<String, BTreeSet<&'static str>>> =
+            BTreeMap::new();
+        for (key, methods) in &per_mod {
+            for (method, kind) in methods {
+                by_method
+                    .entry(method.clone())
+                    .or_default()
+                    .entry(key.clone())
+                    .or_default()
+                    .insert(*kind);
+            }
+        }
+        let mut method_conflicts = 0usize;
+        for (method, mod_kinds) in by_method {
+            if mod_kinds.len() < 2 {
+                continue;
+            }
+            let per_mod_kinds = mod_kinds.values().collect::<Vec<_>>();
+            let mut score = 0u32;
+            for (idx, left_kinds) in per_mod_kinds.iter().enumerate() {
+                for right_kinds in per_mod_kinds.iter().skip(idx + 1) {
+                    for left in left_kinds.iter() {
+                        for right in right_kinds.iter() {
+                            score = score.max(pair_risk_score(left, right));
+                        }
+                    }
+                }
+            }
+            let kinds = mod_kinds
+                .values()
+                .flat_map(|kinds| kinds.iter().map(|kind| kind.to_string()))
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect();
+            out.push(MixinConflict {
+                class_name: class_name.clone(),
+                method: Some(method),
+                risk: risk_label(score).to_string(),
+                risk_score: score,
+                mods: display(mod_kinds.keys().collect()),
+                kinds,
+                mixins: mixins.clone(),
+            });
+            method_conflicts += 1;
+        }
+        if method_conflicts == 0 {
+            out.push(MixinConflict {
+                class_name,
+                method: None,
+                risk: risk_label(SHARED_CLASS_RISK_SCORE).to_string(),
+                risk_score: SHARED_CLASS_RISK_SCORE,
+                mods: display(per_mod.keys().collect()),
+                kinds: vec![],
+                mixins,
+            });
+        }
+    }
+    out.sort_by(|a, b| {
+        b.risk_score
+            .cmp(&a.risk_score)
+            .then_with(|| a.class_name.cmp(&b.class_name))
+            .then_with(|| a.method.cmp(&b.method))
+    });
+    out.truncate(MAX_REPORTED_MIXIN_CONFLICTS);
+    out
+}
+
+/// Display name per mod key, with the filename added where two entries
+/// share a name so a conflict between them still reads as two mods.
+pub(crate) fn conflict_display_names(mods: &[EnabledModMixins]) -> HashMap<String, String> {
+    let mut label_counts: HashMap<String, usize> = HashMap::new();
+    for item in mods {
+        *label_counts
+            .entry(item.label.to_ascii_lowercase())
+            .or_insert(0) += 1;
+    }
+    mods.iter()
+        .map(|item| {
+            let label = if item.label.is_empty() {
+                item.key.clone()
+            } else if label_counts
+                .get(&item.label.to_ascii_lowercase())
+                .is_some_and(|count| *count > 1)
+            {
+                format!("{} ({})", item.label, item.key)
+            } else {
+                item.label.clone()
+            };
+            (item.key.clone(), label)
+        })
+        .collect()
+}
+
+/// Resolves a mixin config name or mod id seen in a log back to an enabled mod.
+pub(crate) fn find_mod_for_log_reference<'a>(
+    mods: &'a [EnabledModMixins],
+    reference: &str,
+) -> Option<&'a EnabledModMixins> {
+    let needle = reference.trim().to_ascii_lowercase();
+    if needle.is_empty() {
+        return None;
+    }
+    if needle.ends_with(".json") {
+        return mods.iter().find(|item| item.scan.owns_config(&needle));
+    }
+    mods.iter()
+        .find(|item| item.label.eq_ignore_ascii_case(&needle) || item.tokens.contains(&needle))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::classfile::test_classes::*;
+    use super::*;
+    use std::io::Write;
+    use zip::write::FileOptions;
+
+    fn build_jar(files: &[(String, Vec<u8>)]) -> Vec<u8> {
+        let mut cursor = Cursor::new(Vec::new());
+        {
+            let mut zip = zip::ZipWriter::new(&mut cursor);
+            for (name, bytes) in files {
+                zip.start_file(name.as_str(), FileOptions::default())
+                    .expect("start zip entry");
+                zip.write_all(bytes).expect("write zip entry");
+            }
+            zip.finish().expect("finish zip");
+        }
+        cursor.into_inner()
+    }
+
+    fn mixin_class(
+        name: &str,
+        target: &str,
+        methods: Vec<(&'static str, &'static str, &'static [&'static str])>,
+    ) -> Vec<u8> {
+        let methods = methods
+            .into_iter()
+            .map(|(method_name, annotation, selectors)| TestMethod {
+                name: method_name,
+                descriptor: "()V",
+                annotations: vec![TestAnnotation {
+                    descriptor: annotation,
+                    values: if selectors.is_empty() {
+                        vec![]
+                    } else {
+                        vec![("method", TestValue::StrArray(selectors))]
+                    },
+                }],
+            })
+            .collect::<Vec<_>>();
+        build_class(
+            name,
+            &[TestAnnotation {
+                descriptor: MIXIN_ANNOTATION,
+                values: vec![("value", TestValue::ClassArray(&[target]))],
+            }],
+            &methods,
+            &[],
+            &[],
+        )
+    }
+
+    fn fabric_mod_jar(mod_id: &str, mixin: Vec<u8>, refmap: Option<&str>) -> Vec<u8> {
+        let refmap_line = refmap
+            .map(|_| format!(r#","refmap":"{mod_id}-refmap.json""#))
+            .unwrap_or_default();
+        let mut files = vec![
+            (
+                "fabric.mod.json".to_string(),
+                format!(r#"{{"id":"{mod_id}","mixins":["{mod_id}.mixins.json"]}}"#).into_bytes(),
+            ),
+            (
+                format!("{mod_id}.mixins.json"),
+                format!(
+                    r#"{{"package":"com.{mod_id}.mixin","mixins":["RendererMixin"]{refmap_line}}}"#
+                )
+                .into_bytes(),
+            ),
+            (format!("com/{mod_id}/mixin/RendererMixin.class"), mixin),
+        ];
+        if let Some(raw) = refmap {
+            files.push((format!("{mod_id}-refmap.json"), raw.as_bytes().to_vec()));
+        }
+        build_jar(&files)
+    }
+
+    #[test]
+    fn reads_configs_from_manifest_and_mods_toml() {
+        let manifest =
+            "Manifest-Version: 1.0\r\nMixinConfigs: alpha.mixins.json,beta.mix\r\n ins.json\r\n";
+        assert_eq!(
+            parse_manifest_mixin_configs(manifest),
+            vec![
+                "alpha.mixins.json".to_string(),
+                "beta.mixins.json".to_string()
+            ]
+        );
+        let toml = "[[mods]]\nmodId=\"x\"\n[[mixins]]\nconfig = \"x.mixins.json\"\n[[dependencies.x]]\nconfig=\"nope.json\"\n";
+        assert_eq!(
+            parse_mods_toml_mixin_configs(toml),
+            vec!["x.mixins.json".to_string()]
+        );
+    }
+
+    #[test]
+    fn scan_resolves_targets_and_methods_through_refmap() {
+        let class = mixin_class(
+            "com/alpha/mixin/RendererMixin",
+            "Lnet/minecraft/class_757;",
+            vec![(
+                "onRender",
+                "Lorg/spongepowered/asm/mixin/injection/Inject;",
+                &["render"],
+            )],
+        );
+        let refmap = r#"{"mappings":{"com/alpha/mixin/RendererMixin":{"render":"Lnet/minecraft/class_757;method_3192(FJZ)V"}}}"#;
+        let scan = scan_mixins_from_jar(&fabric_mod_jar("alpha", class, Some(refmap)));
+        assert_eq!(scan.configs, vec!["alpha.mixins.json".to_string()]);
+        assert_eq!(scan.targets.len(), 1);
+        assert_eq!(scan.targets[0].class_name, "net.minecraft.class_757");
+        assert_eq!(
+            scan.targets[0].methods,
+            vec![("method_3192".to_string(), "inject")]
+        );
+        assert_eq!(scan.targets[0].mixin_class, "com.alpha.mixin.RendererMixin");
+    }
+
+    #[test]
+    fn ranks_overwrite_collisions_above_shared_injections() {
+        let alpha = scan_mixins_from_jar(&fabric_mod_jar(
+            "alpha",
+            mixin_class(
+                "com/alpha/mixin/RendererMixin",
+                "Lnet/minecraft/class_757;",
+                vec![
+                    (
+                        "method_3192",
+                        "Lorg/spongepowered/asm/mixin/Overwrite;",
+                        &[],
+                    ),
+                    (
+                        "onTick",
+                        "Lorg/spongepowered/asm/mixin/injection/Inject;",
+                        &["tick"],
+                    ),
+                ],
+            ),
+            None,
+        ));
+        let beta = scan_mixins_from_jar(&fabric_mod_jar(
+            "beta",
+            mixin_class(
+                "com/beta/mixin/RendererMixin",
+                "Lnet/minecraft/class_757;",
+                vec![
+                    (
+                        "onRender",
+                        "Lorg/spongepowered/asm/mixin/injection/Inject;",
+                        &["method_3192"],
+                    ),
+                    (
+                        "onTick",
+                        "Lorg/spongepowered/asm/mixin/injection/Inject;",
+                        &["tick()V"],
+                    ),
+                ],
+            ),
+            None,
+        ));
+        let mods = vec![
+            EnabledModMixins {
+                key: "alpha.jar".to_string(),
+                label: "Alpha".to_string(),
+                tokens: vec!["alpha".to_string()],
+                scan: alpha,
+            },
+            EnabledModMixins {
+                key: "beta.jar".to_string(),
+                label: "Beta".to_string(),
+                tokens: vec!["beta".to_string()],
+                scan: beta,
+            },
+        ];
+        let conflicts = detect_mixin_conflicts(&mods);
+        assert_eq!(conflicts.len(), 2);
+        assert_eq!(conflicts[0].method.as_deref(), Some("method_3192"));
+        assert_eq!(conflicts[0].risk, "high");
+        assert_eq!(
+            conflicts[0].mods,
+            vec!["Alpha".to_string(), "Beta".to_string()]
+        );
+        assert_eq!(conflicts[1].method.as_deref(), Some("tick"));
+        assert_eq!(conflicts[1].risk, "low");
+
+        assert_eq!(
+            find_mod_for_log_reference(&mods, "beta.mixins.json").map(|m| m.label.as_str()),
+            Some("Beta")
+        );
+        assert_eq!(
+            find_mod_for_log_reference(&mods, "alpha").map(|m| m.label.as_str()),
+            Some("Alpha")
+        );
+    }
+
+    #[test]
+    fn shared_class_without_method_overlap_is_reported_low() {
+        let make = |id: &str, method: &'static [&'static str]| {
+            let class = mixin_class(
+                &format!("com/{id}/mixin/RendererMixin"),
+                "Lnet/minecraft/class_310;",
+                vec![(
+                    "hook",
+                    "Lorg/spongepowered/asm/mixin/injection/Inject;",
+                    method,
+                )],
+            );
+            EnabledModMixins {
+                key: format!("{id}.jar"),
+                label: id.to_string(),
+                tokens: vec![],
+                scan: scan_mixins_from_jar(&fabric_mod_jar(id, class, None)),
+            }
+        };
+        let conflicts =
+            detect_mixin_conflicts(&[make("gamma", &["init"]), make("delta", &["stop"])]);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].class_name, "net.minecraft.class_310");
+        assert!(conflicts[0].method.is_none());
+        assert_eq!(conflicts[0].risk, "low");
+    }
+
+    #[test]
+    fn mods_sharing_a_display_name_are_kept_apart() {
+        let make = |file: &str| {
+            let class = mixin_class(
+                "com/dup/mixin/RendererMixin",
+                "Lnet/minecraft/class_310;",
+                vec![(
+                    "hook",
+                    "Lorg/spongepowered/asm/mixin/injection/Inject;",
+                    &["render"],
+                )],
+            );
+            EnabledModMixins {
+                key: file.to_string(),
+                label: "Duplicate".to_string(),
+                tokens: vec![],
+                scan: scan_mixins_from_jar(&fabric_mod_jar("dup", class, None)),
+            }
+        };
+        let conflicts = detect_mixin_conflicts(&[make("dup-1.0.jar"), make("dup-1.1.jar")]);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(
+            conflicts[0].mods,
+            vec![
+                "Duplicate (dup-1.0.jar)".to_string(),
+                "Duplicate (dup-1.1.jar)".to_string()
+            ]
+        );
+    }
+}
@@ -0,0 +1,3 @@
+pub(crate) mod classfile;
+pub(crate) mod malware;
+pub(crate) mod mixins;
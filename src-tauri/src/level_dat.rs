@@ -0,0 +1,380 @@
+//! World metadata read from a save's `level.dat`.
+
+use crate::nbt::{read_nbt, NbtTag};
+use chrono::{TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fs;
+use std::path::Path;
+
+/// Largest `level.dat` read; real ones are a few kilobytes.
+const MAX_LEVEL_DAT_BYTES: u64 = 16 * 1024 * 1024;
+
+/// `DataVersion` written by each release since DataVersion was introduced.
+const RELEASE_DATA_VERSIONS: &[(&str, i32)] = &[
+    ("1.9", 169),
+    ("1.9.1", 175),
+    ("1.9.2", 176),
+    ("1.9.4", 184),
+    ("1.10", 510),
+    ("1.10.1", 511),
+    ("1.10.2", 512),
+    ("1.11", 819),
+    ("1.11.1", 921),
+    ("1.11.2", 922),
+    ("1.12", 1139),
+    ("1.12.1", 1241),
+    ("1.12.2", 1343),
+    ("1.13", 1519),
+    ("1.13.1", 1628),
+    ("1.13.2", 1631),
+    ("1.14", 1952),
+    ("1.14.1", 1957),
+    ("1.14.2", 1963),
+    ("1.14.3", 1968),
+    ("1.14.4", 1976),
+    ("1.15", 2225),
+    ("1.15.1", 2227),
+    ("1.15.2", 2230),
+    ("1.16", 2566),
+    ("1.16.1", 2567),
+    ("1.16.2", 2578),
+    ("1.16.3", 2580),
+    ("1.16.4", 2584),
+    ("1.16.5", 2586),
+    ("1.17", 2724),
+    ("1.17.1", 2730),
+    ("1.18", 2860),
+    ("1.18.1", 2865),
+    ("1.18.2", 2975),
+    ("1.19", 3105),
+    ("1.19.1", 3117),
+    ("1.19.2", 3120),
+    ("1.19.3", 3218),
+    ("1.19.4", 3337),
+    ("1.20", 3463),
+    ("1.20.1", 3465),
+    ("1.20.2", 3578),
+    ("1.20.3", 3698),
+    ("1.20.4", 3700),
+    ("1.20.5", 3837),
+    ("1.20.6", 3839),
+    ("1.21", 3953),
+    ("1.21.1", 3955),
+    ("1.21.2", 4080),
+    ("1.21.3", 4082),
+    ("1.21.4", 4189),
+    ("1.21.5", 4325),
+    ("1.21.6", 4435),
+    ("1.21.7", 4438),
+    ("1.21.8", 4440),
+];
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct WorldMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<i64>,
+    /// `survival`, `creative`, `adventure` or `spectator`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub game_mode: Option<String>,
+    #[serde(default)]
+    pub hardcore: bool,
+    /// `peaceful`, `easy`, `normal` or `hard`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub difficulty: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_version: Option<i32>,
+    /// Game version that last saved the world, e.g. `1.20.4`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_played_at: Option<String>,
+    #[serde(default)]
+    pub enabled_datapacks: Vec<String>,
+    #[serde(default)]
+    pub disabled_datapacks: Vec<String>,
+    #[serde(default)]
+    pub enabled_features: Vec<String>,
+}
+
+fn game_mode_name(id: i64) -> Option<&'static str> {
+    match id {
+        0 => Some("survival"),
+        1 => Some("creative"),
+        2 => Some("adventure"),
+        3 => Some("spectator"),
+        _ => None,
+    }
+}
+
+fn difficulty_name(id: i64) -> Option<&'static str> {
+    match id {
+        0 => Some("peaceful"),
+        1 => Some("easy"),
+        2 => Some("normal"),
+        3 => Some("hard"),
+        _ => None,
+    }
+}
+
+/// Extracts world metadata from a parsed `level.dat` root.
+pub(crate) fn world_metadata_from_nbt(root: &NbtTag) -> Result<WorldMetadata, String> {
+    let data = root
+        .get("Data")
+        .filter(|tag| matches!(tag, NbtTag::Compound(_)))
+        .ok_or_else(|| "level.dat has no Data compound".to_string())?;
+    let int = |names: &[&str]| data.path(names).and_then(NbtTag::as_i64);
+    let text = |names: &[&str]| {
+        data.path(names)
+            .and_then(NbtTag::as_str)
+            .map(str::to_string)
+    };
+    // Newer releases store difficulty as a name under `difficulty_settings`.
+    let difficulty = text(&["difficulty_settings", "difficulty"]).or_else(|| {
+        int(&["Difficulty"])
+            .and_then(difficulty_name)
+            .map(str::to_string)
+    });
+    Ok(WorldMetadata {
+        level_name: text(&["LevelName"]),
+        // 1.16 moved the seed into WorldGenSettings.
+        seed: int(&["WorldGenSettings", "seed"]).or_else(|| int(&["RandomSeed"])),
+        game_mode: int(&["GameType"])
+            .and_then(game_mode_name)
+            .map(str::to_string),
+        hardcore: int(&["hardcore"]).unwrap_or(0) != 0,
+        difficulty,
+        data_version: int(&["DataVersion"]).and_then(|value| i32::try_from(value).ok()),
+        version_name: text(&["Version", "Name"]),
+        last_played_at: int(&["LastPlayed"])
+            .and_then(|ms| Utc.timestamp_millis_opt(ms).single())
+            .map(|at| at.to_rfc3339()),
+        enabled_datapacks: data
+            .path(&["DataPacks", "Enabled"])
+            .map(NbtTag::string_list)
+            .unwrap_or_default(),
+        disabled_datapacks: data
+            .path(&["DataPacks", "Disabled"])
+            .map(NbtTag::string_list)
+            .unwrap_or_default(),
+        enabled_features: data
+            .get("enabled_features")
+            .map(NbtTag::string_list)
+            .unwrap_or_default(),
+    })
+}
+
+fn read_level_file(path: &Path) -> Result<WorldMetadata, String> {
+    let len = fs::metadata(path)
+        .map_err(|e| format!("read '{}' failed: {e}", path.display()))?
+        .len();
+    if len > MAX_LEVEL_DAT_BYTES {
+        return Err(format!("'{}' is too large", path.display()));
+    }
+    let bytes = fs::read(path).map_err(|e| format!("read '{}' failed: {e}", path.display()))?;
+    let (_, root) = read_nbt(&bytes)?;
+    world_metadata_from_nbt(&root)
+}
+
+/// Reads `level.dat`, falling back to the game's `level.dat_old` copy when
+/// the main file is missing or damaged.
+pub(crate) fn read_world_metadata(world_dir: &Path) -> Result<WorldMetadata, String> {
+    read_level_file(&world_dir.join("level.dat"))
+        .or_else(|err| read_level_file(&world_dir.join("level.dat_old")).map_err(|_| err))
+}
+
+/// `DataVersion` of a release, or `None` for snapshots and versions the
+/// table does not know yet.
+pub(crate) fn release_data_version(mc_version: &str) -> Option<i32> {
+    let wanted = mc_version.trim();
+    RELEASE_DATA_VERSIONS
+        .iter()
+        .find(|(name, _)| *name == wanted)
+        .map(|(_, data_version)| *data_version)
+}
+
+fn is_release_name(version: &str) -> bool {
+    let version = version.trim();
+    version.starts_with(|ch: char| ch.is_ascii_digit())
+        && version.chars().all(|ch| ch.is_ascii_digit() || ch == '.')
+}
+
+/// Whether the world was last saved by a newer game than `mc_version`.
+///
+/// Compares DataVersion when the instance release is known, otherwise falls
+/// back to comparing release names; snapshots that match neither are
+/// treated as not newer.
+pub(crate) fn saved_by_newer_version(meta: &WorldMetadata, mc_version: &str) -> bool {
+    if let (Some(world), Some(instance)) = (meta.data_version, release_data_version(mc_version)) {
+        return world > instance;
+    }
+    match meta.version_name.as_deref() {
+        Some(name) if is_release_name(name) && is_release_name(mc_version) => {
+            crate::advisories::compare_loose_versions(name, mc_version) == Ordering::Greater
+        }
+        _ => false,
+    }
+}
+
+/// Worlds under `saves_dir` last saved by a newer game than `mc_version`,
+/// sorted by folder name.
+pub(crate) fn worlds_newer_than(
+    saves_dir: &Path,
+    mc_version: &str,
+) -> Vec<(String, WorldMetadata)> {
+    let Ok(entries) = fs::read_dir(saves_dir) else {
+        return Vec::new();
+    };
+    let mut out = entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            let meta = read_world_metadata(&entry.path()).ok()?;
+            saved_by_newer_version(&meta, mc_version)
+                .then(|| (entry.file_name().to_string_lossy().to_string(), meta))
+        })
+        .collect::<Vec<_>>();
+    out.sort_by(|a, b| a.0.cmp(&b.0));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nbt::encode::{compound, document, gzip};
+
+    fn level_dat(data: Vec<(&str, NbtTag)>) -> Vec<u8> {
+        gzip(&document("", &compound(vec![("Data", compound(data))])))
+    }
+
+    #[test]
+    fn reads_modern_level_dat_fields() {
+        let bytes = level_dat(vec![
+            ("LevelName", NbtTag::String("Castle".to_string())),
+            ("DataVersion", NbtTag::Int(3700)),
+            (
+                "Version",
+                compound(vec![
+                    ("Id", NbtTag::Int(3700)),
+                    ("Name", NbtTag::String("1.20.4".to_string())),
+                ]),
+            ),
+            (
+                "WorldGenSettings",
+                compound(vec![("seed", NbtTag::Long(-42))]),
+            ),
+            ("GameType", NbtTag::Int(1)),
+            ("hardcore", NbtTag::Byte(1)),
+            ("Difficulty", NbtTag::Byte(3)),
+            ("LastPlayed", NbtTag::Long(1_700_000_000_000)),
+            (
+                "DataPacks",
+                compound(vec![
+                    (
+                        "Enabled",
+                        NbtTag::List(vec![
+                            NbtTag::String("vanilla".to_string()),
+                            NbtTag::String("file/extra.zip".to_string()),
+                        ]),
+                    ),
+                    ("Disabled", NbtTag::List(Vec::new())),
+                ]),
+            ),
+            (
+                "enabled_features",
+                NbtTag::List(vec![NbtTag::String("minecraft:vanilla".to_string())]),
+            ),
+        ]);
+        let (_, root) = read_nbt(&bytes).unwrap();
+        let meta = world_metadata_from_nbt(&root).unwrap();
+        assert_eq!(meta.level_name.as_deref(), Some("Castle"));
+        assert_eq!(meta.seed, Some(-42));
+        assert_eq!(meta.game_mode.as_deref(), Some("creative"));
+        assert!(meta.hardcore);
+        assert_eq!(meta.difficulty.as_deref(), Some("hard"));
+        assert_eq!(meta.data_version, Some(3700));
+        assert_eq!(meta.version_name.as_deref(), Some("1.20.4"));
+        assert_eq!(
+            meta.last_played_at.as_deref(),
+            Some("2023-11-14T22:13:20+00:00")
+        );
+        assert_eq!(meta.enabled_datapacks, vec!["vanilla", "file/extra.zip"]);
+        assert!(meta.disabled_datapacks.is_empty());
+        assert_eq!(meta.enabled_features, vec!["minecraft:vanilla"]);
+    }
+
+    #[test]
+    fn falls_back_to_level_dat_old_and_legacy_fields() {
+        let root = std::env::temp_dir().join(format!("openjar-level-dat-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("level.dat"), b"not nbt at all").unwrap();
+        fs::write(
+            root.join("level.dat_old"),
+            level_dat(vec![
+                ("LevelName", NbtTag::String("Old".to_string())),
+                ("RandomSeed", NbtTag::Long(99)),
+                ("GameType", NbtTag::Int(0)),
+            ]),
+        )
+        .unwrap();
+        let meta = read_world_metadata(&root).unwrap();
+        assert_eq!(meta.level_name.as_deref(), Some("Old"));
+        assert_eq!(meta.seed, Some(99));
+        assert_eq!(meta.game_mode.as_deref(), Some("survival"));
+        assert_eq!(meta.data_version, None);
+
+        fs::remove_file(root.join("level.dat_old")).unwrap();
+        assert!(read_world_metadata(&root).is_err());
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn flags_worlds_saved_by_newer_versions() {
+        let newer = |data_version: Option<i32>, name: &str, mc_version: &str| {
+            let meta = WorldMetadata {
+                data_version,
+                version_name: Some(name.to_string()),
+                ..WorldMetadata::default()
+            };
+            saved_by_newer_version(&meta, mc_version)
+        };
+        assert!(newer(Some(3700), "1.20.4", "1.20.1"));
+        assert!(!newer(Some(3465), "1.20.1", "1.20.1"));
+        assert!(!newer(Some(2586), "1.16.5", "1.20.1"));
+        // A release newer than the table still compares by DataVersion.
+        assert!(newer(Some(4556), "1.21.10", "1.21.8"));
+        // An instance newer than the table falls back to release names.
+        assert!(!newer(Some(4440), "1.21.8", "1.21.10"));
+        assert!(newer(None, "1.21.11", "1.21.10"));
+        assert!(!newer(None, "24w14a", "1.20.1"));
+
+        let saves =
+            std::env::temp_dir().join(format!("openjar-newer-worlds-{}", uuid::Uuid::new_v4()));
+        for (world, data_version, name) in [
+            ("b", 3700, "1.20.4"),
+            ("a", 3953, "1.21"),
+            ("old", 3218, "1.19.3"),
+        ] {
+            fs::create_dir_all(saves.join(world)).unwrap();
+            fs::write(
+                saves.join(world).join("level.dat"),
+                level_dat(vec![
+                    ("DataVersion", NbtTag::Int(data_version)),
+                    (
+                        "Version",
+                        compound(vec![("Name", NbtTag::String(name.to_string()))]),
+                    ),
+                ]),
+            )
+            .unwrap();
+        }
+        let found = worlds_newer_than(&saves, "1.20.1")
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>();
+        assert_eq!(found, vec!["a", "b"]);
+        let _ = fs::remove_dir_all(saves);
+    }
+}
@@ -24,14 +24,37 @@ use std::time::{Duration, Instant, SystemTime};
 use tauri::Manager;
 use tauri::{CustomMenuItem, Menu, MenuItem, Submenu};
 use uuid::Uuid;
+#[cfg(test)]
 use zip::write::FileOptions;
 use zip::ZipArchive;
 
+mod advisories;
+mod anvil;
+mod backup_verify;
 mod commands;
+mod content_store;
+mod content_watcher;
+mod curseforge_handoff;
 mod friend_link;
+mod github_channels;
+mod hardlink_dedupe;
+mod http_cache;
+mod jar_analysis;
+mod level_dat;
+mod maven_repo;
 mod modpack;
+mod nbt;
 mod permissions;
+mod rehydrate;
 pub(crate) mod run_reports;
+mod snapshot_diff;
+mod snapshot_store;
+mod team_repo;
+mod update_batch;
+mod url_source;
+mod world_archive;
+mod world_backup_store;
+mod world_trim;
 #[cfg(test)]
 mod test_support;
 #[cfg(test)]
@@ -73,9 +96,12 @@ const EXTERNAL_PATH_PURPOSE_MODPACK_LOCAL_JAR_IMPORT: &str = "modpack_local_jar_
 const EXTERNAL_PATH_PURPOSE_PRESETS_IMPORT: &str = "presets_import";
 const EXTERNAL_PATH_PURPOSE_PRESETS_EXPORT: &str = "presets_export";
 const EXTERNAL_PATH_PURPOSE_MODPACK_SPEC_IMPORT: &str = "modpack_spec_import";
+const EXTERNAL_PATH_PURPOSE_ADVISORY_BUNDLE_IMPORT: &str = "advisory_bundle_import";
 const EXTERNAL_PATH_PURPOSE_MODPACK_SPEC_EXPORT: &str = "modpack_spec_export";
 const EXTERNAL_PATH_PURPOSE_INSTANCE_MODS_EXPORT: &str = "instance_mods_export";
 const EXTERNAL_PATH_PURPOSE_SUPPORT_BUNDLE_EXPORT: &str = "support_bundle_export";
+const EXTERNAL_PATH_PURPOSE_WORLD_ARCHIVE_IMPORT: &str = "world_archive_import";
+const EXTERNAL_PATH_PURPOSE_WORLD_ARCHIVE_EXPORT: &str = "world_archive_export";
 const MS_TOKEN_URL: &str = "https://login.microsoftonline.com/consumers/oauth2/v2.0/token";
 const MS_DEVICE_CODE_URL: &str =
     "https://login.microsoftonline.com/consumers/oauth2/v2.0/devicecode";
@@ -98,6 +124,7 @@ const DEFAULT_WORLD_BACKUP_INTERVAL_MINUTES: u32 = 10;
 const DEFAULT_WORLD_BACKUP_RETENTION_COUNT: u32 = 1;
 const DEFAULT_SNAPSHOT_RETENTION_COUNT: u32 = 5;
 const DEFAULT_SNAPSHOT_MAX_AGE_DAYS: u32 = 14;
+const SNAPSHOT_PIN_LAST_KNOWN_GOOD: &str = "last_known_good";
 const MENU_CHECK_FOR_UPDATES_ID: &str = "menu_check_for_updates";
 const APP_MENU_CHECK_FOR_UPDATES_EVENT: &str = "app_menu_check_for_updates";
 const UPDATE_ENTRY_WORKERS_MAX_ENV: &str = "MPM_UPDATE_ENTRY_WORKERS_MAX";
@@ -113,6 +140,8 @@ const RUNTIME_RECONCILE_MARKER_FILE: &str = ".runtime_reconcile.v1.done";
 const RUNTIME_SESSION_ACTIVE_MARKER_FILE: &str = ".active_session.v1";
 const STALE_RUNTIME_SESSION_MAX_AGE_HOURS: u64 = 24;
 const STORAGE_ACTION_CLEAR_SHARED_CACHE: &str = "clear_shared_cache";
+const STORAGE_ACTION_GC_CONTENT_STORE: &str = "gc_content_store";
+const STORAGE_ACTION_DEDUPE_HARDLINKS: &str = "dedupe_hardlinks";
 const STORAGE_ACTION_PRUNE_RUNTIME_SESSIONS: &str = "prune_runtime_sessions";
 const STORAGE_ACTION_PRUNE_SNAPSHOTS: &str = "prune_snapshots";
 const STORAGE_ACTION_PRUNE_WORLD_BACKUPS: &str = "prune_world_backups";
@@ -778,6 +807,16 @@ struct InstanceSettings {
     world_backup_interval_minutes: u32,
     #[serde(default = "default_world_backup_retention_count")]
     world_backup_retention_count: u32,
+    #[serde(default)]
+    world_backup_keep_hourly: u32,
+    #[serde(default)]
+    world_backup_keep_daily: u32,
+    #[serde(default)]
+    world_backup_keep_weekly: u32,
+    /// Extra folders this instance's world backups are copied to, on top of
+    /// the launcher-wide destinations.
+    #[serde(default)]
+    world_backup_destinations: Vec<world_backup_store::WorldBackupDestination>,
     #[serde(default = "default_snapshot_retention_count")]
     snapshot_retention_count: u32,
     #[serde(default = "default_snapshot_max_age_days")]
@@ -804,6 +843,10 @@ impl Default for InstanceSettings {
             force_vsync: false,
             world_backup_interval_minutes: default_world_backup_interval_minutes(),
             world_backup_retention_count: default_world_backup_retention_count(),
+            world_backup_keep_hourly: 0,
+            world_backup_keep_daily: 0,
+            world_backup_keep_weekly: 0,
+            world_backup_destinations: Vec::new(),
             snapshot_retention_count: default_snapshot_retention_count(),
             snapshot_max_age_days: default_snapshot_max_age_days(),
         }
@@ -845,6 +888,17 @@ struct LocalModAnalysis {
     scanned_at: String,
 }
 
+/// Result of the static malware scan run on every mod jar the launcher writes.
+/// Findings block launch until `acknowledged_at` is set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct JarSecurityScan {
+    scanned_at: String,
+    #[serde(default)]
+    findings: Vec<jar_analysis::malware::MalwareFinding>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    acknowledged_at: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct LockEntry {
     source: String,
@@ -868,6 +922,12 @@ struct LockEntry {
     provider_candidates: Vec<ProviderCandidate>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     local_analysis: Option<LocalModAnalysis>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    security_scan: Option<JarSecurityScan>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    url_update: Option<url_source::UrlUpdateTemplate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    github_channel: Option<github_channels::GithubChannel>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -909,6 +969,10 @@ struct InstalledMod {
     provider_candidates: Vec<ProviderCandidate>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     local_analysis: Option<LocalModAnalysis>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    security_scan: Option<JarSecurityScan>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    github_channel: Option<github_channels::GithubChannel>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -1099,6 +1163,13 @@ struct LaunchInstanceArgs {
     quick_play_host: Option<String>,
     #[serde(alias = "quickPlayPort", default)]
     quick_play_port: Option<u16>,
+    /// Save folder to open directly with singleplayer Quick Play.
+    #[serde(alias = "quickPlayWorld", default)]
+    quick_play_world: Option<String>,
+    /// Take a world backup first when the Quick Play world was saved by a
+    /// newer game version.
+    #[serde(alias = "backupWorldFirst", default)]
+    backup_world_first: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -1109,6 +1180,46 @@ struct ExportInstanceModsZipArgs {
     grant_id: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct ExportInstanceWorldArgs {
+    #[serde(alias = "instanceId")]
+    instance_id: String,
+    #[serde(alias = "worldId")]
+    world_id: String,
+    #[serde(alias = "grantId")]
+    grant_id: String,
+    #[serde(alias = "includeDatapacks", default)]
+    include_datapacks: bool,
+    #[serde(alias = "includeModList", default)]
+    include_mod_list: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct ImportInstanceWorldArgs {
+    #[serde(alias = "instanceId")]
+    instance_id: String,
+    #[serde(alias = "grantId")]
+    grant_id: String,
+    /// Save folder name to use instead of the one in the archive.
+    #[serde(default)]
+    name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TrimInstanceWorldArgs {
+    #[serde(alias = "instanceId")]
+    instance_id: String,
+    #[serde(alias = "worldId")]
+    world_id: String,
+    /// Chunks players spent less time in than this are removed.
+    #[serde(alias = "minInhabitedSeconds", default)]
+    min_inhabited_seconds: Option<u32>,
+    #[serde(alias = "protectRadiusChunks", default)]
+    protect_radius_chunks: Option<u32>,
+    #[serde(alias = "dryRun", default)]
+    dry_run: bool,
+}
+
 #[derive(Debug, Deserialize)]
 struct PreflightLaunchCompatibilityArgs {
     #[serde(alias = "instanceId")]
@@ -1141,6 +1252,83 @@ struct SetInstalledModPinArgs {
     pin: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+struct SetInstalledModGithubChannelArgs {
+    #[serde(alias = "instanceId")]
+    instance_id: String,
+    #[serde(alias = "versionId")]
+    version_id: String,
+    #[serde(alias = "contentType", default)]
+    content_type: Option<String>,
+    #[serde(default)]
+    filename: Option<String>,
+    #[serde(default)]
+    channel: Option<github_channels::GithubChannel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ScanInstanceContentSecurityArgs {
+    #[serde(alias = "instanceId")]
+    instance_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AcknowledgeInstalledModSecurityArgs {
+    #[serde(alias = "instanceId")]
+    instance_id: String,
+    #[serde(alias = "versionId")]
+    version_id: String,
+    #[serde(alias = "contentType", default)]
+    content_type: Option<String>,
+    #[serde(default)]
+    filename: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VerifyInstanceLockfileArgs {
+    #[serde(alias = "instanceId")]
+    instance_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RedownloadModifiedEntriesArgs {
+    #[serde(alias = "instanceId")]
+    instance_id: String,
+    /// Limits the repair to these entries; every modified or missing entry otherwise.
+    #[serde(alias = "versionIds", default)]
+    version_ids: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RehydrateInstanceContentArgs {
+    #[serde(alias = "instanceId")]
+    instance_id: String,
+    /// Restore from the content store and snapshots only, reporting what needs a download.
+    #[serde(default)]
+    offline: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct SetInstanceContentWatcherArgs {
+    #[serde(alias = "instanceId")]
+    instance_id: String,
+    enabled: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListCurseforgeHandoffItemsArgs {
+    #[serde(alias = "instanceId", default)]
+    instance_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DismissCurseforgeHandoffItemArgs {
+    #[serde(alias = "instanceId")]
+    instance_id: String,
+    #[serde(alias = "projectId")]
+    project_id: String,
+}
+
 #[derive(Debug, Deserialize)]
 struct ListInstanceHistoryEventsArgs {
     #[serde(alias = "instanceId")]
@@ -1259,6 +1447,16 @@ struct SetLauncherSettingsArgs {
     discord_presence_enabled: Option<bool>,
     #[serde(alias = "discordPresenceDetailLevel", default)]
     discord_presence_detail_level: Option<String>,
+    #[serde(alias = "verifyLockHashesBeforeLaunch", default)]
+    verify_lock_hashes_before_launch: Option<bool>,
+    #[serde(alias = "teamRepository", default)]
+    team_repository: Option<String>,
+    #[serde(alias = "curseforgeHandoffDir", default)]
+    curseforge_handoff_dir: Option<String>,
+    #[serde(alias = "worldBackupDestinations", default)]
+    world_backup_destinations: Option<Vec<world_backup_store::WorldBackupDestination>>,
+    #[serde(alias = "backupVerifyIntervalHours", default)]
+    backup_verify_interval_hours: Option<u32>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -1384,6 +1582,41 @@ struct ListInstanceSnapshotsArgs {
     instance_id: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct UpdateInstanceSnapshotArgs {
+    #[serde(alias = "instanceId")]
+    instance_id: String,
+    #[serde(alias = "snapshotId")]
+    snapshot_id: String,
+    #[serde(default)]
+    label: Option<String>,
+    #[serde(default)]
+    pinned: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DiffInstanceSnapshotsArgs {
+    #[serde(alias = "instanceId")]
+    instance_id: String,
+    #[serde(alias = "fromSnapshotId")]
+    from_snapshot_id: String,
+    /// Compared against the live instance when omitted.
+    #[serde(alias = "toSnapshotId", default)]
+    to_snapshot_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RestoreInstanceSnapshotSelectionArgs {
+    #[serde(alias = "instanceId")]
+    instance_id: String,
+    #[serde(alias = "snapshotId")]
+    snapshot_id: String,
+    #[serde(alias = "entryKeys", default)]
+    entry_keys: Vec<String>,
+    #[serde(default)]
+    paths: Vec<String>,
+}
+
 #[derive(Debug, Deserialize)]
 struct ListInstanceWorldsArgs {
     #[serde(alias = "instanceId")]
@@ -1474,6 +1707,20 @@ struct RollbackInstanceWorldBackupArgs {
     world_id: String,
     #[serde(alias = "backupId", default)]
     backup_id: Option<String>,
+    /// Restore the newest backup taken at or before this RFC 3339 time.
+    #[serde(default)]
+    at: Option<String>,
+    /// `local` or a destination path; any location holding the backup when unset.
+    #[serde(default)]
+    location: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListInstanceWorldBackupsArgs {
+    #[serde(alias = "instanceId")]
+    instance_id: String,
+    #[serde(alias = "worldId", default)]
+    world_id: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -1491,6 +1738,47 @@ struct InstallDiscoverContentArgs {
     target_worlds: Vec<String>,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+struct InstallUrlContentArgs {
+    #[serde(alias = "instanceId")]
+    instance_id: String,
+    /// https:// download URL, optionally with a `{version}` placeholder.
+    url: String,
+    #[serde(alias = "contentType", default = "default_content_type_mods")]
+    content_type: String,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    version: Option<String>,
+    #[serde(default)]
+    filename: Option<String>,
+    #[serde(default)]
+    sha256: Option<String>,
+    #[serde(default)]
+    sha512: Option<String>,
+    #[serde(alias = "targetWorlds", default)]
+    target_worlds: Vec<String>,
+    #[serde(alias = "updateTemplate", default)]
+    update_template: Option<url_source::UrlUpdateTemplate>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct InstallMavenContentArgs {
+    #[serde(alias = "instanceId")]
+    instance_id: String,
+    #[serde(alias = "repositoryUrl")]
+    repository_url: String,
+    /// `group:artifact[:version[:classifier]]`; the newest matching version
+    /// is used when the version is omitted.
+    coordinate: String,
+    #[serde(alias = "contentType", default = "default_content_type_mods")]
+    content_type: String,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(alias = "targetWorlds", default)]
+    target_worlds: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct CreatorPresetSettings {
     #[serde(default)]
@@ -1591,6 +1879,19 @@ struct ImportPresetsJsonArgs {
     grant_id: String,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+struct ImportSecurityAdvisoryBundleArgs {
+    #[serde(alias = "grantId")]
+    grant_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct VerifyBackupsArgs {
+    /// Every instance when unset.
+    #[serde(alias = "instanceId", default)]
+    instance_id: Option<String>,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 struct SearchDiscoverContentArgs {
     query: String,
@@ -1739,6 +2040,13 @@ struct CurseforgeLogo {
     url: String,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+struct CurseforgeLinks {
+    #[serde(default)]
+    #[serde(rename = "websiteUrl")]
+    website_url: String,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 struct CurseforgeCategory {
     #[serde(default)]
@@ -1779,6 +2087,8 @@ struct CurseforgeMod {
     categories: Vec<CurseforgeCategory>,
     #[serde(default)]
     logo: Option<CurseforgeLogo>,
+    #[serde(default)]
+    links: Option<CurseforgeLinks>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -1806,6 +2116,12 @@ struct CurseforgeFile {
     hashes: Vec<CurseforgeFileHash>,
     #[serde(default)]
     dependencies: Vec<CurseforgeFileDependency>,
+    #[serde(default)]
+    #[serde(rename = "fileFingerprint")]
+    file_fingerprint: Option<i64>,
+    #[serde(default)]
+    #[serde(rename = "fileLength")]
+    file_length: Option<u64>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -2032,6 +2348,8 @@ struct ContentUpdateCheckResult {
     update_count: usize,
     updates: Vec<ContentUpdateInfo>,
     warnings: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    advisories: Vec<run_reports::RunFinding>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -2079,6 +2397,67 @@ struct LaunchCompatibilityReport {
     permissions: Vec<permissions::LaunchPermissionChecklistItem>,
     #[serde(skip_serializing_if = "Option::is_none")]
     mic_requirement: Option<permissions::LaunchMicRequirementSummary>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    mixin_conflicts: Vec<jar_analysis::mixins::MixinConflict>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct InstanceSecurityScanItem {
+    filename: String,
+    name: String,
+    enabled: bool,
+    tracked: bool,
+    acknowledged: bool,
+    findings: Vec<jar_analysis::malware::MalwareFinding>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct InstanceSecurityScanReport {
+    instance_id: String,
+    scanned_at: String,
+    scanned_files: usize,
+    unacknowledged_count: usize,
+    flagged: Vec<InstanceSecurityScanItem>,
+    /// Files that could not be read, with the reason.
+    unscanned: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct LockEntryIntegrityItem {
+    project_id: String,
+    version_id: String,
+    name: String,
+    filename: String,
+    content_type: String,
+    status: String, // unchanged | modified | missing | no_hash
+    #[serde(skip_serializing_if = "Option::is_none")]
+    algorithm: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expected_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    actual_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<String>,
+    redownloadable: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct LockfileIntegrityReport {
+    instance_id: String,
+    checked_at: String,
+    checked_entries: usize,
+    unchanged: usize,
+    modified: usize,
+    missing: usize,
+    no_hash: usize,
+    items: Vec<LockEntryIntegrityItem>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct LockEntryRedownloadResult {
+    instance_id: String,
+    repaired: Vec<String>,
+    warnings: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -2168,6 +2547,21 @@ struct LauncherSettings {
     discord_presence_enabled: bool,
     #[serde(default = "default_discord_presence_detail_level")]
     discord_presence_detail_level: String,
+    #[serde(default)]
+    verify_lock_hashes_before_launch: bool,
+    /// Directory or http(s) location of a team repository `index.json`.
+    #[serde(default)]
+    team_repository: String,
+    /// Folder watched for manually downloaded CurseForge files; empty means
+    /// the system Downloads folder.
+    #[serde(default)]
+    curseforge_handoff_dir: String,
+    /// Folders every instance's world backups are copied to.
+    #[serde(default)]
+    world_backup_destinations: Vec<world_backup_store::WorldBackupDestination>,
+    /// Hours between scheduled backup integrity checks; 0 turns them off.
+    #[serde(default = "default_backup_verify_interval_hours")]
+    backup_verify_interval_hours: u32,
 }
 
 impl Default for LauncherSettings {
@@ -2185,10 +2579,19 @@ impl Default for LauncherSettings {
             auto_trigger_mic_permission_prompt: default_auto_trigger_mic_permission_prompt(),
             discord_presence_enabled: true,
             discord_presence_detail_level: default_discord_presence_detail_level(),
+            verify_lock_hashes_before_launch: false,
+            team_repository: String::new(),
+            curseforge_handoff_dir: String::new(),
+            world_backup_destinations: Vec::new(),
+            backup_verify_interval_hours: default_backup_verify_interval_hours(),
         }
     }
 }
 
+fn default_backup_verify_interval_hours() -> u32 {
+    backup_verify::DEFAULT_BACKUP_VERIFY_INTERVAL_HOURS
+}
+
 fn default_app_language() -> String {
     "en-US".to_string()
 }
@@ -2340,6 +2743,21 @@ struct SnapshotMeta {
     id: String,
     created_at: String,
     reason: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+    /// Pinned snapshots are exempt from retention and storage cleanup.
+    #[serde(default)]
+    pinned: bool,
+    /// Set when the launcher pinned the snapshot itself, e.g. `last_known_good`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    auto_pin: Option<String>,
+    /// When the last integrity check ran.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    verified_at: Option<String>,
+    /// Problem found by the last integrity check. Damaged snapshots are never
+    /// picked by a "restore latest".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    integrity_error: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -2528,6 +2946,8 @@ struct StorageUsageOverview {
     app_bytes: u64,
     shared_cache_bytes: u64,
     instances_bytes: u64,
+    /// Bytes shared through hardlinks, counted once in `total_bytes`.
+    hardlinked_bytes: u64,
     reclaimable_bytes: u64,
     app_breakdown: Vec<StorageBucketTotal>,
     instance_summaries: Vec<StorageInstanceSummary>,
@@ -2604,6 +3024,12 @@ struct InstanceWorld {
     latest_backup_at: Option<String>,
     #[serde(default)]
     backup_count: usize,
+    /// Read from `level.dat`; absent when the file is missing or unreadable.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    metadata: Option<level_dat::WorldMetadata>,
+    /// Last saved by a newer game version than the instance runs.
+    #[serde(default)]
+    newer_than_instance: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -2660,6 +3086,9 @@ struct ActivePlaySessionRecord {
     isolated: bool,
     pid: u32,
     started_at: String,
+    /// Snapshot of the content the session launched with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    snapshot_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -2740,6 +3169,19 @@ struct WorldBackupMeta {
     reason: String,
     files_count: usize,
     total_bytes: u64,
+    /// `zip` for legacy full archives, `incremental` for manifest backups.
+    #[serde(default = "default_world_backup_format")]
+    format: String,
+    /// Bytes this backup added to the shared object store.
+    #[serde(default)]
+    new_bytes: u64,
+    /// When the last integrity check ran.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    verified_at: Option<String>,
+    /// Problem found by the last integrity check. Damaged backups are never
+    /// picked by a "restore latest".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    integrity_error: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -2925,6 +3367,12 @@ fn normalize_external_open_path_purpose(raw: &str) -> Result<&'static str, Strin
         }
         EXTERNAL_PATH_PURPOSE_PRESETS_IMPORT => Ok(EXTERNAL_PATH_PURPOSE_PRESETS_IMPORT),
         EXTERNAL_PATH_PURPOSE_MODPACK_SPEC_IMPORT => Ok(EXTERNAL_PATH_PURPOSE_MODPACK_SPEC_IMPORT),
+        EXTERNAL_PATH_PURPOSE_ADVISORY_BUNDLE_IMPORT => {
+            Ok(EXTERNAL_PATH_PURPOSE_ADVISORY_BUNDLE_IMPORT)
+        }
+        EXTERNAL_PATH_PURPOSE_WORLD_ARCHIVE_IMPORT => {
+            Ok(EXTERNAL_PATH_PURPOSE_WORLD_ARCHIVE_IMPORT)
+        }
         _ => Err("Unsupported open-picker purpose".to_string()),
     }
 }
@@ -2939,6 +3387,9 @@ fn normalize_external_save_path_purpose(raw: &str) -> Result<&'static str, Strin
         EXTERNAL_PATH_PURPOSE_SUPPORT_BUNDLE_EXPORT => {
             Ok(EXTERNAL_PATH_PURPOSE_SUPPORT_BUNDLE_EXPORT)
         }
+        EXTERNAL_PATH_PURPOSE_WORLD_ARCHIVE_EXPORT => {
+            Ok(EXTERNAL_PATH_PURPOSE_WORLD_ARCHIVE_EXPORT)
+        }
         _ => Err("Unsupported save-picker purpose".to_string()),
     }
 }
@@ -3263,8 +3714,10 @@ fn register_native_play_session_start(
     launch_id: &str,
     pid: u32,
     isolated: bool,
+    snapshot_id: Option<String>,
 ) -> Result<(), String> {
     let instance_dir = instance_dir_for_id(instances_dir, instance_id)?;
+    let started_at = now_iso();
     let mut active = read_active_play_sessions_store(&instance_dir);
     active.active.retain(|entry| entry.launch_id != launch_id);
     active.active.push(ActivePlaySessionRecord {
@@ -3273,7 +3726,8 @@ fn register_native_play_session_start(
         method: "native".to_string(),
         isolated,
         pid,
-        started_at: now_iso(),
+        started_at,
+        snapshot_id,
     });
     write_active_play_sessions_store(&instance_dir, active)
 }
@@ -3296,6 +3750,13 @@ fn finalize_native_play_session(
     };
     let entry = active.active.remove(pos);
     write_active_play_sessions_store(&instance_dir, active)?;
+    if exit_kind.trim().eq_ignore_ascii_case("success") && !recovered {
+        if let Some(snapshot_id) = entry.snapshot_id.as_deref() {
+            if let Err(err) = pin_last_known_good_snapshot(&instance_dir, snapshot_id) {
+                eprintln!("last known good snapshot pin failed for '{instance_id}': {err}");
+            }
+        }
+    }
 
     let ended_at = now_iso();
     let start_secs = created_at_sort_key(&entry.started_at);
@@ -3397,6 +3858,12 @@ fn recover_native_play_sessions_startup(app: &tauri::AppHandle) -> Result<(), St
 }
 
 fn dir_total_size_bytes(path: &Path) -> u64 {
+    dir_unique_size_bytes(path, &mut HashSet::new())
+}
+
+/// Like [`dir_total_size_bytes`], but files hardlinked to anything already in
+/// `seen` are not counted again.
+fn dir_unique_size_bytes(path: &Path, seen: &mut HashSet<(u64, u64)>) -> u64 {
     if !path.exists() {
         return 0;
     }
@@ -3411,7 +3878,7 @@ fn dir_total_size_bytes(path: &Path) -> u64 {
             continue;
         }
         if meta.is_file() {
-            total = total.saturating_add(meta.len());
+            total = total.saturating_add(hardlink_dedupe::unique_file_len(&current, &meta, seen));
             continue;
         }
         if !meta.is_dir() {
@@ -3641,7 +4108,7 @@ fn storage_summary_for_instance(
     .iter()
     .map(|path| dir_total_size_bytes(path))
     .sum::<u64>();
-    let reclaimable_snapshots = storage_snapshot_cleanup_targets(
+    let reclaimable_snapshots = snapshot_prune_targets(
         &instance_dir,
         settings.snapshot_retention_count as usize,
         settings.snapshot_max_age_days as i64,
@@ -3649,13 +4116,18 @@ fn storage_summary_for_instance(
     .iter()
     .map(|path| dir_total_size_bytes(path))
     .sum::<u64>();
-    let reclaimable_world_backups = storage_world_backup_cleanup_targets(
+    let world_backup_targets = storage_world_backup_cleanup_targets(
         &instance_dir,
-        settings.world_backup_retention_count as usize,
-    )?
-    .iter()
-    .map(|path| dir_total_size_bytes(path))
-    .sum::<u64>();
+        world_backup_store::WorldBackupRetention::from_settings(&settings),
+    )?;
+    let reclaimable_world_backups = world_backup_targets
+        .iter()
+        .map(|path| dir_total_size_bytes(path))
+        .sum::<u64>()
+        .saturating_add(world_backup_store::exclusive_object_bytes(
+            &instance_dir,
+            &world_backup_targets,
+        ));
 
     let summary = StorageInstanceSummary {
         instance_id: inst.id.clone(),
@@ -3682,55 +4154,11 @@ fn storage_summary_for_instance(
     Ok(summary)
 }
 
-fn storage_snapshot_cleanup_targets(
+fn storage_world_backup_cleanup_targets(
     instance_dir: &Path,
-    keep: usize,
-    max_age_days: i64,
+    retention: world_backup_store::WorldBackupRetention,
 ) -> Result<Vec<PathBuf>, String> {
-    let metas = list_snapshots(instance_dir)?;
-    let root = snapshots_dir(instance_dir);
-    let cutoff = Utc::now()
-        .timestamp()
-        .saturating_sub(max_age_days.max(0).saturating_mul(86_400));
-    let mut targets = Vec::new();
-    for (idx, meta) in metas.iter().enumerate() {
-        let created_at = created_at_sort_key(&meta.created_at);
-        let over_count = keep > 0 && idx >= keep;
-        let over_age = created_at > 0 && created_at < cutoff;
-        if !over_count && !over_age {
-            continue;
-        }
-        let dir = root.join(&meta.id);
-        if dir.exists() {
-            targets.push(dir);
-        }
-    }
-    Ok(targets)
-}
-
-fn storage_world_backup_cleanup_targets(
-    instance_dir: &Path,
-    keep_per_world: usize,
-) -> Result<Vec<PathBuf>, String> {
-    if keep_per_world == 0 {
-        return Ok(Vec::new());
-    }
-    let metas = list_world_backups(instance_dir)?;
-    let root = world_backups_dir(instance_dir);
-    let mut seen_by_world: HashMap<String, usize> = HashMap::new();
-    let mut targets = Vec::new();
-    for meta in metas {
-        let seen = seen_by_world.entry(meta.world_id.clone()).or_insert(0);
-        *seen += 1;
-        if *seen <= keep_per_world {
-            continue;
-        }
-        let dir = root.join(&meta.id);
-        if dir.exists() {
-            targets.push(dir);
-        }
-    }
-    Ok(targets)
+    world_backup_store::world_backup_prune_targets(instance_dir, None, retention)
 }
 
 fn storage_stale_runtime_session_targets(
@@ -3852,9 +4280,35 @@ fn storage_app_breakdown(
 
 fn storage_recommendations_for_overview(
     shared_cache_bytes: u64,
+    content_store_garbage: (usize, u64),
+    duplicate_content: (usize, u64),
     instance_summaries: &[StorageInstanceSummary],
 ) -> Vec<StorageCleanupRecommendation> {
     let mut out = Vec::new();
+    let (duplicate_files, duplicate_bytes) = duplicate_content;
+    if duplicate_files > 0 {
+        out.push(StorageCleanupRecommendation {
+            action_id: STORAGE_ACTION_DEDUPE_HARDLINKS.to_string(),
+            title: "Share identical files across instances".to_string(),
+            description: "Hardlink byte-identical mods and packs so each is stored once. Launcher updates replace a linked file instead of editing it in place."
+                .to_string(),
+            scope: "app".to_string(),
+            reclaimable_bytes: duplicate_bytes,
+            item_count: duplicate_files,
+        });
+    }
+    let (garbage_blobs, garbage_bytes) = content_store_garbage;
+    if garbage_blobs > 0 {
+        out.push(StorageCleanupRecommendation {
+            action_id: STORAGE_ACTION_GC_CONTENT_STORE.to_string(),
+            title: "Clean up content store".to_string(),
+            description: "Remove stored downloads no instance or snapshot references anymore."
+                .to_string(),
+            scope: "app".to_string(),
+            reclaimable_bytes: garbage_bytes,
+            item_count: garbage_blobs,
+        });
+    }
     if shared_cache_bytes > 0 {
         out.push(StorageCleanupRecommendation {
             action_id: STORAGE_ACTION_CLEAR_SHARED_CACHE.to_string(),
@@ -3935,11 +4389,25 @@ fn scan_storage_usage_overview(app: &tauri::AppHandle) -> StorageUsageOverview {
         }
     };
 
+    let mut content_store_garbage = (0, 0);
+    let mut content_store_dir = None;
+    if let (Ok(launcher), Ok(instances_dir)) = (launcher_dir(app), app_instances_dir(app)) {
+        let store_dir = launcher.join(content_store::STORE_DIR_NAME);
+        let store = content_store::ContentStore::new(store_dir.clone());
+        match store.pending_garbage(&instances_dir) {
+            Ok(garbage) => content_store_garbage = garbage,
+            Err(err) => warnings.push(format!("Content store scan unavailable: {err}")),
+        }
+        content_store_dir = Some(store_dir);
+    }
+
     let mut instance_summaries = Vec::new();
+    let mut instance_dirs = Vec::new();
     match app_instances_dir(app) {
         Ok(instances_dir) => match read_index(&instances_dir) {
             Ok(index) => {
                 for inst in index.instances {
+                    instance_dirs.push(instance_dir_for_instance(&instances_dir, &inst));
                     match storage_summary_for_instance(&instances_dir, &inst) {
                         Ok(summary) => instance_summaries.push(summary),
                         Err(err) => warnings.push(format!(
@@ -3961,12 +4429,30 @@ fn scan_storage_usage_overview(app: &tauri::AppHandle) -> StorageUsageOverview {
                 .cmp(&b.instance_name.to_lowercase())
         })
     });
-    let instances_bytes = instance_summaries
+    // Files hardlinked between instances, or to the content store, count once.
+    let mut seen_links = HashSet::new();
+    let instances_bytes = instance_dirs
         .iter()
-        .map(|summary| summary.total_bytes)
+        .map(|dir| dir_unique_size_bytes(dir, &mut seen_links))
         .sum::<u64>();
-    let recommendations =
-        storage_recommendations_for_overview(shared_cache_bytes, &instance_summaries);
+    let store_linked_bytes = content_store_dir
+        .as_ref()
+        .map(|dir| {
+            dir_total_size_bytes(dir).saturating_sub(dir_unique_size_bytes(dir, &mut seen_links))
+        })
+        .unwrap_or(0);
+    let hardlinked_bytes = instance_summaries
+        .iter()
+        .map(|summary| summary.total_bytes)
+        .sum::<u64>()
+        .saturating_sub(instances_bytes)
+        .saturating_add(store_linked_bytes);
+    let recommendations = storage_recommendations_for_overview(
+        shared_cache_bytes,
+        content_store_garbage,
+        hardlink_dedupe::estimate_duplicates(&instance_dirs),
+        &instance_summaries,
+    );
     let reclaimable_bytes = recommendations
         .iter()
         .map(|item| item.reclaimable_bytes)
@@ -3975,10 +4461,12 @@ fn scan_storage_usage_overview(app: &tauri::AppHandle) -> StorageUsageOverview {
         scanned_at: now_iso(),
         total_bytes: app_bytes
             .saturating_add(shared_cache_bytes)
-            .saturating_add(instances_bytes),
+            .saturating_add(instances_bytes)
+            .saturating_sub(store_linked_bytes),
         app_bytes,
         shared_cache_bytes,
         instances_bytes,
+        hardlinked_bytes,
         reclaimable_bytes,
         app_breakdown,
         instance_summaries,
@@ -4599,6 +5087,10 @@ fn default_world_backup_retention_count() -> u32 {
     DEFAULT_WORLD_BACKUP_RETENTION_COUNT
 }
 
+fn default_world_backup_format() -> String {
+    world_backup_store::WORLD_BACKUP_FORMAT_ZIP.to_string()
+}
+
 fn default_snapshot_retention_count() -> u32 {
     DEFAULT_SNAPSHOT_RETENTION_COUNT
 }
@@ -4812,6 +5304,7 @@ fn snapshot_allowed_root(name: &str) -> bool {
     matches!(name, "mods" | "resourcepacks" | "shaderpacks" | "saves")
 }
 
+#[cfg(test)]
 fn add_dir_recursive_to_zip(
     zip: &mut zip::ZipWriter<File>,
     root: &Path,
@@ -4858,6 +5351,8 @@ fn add_dir_recursive_to_zip(
     Ok(())
 }
 
+/// Legacy full-zip snapshot writer, kept to build migration fixtures.
+#[cfg(test)]
 fn create_instance_content_zip(instance_dir: &Path, zip_path: &Path) -> Result<usize, String> {
     let parent = zip_path
         .parent()
@@ -4891,7 +5386,8 @@ fn create_instance_content_zip(instance_dir: &Path, zip_path: &Path) -> Result<u
     Ok(count)
 }
 
-fn restore_instance_content_zip(zip_path: &Path, instance_dir: &Path) -> Result<usize, String> {
+/// Empties the folders a snapshot covers so a restore leaves no stray files.
+fn clear_snapshot_content_dirs(instance_dir: &Path) -> Result<(), String> {
     for dir_name in ["mods", "resourcepacks", "shaderpacks"] {
         let dir = instance_dir.join(dir_name);
         if dir.exists() {
@@ -4916,6 +5412,11 @@ fn restore_instance_content_zip(zip_path: &Path, instance_dir: &Path) -> Result<
             fs::create_dir_all(&dp_dir).map_err(|e| format!("mkdir datapacks failed: {e}"))?;
         }
     }
+    Ok(())
+}
+
+fn restore_instance_content_zip(zip_path: &Path, instance_dir: &Path) -> Result<usize, String> {
+    clear_snapshot_content_dirs(instance_dir)?;
     if !zip_path.exists() {
         return Ok(0);
     }
@@ -4947,6 +5448,7 @@ fn restore_instance_content_zip(zip_path: &Path, instance_dir: &Path) -> Result<
         if let Some(parent) = out_path.parent() {
             fs::create_dir_all(parent).map_err(|e| format!("mkdir restore parent failed: {e}"))?;
         }
+        hardlink_dedupe::detach_before_write(&out_path)?;
         let mut out =
             File::create(&out_path).map_err(|e| format!("restore mods file failed: {e}"))?;
         std::io::copy(&mut entry, &mut out).map_err(|e| format!("restore copy failed: {e}"))?;
@@ -4991,13 +5493,21 @@ fn list_snapshots(instance_dir: &Path) -> Result<Vec<SnapshotMeta>, String> {
     Ok(out)
 }
 
-fn prune_old_snapshots(instance_dir: &Path, keep: usize, max_age_days: i64) -> Result<(), String> {
+/// Unpinned snapshots beyond the newest `keep` or older than `max_age_days`.
+/// Automatic pruning removes exactly these, and the Storage Manager offers
+/// the same set.
+fn snapshot_prune_targets(
+    instance_dir: &Path,
+    keep: usize,
+    max_age_days: i64,
+) -> Result<Vec<PathBuf>, String> {
     let metas = list_snapshots(instance_dir)?;
     let root = snapshots_dir(instance_dir);
     let cutoff = Utc::now()
         .timestamp()
         .saturating_sub(max_age_days.max(0).saturating_mul(86_400));
-    for (idx, meta) in metas.iter().enumerate() {
+    let mut targets = Vec::new();
+    for (idx, meta) in metas.iter().filter(|meta| !meta.pinned).enumerate() {
         let created_at = created_at_sort_key(&meta.created_at);
         let over_count = keep > 0 && idx >= keep;
         let over_age = created_at > 0 && created_at < cutoff;
@@ -5006,12 +5516,87 @@ fn prune_old_snapshots(instance_dir: &Path, keep: usize, max_age_days: i64) -> R
         }
         let dir = root.join(&meta.id);
         if dir.exists() {
-            fs::remove_dir_all(&dir).map_err(|e| format!("remove old snapshot failed: {e}"))?;
+            targets.push(dir);
         }
     }
+    Ok(targets)
+}
+
+fn prune_old_snapshots(instance_dir: &Path, keep: usize, max_age_days: i64) -> Result<(), String> {
+    for dir in snapshot_prune_targets(instance_dir, keep, max_age_days)? {
+        fs::remove_dir_all(&dir).map_err(|e| format!("remove old snapshot failed: {e}"))?;
+    }
     Ok(())
 }
 
+/// Sets a snapshot's label and/or pin. An empty label clears it; a manual pin
+/// or unpin replaces any automatic pin.
+fn update_snapshot_meta(
+    instance_dir: &Path,
+    snapshot_id: &str,
+    label: Option<&str>,
+    pinned: Option<bool>,
+) -> Result<SnapshotMeta, String> {
+    let mut meta = list_snapshots(instance_dir)?
+        .into_iter()
+        .find(|meta| meta.id == snapshot_id)
+        .ok_or_else(|| "Snapshot not found".to_string())?;
+    if let Some(label) = label {
+        let label = label.trim();
+        meta.label = (!label.is_empty()).then(|| label.to_string());
+    }
+    if let Some(pinned) = pinned {
+        meta.pinned = pinned;
+        meta.auto_pin = None;
+    }
+    write_snapshot_meta(&snapshots_dir(instance_dir).join(&meta.id), &meta)?;
+    Ok(meta)
+}
+
+/// Snapshot matching the content a session launches with: the newest snapshot
+/// when nothing changed since it was taken, otherwise a new one. Taken before
+/// the game starts, whichever launcher runs it.
+fn launch_content_snapshot(instances_dir: &Path, instance_id: &str) -> Result<String, String> {
+    let instance_dir = instance_dir_for_id(instances_dir, instance_id)?;
+    if let Some(newest) = list_snapshots(&instance_dir)?.into_iter().next() {
+        let snapshot = snapshot_diff::load_snapshot_state(&instance_dir, &newest.id)?;
+        let current = snapshot_diff::current_state(
+            &instance_dir,
+            read_lockfile(instances_dir, instance_id)?,
+        )?;
+        let (lock_changes, file_changes) = snapshot_diff::diff_content_states(&snapshot, &current);
+        if lock_changes.is_empty() && file_changes.is_empty() {
+            return Ok(newest.id);
+        }
+    }
+    Ok(create_instance_snapshot(instances_dir, instance_id, "before-launch")?.id)
+}
+
+/// Moves the automatic "last known good" pin to `snapshot_id`. Pins the user
+/// set themselves are left alone.
+fn pin_last_known_good_snapshot(instance_dir: &Path, snapshot_id: &str) -> Result<bool, String> {
+    let root = snapshots_dir(instance_dir);
+    let mut found = false;
+    for mut meta in list_snapshots(instance_dir)? {
+        let auto_pinned = meta.auto_pin.as_deref() == Some(SNAPSHOT_PIN_LAST_KNOWN_GOOD);
+        if meta.id == snapshot_id {
+            found = true;
+            if meta.pinned {
+                continue;
+            }
+            meta.pinned = true;
+            meta.auto_pin = Some(SNAPSHOT_PIN_LAST_KNOWN_GOOD.to_string());
+        } else if auto_pinned {
+            meta.pinned = false;
+            meta.auto_pin = None;
+        } else {
+            continue;
+        }
+        write_snapshot_meta(&root.join(&meta.id), &meta)?;
+    }
+    Ok(found)
+}
+
 fn snapshot_reason_slug(reason: &str) -> String {
     let mut out = String::with_capacity(reason.len());
     let mut last_dash = false;
@@ -5076,11 +5661,16 @@ fn create_instance_snapshot(
     fs::write(snapshot_lock_path(&snapshot_dir), lock_raw)
         .map_err(|e| format!("write snapshot lock failed: {e}"))?;
 
-    let _ = create_instance_content_zip(&instance_dir, &snapshot_content_zip_path(&snapshot_dir))?;
+    let _ = snapshot_store::create_content_snapshot(&instance_dir, &snapshot_dir)?;
     let meta = SnapshotMeta {
         id: snapshot_id,
         created_at: now_iso(),
         reason: reason.to_string(),
+        label: None,
+        pinned: false,
+        auto_pin: None,
+        verified_at: None,
+        integrity_error: None,
     };
     write_snapshot_meta(&snapshot_dir, &meta)?;
     let instance_settings = read_index(instances_dir)
@@ -5148,113 +5738,23 @@ fn list_world_backups(instance_dir: &Path) -> Result<Vec<WorldBackupMeta>, Strin
     Ok(out)
 }
 
-fn prune_old_world_backups(instance_dir: &Path, world_id: &str, keep: usize) -> Result<(), String> {
-    if keep == 0 {
-        return Ok(());
-    }
-    let metas = list_world_backups(instance_dir)?;
-    let root = world_backups_dir(instance_dir);
-    let mut seen = 0usize;
-    for meta in metas {
-        if meta.world_id != world_id {
-            continue;
-        }
-        seen += 1;
-        if seen <= keep {
-            continue;
-        }
-        let dir = root.join(&meta.id);
-        if dir.exists() {
-            fs::remove_dir_all(&dir).map_err(|e| format!("remove old world backup failed: {e}"))?;
-        }
-    }
-    Ok(())
-}
-
-fn add_world_dir_recursive_to_zip(
-    zip: &mut zip::ZipWriter<File>,
-    root: &Path,
-    current: &Path,
-    opts: FileOptions,
-    file_count: &mut usize,
-    total_bytes: &mut u64,
+fn prune_old_world_backups(
+    instance_dir: &Path,
+    world_id: &str,
+    retention: world_backup_store::WorldBackupRetention,
 ) -> Result<(), String> {
-    if !current.exists() {
+    let targets =
+        world_backup_store::world_backup_prune_targets(instance_dir, Some(world_id), retention)?;
+    if targets.is_empty() {
         return Ok(());
     }
-    let entries = fs::read_dir(current)
-        .map_err(|e| format!("read dir '{}' failed: {e}", current.display()))?;
-    for ent in entries {
-        let ent = ent.map_err(|e| format!("read dir entry failed: {e}"))?;
-        let path = ent.path();
-        let meta = match ent.metadata() {
-            Ok(meta) => meta,
-            Err(_) => continue,
-        };
-        if meta.is_dir() {
-            add_world_dir_recursive_to_zip(zip, root, &path, opts, file_count, total_bytes)?;
-            continue;
-        }
-        if !meta.is_file() {
-            continue;
-        }
-        if path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .map(|n| n.eq_ignore_ascii_case("session.lock"))
-            .unwrap_or(false)
-        {
-            continue;
-        }
-        let rel = path
-            .strip_prefix(root)
-            .map_err(|_| "failed to compute relative world backup path".to_string())?;
-        let rel_text = rel
-            .to_string_lossy()
-            .replace('\\', "/")
-            .trim_start_matches('/')
-            .to_string();
-        if rel_text.is_empty() {
-            continue;
-        }
-        let data = match fs::read(&path) {
-            Ok(data) => data,
-            Err(_) => continue,
-        };
-        zip.start_file(rel_text, opts)
-            .map_err(|e| format!("world backup zip start file failed: {e}"))?;
-        zip.write_all(&data)
-            .map_err(|e| format!("world backup zip write failed: {e}"))?;
-        *file_count += 1;
-        *total_bytes += data.len() as u64;
+    for dir in targets {
+        fs::remove_dir_all(&dir).map_err(|e| format!("remove old world backup failed: {e}"))?;
     }
+    world_backup_store::collect_world_backup_objects(instance_dir);
     Ok(())
 }
 
-fn create_world_backup_zip(world_dir: &Path, zip_path: &Path) -> Result<(usize, u64), String> {
-    let parent = zip_path
-        .parent()
-        .ok_or_else(|| "invalid world backup zip path".to_string())?;
-    fs::create_dir_all(parent).map_err(|e| format!("mkdir world backup dir failed: {e}"))?;
-    let file =
-        File::create(zip_path).map_err(|e| format!("create world backup zip failed: {e}"))?;
-    let mut zip = zip::ZipWriter::new(file);
-    let opts = FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
-    let mut file_count = 0usize;
-    let mut total_bytes = 0u64;
-    add_world_dir_recursive_to_zip(
-        &mut zip,
-        world_dir,
-        world_dir,
-        opts,
-        &mut file_count,
-        &mut total_bytes,
-    )?;
-    zip.finish()
-        .map_err(|e| format!("finalize world backup zip failed: {e}"))?;
-    Ok((file_count, total_bytes))
-}
-
 fn restore_world_backup_zip(zip_path: &Path, world_dir: &Path) -> Result<usize, String> {
     if !zip_path.exists() {
         return Err("World backup archive is missing".to_string());
@@ -5301,9 +5801,11 @@ fn restore_world_backup_zip(zip_path: &Path, world_dir: &Path) -> Result<usize,
 
 fn create_world_backup_for_world(
     instance_dir: &Path,
+    instance_id: &str,
     world_id: &str,
     reason: &str,
-    keep_per_world: usize,
+    retention: world_backup_store::WorldBackupRetention,
+    destinations: &[world_backup_store::WorldBackupDestination],
 ) -> Result<WorldBackupMeta, String> {
     let world_name = world_id.trim();
     if world_name.is_empty() {
@@ -5320,21 +5822,48 @@ fn create_world_backup_for_world(
     } else {
         slug_base
     };
+    let lock = world_backup_store::world_backup_lock(instance_dir);
+    let _guard = lock
+        .lock()
+        .map_err(|_| "world backup lock poisoned".to_string())?;
     let backup_id = format!("wb_{}_{}", slug, now_millis());
     let backup_dir = world_backups_dir(instance_dir).join(&backup_id);
     fs::create_dir_all(&backup_dir).map_err(|e| format!("mkdir world backup failed: {e}"))?;
-    let (files_count, total_bytes) =
-        create_world_backup_zip(&world_dir, &world_backup_zip_path(&backup_dir))?;
+    let stats = match world_backup_store::create_incremental_world_backup(
+        instance_dir,
+        world_name,
+        &world_dir,
+        &backup_dir,
+    ) {
+        Ok(stats) => stats,
+        Err(err) => {
+            // A half-written backup without meta would show up as an orphan.
+            let _ = fs::remove_dir_all(&backup_dir);
+            return Err(err);
+        }
+    };
     let meta = WorldBackupMeta {
         id: backup_id,
         world_id: world_name.to_string(),
         created_at: now_iso(),
         reason: reason.to_string(),
-        files_count,
-        total_bytes,
+        files_count: stats.files_count,
+        total_bytes: stats.total_bytes,
+        format: world_backup_store::WORLD_BACKUP_FORMAT_INCREMENTAL.to_string(),
+        new_bytes: stats.new_bytes,
+        verified_at: None,
+        integrity_error: None,
     };
-    write_world_backup_meta(&backup_dir, &meta)?;
-    prune_old_world_backups(instance_dir, world_name, keep_per_world)?;
+    if let Err(err) = write_world_backup_meta(&backup_dir, &meta) {
+        let _ = fs::remove_dir_all(&backup_dir);
+        return Err(err);
+    }
+    for err in
+        world_backup_store::mirror_world_backup(instance_dir, instance_id, &meta, destinations)
+    {
+        eprintln!("world backup destination copy failed: {err}");
+    }
+    prune_old_world_backups(instance_dir, world_name, retention)?;
     Ok(meta)
 }
 
@@ -5342,7 +5871,8 @@ fn create_world_backups_for_instance(
     instances_dir: &Path,
     instance_id: &str,
     reason: &str,
-    keep_per_world: usize,
+    retention: world_backup_store::WorldBackupRetention,
+    destinations: &[world_backup_store::WorldBackupDestination],
 ) -> Result<usize, String> {
     let instance_dir = instance_dir_for_id(instances_dir, instance_id)?;
     let worlds = list_instance_world_names(&instance_dir)?;
@@ -5352,7 +5882,14 @@ fn create_world_backups_for_instance(
     let mut created = 0usize;
     let mut last_error: Option<String> = None;
     for world in worlds {
-        match create_world_backup_for_world(&instance_dir, &world, reason, keep_per_world) {
+        match create_world_backup_for_world(
+            &instance_dir,
+            instance_id,
+            &world,
+            reason,
+            retention,
+            destinations,
+        ) {
             Ok(_) => created += 1,
             Err(e) => last_error = Some(e),
         }
@@ -5477,6 +6014,11 @@ fn normalize_instance_settings(mut settings: InstanceSettings) -> InstanceSettin
     settings.memory_mb = settings.memory_mb.clamp(512, 65536);
     settings.world_backup_interval_minutes = settings.world_backup_interval_minutes.clamp(5, 15);
     settings.world_backup_retention_count = settings.world_backup_retention_count.clamp(1, 2);
+    settings.world_backup_keep_hourly = settings.world_backup_keep_hourly.min(48);
+    settings.world_backup_keep_daily = settings.world_backup_keep_daily.min(31);
+    settings.world_backup_keep_weekly = settings.world_backup_keep_weekly.min(12);
+    settings.world_backup_destinations =
+        world_backup_store::normalize_world_backup_destinations(settings.world_backup_destinations);
     settings.snapshot_retention_count = settings.snapshot_retention_count.clamp(1, 20);
     settings.snapshot_max_age_days = settings.snapshot_max_age_days.clamp(1, 90);
     settings.loader_version_strategy =
@@ -8067,6 +8609,7 @@ fn provider_source_priority(source: &str) -> i32 {
         "modrinth" => 3,
         "curseforge" => 2,
         "github" => 1,
+        "team" => 1,
         _ => 0,
     }
 }
@@ -8222,6 +8765,10 @@ fn detect_provider_matches_for_local_mod(
         }
     }
 
+    if let Some(found) = team_repo::detect_team_match(client, &sha512, &sha256) {
+        matches.push(found);
+    }
+
     if include_metadata_fallback {
         matches.extend(detect_provider_from_github_release_assets(
             client,
@@ -8581,10 +9128,159 @@ fn entry_file_exists(instance_dir: &Path, entry: &LockEntry) -> bool {
     }
 }
 
+/// Strongest recorded hash first; md5 is never used for verification.
+const LOCK_VERIFY_HASH_PREFERENCE: [&str; 3] = ["sha512", "sha256", "sha1"];
+
+fn lock_entry_expected_hash(entry: &LockEntry) -> Option<(&'static str, String)> {
+    preferred_verify_hash(&entry.hashes)
+}
+
+/// The strongest non-empty hash in a provider or lockfile hash map, with the
+/// algorithm name normalized to `sha512`/`sha256`/`sha1`.
+fn preferred_verify_hash(hashes: &HashMap<String, String>) -> Option<(&'static str, String)> {
+    LOCK_VERIFY_HASH_PREFERENCE.iter().find_map(|algorithm| {
+        hashes
+            .iter()
+            .find(|(key, value)| {
+                key.trim().replace('-', "").eq_ignore_ascii_case(algorithm)
+                    && !value.trim().is_empty()
+            })
+            .map(|(_, value)| (*algorithm, value.trim().to_ascii_lowercase()))
+    })
+}
+
+fn hash_bytes_hex(algorithm: &str, bytes: &[u8]) -> String {
+    match algorithm {
+        "sha512" => sha512_hex(bytes),
+        "sha256" => sha256_bytes_hex(bytes),
+        _ => {
+            use sha1::Digest as _;
+            format!("{:x}", sha1::Sha1::digest(bytes))
+        }
+    }
+}
+
+/// Files on disk that belong to an entry, preferring the copy matching its
+/// enabled state. Datapacks yield one file per target world.
+fn lock_entry_existing_files(instance_dir: &Path, entry: &LockEntry) -> Vec<PathBuf> {
+    let pick = |(enabled_path, disabled_path): (PathBuf, PathBuf)| {
+        let (first, second) = if entry.enabled {
+            (enabled_path, disabled_path)
+        } else {
+            (disabled_path, enabled_path)
+        };
+        if first.exists() {
+            Some(first)
+        } else if second.exists() {
+            Some(second)
+        } else {
+            None
+        }
+    };
+    match normalize_lock_content_type(&entry.content_type).as_str() {
+        "datapacks" => entry
+            .target_worlds
+            .iter()
+            .filter_map(|world| pick(datapack_world_paths(instance_dir, world, &entry.filename)))
+            .collect(),
+        "resourcepacks" | "shaderpacks" => pick(content_paths_for_type(
+            instance_dir,
+            &entry.content_type,
+            &entry.filename,
+        ))
+        .into_iter()
+        .collect(),
+        _ => pick(mod_paths(instance_dir, &entry.filename))
+            .into_iter()
+            .collect(),
+    }
+}
+
+fn lock_entry_redownloadable(entry: &LockEntry) -> bool {
+    matches!(
+        entry.source.trim().to_ascii_lowercase().as_str(),
+        "modrinth" | "curseforge" | "team" | "url" | "maven"
+    )
+}
+
+fn verify_lock_entry_integrity(instance_dir: &Path, entry: &LockEntry) -> LockEntryIntegrityItem {
+    let mut item = LockEntryIntegrityItem {
+        project_id: entry.project_id.clone(),
+        version_id: entry.version_id.clone(),
+        name: entry.name.clone(),
+        filename: entry.filename.clone(),
+        content_type: normalize_lock_content_type(&entry.content_type),
+        status: "unchanged".to_string(),
+        algorithm: None,
+        expected_hash: None,
+        actual_hash: None,
+        path: None,
+        redownloadable: lock_entry_redownloadable(entry),
+    };
+    let files = lock_entry_existing_files(instance_dir, entry);
+    if files.is_empty() || !entry_file_exists(instance_dir, entry) {
+        item.status = "missing".to_string();
+        return item;
+    }
+    let Some((algorithm, expected)) = lock_entry_expected_hash(entry) else {
+        item.status = "no_hash".to_string();
+        item.path = files.first().map(|path| path.display().to_string());
+        return item;
+    };
+    item.algorithm = Some(algorithm.to_string());
+    item.expected_hash = Some(expected.clone());
+    for path in files {
+        let actual = match fs::read(&path) {
+            Ok(bytes) => hash_bytes_hex(algorithm, &bytes),
+            Err(_) => {
+                item.status = "missing".to_string();
+                item.path = Some(path.display().to_string());
+                return item;
+            }
+        };
+        if actual != expected {
+            item.status = "modified".to_string();
+            item.actual_hash = Some(actual);
+            item.path = Some(path.display().to_string());
+            return item;
+        }
+        item.path.get_or_insert_with(|| path.display().to_string());
+    }
+    item
+}
+
+/// Rehashes every enabled entry's file(s) against the hash recorded at install.
+fn verify_lockfile_integrity(
+    instance_id: &str,
+    instance_dir: &Path,
+    lock: &Lockfile,
+) -> LockfileIntegrityReport {
+    let items = lock
+        .entries
+        .iter()
+        .filter(|entry| entry.enabled)
+        .map(|entry| verify_lock_entry_integrity(instance_dir, entry))
+        .collect::<Vec<_>>();
+    let count = |status: &str| items.iter().filter(|item| item.status == status).count();
+    LockfileIntegrityReport {
+        instance_id: instance_id.to_string(),
+        checked_at: now_iso(),
+        checked_entries: items.len(),
+        unchanged: count("unchanged"),
+        modified: count("modified"),
+        missing: count("missing"),
+        no_hash: count("no_hash"),
+        items,
+    }
+}
+
 fn lock_entry_provider_candidates(entry: &LockEntry) -> Vec<ProviderCandidate> {
     let mut candidates = entry.provider_candidates.clone();
     let source = entry.source.trim().to_ascii_lowercase();
-    if source == "modrinth" || source == "curseforge" || source == "github" {
+    if matches!(
+        source.as_str(),
+        "modrinth" | "curseforge" | "github" | "team" | "url" | "maven"
+    ) {
         candidates.push(ProviderCandidate {
             source: entry.source.clone(),
             project_id: entry.project_id.clone(),
@@ -8644,6 +9340,8 @@ fn lock_entry_to_installed(instance_dir: &Path, entry: &LockEntry) -> InstalledM
         hashes: entry.hashes.clone(),
         provider_candidates: lock_entry_provider_candidates(entry),
         local_analysis: entry.local_analysis.clone(),
+        security_scan: entry.security_scan.clone(),
+        github_channel: entry.github_channel.clone(),
     }
 }
 
@@ -9013,6 +9711,50 @@ fn format_download_meter(downloaded_bytes: u64, total_bytes: Option<u64>) -> Str
     format!("{downloaded_mb:.1} MB")
 }
 
+/// Stages a provider download, serving it from the content store when one of
+/// `known_hashes` is already stored so repeat installs need no network.
+fn stage_download_with_content_store<F>(
+    client: &Client,
+    url: &str,
+    label: &str,
+    known_hashes: &HashMap<String, String>,
+    temp_path: &Path,
+    mut on_progress: F,
+) -> Result<StreamDownloadResult, String>
+where
+    F: FnMut(u64, Option<u64>),
+{
+    if let Some(digest) = content_store::ContentStore::configured()
+        .and_then(|store| store.stage(known_hashes, temp_path))
+    {
+        on_progress(digest.len, Some(digest.len));
+        return Ok(StreamDownloadResult {
+            sha256: digest.sha256,
+            sha512: digest.sha512,
+            profile: StreamDownloadProfile {
+                content_length: Some(digest.len),
+                ..StreamDownloadProfile::default()
+            },
+        });
+    }
+    download_stream_to_temp_with_retry(client, url, label, temp_path, on_progress)
+}
+
+/// In-memory counterpart of [`stage_download_with_content_store`].
+fn download_bytes_with_content_store(
+    client: &Client,
+    url: &str,
+    label: &str,
+    known_hashes: &HashMap<String, String>,
+) -> Result<Vec<u8>, String> {
+    if let Some(bytes) =
+        content_store::ContentStore::configured().and_then(|store| store.read(known_hashes))
+    {
+        return Ok(bytes);
+    }
+    download_bytes_with_retry(client, url, label)
+}
+
 fn download_stream_to_temp_with_retry<F>(
     client: &Client,
     url: &str,
@@ -11733,19 +12475,14 @@ fn fetch_project_versions(
     project_id: &str,
 ) -> Result<Vec<ModrinthVersion>, String> {
     let versions_url = format!("{}/project/{project_id}/version", modrinth_api_base());
-    let versions_resp = client
-        .get(&versions_url)
-        .send()
-        .map_err(|e| format!("fetch versions failed for {project_id}: {e}"))?;
-    if !versions_resp.status().is_success() {
-        return Err(format!(
-            "fetch versions failed for {project_id} with status {}",
-            versions_resp.status()
-        ));
-    }
+    let body = http_cache::get_text(
+        client,
+        &versions_url,
+        &[],
+        &format!("fetch versions failed for {project_id}"),
+    )?;
 
-    let mut versions: Vec<ModrinthVersion> = versions_resp
-        .json()
+    let mut versions: Vec<ModrinthVersion> = serde_json::from_str(&body)
         .map_err(|e| format!("parse versions failed for {project_id}: {e}"))?;
     for v in &mut versions {
         if v.project_id.trim().is_empty() {
@@ -11757,17 +12494,13 @@ fn fetch_project_versions(
 
 fn fetch_version_by_id(client: &Client, version_id: &str) -> Result<ModrinthVersion, String> {
     let url = format!("{}/version/{version_id}", modrinth_api_base());
-    let resp = client
-        .get(&url)
-        .send()
-        .map_err(|e| format!("fetch dependency version {version_id} failed: {e}"))?;
-    if !resp.status().is_success() {
-        return Err(format!(
-            "fetch dependency version {version_id} failed with status {}",
-            resp.status()
-        ));
-    }
-    resp.json::<ModrinthVersion>()
+    let body = http_cache::get_text(
+        client,
+        &url,
+        &[],
+        &format!("fetch dependency version {version_id} failed"),
+    )?;
+    serde_json::from_str::<ModrinthVersion>(&body)
         .map_err(|e| format!("parse dependency version {version_id} failed: {e}"))
 }
 
@@ -12053,6 +12786,21 @@ fn remove_lock_entry_files_if_unreferenced(
 fn remove_lock_entries_matching<F>(
     lock: &mut Lockfile,
     instance_dir: &Path,
+    predicate: F,
+) -> Result<Vec<LockEntry>, String>
+where
+    F: FnMut(&LockEntry) -> bool,
+{
+    remove_lock_entries_replaced_by(lock, instance_dir, None, predicate)
+}
+
+/// Like `remove_lock_entries_matching`, but leaves the files of removed
+/// entries alone when `replacement` was just written to the same path (e.g. a
+/// `-SNAPSHOT` jar whose filename never changes).
+fn remove_lock_entries_replaced_by<F>(
+    lock: &mut Lockfile,
+    instance_dir: &Path,
+    replacement: Option<&LockEntry>,
     mut predicate: F,
 ) -> Result<Vec<LockEntry>, String>
 where
@@ -12069,6 +12817,9 @@ where
     }
     lock.entries = retained;
     for old in &removed {
+        if replacement.is_some_and(|entry| lock_entries_share_content_target(entry, old)) {
+            continue;
+        }
         remove_lock_entry_files_if_unreferenced(lock, instance_dir, old)?;
     }
     Ok(removed)
@@ -12123,6 +12874,9 @@ fn merge_lock_entry_metadata(target: &mut LockEntry, other: &LockEntry) {
     if target.local_analysis.is_none() {
         target.local_analysis = other.local_analysis.clone();
     }
+    if target.security_scan.is_none() {
+        target.security_scan = other.security_scan.clone();
+    }
     for (key, value) in &other.hashes {
         if !value.trim().is_empty() {
             target
@@ -12156,6 +12910,9 @@ fn duplicate_entry_source_label(source: &str) -> &'static str {
         "modrinth" => "Modrinth",
         "curseforge" => "CurseForge",
         "github" => "GitHub",
+        "team" => "the team repository",
+        "url" => "a direct URL",
+        "maven" => "a Maven repository",
         _ => "another source",
     }
 }
@@ -12191,19 +12948,21 @@ fn enforce_lock_entry_uniqueness(
     let normalized = normalize_lock_content_type(&new_entry.content_type);
     let exact_project_id = new_entry.project_id.trim().to_string();
     if !exact_project_id.is_empty() {
-        let removed = remove_lock_entries_matching(lock, instance_dir, |entry| {
-            normalize_lock_content_type(&entry.content_type) == normalized
-                && entry.project_id.eq_ignore_ascii_case(&exact_project_id)
-        })?;
+        let removed =
+            remove_lock_entries_replaced_by(lock, instance_dir, Some(new_entry), |entry| {
+                normalize_lock_content_type(&entry.content_type) == normalized
+                    && entry.project_id.eq_ignore_ascii_case(&exact_project_id)
+            })?;
         for old in &removed {
             merge_lock_entry_metadata(new_entry, old);
         }
     }
 
     if normalized == "mods" {
-        let removed = remove_lock_entries_matching(lock, instance_dir, |entry| {
-            lock_entries_conflict_for_duplicate_resolution(entry, new_entry)
-        })?;
+        let removed =
+            remove_lock_entries_replaced_by(lock, instance_dir, Some(new_entry), |entry| {
+                lock_entries_conflict_for_duplicate_resolution(entry, new_entry)
+            })?;
         for old in &removed {
             merge_lock_entry_metadata(new_entry, old);
         }
@@ -12267,13 +13026,10 @@ fn remove_replaced_entries_for_content(
 
 fn fetch_project_title(client: &Client, project_id: &str) -> Option<String> {
     let project_url = format!("{}/project/{project_id}", modrinth_api_base());
-    match client.get(&project_url).send() {
-        Ok(resp) if resp.status().is_success() => match resp.json::<ModrinthProjectResponse>() {
-            Ok(project) => Some(project.title),
-            Err(_) => None,
-        },
-        _ => None,
-    }
+    let body = http_cache::get_text(client, &project_url, &[], "fetch project").ok()?;
+    serde_json::from_str::<ModrinthProjectResponse>(&body)
+        .ok()
+        .map(|project| project.title)
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -12317,7 +13073,12 @@ fn normalize_update_content_type_filter(requested: Option<&[String]>) -> Option<
 fn update_scope_allows_source(scope: UpdateScope, source: &str, content_type: &str) -> bool {
     match scope {
         UpdateScope::AllContent => {
-            source == "modrinth" || source == "curseforge" || source == "github"
+            source == "modrinth"
+                || source == "curseforge"
+                || source == "github"
+                || source == "team"
+                || source == "url"
+                || source == "maven"
         }
         UpdateScope::ModrinthModsOnly => source == "modrinth" && content_type == "mods",
     }
@@ -12423,239 +13184,329 @@ fn entry_allowed_in_content_type_filter(
     filter.contains(&content_type)
 }
 
-fn check_single_content_update_entry(
-    client: &Client,
-    instance: &Instance,
-    entry: &LockEntry,
-    cf_key: Option<&str>,
-    scope: UpdateScope,
-) -> Result<(Option<ContentUpdateInfo>, Vec<String>), String> {
-    let mut warnings: Vec<String> = Vec::new();
+/// Provider-resolved identity of a lock entry for update checks.
+#[derive(Debug, Clone)]
+struct UpdateCheckTarget {
+    source: String,
+    content_type: String,
+    project_id: String,
+    current_version_id: String,
+    current_version_number: String,
+    display_name: String,
+}
 
-    if entry
-        .pinned_version
-        .as_ref()
-        .map(|v| !v.trim().is_empty())
-        .unwrap_or(false)
-    {
-        warnings.push(format!(
-            "Skipped pinned entry '{}' ({})",
-            entry.name, entry.project_id
-        ));
-        return Ok((None, warnings));
+fn update_check_target(entry: &LockEntry, provider: &ProviderCandidate) -> UpdateCheckTarget {
+    UpdateCheckTarget {
+        source: provider.source.trim().to_ascii_lowercase(),
+        content_type: normalize_lock_content_type(&entry.content_type),
+        project_id: provider.project_id.trim().to_string(),
+        current_version_id: if provider.version_id.trim().is_empty() {
+            entry.version_id.clone()
+        } else {
+            provider.version_id.clone()
+        },
+        current_version_number: if provider.version_number.trim().is_empty() {
+            entry.version_number.clone()
+        } else {
+            provider.version_number.clone()
+        },
+        display_name: if provider.name.trim().is_empty() {
+            entry.name.clone()
+        } else {
+            provider.name.clone()
+        },
     }
+}
 
-    let Some(effective_provider) = effective_updatable_provider_for_entry(entry, scope) else {
-        return Ok((None, warnings));
-    };
-    let source = effective_provider.source.trim().to_ascii_lowercase();
-    let content_type = normalize_lock_content_type(&entry.content_type);
-    let project_id = effective_provider.project_id.trim().to_string();
-    let current_version_id = if effective_provider.version_id.trim().is_empty() {
-        entry.version_id.clone()
-    } else {
-        effective_provider.version_id.clone()
-    };
-    let current_version_number = if effective_provider.version_number.trim().is_empty() {
-        entry.version_number.clone()
-    } else {
-        effective_provider.version_number.clone()
-    };
-    let display_name = if effective_provider.name.trim().is_empty() {
-        entry.name.clone()
-    } else {
-        effective_provider.name.clone()
-    };
-
-    if source == "modrinth" {
-        let versions = fetch_project_versions(client, &project_id)?;
-        let latest_any = versions
-            .iter()
-            .max_by(|a, b| a.date_published.cmp(&b.date_published))
-            .cloned();
-        let Some(latest) = pick_compatible_version_for_content(versions, instance, &content_type)
-        else {
-            if let Some(incompatible_latest) = latest_any {
-                warnings.push(format!(
-                    "No compatible Modrinth update found for '{}' ({}). Latest release '{}' targets MC [{}] loaders [{}].",
-                    display_name,
-                    project_id,
-                    incompatible_latest.version_number,
-                    incompatible_latest.game_versions.join(", "),
-                    incompatible_latest.loaders.join(", "),
-                ));
-            } else {
-                warnings.push(format!(
-                    "No compatible Modrinth update found for '{}' ({})",
-                    display_name, project_id
-                ));
-            }
-            return Ok((None, warnings));
-        };
-        if latest.id == current_version_id {
-            return Ok((None, warnings));
-        }
-        let latest_file = latest
-            .files
-            .iter()
-            .find(|f| f.primary.unwrap_or(false))
-            .or_else(|| latest.files.first());
-        let required_dependencies = latest
-            .dependencies
-            .iter()
-            .filter(|dep| dep.dependency_type.eq_ignore_ascii_case("required"))
-            .filter_map(|dep| dep.project_id.as_ref())
-            .map(|project_id| project_id.trim().to_string())
-            .filter(|dependency_project_id| {
-                !dependency_project_id.is_empty() && dependency_project_id != &project_id
-            })
-            .collect::<Vec<_>>();
-        let mut compatibility_notes: Vec<String> = Vec::new();
+fn modrinth_update_from_versions(
+    instance: &Instance,
+    entry: &LockEntry,
+    target: &UpdateCheckTarget,
+    versions: Vec<ModrinthVersion>,
+    warnings: &mut Vec<String>,
+) -> Option<ContentUpdateInfo> {
+    let project_id = &target.project_id;
+    let display_name = &target.display_name;
+    let latest_any = versions
+        .iter()
+        .max_by(|a, b| a.date_published.cmp(&b.date_published))
+        .cloned();
+    let Some(latest) =
+        pick_compatible_version_for_content(versions, instance, &target.content_type)
+    else {
         if let Some(incompatible_latest) = latest_any {
-            if incompatible_latest.id != latest.id {
-                compatibility_notes.push(format!(
-                    "Newest release '{}' is not compatible with this instance ({} / {}); selected latest compatible '{}'.",
-                    incompatible_latest.version_number,
-                    instance.loader,
-                    instance.mc_version,
-                    latest.version_number
-                ));
-            }
+            warnings.push(format!(
+                "No compatible Modrinth update found for '{}' ({}). Latest release '{}' targets MC [{}] loaders [{}].",
+                display_name,
+                project_id,
+                incompatible_latest.version_number,
+                incompatible_latest.game_versions.join(", "),
+                incompatible_latest.loaders.join(", "),
+            ));
+        } else {
+            warnings.push(format!(
+                "No compatible Modrinth update found for '{}' ({})",
+                display_name, project_id
+            ));
         }
-        return Ok((
-            Some(ContentUpdateInfo {
-                source: "modrinth".to_string(),
-                content_type,
-                project_id: project_id.clone(),
-                name: display_name.clone(),
-                current_version_id,
-                current_version_number,
-                latest_version_id: latest.id,
-                latest_version_number: latest.version_number,
-                enabled: entry.enabled,
-                target_worlds: entry.target_worlds.clone(),
-                latest_file_name: latest_file.map(|f| f.filename.clone()),
-                latest_download_url: latest_file.map(|f| f.url.clone()),
-                latest_hashes: latest_file.map(|f| f.hashes.clone()).unwrap_or_default(),
-                required_dependencies,
-                compatibility_status: Some("compatible".to_string()),
-                compatibility_notes,
-            }),
-            warnings,
-        ));
+        return None;
+    };
+    if latest.id == target.current_version_id {
+        return None;
     }
-
-    if source == "curseforge" {
-        let Some(api_key) = cf_key else {
-            return Ok((None, warnings));
-        };
-        let mod_id = parse_curseforge_project_id(&project_id)?;
-        let mut files = fetch_curseforge_files(client, api_key, mod_id)?;
-        let latest_any = files
-            .iter()
-            .max_by(|a, b| a.file_date.cmp(&b.file_date))
-            .cloned();
-        files.retain(|f| {
-            !f.file_name.trim().is_empty()
-                && file_looks_compatible_with_instance(f, instance, &content_type)
-        });
-        files.sort_by(|a, b| b.file_date.cmp(&a.file_date));
-        let Some(latest) = files.into_iter().next() else {
-            if let Some(incompatible_latest) = latest_any {
-                warnings.push(format!(
-                    "No compatible CurseForge update found for '{}' ({}). Latest file '{}' supports [{}].",
-                    display_name,
-                    project_id,
-                    if incompatible_latest.display_name.trim().is_empty() {
-                        incompatible_latest.file_name.clone()
-                    } else {
-                        incompatible_latest.display_name.clone()
-                    },
-                    incompatible_latest.game_versions.join(", "),
-                ));
-            } else {
-                warnings.push(format!(
-                    "No compatible CurseForge update found for '{}' ({})",
-                    display_name, project_id
-                ));
-            }
-            return Ok((None, warnings));
-        };
-        let latest_version_id = format!("cf_file:{}", latest.id);
-        if latest_version_id == current_version_id {
-            return Ok((None, warnings));
+    let latest_file = latest
+        .files
+        .iter()
+        .find(|f| f.primary.unwrap_or(false))
+        .or_else(|| latest.files.first());
+    let required_dependencies = latest
+        .dependencies
+        .iter()
+        .filter(|dep| dep.dependency_type.eq_ignore_ascii_case("required"))
+        .filter_map(|dep| dep.project_id.as_ref())
+        .map(|project_id| project_id.trim().to_string())
+        .filter(|dependency_project_id| {
+            !dependency_project_id.is_empty() && dependency_project_id != project_id
+        })
+        .collect::<Vec<_>>();
+    let mut compatibility_notes: Vec<String> = Vec::new();
+    if let Some(incompatible_latest) = latest_any {
+        if incompatible_latest.id != latest.id {
+            compatibility_notes.push(format!(
+                "Newest release '{}' is not compatible with this instance ({} / {}); selected latest compatible '{}'.",
+                incompatible_latest.version_number,
+                instance.loader,
+                instance.mc_version,
+                latest.version_number
+            ));
         }
-        let latest_version_number = if latest.display_name.trim().is_empty() {
-            latest.file_name.clone()
+    }
+    Some(ContentUpdateInfo {
+        source: "modrinth".to_string(),
+        content_type: target.content_type.clone(),
+        project_id: project_id.clone(),
+        name: display_name.clone(),
+        current_version_id: target.current_version_id.clone(),
+        current_version_number: target.current_version_number.clone(),
+        latest_version_id: latest.id,
+        latest_version_number: latest.version_number,
+        enabled: entry.enabled,
+        target_worlds: entry.target_worlds.clone(),
+        latest_file_name: latest_file.map(|f| f.filename.clone()),
+        latest_download_url: latest_file.map(|f| f.url.clone()),
+        latest_hashes: latest_file.map(|f| f.hashes.clone()).unwrap_or_default(),
+        required_dependencies,
+        compatibility_status: Some("compatible".to_string()),
+        compatibility_notes,
+    })
+}
+
+fn curseforge_update_from_files(
+    client: &Client,
+    api_key: &str,
+    instance: &Instance,
+    entry: &LockEntry,
+    target: &UpdateCheckTarget,
+    mut files: Vec<CurseforgeFile>,
+    warnings: &mut Vec<String>,
+) -> Result<Option<ContentUpdateInfo>, String> {
+    let project_id = &target.project_id;
+    let display_name = &target.display_name;
+    let content_type = &target.content_type;
+    let mod_id = parse_curseforge_project_id(project_id)?;
+    let latest_any = files
+        .iter()
+        .max_by(|a, b| a.file_date.cmp(&b.file_date))
+        .cloned();
+    files.retain(|f| {
+        !f.file_name.trim().is_empty()
+            && file_looks_compatible_with_instance(f, instance, content_type)
+    });
+    files.sort_by(|a, b| b.file_date.cmp(&a.file_date));
+    let Some(latest) = files.into_iter().next() else {
+        if let Some(incompatible_latest) = latest_any {
+            warnings.push(format!(
+                "No compatible CurseForge update found for '{}' ({}). Latest file '{}' supports [{}].",
+                display_name,
+                project_id,
+                if incompatible_latest.display_name.trim().is_empty() {
+                    incompatible_latest.file_name.clone()
+                } else {
+                    incompatible_latest.display_name.clone()
+                },
+                incompatible_latest.game_versions.join(", "),
+            ));
         } else {
-            latest.display_name.clone()
-        };
-        let mut latest_download_url = latest
-            .download_url
-            .as_ref()
-            .map(|v| v.trim().to_string())
-            .filter(|v| !v.is_empty());
-        if latest_download_url.is_none() {
-            match resolve_curseforge_file_download_url(client, api_key, mod_id, &latest) {
-                Ok(url) => latest_download_url = Some(url),
-                Err(err) => {
-                    if error_mentions_forbidden(&err) {
-                        warnings.push(format!(
-                            "Skipped CurseForge update '{}' ({}): provider blocked automated download URL (403).",
-                            display_name, project_id
-                        ));
-                        return Ok((None, warnings));
-                    }
+            warnings.push(format!(
+                "No compatible CurseForge update found for '{}' ({})",
+                display_name, project_id
+            ));
+        }
+        return Ok(None);
+    };
+    let latest_version_id = format!("cf_file:{}", latest.id);
+    if latest_version_id == target.current_version_id {
+        return Ok(None);
+    }
+    let latest_version_number = if latest.display_name.trim().is_empty() {
+        latest.file_name.clone()
+    } else {
+        latest.display_name.clone()
+    };
+    let mut latest_download_url = latest
+        .download_url
+        .as_ref()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty());
+    if latest_download_url.is_none() {
+        match resolve_curseforge_file_download_url(client, api_key, mod_id, &latest) {
+            Ok(url) => latest_download_url = Some(url),
+            Err(err) => {
+                if error_mentions_forbidden(&err) {
                     warnings.push(format!(
-                        "Could not resolve download url for CurseForge update '{}' ({}): {}",
-                        display_name, project_id, err
+                        "Skipped CurseForge update '{}' ({}): provider blocked automated download URL (403).",
+                        display_name, project_id
                     ));
+                    return Ok(None);
                 }
-            }
-        }
-        let mut compatibility_notes: Vec<String> = Vec::new();
-        if let Some(incompatible_latest) = latest_any {
-            if incompatible_latest.id != latest.id {
-                let latest_label = if incompatible_latest.display_name.trim().is_empty() {
-                    incompatible_latest.file_name
-                } else {
-                    incompatible_latest.display_name
-                };
-                compatibility_notes.push(format!(
-                    "Newest file '{}' is not compatible with this instance ({} / {}).",
-                    latest_label, instance.loader, instance.mc_version
+                warnings.push(format!(
+                    "Could not resolve download url for CurseForge update '{}' ({}): {}",
+                    display_name, project_id, err
                 ));
             }
         }
-        return Ok((
-            Some(ContentUpdateInfo {
-                source: "curseforge".to_string(),
-                content_type,
-                project_id: project_id.clone(),
-                name: display_name.clone(),
-                current_version_id,
-                current_version_number,
-                latest_version_id,
-                latest_version_number,
-                enabled: entry.enabled,
-                target_worlds: entry.target_worlds.clone(),
-                latest_file_name: Some(latest.file_name.clone()),
-                latest_download_url,
-                latest_hashes: parse_cf_hashes(&latest),
-                required_dependencies: latest
-                    .dependencies
-                    .iter()
-                    .filter(|dep| {
-                        dep.mod_id > 0 && curseforge_relation_is_required(dep.relation_type)
-                    })
-                    .map(|dep| format!("cf:{}", dep.mod_id))
-                    .filter(|dependency_project_id| dependency_project_id != &project_id)
-                    .collect(),
-                compatibility_status: Some("compatible".to_string()),
-                compatibility_notes,
-            }),
-            warnings,
+    }
+    let mut compatibility_notes: Vec<String> = Vec::new();
+    if let Some(incompatible_latest) = latest_any {
+        if incompatible_latest.id != latest.id {
+            let latest_label = if incompatible_latest.display_name.trim().is_empty() {
+                incompatible_latest.file_name
+            } else {
+                incompatible_latest.display_name
+            };
+            compatibility_notes.push(format!(
+                "Newest file '{}' is not compatible with this instance ({} / {}).",
+                latest_label, instance.loader, instance.mc_version
+            ));
+        }
+    }
+    Ok(Some(ContentUpdateInfo {
+        source: "curseforge".to_string(),
+        content_type: content_type.clone(),
+        project_id: project_id.clone(),
+        name: display_name.clone(),
+        current_version_id: target.current_version_id.clone(),
+        current_version_number: target.current_version_number.clone(),
+        latest_version_id,
+        latest_version_number,
+        enabled: entry.enabled,
+        target_worlds: entry.target_worlds.clone(),
+        latest_file_name: Some(latest.file_name.clone()),
+        latest_download_url,
+        latest_hashes: parse_cf_hashes(&latest),
+        required_dependencies: latest
+            .dependencies
+            .iter()
+            .filter(|dep| dep.mod_id > 0 && curseforge_relation_is_required(dep.relation_type))
+            .map(|dep| format!("cf:{}", dep.mod_id))
+            .filter(|dependency_project_id| dependency_project_id != project_id)
+            .collect(),
+        compatibility_status: Some("compatible".to_string()),
+        compatibility_notes,
+    }))
+}
+
+fn check_single_content_update_entry(
+    client: &Client,
+    instance: &Instance,
+    entry: &LockEntry,
+    cf_key: Option<&str>,
+    scope: UpdateScope,
+) -> Result<(Option<ContentUpdateInfo>, Vec<String>), String> {
+    let mut warnings: Vec<String> = Vec::new();
+
+    if entry
+        .pinned_version
+        .as_ref()
+        .map(|v| !v.trim().is_empty())
+        .unwrap_or(false)
+    {
+        warnings.push(format!(
+            "Skipped pinned entry '{}' ({})",
+            entry.name, entry.project_id
         ));
+        return Ok((None, warnings));
+    }
+
+    let Some(effective_provider) = effective_updatable_provider_for_entry(entry, scope) else {
+        return Ok((None, warnings));
+    };
+    let target = update_check_target(entry, &effective_provider);
+
+    if target.source == "modrinth" {
+        let versions = fetch_project_versions(client, &target.project_id)?;
+        let update =
+            modrinth_update_from_versions(instance, entry, &target, versions, &mut warnings);
+        return Ok((update, warnings));
+    }
+
+    if target.source == "curseforge" {
+        let Some(api_key) = cf_key else {
+            return Ok((None, warnings));
+        };
+        let mod_id = parse_curseforge_project_id(&target.project_id)?;
+        let files = fetch_curseforge_files(client, api_key, mod_id)?;
+        let update = curseforge_update_from_files(
+            client,
+            api_key,
+            instance,
+            entry,
+            &target,
+            files,
+            &mut warnings,
+        )?;
+        return Ok((update, warnings));
+    }
+
+    let UpdateCheckTarget {
+        source,
+        content_type,
+        project_id,
+        current_version_id,
+        current_version_number,
+        display_name,
+    } = target;
+
+    if source == "team" {
+        let update = team_repo::check_team_content_update(
+            client,
+            instance,
+            entry,
+            &effective_provider,
+            &mut warnings,
+        )?;
+        return Ok((update, warnings));
+    }
+
+    if source == "maven" {
+        let update = maven_repo::check_maven_content_update(
+            client,
+            instance,
+            entry,
+            &effective_provider,
+            &mut warnings,
+        )?;
+        return Ok((update, warnings));
+    }
+
+    if source == "url" {
+        let update = url_source::check_url_content_update(
+            client,
+            entry,
+            &effective_provider,
+            &mut warnings,
+        )?;
+        return Ok((update, warnings));
     }
 
     if source == "github" {
@@ -12671,10 +13522,25 @@ fn check_single_content_update_entry(
             ));
             return Ok((None, warnings));
         }
-        let releases = fetch_github_releases(client, &owner, &repo_name)?;
         let query_hint = github_release_query_hint(&entry.filename, &display_name, &repo);
+        let channel = entry.github_channel.as_ref();
+        if let Some(channel) = channel.filter(|c| github_channels::is_artifact_channel(Some(c))) {
+            let update = github_channels::check_github_artifact_update(
+                client,
+                entry,
+                channel,
+                &owner,
+                &repo_name,
+                &display_name,
+                &query_hint,
+                &current_version_id,
+                &mut warnings,
+            )?;
+            return Ok((update, warnings));
+        }
+        let releases = fetch_github_releases(client, &owner, &repo_name)?;
         let mut repo_loader_hints: HashSet<String> = HashSet::new();
-        let mut selection = select_github_release_with_asset(
+        let mut selection = github_channels::select_github_release_for_channel(
             &repo,
             &releases,
             &query_hint,
@@ -12682,6 +13548,7 @@ fn check_single_content_update_entry(
             Some(&instance.loader),
             None,
             None,
+            channel,
         );
         if selection.is_none() {
             repo_loader_hints = fetch_github_repo_loader_hints(client, &repo);
@@ -12690,7 +13557,7 @@ fn check_single_content_update_entry(
             } else {
                 Some(&repo_loader_hints)
             };
-            selection = select_github_release_with_asset(
+            selection = github_channels::select_github_release_for_channel(
                 &repo,
                 &releases,
                 &query_hint,
@@ -12698,6 +13565,7 @@ fn check_single_content_update_entry(
                 Some(&instance.loader),
                 None,
                 repo_loader_hints_opt,
+                channel,
             );
         }
         let Some(selection) = selection else {
@@ -12706,7 +13574,7 @@ fn check_single_content_update_entry(
             } else {
                 Some(&repo_loader_hints)
             };
-            let has_any_release = select_github_release_with_asset(
+            let has_any_release = github_channels::select_github_release_for_channel(
                 &repo,
                 &releases,
                 &query_hint,
@@ -12714,6 +13582,7 @@ fn check_single_content_update_entry(
                 None,
                 None,
                 repo_loader_hints_opt,
+                channel,
             )
             .is_some();
             if has_any_release {
@@ -12862,9 +13731,30 @@ fn check_instance_content_updates_inner(
             update_count: 0,
             updates,
             warnings,
+            advisories: vec![],
         });
     }
 
+    let batched = update_batch::batch_check_content_updates(
+        client,
+        instance,
+        &candidate_entries,
+        scope,
+        cf_key.as_deref(),
+        &update_batch::UpdateBatchEndpoints::from_environment(),
+    );
+    for (_, maybe_update, mut batch_warnings) in batched.resolved {
+        if let Some(update) = maybe_update {
+            updates.push(update);
+        }
+        warnings.append(&mut batch_warnings);
+    }
+    let candidate_entries: Vec<LockEntry> = batched
+        .remaining
+        .iter()
+        .map(|idx| candidate_entries[*idx].clone())
+        .collect();
+
     let update_entry_worker_max =
         env_worker_cap_or_default(UPDATE_ENTRY_WORKERS_MAX_ENV, 12, 1, 24);
     let parallelism = std::thread::available_parallelism()
@@ -13017,6 +13907,7 @@ fn check_instance_content_updates_inner(
         update_count: updates.len(),
         updates,
         warnings,
+        advisories: vec![],
     })
 }
 
@@ -13163,6 +14054,14 @@ fn carried_pinned_version_for_update(
                 return entry_pin;
             }
         }
+        if matches!(update_source.as_str(), "team" | "url" | "maven")
+            && entry
+                .project_id
+                .trim()
+                .eq_ignore_ascii_case(update_project_id)
+        {
+            return entry_pin;
+        }
         if update_source == "github" {
             let update_repo = parse_github_project_id(update_project_id).ok();
             let entry_repo = parse_github_project_id(&entry.project_id).ok();
@@ -13270,6 +14169,10 @@ fn prefetch_update_downloads(
         if source != "modrinth" && source != "curseforge" && source != "github" {
             continue;
         }
+        // Artifact zips need an authenticated download and are unpacked at install.
+        if github_channels::parse_github_artifact_id(&update.latest_version_id).is_some() {
+            continue;
+        }
         let Some(download_url) = update
             .latest_download_url
             .as_ref()
@@ -13325,10 +14228,14 @@ fn prefetch_update_downloads(
             let Some(download_url) = download_url else {
                 continue;
             };
-            let result =
-                download_bytes_with_retry(&client_ref, &download_url, &job.update.project_id)
-                    .map(PrefetchedDownload::Ready)
-                    .unwrap_or_else(PrefetchedDownload::Failed);
+            let result = download_bytes_with_content_store(
+                &client_ref,
+                &download_url,
+                &job.update.project_id,
+                &job.update.latest_hashes,
+            )
+            .map(PrefetchedDownload::Ready)
+            .unwrap_or_else(PrefetchedDownload::Failed);
             if let Ok(mut guard) = out_ref.lock() {
                 guard.insert(job.index, result);
             }
@@ -13362,6 +14269,29 @@ fn try_fast_install_content_update(
     let mut lock = read_lockfile(instances_dir, &args.instance_id)?;
     let carried_pin = carried_pinned_version_for_update(&lock, update);
 
+    if source == "url" || source == "maven" {
+        let new_entry = if source == "url" {
+            url_source::install_url_content_update(
+                &instance_dir,
+                &mut lock,
+                client,
+                update,
+                carried_pin,
+            )?
+        } else {
+            maven_repo::install_maven_content_update(
+                &instance_dir,
+                &mut lock,
+                client,
+                update,
+                carried_pin,
+            )?
+        };
+        lock.entries.sort_by_key(|entry| entry.name.to_lowercase());
+        write_lockfile(instances_dir, &args.instance_id, &lock)?;
+        return Ok(Some(lock_entry_to_installed(&instance_dir, &new_entry)));
+    }
+
     if source == "modrinth" {
         let latest_version_id = update.latest_version_id.trim();
         if latest_version_id.is_empty() {
@@ -13425,7 +14355,12 @@ fn try_fast_install_content_update(
                 }
             }
         } else {
-            download_bytes_with_retry(client, &download_url, &update.project_id)?
+            download_bytes_with_content_store(
+                client,
+                &download_url,
+                &update.project_id,
+                &latest_hashes,
+            )?
         };
 
         let worlds = if normalized == "datapacks" {
@@ -13473,9 +14408,12 @@ fn try_fast_install_content_update(
                 verification_status: None,
             }],
             local_analysis: None,
+            security_scan: None,
+            url_update: None,
+            github_channel: None,
         };
         validate_lock_entry_uniqueness(&lock, &new_entry)?;
-        write_download_to_content_targets(
+        new_entry.security_scan = write_download_to_content_targets(
             &instance_dir,
             &normalized,
             &safe_filename,
@@ -13534,8 +14472,28 @@ fn try_fast_install_content_update(
                 return Ok(None);
             }
             latest_file_name = Some(file.file_name.clone());
-            download_url = Some(resolve_curseforge_file_download_url(
-                client, api_key, mod_id, &file,
+            download_url = Some(curseforge_handoff::resolve_download_url_or_queue(
+                client,
+                api_key,
+                &instance_dir,
+                mod_id,
+                &file,
+                || {
+                    let worlds = if normalized == "datapacks" {
+                        normalize_target_worlds_for_datapack(&instance_dir, &update.target_worlds)?
+                    } else {
+                        vec![]
+                    };
+                    Ok(curseforge_handoff::handoff_item_for_file(
+                        None,
+                        mod_id,
+                        &file,
+                        &update.name,
+                        &normalized,
+                        &worlds,
+                        update.enabled,
+                    ))
+                },
             )?);
             latest_hashes = parse_cf_hashes(&file);
             latest_version_number = if file.display_name.trim().is_empty() {
@@ -13561,10 +14519,11 @@ fn try_fast_install_content_update(
                 }
             }
         } else {
-            download_bytes_with_retry(
+            download_bytes_with_content_store(
                 client,
                 &download_url,
                 &format!("cf:{mod_id}:{latest_file_id}"),
+                &latest_hashes,
             )?
         };
 
@@ -13612,9 +14571,12 @@ fn try_fast_install_content_update(
                 verification_status: None,
             }],
             local_analysis: None,
+            security_scan: None,
+            url_update: None,
+            github_channel: None,
         };
         validate_lock_entry_uniqueness(&lock, &new_entry)?;
-        write_download_to_content_targets(
+        new_entry.security_scan = write_download_to_content_targets(
             &instance_dir,
             &normalized,
             &safe_filename,
@@ -13637,6 +14599,22 @@ fn try_fast_install_content_update(
             return Ok(None);
         }
         let (owner, repo_name) = parse_github_project_id(&update.project_id)?;
+        let channel = github_channels::github_channel_for_update(&lock, update);
+        if github_channels::parse_github_artifact_id(&update.latest_version_id).is_some() {
+            let new_entry = github_channels::install_github_artifact_update(
+                &instance_dir,
+                &mut lock,
+                client,
+                update,
+                &owner,
+                &repo_name,
+                carried_pin,
+                channel,
+            )?;
+            lock.entries.sort_by_key(|entry| entry.name.to_lowercase());
+            write_lockfile(instances_dir, &args.instance_id, &lock)?;
+            return Ok(Some(lock_entry_to_installed(&instance_dir, &new_entry)));
+        }
         let mut repo_full_name_hint: Option<String> = None;
         let mut latest_release_id = parse_github_release_id(&update.latest_version_id);
         let mut latest_file_name = update
@@ -13681,7 +14659,7 @@ fn try_fast_install_content_update(
                 .map(|entry| entry.filename.clone())
                 .unwrap_or_default();
             let query_hint = github_release_query_hint(&current_filename_hint, &update.name, &repo);
-            let mut selection = select_github_release_with_asset(
+            let mut selection = github_channels::select_github_release_for_channel(
                 &repo,
                 &releases,
                 &query_hint,
@@ -13689,6 +14667,7 @@ fn try_fast_install_content_update(
                 Some(&instance.loader),
                 None,
                 None,
+                channel.as_ref(),
             );
             if selection.is_none() {
                 let repo_loader_hints = fetch_github_repo_loader_hints(client, &repo);
@@ -13697,7 +14676,7 @@ fn try_fast_install_content_update(
                 } else {
                     Some(&repo_loader_hints)
                 };
-                selection = select_github_release_with_asset(
+                selection = github_channels::select_github_release_for_channel(
                     &repo,
                     &releases,
                     &query_hint,
@@ -13705,6 +14684,7 @@ fn try_fast_install_content_update(
                     Some(&instance.loader),
                     None,
                     repo_loader_hints_opt,
+                    channel.as_ref(),
                 );
             }
             let Some(selection) = selection else {
@@ -13746,10 +14726,11 @@ fn try_fast_install_content_update(
                 }
             }
         } else {
-            download_bytes_with_retry(
+            download_bytes_with_content_store(
                 client,
                 &download_url,
                 &format!("gh:{owner}/{repo_name}:{latest_release_id}"),
+                &latest_hashes,
             )?
         };
 
@@ -13810,9 +14791,18 @@ fn try_fast_install_content_update(
                 verification_status: Some("verified".to_string()),
             }],
             local_analysis: None,
+            security_scan: None,
+            url_update: None,
+            github_channel: channel,
         };
         validate_lock_entry_uniqueness(&lock, &new_entry)?;
-        write_download_to_content_targets(&instance_dir, &normalized, &safe_filename, &[], &bytes)?;
+        new_entry.security_scan = write_download_to_content_targets(
+            &instance_dir,
+            &normalized,
+            &safe_filename,
+            &[],
+            &bytes,
+        )?;
         if !new_entry.enabled {
             disable_mod_file(&instance_dir, &new_entry.filename)?;
         }
@@ -13894,7 +14884,12 @@ fn discover_index_sort_field(index: &str) -> i64 {
     }
 }
 
-fn github_request(client: &Client, url: &str, token: Option<&str>) -> Result<Response, String> {
+fn github_request(
+    client: &Client,
+    url: &str,
+    token: Option<&str>,
+    validators: &[(&'static str, String)],
+) -> Result<Response, String> {
     let mut req = client
         .get(url)
         .header("Accept", "application/vnd.github+json")
@@ -13902,6 +14897,9 @@ fn github_request(client: &Client, url: &str, token: Option<&str>) -> Result<Res
     if let Some(value) = token {
         req = req.bearer_auth(value);
     }
+    for (name, value) in validators {
+        req = req.header(*name, value.as_str());
+    }
     req.send()
         .map_err(|e| format!("GitHub request failed: {e}"))
 }
@@ -14128,13 +15126,67 @@ fn github_http_error_message(
     }
 }
 
+enum GithubFetched {
+    Body(String, reqwest::header::HeaderMap),
+    NotModified(reqwest::header::HeaderMap),
+}
+
 fn github_get_json<T: for<'de> Deserialize<'de>>(client: &Client, url: &str) -> Result<T, String> {
     if let Some(cached) = github_api_cache_get(url) {
         if let Ok(parsed) = serde_json::from_str::<T>(&cached) {
             return Ok(parsed);
         }
     }
+    let disk_cache = http_cache::HttpMetadataCache::configured();
+    if let Some(body) = disk_cache.as_ref().and_then(|cache| cache.fresh(url)) {
+        if let Ok(parsed) = serde_json::from_str::<T>(&body) {
+            github_api_cache_put(url, body);
+            return Ok(parsed);
+        }
+    }
+
+    let validators = disk_cache
+        .as_ref()
+        .map(|cache| cache.validators(url))
+        .unwrap_or_default();
+    let body = match github_fetch_text(client, url, &validators) {
+        Ok(GithubFetched::Body(body, headers)) => {
+            if let Some(cache) = disk_cache.as_ref() {
+                cache.store(url, &body, &headers);
+            }
+            body
+        }
+        Ok(GithubFetched::NotModified(headers)) => disk_cache
+            .as_ref()
+            .and_then(|cache| cache.revalidated(url, &headers))
+            .ok_or_else(|| {
+                "GitHub returned 304 for a response that is no longer cached".to_string()
+            })?,
+        Err(err) => {
+            // Offline or rate limited: an older copy beats no data.
+            let unreachable =
+                err.starts_with("GitHub request failed:") || github_error_is_rate_limit(&err);
+            match disk_cache
+                .as_ref()
+                .filter(|_| unreachable)
+                .and_then(|cache| cache.stale(url))
+            {
+                Some(body) => body,
+                None => return Err(err),
+            }
+        }
+    };
+    github_api_cache_put(url, body.clone());
+    serde_json::from_str::<T>(&body).map_err(|e| format!("parse GitHub response failed: {e}"))
+}
 
+/// Conditional GitHub GET with token rotation. A 304 is not counted against
+/// the rate limit by GitHub, so cached responses are revalidated cheaply.
+fn github_fetch_text(
+    client: &Client,
+    url: &str,
+    validators: &[(&'static str, String)],
+) -> Result<GithubFetched, String> {
     let all_tokens = github_api_tokens();
     if all_tokens.is_empty() {
         let (rate_limited, reset_local) = github_unauth_cooldown_state();
@@ -14149,8 +15201,8 @@ fn github_get_json<T: for<'de> Deserialize<'de>>(client: &Client, url: &str) ->
     let mut token_attempts = 0usize;
     let mut retried_without_token = false;
     let mut response = if tokens.is_empty() {
-        let resp = github_request(client, url, None)?;
-        if resp.status().is_success() {
+        let resp = github_request(client, url, None, validators)?;
+        if resp.status().is_success() || resp.status() == reqwest::StatusCode::NOT_MODIFIED {
             github_clear_unauth_cooldown();
         } else if github_is_rate_limited_from_headers(resp.status(), resp.headers()) {
             github_mark_unauth_cooldown(resp.headers());
@@ -14160,9 +15212,9 @@ fn github_get_json<T: for<'de> Deserialize<'de>>(client: &Client, url: &str) ->
         let mut selected: Option<Response> = None;
         for token in tokens.iter() {
             token_attempts += 1;
-            let attempt = github_request(client, url, Some(token.as_str()))?;
+            let attempt = github_request(client, url, Some(token.as_str()), validators)?;
             let status = attempt.status();
-            if status.is_success() {
+            if status.is_success() || status == reqwest::StatusCode::NOT_MODIFIED {
                 github_clear_token_cooldown(token);
                 selected = Some(attempt);
                 break;
@@ -14194,14 +15246,18 @@ fn github_get_json<T: for<'de> Deserialize<'de>>(client: &Client, url: &str) ->
             ));
         }
         retried_without_token = true;
-        response = github_request(client, url, None)?;
-        if response.status().is_success() {
+        response = github_request(client, url, None, validators)?;
+        if response.status().is_success() || response.status() == reqwest::StatusCode::NOT_MODIFIED
+        {
             github_clear_unauth_cooldown();
         } else if github_is_rate_limited_from_headers(response.status(), response.headers()) {
             github_mark_unauth_cooldown(response.headers());
         }
     }
 
+    if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+        return Ok(GithubFetched::NotModified(response.headers().clone()));
+    }
     if !response.status().is_success() {
         let status = response.status();
         let headers = response.headers().clone();
@@ -14216,11 +15272,11 @@ fn github_get_json<T: for<'de> Deserialize<'de>>(client: &Client, url: &str) ->
         ));
     }
 
+    let headers = response.headers().clone();
     let body = response
         .text()
         .map_err(|e| format!("read GitHub response body failed: {e}"))?;
-    github_api_cache_put(url, body.clone());
-    serde_json::from_str::<T>(&body).map_err(|e| format!("parse GitHub response failed: {e}"))
+    Ok(GithubFetched::Body(body, headers))
 }
 
 fn github_release_version_label(release: &GithubRelease) -> String {
@@ -16304,20 +17360,13 @@ fn fetch_curseforge_project(
     api_key: &str,
     mod_id: i64,
 ) -> Result<CurseforgeMod, String> {
-    let mod_resp = client
-        .get(format!("{}/mods/{}", CURSEFORGE_API_BASE, mod_id))
-        .header("Accept", "application/json")
-        .header("x-api-key", api_key)
-        .send()
-        .map_err(|e| format!("CurseForge project lookup failed: {e}"))?;
-    if !mod_resp.status().is_success() {
-        return Err(format!(
-            "CurseForge project lookup failed with status {}",
-            mod_resp.status()
-        ));
-    }
-    Ok(mod_resp
-        .json::<CurseforgeModResponse>()
+    let body = http_cache::get_text(
+        client,
+        &format!("{}/mods/{}", CURSEFORGE_API_BASE, mod_id),
+        &[("Accept", "application/json"), ("x-api-key", api_key)],
+        "CurseForge project lookup failed",
+    )?;
+    Ok(serde_json::from_str::<CurseforgeModResponse>(&body)
         .map_err(|e| format!("parse CurseForge project failed: {e}"))?
         .data)
 }
@@ -16327,23 +17376,16 @@ fn fetch_curseforge_files(
     api_key: &str,
     mod_id: i64,
 ) -> Result<Vec<CurseforgeFile>, String> {
-    let files_resp = client
-        .get(format!(
+    let body = http_cache::get_text(
+        client,
+        &format!(
             "{}/mods/{}/files?pageSize=80&index=0",
             CURSEFORGE_API_BASE, mod_id
-        ))
-        .header("Accept", "application/json")
-        .header("x-api-key", api_key)
-        .send()
-        .map_err(|e| format!("CurseForge files lookup failed: {e}"))?;
-    if !files_resp.status().is_success() {
-        return Err(format!(
-            "CurseForge files lookup failed with status {}",
-            files_resp.status()
-        ));
-    }
-    Ok(files_resp
-        .json::<CurseforgeFilesResponse>()
+        ),
+        &[("Accept", "application/json"), ("x-api-key", api_key)],
+        "CurseForge files lookup failed",
+    )?;
+    Ok(serde_json::from_str::<CurseforgeFilesResponse>(&body)
         .map_err(|e| format!("parse CurseForge files failed: {e}"))?
         .data)
 }
@@ -16354,23 +17396,13 @@ fn fetch_curseforge_file(
     mod_id: i64,
     file_id: i64,
 ) -> Result<CurseforgeFile, String> {
-    let resp = client
-        .get(format!(
-            "{}/mods/{}/files/{}",
-            CURSEFORGE_API_BASE, mod_id, file_id
-        ))
-        .header("Accept", "application/json")
-        .header("x-api-key", api_key)
-        .send()
-        .map_err(|e| format!("CurseForge file lookup failed: {e}"))?;
-    if !resp.status().is_success() {
-        return Err(format!(
-            "CurseForge file lookup failed with status {}",
-            resp.status()
-        ));
-    }
-    Ok(resp
-        .json::<CurseforgeFileResponse>()
+    let body = http_cache::get_text(
+        client,
+        &format!("{}/mods/{}/files/{}", CURSEFORGE_API_BASE, mod_id, file_id),
+        &[("Accept", "application/json"), ("x-api-key", api_key)],
+        "CurseForge file lookup failed",
+    )?;
+    Ok(serde_json::from_str::<CurseforgeFileResponse>(&body)
         .map_err(|e| format!("parse CurseForge file failed: {e}"))?
         .data)
 }
@@ -16927,22 +17959,91 @@ fn normalize_target_worlds_for_datapack(
     Ok(out)
 }
 
+fn scan_mod_jar_security(bytes: &[u8]) -> JarSecurityScan {
+    JarSecurityScan {
+        scanned_at: now_iso(),
+        findings: jar_analysis::malware::scan_jar_bytes(bytes),
+        acknowledged_at: None,
+    }
+}
+
+fn lock_entry_security_unacknowledged(entry: &LockEntry) -> bool {
+    entry
+        .security_scan
+        .as_ref()
+        .map(|scan| !scan.findings.is_empty() && scan.acknowledged_at.is_none())
+        .unwrap_or(false)
+}
+
+/// Keeps an earlier acknowledgement when a rescan reports exactly the same
+/// findings; anything new has to be acknowledged again.
+fn carry_security_acknowledgement(
+    previous: Option<&JarSecurityScan>,
+    mut next: JarSecurityScan,
+) -> JarSecurityScan {
+    if let Some(previous) = previous {
+        if previous.acknowledged_at.is_some() && previous.findings == next.findings {
+            next.acknowledged_at = previous.acknowledged_at.clone();
+        }
+    }
+    next
+}
+
+fn ensure_mod_security_findings_acknowledged(lock: &Lockfile) -> Result<(), String> {
+    let flagged = lock
+        .entries
+        .iter()
+        .filter(|entry| entry.enabled && lock_entry_security_unacknowledged(entry))
+        .map(|entry| entry.name.clone())
+        .collect::<Vec<_>>();
+    if flagged.is_empty() {
+        return Ok(());
+    }
+    Err(format!(
+        "Launch blocked: the malware scan flagged {} enabled mod(s) ({}). Review and acknowledge the findings, or remove the mods, before launching.",
+        flagged.len(),
+        flagged.join(", ")
+    ))
+}
+
+/// Writes one installed content file, materializing it from the content store
+/// when the bytes were stored there.
+fn write_content_file(
+    stored: Option<&(content_store::ContentStore, String)>,
+    out_path: &Path,
+    bytes: &[u8],
+) -> Result<(), String> {
+    if let Some((store, sha512)) = stored {
+        if store.materialize(sha512, out_path).is_ok() {
+            return Ok(());
+        }
+    }
+    hardlink_dedupe::detach_before_write(out_path)?;
+    fs::write(out_path, bytes).map_err(|e| format!("write '{}' failed: {e}", out_path.display()))
+}
+
+/// Writes downloaded content into the instance and returns the malware scan of
+/// the bytes when they are a mod jar, for the caller to store on the lock entry.
 fn write_download_to_content_targets(
     instance_dir: &Path,
     content_type: &str,
     filename: &str,
     target_worlds: &[String],
     bytes: &[u8],
-) -> Result<(), String> {
+) -> Result<Option<JarSecurityScan>, String> {
     let normalized = normalize_lock_content_type(content_type);
+    let security_scan = (normalized == "mods").then(|| scan_mod_jar_security(bytes));
+    let stored = content_store::ContentStore::configured().and_then(|store| {
+        let digest = store.put_bytes(bytes).ok()?;
+        Some((store, digest.sha512))
+    });
     match normalized.as_str() {
         "mods" | "resourcepacks" | "shaderpacks" => {
             let dir = content_dir_for_type(instance_dir, &normalized);
             fs::create_dir_all(&dir)
                 .map_err(|e| format!("mkdir '{}' failed: {e}", dir.display()))?;
             let out_path = dir.join(filename);
-            fs::write(&out_path, bytes)
-                .map_err(|e| format!("write '{}' failed: {e}", out_path.display()))?;
+            write_content_file(stored.as_ref(), &out_path, bytes)?;
         }
         "datapacks" => {
             for world in target_worlds {
@@ -16950,25 +18051,31 @@ fn write_download_to_content_targets(
                 fs::create_dir_all(&dir)
                     .map_err(|e| format!("mkdir '{}' failed: {e}", dir.display()))?;
                 let out_path = dir.join(filename);
-                fs::write(&out_path, bytes)
-                    .map_err(|e| format!("write '{}' failed: {e}", out_path.display()))?;
+                write_content_file(stored.as_ref(), &out_path, bytes)?;
             }
         }
         _ => {
             return Err("Unsupported content type for direct install".to_string());
         }
     }
-    Ok(())
+    Ok(security_scan)
 }
 
+/// Staged variant of [`write_download_to_content_targets`]; mod jars are read
+/// back once in place so they get the same malware scan.
 fn write_staged_download_to_content_targets(
     instance_dir: &Path,
     content_type: &str,
     filename: &str,
     target_worlds: &[String],
     staged_path: &Path,
-) -> Result<(), String> {
+) -> Result<Option<JarSecurityScan>, String> {
     let normalized = normalize_lock_content_type(content_type);
+    let mut security_scan = None;
+    let stored = content_store::ContentStore::configured().and_then(|store| {
+        let digest = store.put_file(staged_path).ok()?;
+        Some((store, digest.sha512))
+    });
     match normalized.as_str() {
         "mods" | "resourcepacks" | "shaderpacks" => {
             let dir = content_dir_for_type(instance_dir, &normalized);
@@ -16979,19 +18086,32 @@ fn write_staged_download_to_content_targets(
                 fs::remove_file(&out_path)
                     .map_err(|e| format!("remove '{}' failed: {e}", out_path.display()))?;
             }
-            match fs::rename(staged_path, &out_path) {
-                Ok(()) => {}
-                Err(_) => {
-                    fs::copy(staged_path, &out_path).map_err(|e| {
-                        format!(
-                            "copy '{}' -> '{}' failed: {e}",
-                            staged_path.display(),
-                            out_path.display()
-                        )
-                    })?;
-                    let _ = fs::remove_file(staged_path);
+            let materialized = stored
+                .as_ref()
+                .map(|(store, sha512)| store.materialize(sha512, &out_path).is_ok())
+                .unwrap_or(false);
+            if materialized {
+                let _ = fs::remove_file(staged_path);
+            } else {
+                match fs::rename(staged_path, &out_path) {
+                    Ok(()) => {}
+                    Err(_) => {
+                        fs::copy(staged_path, &out_path).map_err(|e| {
+                            format!(
+                                "copy '{}' -> '{}' failed: {e}",
+                                staged_path.display(),
+                                out_path.display()
+                            )
+                        })?;
+                        let _ = fs::remove_file(staged_path);
+                    }
                 }
             }
+            if normalized == "mods" {
+                let bytes = fs::read(&out_path)
+                    .map_err(|e| format!("read '{}' failed: {e}", out_path.display()))?;
+                security_scan = Some(scan_mod_jar_security(&bytes));
+            }
         }
         "datapacks" => {
             for world in target_worlds {
@@ -17003,13 +18123,19 @@ fn write_staged_download_to_content_targets(
                 if part_path.exists() {
                     let _ = fs::remove_file(&part_path);
                 }
-                fs::copy(staged_path, &part_path).map_err(|e| {
-                    format!(
-                        "copy staged datapack '{}' -> '{}' failed: {e}",
-                        staged_path.display(),
-                        part_path.display()
-                    )
-                })?;
+                let materialized = stored
+                    .as_ref()
+                    .map(|(store, sha512)| store.materialize(sha512, &part_path).is_ok())
+                    .unwrap_or(false);
+                if !materialized {
+                    fs::copy(staged_path, &part_path).map_err(|e| {
+                        format!(
+                            "copy staged datapack '{}' -> '{}' failed: {e}",
+                            staged_path.display(),
+                            part_path.display()
+                        )
+                    })?;
+                }
                 if out_path.exists() {
                     fs::remove_file(&out_path)
                         .map_err(|e| format!("remove '{}' failed: {e}", out_path.display()))?;
@@ -17028,7 +18154,7 @@ fn write_staged_download_to_content_targets(
             return Err("Unsupported content type for direct install".to_string());
         }
     }
-    Ok(())
+    Ok(security_scan)
 }
 
 fn install_modrinth_content_inner<F>(
@@ -17093,10 +18219,11 @@ where
     fs::create_dir_all(&tmp_dir)
         .map_err(|e| format!("mkdir '{}' failed: {e}", tmp_dir.display()))?;
     let tmp_path = tmp_dir.join(format!("{safe_filename}.{}.part", version.id));
-    let mut stream_result = download_stream_to_temp_with_retry(
+    let mut stream_result = stage_download_with_content_store(
         client,
         &file.url,
         project_id,
+        &file.hashes,
         &tmp_path,
         |downloaded_bytes, total_bytes| on_progress(downloaded_bytes, total_bytes),
     )?;
@@ -17143,9 +18270,12 @@ where
             verification_status: None,
         }],
         local_analysis: None,
+        security_scan: None,
+        url_update: None,
+        github_channel: None,
     };
     validate_lock_entry_uniqueness(lock, &new_entry)?;
-    write_staged_download_to_content_targets(
+    new_entry.security_scan = write_staged_download_to_content_targets(
         instance_dir,
         &normalized,
         &safe_filename,
@@ -17214,15 +18344,42 @@ where
     if safe_filename.is_empty() {
         return Err("Resolved CurseForge filename is invalid".to_string());
     }
-    let download_url = resolve_curseforge_file_download_url(client, api_key, mod_id, &file)?;
+    let download_url = curseforge_handoff::resolve_download_url_or_queue(
+        client,
+        api_key,
+        instance_dir,
+        mod_id,
+        &file,
+        || {
+            let name = project_title
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+                .unwrap_or_else(|| project.name.clone());
+            let worlds = if normalized == "datapacks" {
+                normalize_target_worlds_for_datapack(instance_dir, target_worlds)?
+            } else {
+                vec![]
+            };
+            Ok(curseforge_handoff::handoff_item_for_file(
+                Some(&project),
+                mod_id,
+                &file,
+                &name,
+                &normalized,
+                &worlds,
+                true,
+            ))
+        },
+    )?;
     let tmp_dir = instance_dir.join(".openjar_downloads");
     fs::create_dir_all(&tmp_dir)
         .map_err(|e| format!("mkdir '{}' failed: {e}", tmp_dir.display()))?;
     let tmp_path = tmp_dir.join(format!("{safe_filename}.{}.part", file.id));
-    let mut stream_result = download_stream_to_temp_with_retry(
+    let mut stream_result = stage_download_with_content_store(
         client,
         &download_url,
         &format!("cf:{mod_id}:{}", file.id),
+        &parse_cf_hashes(&file),
         &tmp_path,
         |downloaded_bytes, total_bytes| on_progress(downloaded_bytes, total_bytes),
     )?;
@@ -17261,7 +18418,15 @@ where
         target_worlds: worlds.clone(),
         pinned_version: None,
         enabled: true,
-        hashes: parse_cf_hashes(&file),
+        hashes: {
+            let mut hashes = parse_cf_hashes(&file);
+            if !stream_result.sha512.trim().is_empty() {
+                hashes
+                    .entry("sha512".to_string())
+                    .or_insert_with(|| stream_result.sha512.clone());
+            }
+            hashes
+        },
         provider_candidates: vec![ProviderCandidate {
             source: "curseforge".to_string(),
             project_id: format!("cf:{mod_id}"),
@@ -17280,9 +18445,12 @@ where
             verification_status: None,
         }],
         local_analysis: None,
+        security_scan: None,
+        url_update: None,
+        github_channel: None,
     };
     validate_lock_entry_uniqueness(lock, &new_entry)?;
-    write_staged_download_to_content_targets(
+    new_entry.security_scan = write_staged_download_to_content_targets(
         instance_dir,
         &normalized,
         &safe_filename,
@@ -17374,16 +18542,17 @@ where
     fs::create_dir_all(&tmp_dir)
         .map_err(|e| format!("mkdir '{}' failed: {e}", tmp_dir.display()))?;
     let tmp_path = tmp_dir.join(format!("{safe_filename}.{}.part", selection.release.id));
-    let mut stream_result = download_stream_to_temp_with_retry(
+    let mut hashes = extract_github_asset_digest(&selection.asset);
+    let mut stream_result = stage_download_with_content_store(
         client,
         &selection.asset.browser_download_url,
         &format!("gh:{owner}/{repo_name}:{}", selection.release.id),
+        &hashes,
         &tmp_path,
         |downloaded_bytes, total_bytes| on_progress(downloaded_bytes, total_bytes),
     )?;
 
     let project_key = github_project_key(&owner, &repo_name);
-    let mut hashes = extract_github_asset_digest(&selection.asset);
     hashes.insert("sha256".to_string(), stream_result.sha256.clone());
     if !stream_result.sha512.trim().is_empty() {
         hashes
@@ -17434,11 +18603,14 @@ where
             verification_status: Some("verified".to_string()),
         }],
         local_analysis: None,
+        security_scan: None,
+        url_update: None,
+        github_channel: None,
     };
     validate_lock_entry_uniqueness(lock, &new_entry)?;
     on_stage("installing", "Installing downloaded GitHub mod…", None);
     let post_process_started = Instant::now();
-    write_staged_download_to_content_targets(
+    new_entry.security_scan = write_staged_download_to_content_targets(
         instance_dir,
         &normalized,
         &safe_filename,
@@ -18502,6 +19674,7 @@ fn copy_dir_recursive_count(src: &Path, dst: &Path) -> Result<usize, String> {
                 fs::create_dir_all(parent)
                     .map_err(|e| format!("mkdir '{}' failed: {e}", parent.display()))?;
             }
+            hardlink_dedupe::detach_before_write(&dst_path)?;
             fs::copy(&src_path, &dst_path).map_err(|e| {
                 format!(
                     "copy '{}' -> '{}' failed: {e}",
@@ -18539,6 +19712,7 @@ fn copy_file_if_exists(src: &Path, dst: &Path) -> Result<usize, String> {
         fs::create_dir_all(parent)
             .map_err(|e| format!("mkdir '{}' failed: {e}", parent.display()))?;
     }
+    hardlink_dedupe::detach_before_write(dst)?;
     fs::copy(src, dst).map_err(|e| {
         format!(
             "copy '{}' -> '{}' failed: {e}",
@@ -18731,6 +19905,7 @@ fn extract_overrides_from_modpack(
         if let Some(parent) = out_path.parent() {
             fs::create_dir_all(parent).map_err(|e| format!("mkdir override parent failed: {e}"))?;
         }
+        hardlink_dedupe::detach_before_write(&out_path)?;
         let mut out =
             File::create(&out_path).map_err(|e| format!("write override file failed: {e}"))?;
         std::io::copy(&mut entry, &mut out).map_err(|e| format!("extract override failed: {e}"))?;
@@ -18758,6 +19933,7 @@ fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<(), String> {
                 fs::create_dir_all(parent)
                     .map_err(|e| format!("mkdir '{}' failed: {e}", parent.display()))?;
             }
+            hardlink_dedupe::detach_before_write(&dst_path)?;
             fs::copy(&src_path, &dst_path).map_err(|e| {
                 format!(
                     "copy '{}' -> '{}' failed: {e}",
@@ -19515,6 +20691,17 @@ fn required_java_major_for_mc(mc_version: &str) -> u32 {
     17
 }
 
+/// `--quickPlaySingleplayer` (and Prism's `--world`) arrived in 1.20; older
+/// clients ignore it and stop on the title screen. Unrecognised version ids
+/// such as snapshots are let through.
+fn supports_world_quick_play(mc_version: &str) -> bool {
+    let release = mc_version.trim().split('-').next().unwrap_or_default();
+    match parse_mc_release_triplet(release) {
+        Some((major, minor, _)) => major > 1 || minor >= 20,
+        None => true,
+    }
+}
+
 fn tail_lines_from_file(path: &Path, max_lines: usize) -> Option<String> {
     let text = fs::read_to_string(path).ok()?;
     let mut lines: Vec<&str> = text.lines().collect();
@@ -19586,6 +20773,7 @@ fn launch_prism_instance(
     prism_instance_id: &str,
     quick_play_host: Option<&str>,
     quick_play_port: Option<u16>,
+    quick_play_world: Option<&str>,
 ) -> Result<(), String> {
     let mut attempts: Vec<(OsString, Vec<OsString>)> = Vec::new();
     let root = OsString::from(prism_root.as_os_str());
@@ -19597,6 +20785,8 @@ fn launch_prism_instance(
             args.push(OsString::from(port.to_string()));
         }
         args
+    } else if let Some(world) = quick_play_world {
+        vec![OsString::from("--world"), OsString::from(world)]
     } else {
         vec![]
     };
@@ -19789,6 +20979,48 @@ fn main() {
             if let Err(err) = recover_native_play_sessions_startup(&app.handle()) {
                 eprintln!("startup play session recovery warning: {err}");
             }
+            if let Ok(settings) = read_launcher_settings(&app.handle()) {
+                team_repo::set_configured_location(&settings.team_repository);
+            }
+            if let Ok(launcher) = launcher_dir(&app.handle()) {
+                content_store::set_store_root(&launcher);
+            }
+            if let Ok(instances_dir) = app_instances_dir(&app.handle()) {
+                thread::spawn(move || {
+                    let migrated = snapshot_store::migrate_all_snapshots(&instances_dir);
+                    if migrated > 0 {
+                        eprintln!("startup migrated {migrated} zip snapshot(s) to manifests");
+                    }
+                });
+            }
+            if let Ok(cache_dir) = launcher_cache_dir(&app.handle()) {
+                http_cache::set_cache_root(&cache_dir);
+                thread::spawn(move || {
+                    if let Some(cache) = http_cache::HttpMetadataCache::configured() {
+                        cache.prune();
+                    }
+                });
+            }
+            let watcher_app = app.handle();
+            thread::spawn(move || {
+                if let Err(err) = content_watcher::start_all_instance_watchers(&watcher_app) {
+                    eprintln!("startup content watcher warning: {err}");
+                }
+            });
+            let handoff_app = app.handle();
+            thread::spawn(move || {
+                if let Err(err) = curseforge_handoff::restart_handoff_watcher(&handoff_app) {
+                    eprintln!("startup curseforge handoff watcher warning: {err}");
+                }
+            });
+            let advisory_app = app.handle();
+            thread::spawn(move || {
+                if let Err(err) = advisories::scan_all_instances(&advisory_app, "startup") {
+                    eprintln!("startup security advisory check warning: {err}");
+                }
+            });
+            let verify_app = app.handle();
+            thread::spawn(move || backup_verify::run_verify_schedule(verify_app));
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -19820,6 +21052,24 @@ fn main() {
             commands::impls::list_installed_mods,
             commands::impls::set_installed_mod_enabled,
             commands::impls::set_installed_mod_pin,
+            commands::impls::set_installed_mod_github_channel,
+            commands::impls::acknowledge_installed_mod_security,
+            commands::impls::scan_instance_content_security,
+            commands::impls::get_security_advisory_status,
+            commands::impls::run_security_advisory_scan,
+            commands::impls::import_security_advisory_bundle,
+            commands::impls::verify_backups,
+            commands::impls::get_backup_verify_report,
+            commands::impls::verify_instance_lockfile,
+            commands::impls::redownload_modified_lock_entries,
+            commands::impls::rehydrate_instance_content,
+            commands::impls::set_instance_content_watcher,
+            commands::impls::list_content_watchers,
+            commands::impls::install_url_content,
+            commands::impls::install_maven_content,
+            commands::impls::list_curseforge_handoff_items,
+            commands::impls::scan_curseforge_handoff_folder,
+            commands::impls::dismiss_curseforge_handoff_item,
             commands::impls::set_installed_mod_provider,
             commands::impls::attach_installed_mod_github_repo,
             commands::impls::remove_installed_mod,
@@ -19847,6 +21097,7 @@ fn main() {
             commands::impls::cancel_instance_launch,
             commands::impls::list_instance_snapshots,
             commands::impls::list_instance_worlds,
+            commands::impls::list_instance_world_backups,
             commands::impls::get_instance_disk_usage,
             commands::impls::get_storage_usage_overview,
             commands::impls::get_storage_usage_entries,
@@ -19862,6 +21113,9 @@ fn main() {
             commands::impls::read_world_config_file,
             commands::impls::write_world_config_file,
             commands::impls::rollback_instance,
+            commands::impls::update_instance_snapshot,
+            commands::impls::diff_instance_snapshots,
+            commands::impls::restore_instance_snapshot_selection,
             commands::impls::rollback_instance_world_backup,
             commands::impls::read_instance_logs,
             commands::impls::install_discover_content,
@@ -19921,6 +21175,9 @@ fn main() {
             commands::impls::remove_quick_play_server,
             commands::impls::launch_quick_play_server,
             commands::impls::export_instance_mods_zip,
+            commands::impls::export_instance_world,
+            commands::impls::import_instance_world,
+            commands::impls::trim_instance_world,
             commands::impls::export_instance_support_bundle
         ])
         .run(tauri::generate_context!())
@@ -0,0 +1,608 @@
+//! Maven repository provider for library-style mods published with
+//! `maven-metadata.xml`.
+//!
+//! Lock entries use source `maven`, project ids
+//! `maven:<group>:<artifact>[:<classifier>]@<repository url>` and version ids
+//! `maven_ver:<version>`. Jars are only accepted when they match the `.sha512`
+//! or `.sha1` sidecar published next to them.
+
+use crate::advisories::compare_loose_versions;
+use crate::url_source::{parse_maven_metadata_versions, require_https};
+use crate::{
+    canonical_lock_entry_name, download_bytes_with_retry, enforce_lock_entry_uniqueness,
+    hash_bytes_hex, minecraft_version_matches_advertised, normalize_lock_content_type,
+    normalize_target_worlds_for_datapack, sha256_bytes_hex, sha512_hex,
+    validate_lock_entry_uniqueness, write_download_to_content_targets, ContentUpdateInfo,
+    InstallMavenContentArgs, Instance, LockEntry, Lockfile, ProviderCandidate,
+};
+use reqwest::blocking::Client;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::path::Path;
+
+pub(crate) const MAVEN_SOURCE: &str = "maven";
+const PROJECT_PREFIX: &str = "maven:";
+const VERSION_PREFIX: &str = "maven_ver:";
+const KNOWN_LOADERS: [&str; 4] = ["fabric", "forge", "neoforge", "quilt"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct MavenCoordinate {
+    pub group: String,
+    pub artifact: String,
+    pub version: Option<String>,
+    pub classifier: Option<String>,
+}
+
+fn valid_coordinate_part(part: &str) -> bool {
+    !part.is_empty()
+        && part
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '+'))
+        && part != "."
+        && part != ".."
+}
+
+/// Parses `group:artifact[:version[:classifier]]`.
+pub(crate) fn parse_maven_coordinate(raw: &str) -> Result<MavenCoordinate, String> {
+    let parts = raw.trim().split(':').map(str::trim).collect::<Vec<_>>();
+    if !(2..=4).contains(&parts.len()) {
+        return Err(format!(
+            "'{raw}' is not a Maven coordinate (group:artifact[:version[:classifier]])"
+        ));
+    }
+    let optional = |idx: usize| {
+        parts
+            .get(idx)
+            .filter(|value| !value.is_empty())
+            .map(|value| value.to_string())
+    };
+    let coordinate = MavenCoordinate {
+        group: parts[0].to_string(),
+        artifact: parts[1].to_string(),
+        version: optional(2),
+        classifier: optional(3),
+    };
+    let all_valid = [Some(&coordinate.group), Some(&coordinate.artifact)]
+        .into_iter()
+        .chain([coordinate.version.as_ref(), coordinate.classifier.as_ref()])
+        .flatten()
+        .all(|part| valid_coordinate_part(part));
+    if !all_valid {
+        return Err(format!("Maven coordinate '{raw}' has invalid characters"));
+    }
+    Ok(coordinate)
+}
+
+pub(crate) fn normalize_repository_url(raw: &str) -> Result<String, String> {
+    let trimmed = raw.trim().trim_end_matches('/');
+    require_https(trimmed, "Maven repository URL")?;
+    if trimmed.contains('@') {
+        return Err("Maven repository URL may not contain credentials".to_string());
+    }
+    Ok(trimmed.to_string())
+}
+
+pub(crate) fn maven_project_key(repository: &str, coordinate: &MavenCoordinate) -> String {
+    let mut key = format!(
+        "{PROJECT_PREFIX}{}:{}",
+        coordinate.group, coordinate.artifact
+    );
+    if let Some(classifier) = coordinate.classifier.as_ref() {
+        key.push(':');
+        key.push_str(classifier);
+    }
+    format!("{key}@{repository}")
+}
+
+/// Splits a project id back into its repository and versionless coordinate.
+pub(crate) fn parse_maven_project_id(
+    project_id: &str,
+) -> Result<(String, MavenCoordinate), String> {
+    let invalid = || format!("'{project_id}' is not a Maven project id");
+    let rest = project_id
+        .trim()
+        .strip_prefix(PROJECT_PREFIX)
+        .ok_or_else(invalid)?;
+    let (coordinate, repository) = rest.split_once('@').ok_or_else(invalid)?;
+    let mut parts = coordinate.split(':').collect::<Vec<_>>();
+    if parts.len() == 3 {
+        // group:artifact:classifier -> group:artifact::classifier
+        parts.insert(2, "");
+    }
+    let coordinate = parse_maven_coordinate(&parts.join(":"))?;
+    Ok((normalize_repository_url(repository)?, coordinate))
+}
+
+fn maven_version_id(version: &str) -> String {
+    format!("{VERSION_PREFIX}{}", version.trim())
+}
+
+fn artifact_base_url(repository: &str, coordinate: &MavenCoordinate) -> String {
+    format!(
+        "{repository}/{}/{}",
+        coordinate.group.replace('.', "/"),
+        coordinate.artifact
+    )
+}
+
+pub(crate) fn maven_metadata_url(repository: &str, coordinate: &MavenCoordinate) -> String {
+    format!(
+        "{}/maven-metadata.xml",
+        artifact_base_url(repository, coordinate)
+    )
+}
+
+pub(crate) fn maven_jar_filename(coordinate: &MavenCoordinate, version: &str) -> String {
+    match coordinate.classifier.as_ref() {
+        Some(classifier) => format!("{}-{version}-{classifier}.jar", coordinate.artifact),
+        None => format!("{}-{version}.jar", coordinate.artifact),
+    }
+}
+
+pub(crate) fn maven_jar_url(
+    repository: &str,
+    coordinate: &MavenCoordinate,
+    version: &str,
+) -> String {
+    format!(
+        "{}/{version}/{}",
+        artifact_base_url(repository, coordinate),
+        maven_jar_filename(coordinate, version)
+    )
+}
+
+fn tokens(raw: &str) -> Vec<String> {
+    raw.to_ascii_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn looks_like_minecraft_version(raw: &str) -> bool {
+    let parts = raw.split('.').collect::<Vec<_>>();
+    parts.first() == Some(&"1")
+        && (2..=3).contains(&parts.len())
+        && parts
+            .iter()
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Version naming conventions used by mod libraries: a Minecraft version
+/// next to the library version (`1.4.0+1.20.1`, `1.20.1-1.4.0`, `mc1.20.1-…`)
+/// and a loader name in the version, classifier or artifact
+/// (`-fabric`, `+neoforge`). Versions without either marker match any instance.
+pub(crate) fn maven_version_matches_instance(
+    version: &str,
+    coordinate: &MavenCoordinate,
+    instance: &Instance,
+) -> bool {
+    let segments = version
+        .trim()
+        .split(['+', '-', '_'])
+        .map(|segment| {
+            let lower = segment.to_ascii_lowercase();
+            lower
+                .strip_prefix("mc")
+                .map(str::to_string)
+                .unwrap_or(lower)
+        })
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>();
+    let mc_markers = segments
+        .iter()
+        .filter(|segment| looks_like_minecraft_version(segment))
+        .collect::<Vec<_>>();
+    let mc_matches = mc_markers.iter().any(|marker| {
+        minecraft_version_matches_advertised(marker, &instance.mc_version, false)
+            || (marker.split('.').count() == 2
+                && minecraft_version_matches_advertised(marker, &instance.mc_version, true))
+    });
+    let explicit_mc = version.to_ascii_lowercase().contains("mc1.");
+    if !mc_matches && !mc_markers.is_empty() && (segments.len() >= 2 || explicit_mc) {
+        return false;
+    }
+
+    let loader = instance.loader.trim().to_ascii_lowercase();
+    let named_loaders = [
+        Some(version),
+        coordinate.classifier.as_deref(),
+        Some(coordinate.artifact.as_str()),
+    ]
+    .into_iter()
+    .flatten()
+    .flat_map(tokens)
+    .filter(|token| KNOWN_LOADERS.contains(&token.as_str()))
+    .collect::<Vec<_>>();
+    named_loaders.is_empty()
+        || named_loaders
+            .iter()
+            .any(|named| *named == loader || (loader == "quilt" && named == "fabric"))
+}
+
+pub(crate) fn pick_maven_version(
+    versions: &[String],
+    coordinate: &MavenCoordinate,
+    instance: &Instance,
+) -> Option<String> {
+    versions
+        .iter()
+        .filter(|version| maven_version_matches_instance(version, coordinate, instance))
+        .max_by(|a, b| compare_loose_versions(a, b))
+        .cloned()
+}
+
+pub(crate) fn fetch_maven_versions(
+    client: &Client,
+    repository: &str,
+    coordinate: &MavenCoordinate,
+) -> Result<Vec<String>, String> {
+    let url = maven_metadata_url(repository, coordinate);
+    let bytes = download_bytes_with_retry(client, &url, "Maven metadata")?;
+    let xml = String::from_utf8(bytes).map_err(|_| "Maven metadata is not valid UTF-8")?;
+    Ok(parse_maven_metadata_versions(&xml))
+}
+
+/// The published `.sha512` sidecar, falling back to `.sha1`.
+fn fetch_sidecar_digest(client: &Client, jar_url: &str) -> Result<(&'static str, String), String> {
+    let mut last_err = String::new();
+    for (algorithm, len) in [("sha512", 128usize), ("sha1", 40)] {
+        let url = format!("{jar_url}.{algorithm}");
+        match download_bytes_with_retry(client, &url, &url) {
+            Ok(bytes) => {
+                let text = String::from_utf8_lossy(&bytes);
+                let digest = text
+                    .split_whitespace()
+                    .next()
+                    .unwrap_or_default()
+                    .to_ascii_lowercase();
+                if digest.len() == len && digest.chars().all(|c| c.is_ascii_hexdigit()) {
+                    return Ok((algorithm, digest));
+                }
+                last_err = format!("{url} does not hold a {algorithm} digest");
+            }
+            Err(err) => last_err = err,
+        }
+    }
+    Err(format!(
+        "No usable .sha512/.sha1 checksum is published for {jar_url}: {last_err}"
+    ))
+}
+
+/// Downloads a jar and verifies it against its sidecar checksum. Returns the
+/// bytes and the hashes to record.
+pub(crate) fn fetch_verified_maven_jar(
+    client: &Client,
+    repository: &str,
+    coordinate: &MavenCoordinate,
+    version: &str,
+) -> Result<(Vec<u8>, HashMap<String, String>), String> {
+    let jar_url = maven_jar_url(repository, coordinate, version);
+    let (algorithm, expected) = fetch_sidecar_digest(client, &jar_url)?;
+    let bytes = download_bytes_with_retry(client, &jar_url, &jar_url)?;
+    if hash_bytes_hex(algorithm, &bytes) != expected {
+        return Err(format!(
+            "Maven artifact {jar_url} does not match its .{algorithm} checksum"
+        ));
+    }
+    let mut hashes = HashMap::from([
+        ("sha256".to_string(), sha256_bytes_hex(&bytes)),
+        ("sha512".to_string(), sha512_hex(&bytes)),
+    ]);
+    hashes.insert(algorithm.to_string(), expected);
+    Ok((bytes, hashes))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn write_maven_entry(
+    instance_dir: &Path,
+    lock: &mut Lockfile,
+    project_id: String,
+    coordinate: &MavenCoordinate,
+    version: &str,
+    display_name: &str,
+    content_type: &str,
+    target_worlds: &[String],
+    enabled: bool,
+    pinned_version: Option<String>,
+    fetched: (Vec<u8>, HashMap<String, String>),
+) -> Result<LockEntry, String> {
+    let (bytes, hashes) = fetched;
+    let filename = maven_jar_filename(coordinate, version);
+    let worlds = if content_type == "datapacks" {
+        normalize_target_worlds_for_datapack(instance_dir, target_worlds)?
+    } else {
+        vec![]
+    };
+    let name = canonical_lock_entry_name(content_type, &filename, display_name);
+    let mut new_entry = LockEntry {
+        source: MAVEN_SOURCE.to_string(),
+        project_id: project_id.clone(),
+        version_id: maven_version_id(version),
+        name: name.clone(),
+        version_number: version.to_string(),
+        filename: filename.clone(),
+        content_type: content_type.to_string(),
+        target_scope: if content_type == "datapacks" {
+            "world".to_string()
+        } else {
+            "instance".to_string()
+        },
+        target_worlds: worlds.clone(),
+        pinned_version,
+        enabled,
+        hashes,
+        provider_candidates: vec![ProviderCandidate {
+            source: MAVEN_SOURCE.to_string(),
+            project_id,
+            version_id: maven_version_id(version),
+            name,
+            version_number: version.to_string(),
+            confidence: None,
+            reason: None,
+            verification_status: Some("verified".to_string()),
+        }],
+        local_analysis: None,
+        security_scan: None,
+        url_update: None,
+        github_channel: None,
+    };
+    validate_lock_entry_uniqueness(lock, &new_entry)?;
+    new_entry.security_scan =
+        write_download_to_content_targets(instance_dir, content_type, &filename, &worlds, &bytes)?;
+    if content_type == "mods" && !enabled {
+        crate::disable_mod_file(instance_dir, &new_entry.filename)?;
+    }
+    enforce_lock_entry_uniqueness(lock, instance_dir, &mut new_entry)?;
+    lock.entries.push(new_entry.clone());
+    Ok(new_entry)
+}
+
+pub(crate) fn install_maven_content_inner(
+    instance: &Instance,
+    instance_dir: &Path,
+    lock: &mut Lockfile,
+    client: &Client,
+    args: &InstallMavenContentArgs,
+) -> Result<LockEntry, String> {
+    let normalized = normalize_lock_content_type(&args.content_type);
+    if normalized == "modpacks" {
+        return Err(
+            "Modpack entries are template-only. Import as template in Modpacks & Presets."
+                .to_string(),
+        );
+    }
+    let repository = normalize_repository_url(&args.repository_url)?;
+    let coordinate = parse_maven_coordinate(&args.coordinate)?;
+    let version = match coordinate.version.clone() {
+        Some(version) => version,
+        None => {
+            let versions = fetch_maven_versions(client, &repository, &coordinate)?;
+            pick_maven_version(&versions, &coordinate, instance).ok_or_else(|| {
+                format!(
+                    "No version of {}:{} matches {} {}",
+                    coordinate.group, coordinate.artifact, instance.loader, instance.mc_version
+                )
+            })?
+        }
+    };
+    let fetched = fetch_verified_maven_jar(client, &repository, &coordinate, &version)?;
+    let display_name = args
+        .name
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .unwrap_or(&coordinate.artifact)
+        .to_string();
+    write_maven_entry(
+        instance_dir,
+        lock,
+        maven_project_key(&repository, &coordinate),
+        &coordinate,
+        &version,
+        &display_name,
+        &normalized,
+        &args.target_worlds,
+        true,
+        None,
+        fetched,
+    )
+}
+
+pub(crate) fn check_maven_content_update(
+    client: &Client,
+    instance: &Instance,
+    entry: &LockEntry,
+    provider: &ProviderCandidate,
+    warnings: &mut Vec<String>,
+) -> Result<Option<ContentUpdateInfo>, String> {
+    let (repository, coordinate) = parse_maven_project_id(&provider.project_id)?;
+    let versions = fetch_maven_versions(client, &repository, &coordinate)?;
+    let Some(latest) = pick_maven_version(&versions, &coordinate, instance) else {
+        warnings.push(format!(
+            "No Maven version of '{}' matches {} {} ({})",
+            entry.name, instance.loader, instance.mc_version, provider.project_id
+        ));
+        return Ok(None);
+    };
+    if compare_loose_versions(&latest, &entry.version_number) != Ordering::Greater {
+        return Ok(None);
+    }
+    Ok(Some(ContentUpdateInfo {
+        source: MAVEN_SOURCE.to_string(),
+        content_type: normalize_lock_content_type(&entry.content_type),
+        project_id: provider.project_id.clone(),
+        name: entry.name.clone(),
+        current_version_id: entry.version_id.clone(),
+        current_version_number: entry.version_number.clone(),
+        latest_version_id: maven_version_id(&latest),
+        latest_version_number: latest.clone(),
+        enabled: entry.enabled,
+        target_worlds: entry.target_worlds.clone(),
+        latest_file_name: Some(maven_jar_filename(&coordinate, &latest)),
+        latest_download_url: Some(maven_jar_url(&repository, &coordinate, &latest)),
+        latest_hashes: HashMap::new(),
+        required_dependencies: vec![],
+        compatibility_status: Some("compatible".to_string()),
+        compatibility_notes: vec![],
+    }))
+}
+
+pub(crate) fn install_maven_content_update(
+    instance_dir: &Path,
+    lock: &mut Lockfile,
+    client: &Client,
+    update: &ContentUpdateInfo,
+    carried_pin: Option<String>,
+) -> Result<LockEntry, String> {
+    let (repository, coordinate) = parse_maven_project_id(&update.project_id)?;
+    let version = update.latest_version_number.trim();
+    let fetched = fetch_verified_maven_jar(client, &repository, &coordinate, version)?;
+    write_maven_entry(
+        instance_dir,
+        lock,
+        update.project_id.clone(),
+        &coordinate,
+        version,
+        &update.name,
+        &normalize_lock_content_type(&update.content_type),
+        &update.target_worlds,
+        update.enabled,
+        carried_pin,
+        fetched,
+    )
+}
+
+/// Fetches the exact version of a Maven entry, for redownloads and Friend Link.
+pub(crate) fn download_maven_entry_bytes(
+    client: &Client,
+    project_id: &str,
+    version_number: &str,
+) -> Result<Vec<u8>, String> {
+    let (repository, coordinate) = parse_maven_project_id(project_id)?;
+    fetch_verified_maven_jar(client, &repository, &coordinate, version_number.trim())
+        .map(|(bytes, _)| bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instance(loader: &str, mc_version: &str) -> Instance {
+        Instance {
+            id: "inst".to_string(),
+            name: "Inst".to_string(),
+            origin: "custom".to_string(),
+            folder_name: None,
+            mc_version: mc_version.to_string(),
+            loader: loader.to_string(),
+            created_at: "now".to_string(),
+            icon_path: None,
+            settings: Default::default(),
+        }
+    }
+
+    #[test]
+    fn parses_coordinates_and_round_trips_project_ids() {
+        let coordinate =
+            parse_maven_coordinate("dev.example:cool-lib:1.2.0:fabric").expect("parse");
+        assert_eq!(coordinate.version.as_deref(), Some("1.2.0"));
+        assert_eq!(coordinate.classifier.as_deref(), Some("fabric"));
+        assert!(parse_maven_coordinate("only-artifact").is_err());
+        assert!(parse_maven_coordinate("dev.example:../x").is_err());
+
+        let repo = normalize_repository_url("https://maven.example/releases/").expect("repo");
+        let key = maven_project_key(&repo, &coordinate);
+        assert_eq!(
+            key,
+            "maven:dev.example:cool-lib:fabric@https://maven.example/releases"
+        );
+        let (parsed_repo, parsed) = parse_maven_project_id(&key).expect("project id");
+        assert_eq!(parsed_repo, repo);
+        assert_eq!(parsed.classifier.as_deref(), Some("fabric"));
+        assert_eq!(parsed.version, None);
+        assert_eq!(
+            maven_jar_url(&parsed_repo, &parsed, "1.3.0"),
+            "https://maven.example/releases/dev/example/cool-lib/1.3.0/cool-lib-1.3.0-fabric.jar"
+        );
+        assert_eq!(
+            maven_metadata_url(&parsed_repo, &parsed),
+            "https://maven.example/releases/dev/example/cool-lib/maven-metadata.xml"
+        );
+        assert!(normalize_repository_url("http://maven.example").is_err());
+    }
+
+    #[test]
+    fn matches_minecraft_and_loader_conventions() {
+        let plain = parse_maven_coordinate("dev.example:lib").expect("parse");
+        let fabric = instance("fabric", "1.20.1");
+        assert!(maven_version_matches_instance("1.4.0", &plain, &fabric));
+        assert!(maven_version_matches_instance(
+            "1.4.0+1.20.1",
+            &plain,
+            &fabric
+        ));
+        assert!(maven_version_matches_instance(
+            "1.4.0+1.20",
+            &plain,
+            &fabric
+        ));
+        assert!(!maven_version_matches_instance(
+            "1.4.0+1.19.2",
+            &plain,
+            &fabric
+        ));
+        assert!(maven_version_matches_instance(
+            "1.20.1-1.4.0",
+            &plain,
+            &fabric
+        ));
+        assert!(!maven_version_matches_instance(
+            "mc1.21-2.0.0",
+            &plain,
+            &fabric
+        ));
+        assert!(maven_version_matches_instance(
+            "1.4.0+1.20.1-fabric",
+            &plain,
+            &fabric
+        ));
+        assert!(!maven_version_matches_instance(
+            "1.4.0+1.20.1-forge",
+            &plain,
+            &fabric
+        ));
+        assert!(maven_version_matches_instance(
+            "1.4.0+1.20.1-fabric",
+            &plain,
+            &instance("quilt", "1.20.1")
+        ));
+
+        let forge_artifact = parse_maven_coordinate("dev.example:lib-forge").expect("parse");
+        assert!(!maven_version_matches_instance(
+            "1.4.0",
+            &forge_artifact,
+            &fabric
+        ));
+    }
+
+    #[test]
+    fn picks_newest_matching_version() {
+        let coordinate = parse_maven_coordinate("dev.example:lib").expect("parse");
+        let versions = [
+            "1.9.0+1.20.1",
+            "1.10.0+1.20.1",
+            "2.0.0+1.21",
+            "1.10.1+1.20.1-forge",
+        ]
+        .map(str::to_string);
+        assert_eq!(
+            pick_maven_version(&versions, &coordinate, &instance("fabric", "1.20.1")),
+            Some("1.10.0+1.20.1".to_string())
+        );
+        assert_eq!(
+            pick_maven_version(&versions, &coordinate, &instance("fabric", "1.21")),
+            Some("2.0.0+1.21".to_string())
+        );
+    }
+}
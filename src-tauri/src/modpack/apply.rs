@@ -32,6 +32,8 @@ pub fn apply_plan_to_instance(
     let instances_dir = crate::app_instances_dir(app)?;
     let instance = crate::find_instance(&instances_dir, &plan.target.id)?;
     let instance_dir = crate::instance_dir_for_instance(&instances_dir, &instance);
+    let mutation_lock = crate::commands::impls::instance_mutation_lock(&instance.id);
+    let _guard = mutation_lock.lock()?;
     let mut lock = crate::read_lockfile(&instances_dir, &instance.id)?;
 
     let snapshot_id = if !plan.resolved_mods.is_empty() {
@@ -109,6 +111,26 @@ pub fn apply_plan_to_instance(
     ))
 }
 
+fn resolve_target_worlds(
+    instance_dir: &std::path::Path,
+    content_type: &str,
+    item: &crate::modpack::types::ResolvedMod,
+) -> Result<Vec<String>, String> {
+    if content_type != "datapacks" {
+        return Ok(vec![]);
+    }
+    let requested_worlds = if item
+        .target_worlds
+        .iter()
+        .any(|world| world == crate::modpack::DATAPACK_ALL_WORLDS_SENTINEL)
+    {
+        crate::list_instance_world_names(instance_dir)?
+    } else {
+        item.target_worlds.clone()
+    };
+    crate::normalize_target_worlds_for_datapack(instance_dir, &requested_worlds)
+}
+
 fn apply_single_resolved(
     client: &Client,
     instance: &crate::Instance,
@@ -137,29 +159,34 @@ fn apply_single_resolved(
             .into_iter()
             .find(|f| f.id == wanted)
             .ok_or_else(|| format!("CurseForge file {} no longer available", wanted))?;
-        let download_url =
-            crate::resolve_curseforge_file_download_url(client, &api_key, mod_id, &file)?;
+        let download_url = crate::curseforge_handoff::resolve_download_url_or_queue(
+            client,
+            &api_key,
+            instance_dir,
+            mod_id,
+            &file,
+            || {
+                let project = crate::fetch_curseforge_project(client, &api_key, mod_id).ok();
+                let worlds = resolve_target_worlds(instance_dir, &content_type, item)?;
+                Ok(crate::curseforge_handoff::handoff_item_for_file(
+                    project.as_ref(),
+                    mod_id,
+                    &file,
+                    &item.name,
+                    &content_type,
+                    &worlds,
+                    item.enabled,
+                ))
+            },
+        )?;
         crate::download_bytes_with_retry(client, &download_url, &download_label)?
     } else {
         return Err("unsupported provider".to_string());
     };
 
-    let target_worlds = if content_type == "datapacks" {
-        let requested_worlds = if item
-            .target_worlds
-            .iter()
-            .any(|world| world == crate::modpack::DATAPACK_ALL_WORLDS_SENTINEL)
-        {
-            crate::list_instance_world_names(instance_dir)?
-        } else {
-            item.target_worlds.clone()
-        };
-        crate::normalize_target_worlds_for_datapack(instance_dir, &requested_worlds)?
-    } else {
-        vec![]
-    };
+    let target_worlds = resolve_target_worlds(instance_dir, &content_type, item)?;
 
-    crate::write_download_to_content_targets(
+    let security_scan = crate::write_download_to_content_targets(
         instance_dir,
         &content_type,
         &item.filename,
@@ -193,6 +220,9 @@ fn apply_single_resolved(
         hashes: item.hashes.clone(),
         provider_candidates: vec![],
         local_analysis: None,
+        security_scan,
+        url_update: None,
+        github_channel: None,
     };
 
     if content_type == "mods" && !item.enabled {
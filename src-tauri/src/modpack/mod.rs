@@ -1667,6 +1667,8 @@ pub fn rollback_instance_to_last_modpack_snapshot(
 
     let instances_dir = crate::app_instances_dir(&app)?;
     let _ = crate::find_instance(&instances_dir, &args.instance_id)?;
+    let mutation_lock = crate::commands::impls::instance_mutation_lock(&args.instance_id);
+    let _guard = mutation_lock.lock()?;
     let instance_dir = crate::instance_dir_for_id(&instances_dir, &args.instance_id)?;
     let snapshots = crate::list_snapshots(&instance_dir)?;
     let selected = snapshots
@@ -1680,10 +1682,8 @@ pub fn rollback_instance_to_last_modpack_snapshot(
     let lock: crate::Lockfile =
         serde_json::from_str(&lock_raw).map_err(|e| format!("parse snapshot lock failed: {e}"))?;
 
-    let restored_files = crate::restore_instance_content_zip(
-        &crate::snapshot_content_zip_path(&snapshot_dir),
-        &instance_dir,
-    )?;
+    let restored_files =
+        crate::snapshot_store::restore_snapshot_content(&snapshot_dir, &instance_dir)?;
     crate::write_lockfile(&instances_dir, &args.instance_id, &lock)?;
 
     Ok(crate::RollbackResult {
@@ -991,6 +991,8 @@ mod resolver_loader_tests {
             game_versions: game_versions.into_iter().map(str::to_string).collect(),
             hashes: vec![],
             dependencies: vec![],
+            file_fingerprint: None,
+            file_length: None,
         }
     }
 
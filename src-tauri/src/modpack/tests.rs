@@ -139,6 +139,9 @@ mod modpack_tests {
                 hashes: HashMap::new(),
                 provider_candidates: vec![],
                 local_analysis: None,
+                security_scan: None,
+                url_update: None,
+                github_channel: None,
             }],
         };
 
@@ -159,6 +162,9 @@ mod modpack_tests {
                 hashes: HashMap::new(),
                 provider_candidates: vec![],
                 local_analysis: None,
+                security_scan: None,
+                url_update: None,
+                github_channel: None,
             }],
         };
 
@@ -0,0 +1,392 @@
+//! Minimal reader for Minecraft's NBT format.
+//!
+//! Handles the big-endian Java edition encoding, gzip or zlib compressed or
+//! raw. Input comes from save folders the user may have downloaded, so every
+//! length is checked against the remaining bytes and nesting depth is capped
+//! instead of trusting the file.
+
+use flate2::read::{GzDecoder, ZlibDecoder};
+use std::io::Read;
+
+const MAX_DEPTH: usize = 512;
+/// Decompressed size cap; `level.dat` and chunks are far smaller.
+const MAX_DECOMPRESSED_BYTES: u64 = 64 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum NbtTag {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray(Vec<i8>),
+    String(String),
+    List(Vec<NbtTag>),
+    /// Entries keep their on-disk order.
+    Compound(Vec<(String, NbtTag)>),
+    IntArray(Vec<i32>),
+    LongArray(Vec<i64>),
+}
+
+impl NbtTag {
+    /// Child of a compound by name.
+    pub(crate) fn get(&self, name: &str) -> Option<&NbtTag> {
+        match self {
+            NbtTag::Compound(entries) => entries
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, tag)| tag),
+            _ => None,
+        }
+    }
+
+    /// Follows a path of compound names, e.g. `["Data", "Version", "Name"]`.
+    pub(crate) fn path(&self, names: &[&str]) -> Option<&NbtTag> {
+        names.iter().try_fold(self, |tag, name| tag.get(name))
+    }
+
+    /// Any integer tag widened to `i64`.
+    pub(crate) fn as_i64(&self) -> Option<i64> {
+        match self {
+            NbtTag::Byte(value) => Some(i64::from(*value)),
+            NbtTag::Short(value) => Some(i64::from(*value)),
+            NbtTag::Int(value) => Some(i64::from(*value)),
+            NbtTag::Long(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_str(&self) -> Option<&str> {
+        match self {
+            NbtTag::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_list(&self) -> Option<&[NbtTag]> {
+        match self {
+            NbtTag::List(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    /// String entries of a list tag; other entries are skipped.
+    pub(crate) fn string_list(&self) -> Vec<String> {
+        self.as_list()
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|item| item.as_str().map(str::to_string))
+            .collect()
+    }
+}
+
+/// Undoes gzip or zlib compression when present; other input is returned as is.
+pub(crate) fn decompress(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    let read = match bytes {
+        [0x1f, 0x8b, ..] => GzDecoder::new(bytes)
+            .take(MAX_DECOMPRESSED_BYTES + 1)
+            .read_to_end(&mut out),
+        [0x78, second, ..] if (u16::from(bytes[0]) << 8 | u16::from(*second)) % 31 == 0 => {
+            ZlibDecoder::new(bytes)
+                .take(MAX_DECOMPRESSED_BYTES + 1)
+                .read_to_end(&mut out)
+        }
+        _ => return Ok(bytes.to_vec()),
+    };
+    read.map_err(|e| format!("decompress NBT failed: {e}"))?;
+    if out.len() as u64 > MAX_DECOMPRESSED_BYTES {
+        return Err("NBT data is too large".to_string());
+    }
+    Ok(out)
+}
+
+/// Parses a possibly compressed NBT document into its root name and tag.
+pub(crate) fn read_nbt(bytes: &[u8]) -> Result<(String, NbtTag), String> {
+    let raw = decompress(bytes)?;
+    let mut reader = Reader {
+        bytes: &raw,
+        pos: 0,
+    };
+    let kind = reader.u8()?;
+    if kind != 10 {
+        return Err(format!(
+            "NBT root must be a compound, found tag type {kind}"
+        ));
+    }
+    let name = reader.string()?;
+    let root = reader.payload(kind, 0)?;
+    Ok((name, root))
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| "NBT data ended unexpectedly".to_string())?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        let mut out = [0u8; N];
+        out.copy_from_slice(self.take(N)?);
+        Ok(out)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn i16(&mut self) -> Result<i16, String> {
+        Ok(i16::from_be_bytes(self.array()?))
+    }
+
+    fn i32(&mut self) -> Result<i32, String> {
+        Ok(i32::from_be_bytes(self.array()?))
+    }
+
+    fn i64(&mut self) -> Result<i64, String> {
+        Ok(i64::from_be_bytes(self.array()?))
+    }
+
+    /// Element count of an array or list, rejected when the remaining input
+    /// cannot hold that many elements of `min_size` bytes.
+    fn count(&mut self, min_size: usize) -> Result<usize, String> {
+        let len = self.i32()?;
+        let len = usize::try_from(len).map_err(|_| format!("negative NBT length {len}"))?;
+        let remaining = self.bytes.len() - self.pos;
+        if len.saturating_mul(min_size) > remaining {
+            return Err("NBT length exceeds the data".to_string());
+        }
+        Ok(len)
+    }
+
+    /// Java's modified UTF-8; the rare encodings that differ from UTF-8 are
+    /// decoded lossily.
+    fn string(&mut self) -> Result<String, String> {
+        let len = u16::from_be_bytes(self.array()?) as usize;
+        Ok(String::from_utf8_lossy(self.take(len)?).into_owned())
+    }
+
+    fn payload(&mut self, kind: u8, depth: usize) -> Result<NbtTag, String> {
+        if depth > MAX_DEPTH {
+            return Err("NBT nesting is too deep".to_string());
+        }
+        Ok(match kind {
+            1 => NbtTag::Byte(self.u8()? as i8),
+            2 => NbtTag::Short(self.i16()?),
+            3 => NbtTag::Int(self.i32()?),
+            4 => NbtTag::Long(self.i64()?),
+            5 => NbtTag::Float(f32::from_be_bytes(self.array()?)),
+            6 => NbtTag::Double(f64::from_be_bytes(self.array()?)),
+            7 => {
+                let len = self.count(1)?;
+                NbtTag::ByteArray(self.take(len)?.iter().map(|b| *b as i8).collect())
+            }
+            8 => NbtTag::String(self.string()?),
+            9 => {
+                let item_kind = self.u8()?;
+                let len = self.count(if item_kind == 0 { 0 } else { 1 })?;
+                if item_kind == 0 && len > 0 {
+                    return Err("NBT list of end tags".to_string());
+                }
+                let mut items = Vec::with_capacity(len.min(4096));
+                for _ in 0..len {
+                    items.push(self.payload(item_kind, depth + 1)?);
+                }
+                NbtTag::List(items)
+            }
+            10 => {
+                let mut entries = Vec::new();
+                loop {
+                    let child = self.u8()?;
+                    if child == 0 {
+                        break;
+                    }
+                    let name = self.string()?;
+                    entries.push((name, self.payload(child, depth + 1)?));
+                }
+                NbtTag::Compound(entries)
+            }
+            11 => {
+                let len = self.count(4)?;
+                let mut values = Vec::with_capacity(len);
+                for _ in 0..len {
+                    values.push(self.i32()?);
+                }
+                NbtTag::IntArray(values)
+            }
+            12 => {
+                let len = self.count(8)?;
+                let mut values = Vec::with_capacity(len);
+                for _ in 0..len {
+                    values.push(self.i64()?);
+                }
+                NbtTag::LongArray(values)
+            }
+            other => return Err(format!("unknown NBT tag type {other}")),
+        })
+    }
+}
+
+/// Test-only encoder used to build fixtures.
+#[cfg(test)]
+pub(crate) mod encode {
+    use super::NbtTag;
+
+    fn kind(tag: &NbtTag) -> u8 {
+        match tag {
+            NbtTag::Byte(_) => 1,
+            NbtTag::Short(_) => 2,
+            NbtTag::Int(_) => 3,
+            NbtTag::Long(_) => 4,
+            NbtTag::Float(_) => 5,
+            NbtTag::Double(_) => 6,
+            NbtTag::ByteArray(_) => 7,
+            NbtTag::String(_) => 8,
+            NbtTag::List(_) => 9,
+            NbtTag::Compound(_) => 10,
+            NbtTag::IntArray(_) => 11,
+            NbtTag::LongArray(_) => 12,
+        }
+    }
+
+    fn string(out: &mut Vec<u8>, value: &str) {
+        out.extend_from_slice(&(value.len() as u16).to_be_bytes());
+        out.extend_from_slice(value.as_bytes());
+    }
+
+    fn payload(out: &mut Vec<u8>, tag: &NbtTag) {
+        match tag {
+            NbtTag::Byte(value) => out.push(*value as u8),
+            NbtTag::Short(value) => out.extend_from_slice(&value.to_be_bytes()),
+            NbtTag::Int(value) => out.extend_from_slice(&value.to_be_bytes()),
+            NbtTag::Long(value) => out.extend_from_slice(&value.to_be_bytes()),
+            NbtTag::Float(value) => out.extend_from_slice(&value.to_be_bytes()),
+            NbtTag::Double(value) => out.extend_from_slice(&value.to_be_bytes()),
+            NbtTag::ByteArray(values) => {
+                out.extend_from_slice(&(values.len() as i32).to_be_bytes());
+                out.extend(values.iter().map(|b| *b as u8));
+            }
+            NbtTag::String(value) => string(out, value),
+            NbtTag::List(items) => {
+                out.push(items.first().map(kind).unwrap_or(0));
+                out.extend_from_slice(&(items.len() as i32).to_be_bytes());
+                for item in items {
+                    payload(out, item);
+                }
+            }
+            NbtTag::Compound(entries) => {
+                for (name, child) in entries {
+                    out.push(kind(child));
+                    string(out, name);
+                    payload(out, child);
+                }
+                out.push(0);
+            }
+            NbtTag::IntArray(values) => {
+                out.extend_from_slice(&(values.len() as i32).to_be_bytes());
+                for value in values {
+                    out.extend_from_slice(&value.to_be_bytes());
+                }
+            }
+            NbtTag::LongArray(values) => {
+                out.extend_from_slice(&(values.len() as i32).to_be_bytes());
+                for value in values {
+                    out.extend_from_slice(&value.to_be_bytes());
+                }
+            }
+        }
+    }
+
+    /// Uncompressed document with a root compound named `name`.
+    pub(crate) fn document(name: &str, root: &NbtTag) -> Vec<u8> {
+        let mut out = vec![10];
+        string(&mut out, name);
+        payload(&mut out, root);
+        out
+    }
+
+    pub(crate) fn compound(entries: Vec<(&str, NbtTag)>) -> NbtTag {
+        NbtTag::Compound(
+            entries
+                .into_iter()
+                .map(|(name, tag)| (name.to_string(), tag))
+                .collect(),
+        )
+    }
+
+    pub(crate) fn gzip(bytes: &[u8]) -> Vec<u8> {
+        use flate2::write::GzEncoder;
+        use std::io::Write;
+        let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(bytes).unwrap();
+        encoder.finish().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::encode::{compound, document, gzip};
+    use super::*;
+
+    fn sample() -> NbtTag {
+        compound(vec![
+            ("name", NbtTag::String("Test".to_string())),
+            ("count", NbtTag::Int(-7)),
+            ("big", NbtTag::Long(1 << 40)),
+            ("ratio", NbtTag::Double(0.5)),
+            ("bytes", NbtTag::ByteArray(vec![1, -1])),
+            ("longs", NbtTag::LongArray(vec![3, -4])),
+            (
+                "tags",
+                NbtTag::List(vec![
+                    NbtTag::String("a".to_string()),
+                    NbtTag::String("b".to_string()),
+                ]),
+            ),
+            ("empty", NbtTag::List(Vec::new())),
+            ("nested", compound(vec![("flag", NbtTag::Byte(1))])),
+        ])
+    }
+
+    #[test]
+    fn reads_raw_gzip_and_zlib_documents() {
+        let raw = document("", &sample());
+        let mut zlib = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::fast());
+        std::io::Write::write_all(&mut zlib, &raw).unwrap();
+        for bytes in [raw.clone(), gzip(&raw), zlib.finish().unwrap()] {
+            let (name, root) = read_nbt(&bytes).expect("parse");
+            assert_eq!(name, "");
+            assert_eq!(root, sample());
+        }
+        let root = sample();
+        assert_eq!(
+            root.path(&["nested", "flag"]).and_then(NbtTag::as_i64),
+            Some(1)
+        );
+        assert_eq!(root.get("tags").unwrap().string_list(), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn rejects_truncated_and_oversized_input() {
+        let raw = document("", &sample());
+        assert!(read_nbt(&raw[..raw.len() - 3]).is_err());
+
+        // A byte array claiming two billion entries must not allocate them.
+        let mut hostile = vec![10, 0, 0, 7, 0, 1, b'x'];
+        hostile.extend_from_slice(&i32::MAX.to_be_bytes());
+        assert!(read_nbt(&hostile).is_err());
+        assert!(read_nbt(&[8, 0, 0]).is_err());
+    }
+}
@@ -790,6 +790,9 @@ mod tests {
             hashes: HashMap::new(),
             provider_candidates: Vec::new(),
             local_analysis: None,
+            security_scan: None,
+            url_update: None,
+            github_channel: None,
         }
     }
 
@@ -0,0 +1,441 @@
+//! Rebuilds an instance's content from its lockfile.
+//!
+//! Every entry whose file is missing or no longer matches its recorded hash is
+//! restored from the first source holding a verified copy: the launcher content
+//! store, then the instance's snapshots (newest first), then the entry's
+//! provider. Offline runs skip providers and report which entries still need
+//! network access.
+
+use crate::content_store::ContentStore;
+use crate::snapshot_store::{
+    migrate_instance_snapshots, object_store_for, read_manifest, SnapshotManifest,
+};
+use crate::{
+    carry_security_acknowledgement, content_paths_for_type, datapack_world_paths, hash_bytes_hex,
+    list_snapshots, lock_entry_expected_hash, lock_entry_redownloadable, mod_paths,
+    normalize_lock_content_type, snapshots_dir, verify_lock_entry_integrity,
+    write_download_to_content_targets, LockEntry, Lockfile,
+};
+use reqwest::blocking::Client;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub(crate) const ORIGIN_CONTENT_STORE: &str = "content_store";
+pub(crate) const ORIGIN_SNAPSHOT: &str = "snapshot";
+pub(crate) const ORIGIN_PROVIDER: &str = "provider";
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct RehydrateEntryOutcome {
+    pub project_id: String,
+    pub version_id: String,
+    pub name: String,
+    pub filename: String,
+    pub content_type: String,
+    pub status: String, // present | restored | needs_network | unavailable | failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub origin: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct InstanceRehydrateReport {
+    pub instance_id: String,
+    pub offline: bool,
+    pub present: usize,
+    pub restored: usize,
+    pub needs_network: usize,
+    pub unavailable: usize,
+    pub failed: usize,
+    pub entries: Vec<RehydrateEntryOutcome>,
+}
+
+/// Snapshot manifests, newest first, read once per run.
+struct SnapshotManifests {
+    manifests: Vec<SnapshotManifest>,
+    store: ContentStore,
+}
+
+impl SnapshotManifests {
+    fn open(instance_dir: &Path) -> Self {
+        let _ = migrate_instance_snapshots(instance_dir);
+        let root = snapshots_dir(instance_dir);
+        let manifests = list_snapshots(instance_dir)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|meta| read_manifest(&root.join(meta.id)))
+            .collect();
+        Self {
+            manifests,
+            store: object_store_for(instance_dir),
+        }
+    }
+
+    /// First snapshot copy of any of `names` that matches the entry's hash.
+    fn find(&self, names: &[String], entry: &LockEntry) -> Option<Vec<u8>> {
+        for manifest in &self.manifests {
+            for file in &manifest.files {
+                if !names.contains(&file.path) {
+                    continue;
+                }
+                let hashes = HashMap::from([("sha512".to_string(), file.sha512.clone())]);
+                if let Some(bytes) = self.store.read(&hashes) {
+                    if bytes_match_entry(entry, &bytes) {
+                        return Some(bytes);
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+fn bytes_match_entry(entry: &LockEntry, bytes: &[u8]) -> bool {
+    match lock_entry_expected_hash(entry) {
+        Some((algorithm, expected)) => hash_bytes_hex(algorithm, bytes) == expected,
+        None => true,
+    }
+}
+
+/// Enabled/disabled path pairs the entry occupies inside the instance.
+fn lock_entry_path_pairs(instance_dir: &Path, entry: &LockEntry) -> Vec<(PathBuf, PathBuf)> {
+    match normalize_lock_content_type(&entry.content_type).as_str() {
+        "datapacks" => entry
+            .target_worlds
+            .iter()
+            .map(|world| datapack_world_paths(instance_dir, world, &entry.filename))
+            .collect(),
+        "resourcepacks" | "shaderpacks" => vec![content_paths_for_type(
+            instance_dir,
+            &entry.content_type,
+            &entry.filename,
+        )],
+        _ => vec![mod_paths(instance_dir, &entry.filename)],
+    }
+}
+
+/// Archive names a snapshot may hold the entry's file under.
+pub(crate) fn snapshot_names(instance_dir: &Path, entry: &LockEntry) -> Vec<String> {
+    lock_entry_path_pairs(instance_dir, entry)
+        .into_iter()
+        .flat_map(|(enabled, disabled)| [enabled, disabled])
+        .filter_map(|path| {
+            path.strip_prefix(instance_dir)
+                .ok()
+                .map(|rel| rel.to_string_lossy().replace('\\', "/"))
+        })
+        .collect()
+}
+
+fn place_entry_bytes(
+    instance_dir: &Path,
+    entry: &mut LockEntry,
+    bytes: &[u8],
+) -> Result<(), String> {
+    let scan = write_download_to_content_targets(
+        instance_dir,
+        &entry.content_type,
+        &entry.filename,
+        &entry.target_worlds,
+        bytes,
+    )?;
+    if !entry.enabled {
+        for (enabled, disabled) in lock_entry_path_pairs(instance_dir, entry) {
+            if disabled.exists() {
+                let _ = fs::remove_file(&disabled);
+            }
+            fs::rename(&enabled, &disabled).map_err(|e| {
+                format!(
+                    "disable '{}' -> '{}' failed: {e}",
+                    enabled.display(),
+                    disabled.display()
+                )
+            })?;
+        }
+    }
+    if let Some(scan) = scan {
+        entry.security_scan = Some(carry_security_acknowledgement(
+            entry.security_scan.as_ref(),
+            scan,
+        ));
+    }
+    Ok(())
+}
+
+/// Restores every missing or modified lock entry. `client: None` keeps the run
+/// offline.
+pub(crate) fn rehydrate_instance_content(
+    instance_id: &str,
+    instance_dir: &Path,
+    lock: &mut Lockfile,
+    store: Option<&ContentStore>,
+    client: Option<&Client>,
+) -> InstanceRehydrateReport {
+    let mut report = InstanceRehydrateReport {
+        instance_id: instance_id.to_string(),
+        offline: client.is_none(),
+        present: 0,
+        restored: 0,
+        needs_network: 0,
+        unavailable: 0,
+        failed: 0,
+        entries: Vec::new(),
+    };
+    let mut snapshots = None::<SnapshotManifests>;
+
+    for entry in lock.entries.iter_mut() {
+        let mut outcome = RehydrateEntryOutcome {
+            project_id: entry.project_id.clone(),
+            version_id: entry.version_id.clone(),
+            name: entry.name.clone(),
+            filename: entry.filename.clone(),
+            content_type: normalize_lock_content_type(&entry.content_type),
+            status: "present".to_string(),
+            origin: None,
+            message: None,
+        };
+        let integrity = verify_lock_entry_integrity(instance_dir, entry);
+        if integrity.status == "unchanged" || integrity.status == "no_hash" {
+            report.present += 1;
+            report.entries.push(outcome);
+            continue;
+        }
+
+        let mut found = store
+            .and_then(|store| store.read(&entry.hashes))
+            .filter(|bytes| bytes_match_entry(entry, bytes))
+            .map(|bytes| (ORIGIN_CONTENT_STORE, bytes));
+        if found.is_none() {
+            let names = snapshot_names(instance_dir, entry);
+            found = snapshots
+                .get_or_insert_with(|| SnapshotManifests::open(instance_dir))
+                .find(&names, entry)
+                .map(|bytes| (ORIGIN_SNAPSHOT, bytes));
+        }
+        if found.is_none() {
+            match client {
+                _ if !lock_entry_redownloadable(entry) => {
+                    outcome.status = "unavailable".to_string();
+                    outcome.message = Some(format!(
+                        "No local copy, and source '{}' cannot be re-downloaded automatically.",
+                        entry.source
+                    ));
+                }
+                None => {
+                    outcome.status = "needs_network".to_string();
+                    outcome.message =
+                        Some("No verified local copy; needs a provider download.".to_string());
+                }
+                Some(client) => {
+                    match crate::friend_link::download_launcher_lock_entry_bytes(
+                        client,
+                        entry,
+                        instance_dir,
+                    ) {
+                        Ok(Some(bytes)) if bytes_match_entry(entry, &bytes) => {
+                            found = Some((ORIGIN_PROVIDER, bytes));
+                        }
+                        Ok(Some(_)) => {
+                            outcome.status = "failed".to_string();
+                            outcome.message = Some(
+                                "Downloaded file does not match the recorded hash.".to_string(),
+                            );
+                        }
+                        Ok(None) => {
+                            outcome.status = "unavailable".to_string();
+                            outcome.message =
+                                Some("Provider did not return the pinned file.".to_string());
+                        }
+                        Err(err) => {
+                            outcome.status = "failed".to_string();
+                            outcome.message = Some(err);
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some((origin, bytes)) = found {
+            outcome.origin = Some(origin.to_string());
+            match place_entry_bytes(instance_dir, entry, &bytes) {
+                Ok(()) => outcome.status = "restored".to_string(),
+                Err(err) => {
+                    outcome.status = "failed".to_string();
+                    outcome.message = Some(err);
+                }
+            }
+        }
+        match outcome.status.as_str() {
+            "restored" => report.restored += 1,
+            "needs_network" => report.needs_network += 1,
+            "unavailable" => report.unavailable += 1,
+            _ => report.failed += 1,
+        }
+        report.entries.push(outcome);
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot_store::create_content_snapshot;
+    use crate::test_support::temp_root;
+    use crate::{
+        create_instance_content_zip, sha512_hex, snapshot_content_zip_path, write_snapshot_meta,
+        SnapshotMeta,
+    };
+    use std::collections::HashMap;
+
+    fn mod_entry(filename: &str, bytes: &[u8], source: &str) -> LockEntry {
+        LockEntry {
+            source: source.to_string(),
+            project_id: format!("proj-{filename}"),
+            version_id: format!("ver-{filename}"),
+            name: filename.trim_end_matches(".jar").to_string(),
+            version_number: "1.0.0".to_string(),
+            filename: filename.to_string(),
+            content_type: "resourcepacks".to_string(),
+            target_scope: "instance".to_string(),
+            target_worlds: vec![],
+            pinned_version: None,
+            enabled: true,
+            hashes: HashMap::from([("sha512".to_string(), sha512_hex(bytes))]),
+            provider_candidates: vec![],
+            local_analysis: None,
+            security_scan: None,
+            url_update: None,
+            github_channel: None,
+        }
+    }
+
+    fn status_of<'a>(
+        report: &'a InstanceRehydrateReport,
+        filename: &str,
+    ) -> (&'a str, Option<&'a str>) {
+        let outcome = report
+            .entries
+            .iter()
+            .find(|item| item.filename == filename)
+            .expect("entry outcome");
+        (outcome.status.as_str(), outcome.origin.as_deref())
+    }
+
+    #[test]
+    fn offline_rehydrate_uses_store_then_snapshots_and_reports_the_rest() {
+        let root = temp_root("offline");
+        let instance_dir = root.join("instance");
+        let packs = instance_dir.join("resourcepacks");
+        fs::create_dir_all(&packs).expect("mkdir packs");
+        let store = ContentStore::new(root.join("store"));
+
+        fs::write(packs.join("kept.zip"), b"kept").expect("write kept");
+        fs::write(packs.join("snap.zip"), b"from snapshot").expect("write snap");
+        let snapshot_dir = snapshots_dir(&instance_dir).join("snap-1");
+        create_content_snapshot(&instance_dir, &snapshot_dir).expect("snapshot manifest");
+        write_snapshot_meta(
+            &snapshot_dir,
+            &SnapshotMeta {
+                id: "snap-1".to_string(),
+                created_at: "2026-01-01T00:00:00Z".to_string(),
+                reason: "test".to_string(),
+                label: None,
+                pinned: false,
+                auto_pin: None,
+                verified_at: None,
+                integrity_error: None,
+            },
+        )
+        .expect("snapshot meta");
+        fs::remove_file(packs.join("snap.zip")).expect("lose snap");
+        store.put_bytes(b"from store").expect("store blob");
+
+        let mut disabled = mod_entry("stored.zip", b"from store", "modrinth");
+        disabled.enabled = false;
+        let mut lock = Lockfile {
+            version: 2,
+            entries: vec![
+                mod_entry("kept.zip", b"kept", "modrinth"),
+                disabled,
+                mod_entry("snap.zip", b"from snapshot", "modrinth"),
+                mod_entry("remote.zip", b"remote only", "modrinth"),
+                mod_entry("local.zip", b"never stored", "local"),
+            ],
+        };
+
+        let report =
+            rehydrate_instance_content("inst", &instance_dir, &mut lock, Some(&store), None);
+
+        assert!(report.offline);
+        assert_eq!(status_of(&report, "kept.zip"), ("present", None));
+        assert_eq!(
+            status_of(&report, "stored.zip"),
+            ("restored", Some(ORIGIN_CONTENT_STORE))
+        );
+        assert_eq!(
+            status_of(&report, "snap.zip"),
+            ("restored", Some(ORIGIN_SNAPSHOT))
+        );
+        assert_eq!(status_of(&report, "remote.zip"), ("needs_network", None));
+        assert_eq!(status_of(&report, "local.zip"), ("unavailable", None));
+        assert_eq!(
+            (
+                report.present,
+                report.restored,
+                report.needs_network,
+                report.unavailable
+            ),
+            (1, 2, 1, 1)
+        );
+        assert_eq!(
+            fs::read(packs.join("stored.zip.disabled")).expect("disabled restored"),
+            b"from store"
+        );
+        assert!(!packs.join("stored.zip").exists());
+        assert_eq!(
+            fs::read(packs.join("snap.zip")).expect("snapshot restored"),
+            b"from snapshot"
+        );
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn modified_files_are_replaced_only_by_verified_copies() {
+        let root = temp_root("verify");
+        let instance_dir = root.join("instance");
+        let packs = instance_dir.join("resourcepacks");
+        fs::create_dir_all(&packs).expect("mkdir packs");
+        fs::write(packs.join("pack.zip"), b"tampered").expect("write tampered");
+        let snapshot_dir = snapshots_dir(&instance_dir).join("snap-1");
+        // A legacy zip snapshot, migrated when rehydrate reads it.
+        create_instance_content_zip(&instance_dir, &snapshot_content_zip_path(&snapshot_dir))
+            .expect("snapshot zip");
+        write_snapshot_meta(
+            &snapshot_dir,
+            &SnapshotMeta {
+                id: "snap-1".to_string(),
+                created_at: "2026-01-01T00:00:00Z".to_string(),
+                reason: "test".to_string(),
+                label: None,
+                pinned: false,
+                auto_pin: None,
+                verified_at: None,
+                integrity_error: None,
+            },
+        )
+        .expect("snapshot meta");
+
+        let mut lock = Lockfile {
+            version: 2,
+            entries: vec![mod_entry("pack.zip", b"original", "modrinth")],
+        };
+        let report = rehydrate_instance_content("inst", &instance_dir, &mut lock, None, None);
+
+        // The only snapshot copy is the tampered file, so it must not be used.
+        assert_eq!(status_of(&report, "pack.zip"), ("needs_network", None));
+        assert_eq!(fs::read(packs.join("pack.zip")).expect("read"), b"tampered");
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -1,4 +1,7 @@
 use super::RunFinding;
+use crate::jar_analysis::mixins::{
+    conflict_display_names, detect_mixin_conflicts, find_mod_for_log_reference, EnabledModMixins,
+};
 use crate::{entry_file_exists, normalize_lock_content_type, Instance, Lockfile};
 use std::collections::{HashMap, HashSet};
 use std::path::Path;
@@ -13,6 +16,7 @@ pub(crate) struct ClassifierInput<'a> {
     pub required_java_major: u32,
     pub exit_code: Option<i32>,
     pub exit_message: Option<&'a str>,
+    pub mixin_mods: &'a [EnabledModMixins],
 }
 
 pub(crate) struct ClassifierOutput {
@@ -337,6 +341,44 @@ fn extract_linkage_symbol(line: &str) -> Option<String> {
     None
 }
 
+fn is_mixin_failure_line(lower: &str) -> bool {
+    text_contains_any(
+        lower,
+        &[
+            "mixinapplyerror",
+            "mixin apply failed",
+            "mixin apply for mod",
+            "mixintransformererror",
+            "invalidinjectionexception",
+            "invalidmixinexception",
+            "injectionerror",
+            "critical injection failure",
+            "method overwrite conflict",
+        ],
+    )
+}
+
+fn mixin_log_references(line: &str) -> Vec<String> {
+    let mut out = Vec::<String>::new();
+    let lower = line.to_ascii_lowercase();
+    if let Some(idx) = lower.find("from mod ") {
+        let token = line[idx + "from mod ".len()..]
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+            .collect::<String>();
+        let token = token.trim_end_matches('.');
+        if !token.is_empty() {
+            out.push(token.to_string());
+        }
+    }
+    for token in line.split(|c: char| !c.is_ascii_alphanumeric() && !matches!(c, '_' | '-' | '.')) {
+        if token.len() > 5 && token.to_ascii_lowercase().ends_with(".json") && !out.iter().any(|v| v == token) {
+            out.push(token.to_string());
+        }
+    }
+    out
+}
+
 fn push_or_update_finding(findings: &mut Vec<RunFinding>, mut next: RunFinding) {
     next.confidence = clamp_confidence(next.confidence);
     if let Some(existing) = findings.iter_mut().find(|item| item.id == next.id) {
@@ -550,6 +592,120 @@ pub(crate) fn classify(input: &ClassifierInput<'_>) -> ClassifierOutput {
         );
     }
 
+    let mixin_lines = collect_matching_evidence(&lines, 6, |_, lower| is_mixin_failure_line(lower));
+    if !mixin_lines.is_empty() {
+        let names = conflict_display_names(input.mixin_mods);
+        let mut involved = Vec::<String>::new();
+        let mut target_class: Option<String> = None;
+        for line in &mixin_lines {
+            for reference in mixin_log_references(line) {
+                if let Some(found) = find_mod_for_log_reference(input.mixin_mods, &reference) {
+                    let name = names.get(&found.key).unwrap_or(&found.label);
+                    if !involved.contains(name) {
+                        involved.push(name.clone());
+                    }
+                }
+            }
+            if target_class.is_none() {
+                target_class = input
+                    .mixin_mods
+                    .iter()
+                    .flat_map(|item| item.scan.targets.iter())
+                    .map(|target| &target.class_name)
+                    .find(|class_name| {
+                        line.contains(class_name.as_str())
+                            || line.contains(&class_name.replace('.', "/"))
+                    })
+                    .cloned();
+            }
+        }
+
+        let conflicts = detect_mixin_conflicts(input.mixin_mods);
+        let mut failing = involved.first().cloned();
+        let mut partner = involved.get(1).cloned();
+        let mut conflict_method: Option<String> = None;
+        let best = conflicts.iter().find(|conflict| {
+            failing
+                .as_ref()
+                .map(|label| conflict.mods.contains(label))
+                .unwrap_or(target_class.is_some())
+                && partner
+                    .as_ref()
+                    .map(|label| conflict.mods.contains(label))
+                    .unwrap_or(true)
+                && target_class
+                    .as_ref()
+                    .map(|class_name| &conflict.class_name == class_name)
+                    .unwrap_or(true)
+        });
+        if let Some(best) = best {
+            if failing.is_none() {
+                failing = best.mods.first().cloned();
+            }
+            if partner.is_none() {
+                partner = best
+                    .mods
+                    .iter()
+                    .find(|label| Some(*label) != failing.as_ref())
+                    .cloned();
+            }
+            if target_class.is_none() {
+                target_class = Some(best.class_name.clone());
+            }
+            conflict_method = best.method.clone();
+        }
+
+        for label in failing.iter().chain(partner.iter()) {
+            suspect_mod_tokens.insert(normalize_mod_token(label));
+        }
+        let target_label = match (target_class.as_ref(), conflict_method.as_ref()) {
+            (Some(class_name), Some(method)) => format!("{class_name}.{method}"),
+            (Some(class_name), None) => class_name.clone(),
+            _ => "a game class".to_string(),
+        };
+        let (title, explanation, likely_fix, confidence) = match (failing.as_ref(), partner.as_ref()) {
+            (Some(failing), Some(partner)) => (
+                format!("Mixin conflict: {failing} and {partner}"),
+                format!(
+                    "{failing} failed to apply a mixin to {target_label}, which {partner} also modifies."
+                ),
+                format!(
+                    "Update {failing} and {partner} to versions known to work together, or disable one of them and relaunch."
+                ),
+                0.97,
+            ),
+            (Some(failing), None) => (
+                format!("Mixin failed to apply: {failing}"),
+                format!(
+                    "{failing} failed to apply a mixin to {target_label}; no other enabled mod targets the same code, so the mod likely does not match this Minecraft/loader version."
+                ),
+                format!("Update or disable {failing}, then relaunch."),
+                0.9,
+            ),
+            _ => (
+                "Mixin failed to apply".to_string(),
+                "A mod's mixin could not be applied during startup.".to_string(),
+                "Check the mixin config named in the log, then update or disable that mod."
+                    .to_string(),
+                0.8,
+            ),
+        };
+        push_or_update_finding(
+            &mut findings,
+            RunFinding {
+                id: "mixin_apply_conflict".to_string(),
+                category: "mod_loading".to_string(),
+                title,
+                explanation,
+                confidence,
+                evidence: mixin_lines.into_iter().take(3).collect(),
+                likely_fix: Some(likely_fix),
+                mod_id: failing,
+                file_path: None,
+            },
+        );
+    }
+
     let loader_mismatch_lines = collect_evidence(
         &lines,
         &[
@@ -1036,6 +1192,9 @@ mod tests {
             hashes: HashMap::new(),
             provider_candidates: vec![],
             local_analysis: None,
+            security_scan: None,
+            url_update: None,
+            github_channel: None,
         }
     }
 
@@ -1060,6 +1219,7 @@ mod tests {
             required_java_major: 17,
             exit_code: Some(1),
             exit_message: Some("Game exited"),
+            mixin_mods: &[],
         });
         assert!(output
             .findings
@@ -1083,6 +1243,7 @@ mod tests {
             required_java_major: 17,
             exit_code: Some(1),
             exit_message: None,
+            mixin_mods: &[],
         });
         assert!(output
             .findings
@@ -1106,6 +1267,7 @@ mod tests {
             required_java_major: 17,
             exit_code: Some(1),
             exit_message: None,
+            mixin_mods: &[],
         });
         assert!(output
             .findings
@@ -1136,6 +1298,7 @@ mod tests {
             required_java_major: 17,
             exit_code: Some(1),
             exit_message: None,
+            mixin_mods: &[],
         });
         assert!(output
             .findings
@@ -1163,6 +1326,7 @@ mod tests {
             required_java_major: 17,
             exit_code: Some(1),
             exit_message: None,
+            mixin_mods: &[],
         });
         assert!(output
             .findings
@@ -1186,6 +1350,7 @@ mod tests {
             required_java_major: 17,
             exit_code: Some(1),
             exit_message: None,
+            mixin_mods: &[],
         });
         assert!(output
             .findings
@@ -1209,6 +1374,7 @@ mod tests {
             required_java_major: 17,
             exit_code: Some(1),
             exit_message: None,
+            mixin_mods: &[],
         });
         assert_eq!(output.phase.as_deref(), Some("render"));
         assert!(output
@@ -1254,6 +1420,7 @@ Caused by: java.lang.NoClassDefFoundError: meteordevelopment/starscript/utils/St
             required_java_major: 21,
             exit_code: Some(255),
             exit_message: Some("Game exited with status Some(255)"),
+            mixin_mods: &[],
         });
 
         assert_eq!(
@@ -1303,6 +1470,7 @@ Caused by: java.lang.NoClassDefFoundError: meteordevelopment/starscript/utils/St
             required_java_major: 21,
             exit_code: Some(255),
             exit_message: None,
+            mixin_mods: &[],
         });
 
         let finding = output
@@ -1315,4 +1483,56 @@ Caused by: java.lang.NoClassDefFoundError: meteordevelopment/starscript/utils/St
 
         let _ = fs::remove_dir_all(dir);
     }
+
+    fn mixin_mod(label: &str, config: &str, method: &str, kind: &'static str) -> EnabledModMixins {
+        use crate::jar_analysis::mixins::{MixinScan, MixinTarget};
+        EnabledModMixins {
+            key: format!("{label}.jar"),
+            label: label.to_string(),
+            tokens: vec![label.to_ascii_lowercase()],
+            scan: MixinScan {
+                configs: vec![config.to_string()],
+                targets: vec![MixinTarget {
+                    class_name: "net.minecraft.client.render.WorldRenderer".to_string(),
+                    mixin_class: format!("{label}.mixin.WorldRendererMixin"),
+                    config: config.to_string(),
+                    methods: vec![(method.to_string(), kind)],
+                }],
+            },
+        }
+    }
+
+    #[test]
+    fn names_both_mods_in_mixin_conflict() {
+        let instance = make_instance("fabric", "1.20.1");
+        let lock = Lockfile::default();
+        let dir = make_temp_instance_dir();
+        let mixin_mods = vec![
+            mixin_mod("Sodium", "sodium.mixins.json", "render", "overwrite"),
+            mixin_mod("FancyFog", "fancyfog.mixins.json", "render", "redirect"),
+        ];
+        let output = classify(&ClassifierInput {
+            instance: &instance,
+            lock: &lock,
+            instance_dir: &dir,
+            launch_log_text: "org.spongepowered.asm.mixin.transformer.throwables.MixinTransformerError: An unexpected critical error was encountered\nCaused by: org.spongepowered.asm.mixin.throwables.MixinApplyError: Mixin [fancyfog.mixins.json:WorldRendererMixin] from phase [DEFAULT] in config [fancyfog.mixins.json] FAILED during APPLY",
+            crash_log_text: "",
+            java_major: Some(17),
+            required_java_major: 17,
+            exit_code: Some(1),
+            exit_message: None,
+            mixin_mods: &mixin_mods,
+        });
+        let finding = output
+            .findings
+            .iter()
+            .find(|item| item.id == "mixin_apply_conflict")
+            .expect("mixin finding");
+        assert_eq!(finding.title, "Mixin conflict: FancyFog and Sodium");
+        assert_eq!(finding.mod_id.as_deref(), Some("FancyFog"));
+        assert!(finding.explanation.contains("WorldRenderer.render"));
+        assert!(output.suspect_mod_tokens.contains(&"sodium".to_string()));
+
+        let _ = fs::remove_dir_all(dir);
+    }
 }
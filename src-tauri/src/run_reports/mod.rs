@@ -266,9 +266,11 @@ fn build_suggested_actions(
 ) -> Vec<RunSuggestedAction> {
     let mut out = Vec::<RunSuggestedAction>::new();
 
-    let snapshot = list_snapshots(instance_dir)
-        .ok()
-        .and_then(|items| items.into_iter().next());
+    let snapshot = list_snapshots(instance_dir).ok().and_then(|items| {
+        items
+            .into_iter()
+            .find(|item| item.integrity_error.is_none())
+    });
     if let Some(item) = snapshot {
         out.push(RunSuggestedAction {
             id: action_id("rollback_snapshot"),
@@ -516,6 +518,14 @@ pub(crate) fn capture_and_store_run_report(
         .unwrap_or_default();
 
     let lock = read_lockfile(&instances_dir, &input.instance_id).unwrap_or_default();
+    // Reading every enabled jar is only worth it when the logs mention mixins.
+    let mixin_mods = if launch_log_text.to_ascii_lowercase().contains("mixin")
+        || crash_log_text.to_ascii_lowercase().contains("mixin")
+    {
+        crate::jar_analysis::mixins::collect_enabled_mod_mixins(&lock, &instance_dir)
+    } else {
+        Vec::new()
+    };
     let classifier_out = classifier::classify(&classifier::ClassifierInput {
         instance: &instance,
         lock: &lock,
@@ -526,6 +536,7 @@ pub(crate) fn capture_and_store_run_report(
         required_java_major,
         exit_code: input.exit_code,
         exit_message: input.message.as_deref(),
+        mixin_mods: &mixin_mods,
     });
     let _classifier_hints = (
         &classifier_out.suspect_mod_tokens,
@@ -0,0 +1,531 @@
+//! Snapshot comparison and selective restore.
+//!
+//! A diff compares two content states — a snapshot or the live instance —
+//! by lockfile entry (keyed by source, content type and project) and by raw
+//! file hash. A selective restore brings back only the chosen entries or
+//! files from a snapshot; anything chosen that the snapshot does not hold is
+//! removed from the instance instead.
+
+use crate::content_store::ContentStore;
+use crate::rehydrate::snapshot_names;
+use crate::snapshot_store::{
+    current_content_manifest, migrate_legacy_snapshot, object_store_for, read_manifest,
+    snapshot_path_allowed, SnapshotManifest,
+};
+use crate::{
+    normalize_lock_content_type, read_snapshot_meta, snapshot_lock_path, snapshots_dir, LockEntry,
+    Lockfile,
+};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::Path;
+
+/// Diff side name for the live instance.
+pub(crate) const CURRENT_STATE: &str = "current";
+
+#[derive(Debug, Clone)]
+pub(crate) struct ContentState {
+    pub lock: Lockfile,
+    pub manifest: SnapshotManifest,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub(crate) struct LockEntrySide {
+    pub version_id: String,
+    pub version_number: String,
+    pub filename: String,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct SnapshotLockChange {
+    pub key: String,
+    pub name: String,
+    pub content_type: String,
+    pub change: String, // added | removed | changed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<LockEntrySide>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<LockEntrySide>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct SnapshotFileChange {
+    pub path: String,
+    pub change: String, // added | removed | modified
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before_size: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after_size: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct SnapshotDiff {
+    pub instance_id: String,
+    pub from: String,
+    pub to: String,
+    pub lock_changes: Vec<SnapshotLockChange>,
+    pub file_changes: Vec<SnapshotFileChange>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct SelectiveRestoreResult {
+    pub snapshot_id: String,
+    pub undo_snapshot_id: String,
+    pub restored_entries: usize,
+    pub removed_entries: usize,
+    pub restored_files: usize,
+    pub removed_files: usize,
+    pub message: String,
+}
+
+/// Work a selective restore will do, computed before anything is touched.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SelectionPlan {
+    pub lock: Lockfile,
+    /// Relative paths and the blob each one is restored from.
+    pub place: BTreeMap<String, String>,
+    pub remove: BTreeSet<String>,
+    pub restored_entries: usize,
+    pub removed_entries: usize,
+}
+
+/// Stable identity of a lock entry across snapshots.
+pub(crate) fn lock_entry_key(entry: &LockEntry) -> String {
+    format!(
+        "{}:{}:{}",
+        entry.source.trim().to_lowercase(),
+        normalize_lock_content_type(&entry.content_type),
+        entry.project_id.trim().to_lowercase()
+    )
+}
+
+fn lock_entry_side(entry: &LockEntry) -> LockEntrySide {
+    LockEntrySide {
+        version_id: entry.version_id.clone(),
+        version_number: entry.version_number.clone(),
+        filename: entry.filename.clone(),
+        enabled: entry.enabled,
+    }
+}
+
+fn lock_entries_differ(a: &LockEntry, b: &LockEntry) -> bool {
+    let mut worlds_a = a.target_worlds.clone();
+    let mut worlds_b = b.target_worlds.clone();
+    worlds_a.sort();
+    worlds_b.sort();
+    lock_entry_side(a) != lock_entry_side(b) || a.hashes != b.hashes || worlds_a != worlds_b
+}
+
+fn entries_by_key(lock: &Lockfile) -> BTreeMap<String, &LockEntry> {
+    lock.entries
+        .iter()
+        .map(|entry| (lock_entry_key(entry), entry))
+        .collect()
+}
+
+/// Loads a snapshot's lockfile and manifest, migrating a legacy zip first.
+pub(crate) fn load_snapshot_state(
+    instance_dir: &Path,
+    snapshot_id: &str,
+) -> Result<ContentState, String> {
+    let snapshot_dir = snapshots_dir(instance_dir).join(snapshot_id);
+    if snapshot_id.contains(['/', '\\']) || read_snapshot_meta(&snapshot_dir).is_err() {
+        return Err("Snapshot not found".to_string());
+    }
+    migrate_legacy_snapshot(&snapshot_dir, &object_store_for(instance_dir))?;
+    let lock_raw = fs::read_to_string(snapshot_lock_path(&snapshot_dir))
+        .map_err(|e| format!("read snapshot lock failed: {e}"))?;
+    let lock: Lockfile =
+        serde_json::from_str(&lock_raw).map_err(|e| format!("parse snapshot lock failed: {e}"))?;
+    Ok(ContentState {
+        lock,
+        manifest: read_manifest(&snapshot_dir).unwrap_or_default(),
+    })
+}
+
+/// The live instance as a content state.
+pub(crate) fn current_state(instance_dir: &Path, lock: Lockfile) -> Result<ContentState, String> {
+    Ok(ContentState {
+        lock,
+        manifest: current_content_manifest(instance_dir)?,
+    })
+}
+
+pub(crate) fn diff_content_states(
+    from: &ContentState,
+    to: &ContentState,
+) -> (Vec<SnapshotLockChange>, Vec<SnapshotFileChange>) {
+    let before = entries_by_key(&from.lock);
+    let after = entries_by_key(&to.lock);
+    let keys = before.keys().chain(after.keys()).collect::<BTreeSet<_>>();
+    let mut lock_changes = Vec::new();
+    for key in keys {
+        let (old, new) = (before.get(key), after.get(key));
+        let change = match (old, new) {
+            (None, Some(_)) => "added",
+            (Some(_), None) => "removed",
+            (Some(a), Some(b)) if lock_entries_differ(a, b) => "changed",
+            _ => continue,
+        };
+        let named = new.or(old).expect("entry on one side");
+        lock_changes.push(SnapshotLockChange {
+            key: key.clone(),
+            name: named.name.clone(),
+            content_type: normalize_lock_content_type(&named.content_type),
+            change: change.to_string(),
+            before: old.map(|entry| lock_entry_side(entry)),
+            after: new.map(|entry| lock_entry_side(entry)),
+        });
+    }
+
+    let before = from
+        .manifest
+        .files
+        .iter()
+        .map(|file| (file.path.as_str(), file))
+        .collect::<BTreeMap<_, _>>();
+    let after = to
+        .manifest
+        .files
+        .iter()
+        .map(|file| (file.path.as_str(), file))
+        .collect::<BTreeMap<_, _>>();
+    let paths = before.keys().chain(after.keys()).collect::<BTreeSet<_>>();
+    let mut file_changes = Vec::new();
+    for path in paths {
+        let (old, new) = (before.get(path), after.get(path));
+        let change = match (old, new) {
+            (None, Some(_)) => "added",
+            (Some(_), None) => "removed",
+            (Some(a), Some(b)) if !a.sha512.eq_ignore_ascii_case(&b.sha512) => "modified",
+            _ => continue,
+        };
+        file_changes.push(SnapshotFileChange {
+            path: path.to_string(),
+            change: change.to_string(),
+            before_size: old.map(|file| file.size),
+            after_size: new.map(|file| file.size),
+        });
+    }
+    (lock_changes, file_changes)
+}
+
+/// Works out which files and lock entries to bring back from `snapshot`.
+pub(crate) fn plan_selective_restore(
+    instance_dir: &Path,
+    snapshot: &ContentState,
+    current_lock: &Lockfile,
+    entry_keys: &[String],
+    paths: &[String],
+) -> Result<SelectionPlan, String> {
+    let stored = snapshot
+        .manifest
+        .files
+        .iter()
+        .map(|file| (file.path.as_str(), file.sha512.as_str()))
+        .collect::<HashMap<_, _>>();
+    let mut plan = SelectionPlan {
+        lock: current_lock.clone(),
+        ..SelectionPlan::default()
+    };
+
+    for key in entry_keys {
+        let key = key.trim().to_lowercase();
+        let old = snapshot
+            .lock
+            .entries
+            .iter()
+            .find(|entry| lock_entry_key(entry) == key);
+        let current_index = plan
+            .lock
+            .entries
+            .iter()
+            .position(|entry| lock_entry_key(entry) == key);
+        if old.is_none() && current_index.is_none() {
+            return Err(format!(
+                "Lock entry '{key}' is in neither the snapshot nor the instance"
+            ));
+        }
+        if let Some(index) = current_index {
+            let current = plan.lock.entries.remove(index);
+            for name in snapshot_names(instance_dir, &current) {
+                if instance_dir.join(&name).is_file() {
+                    plan.remove.insert(name);
+                }
+            }
+        }
+        match old {
+            Some(entry) => {
+                let files = snapshot_names(instance_dir, entry)
+                    .into_iter()
+                    .filter_map(|name| stored.get(name.as_str()).map(|hash| (name, *hash)))
+                    .collect::<Vec<_>>();
+                if files.is_empty() {
+                    return Err(format!(
+                        "Snapshot holds no file for '{}' ({})",
+                        entry.name, entry.filename
+                    ));
+                }
+                for (name, hash) in files {
+                    plan.place.insert(name, hash.to_string());
+                }
+                let at = current_index.unwrap_or(plan.lock.entries.len());
+                plan.lock.entries.insert(at, entry.clone());
+                plan.restored_entries += 1;
+            }
+            None => plan.removed_entries += 1,
+        }
+    }
+
+    for path in paths {
+        let path = path.trim().replace('\\', "/");
+        if !snapshot_path_allowed(&path) {
+            return Err(format!("'{path}' is not part of instance snapshots"));
+        }
+        if let Some(hash) = stored.get(path.as_str()) {
+            plan.place.insert(path, hash.to_string());
+        } else if instance_dir.join(&path).is_file() {
+            plan.remove.insert(path);
+        } else {
+            return Err(format!(
+                "'{path}' is in neither the snapshot nor the instance"
+            ));
+        }
+    }
+    plan.remove.retain(|path| !plan.place.contains_key(path));
+    Ok(plan)
+}
+
+/// Checks every blob the plan needs before the instance is touched.
+pub(crate) fn verify_selection_plan(
+    plan: &SelectionPlan,
+    store: &ContentStore,
+) -> Result<(), String> {
+    let missing = plan
+        .place
+        .iter()
+        .filter(|(_, hash)| !store.verify(hash))
+        .map(|(path, _)| path.as_str())
+        .collect::<Vec<_>>();
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Snapshot is missing or has damaged {} stored file(s): {}",
+            missing.len(),
+            missing.join(", ")
+        ))
+    }
+}
+
+/// Applies a verified plan; returns `(restored_files, removed_files)`.
+pub(crate) fn apply_selection_plan(
+    instance_dir: &Path,
+    plan: &SelectionPlan,
+    store: &ContentStore,
+) -> Result<(usize, usize), String> {
+    let mut removed = 0usize;
+    for path in &plan.remove {
+        match fs::remove_file(instance_dir.join(path)) {
+            Ok(()) => removed += 1,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => return Err(format!("remove '{path}' failed: {err}")),
+        }
+    }
+    for (path, hash) in &plan.place {
+        let out_path = instance_dir.join(path);
+        if let Some(parent) = out_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("mkdir restore parent failed: {e}"))?;
+        }
+        store.materialize(hash, &out_path)?;
+    }
+    Ok((plan.place.len(), removed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot_store::write_content_manifest;
+    use crate::test_support::{temp_root, write_file};
+
+    fn mod_entry(project_id: &str, version: &str, filename: &str) -> LockEntry {
+        LockEntry {
+            source: "modrinth".to_string(),
+            project_id: project_id.to_string(),
+            version_id: format!("{project_id}-{version}"),
+            name: project_id.to_string(),
+            version_number: version.to_string(),
+            filename: filename.to_string(),
+            content_type: "mods".to_string(),
+            target_scope: "instance".to_string(),
+            target_worlds: vec![],
+            pinned_version: None,
+            enabled: true,
+            hashes: HashMap::new(),
+            provider_candidates: vec![],
+            local_analysis: None,
+            security_scan: None,
+            url_update: None,
+            github_channel: None,
+        }
+    }
+
+    fn snapshot_of(instance: &Path, store: &ContentStore, lock: Lockfile) -> ContentState {
+        let dir = instance.join("snapshots").join("snap");
+        write_content_manifest(instance, &dir, store, None).expect("manifest");
+        ContentState {
+            lock,
+            manifest: read_manifest(&dir).expect("read manifest"),
+        }
+    }
+
+    #[test]
+    fn diff_reports_lock_and_file_changes() {
+        let root = temp_root("diff");
+        let instance = root.join("instance");
+        let store = ContentStore::new(root.join("store"));
+        write_file(&instance.join("mods").join("sodium-1.jar"), b"sodium 1");
+        write_file(&instance.join("mods").join("lithium.jar"), b"lithium");
+        write_file(&instance.join("resourcepacks").join("pack.zip"), b"pack v1");
+        let before = snapshot_of(
+            &instance,
+            &store,
+            Lockfile {
+                version: 2,
+                entries: vec![
+                    mod_entry("sodium", "1", "sodium-1.jar"),
+                    mod_entry("lithium", "1", "lithium.jar"),
+                ],
+            },
+        );
+
+        fs::remove_file(instance.join("mods").join("sodium-1.jar")).unwrap();
+        fs::remove_file(instance.join("mods").join("lithium.jar")).unwrap();
+        write_file(&instance.join("mods").join("sodium-2.jar"), b"sodium 2");
+        write_file(
+            &instance.join("resourcepacks").join("pack.zip"),
+            b"pack v2!",
+        );
+        let after = current_state(
+            &instance,
+            Lockfile {
+                version: 2,
+                entries: vec![mod_entry("sodium", "2", "sodium-2.jar")],
+            },
+        )
+        .expect("current state");
+
+        let (locks, files) = diff_content_states(&before, &after);
+        let locks = locks
+            .iter()
+            .map(|c| (c.key.as_str(), c.change.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            locks,
+            vec![
+                ("modrinth:mods:lithium", "removed"),
+                ("modrinth:mods:sodium", "changed")
+            ]
+        );
+        let files = files
+            .iter()
+            .map(|c| (c.path.as_str(), c.change.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            files,
+            vec![
+                ("mods/lithium.jar", "removed"),
+                ("mods/sodium-1.jar", "removed"),
+                ("mods/sodium-2.jar", "added"),
+                ("resourcepacks/pack.zip", "modified"),
+            ]
+        );
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn selective_restore_brings_back_only_the_chosen_entry() {
+        let root = temp_root("select");
+        let instance = root.join("instance");
+        let store = ContentStore::new(root.join("store"));
+        write_file(&instance.join("mods").join("sodium-1.jar"), b"sodium 1");
+        write_file(&instance.join("mods").join("lithium-1.jar"), b"lithium 1");
+        let snapshot = snapshot_of(
+            &instance,
+            &store,
+            Lockfile {
+                version: 2,
+                entries: vec![
+                    mod_entry("sodium", "1", "sodium-1.jar"),
+                    mod_entry("lithium", "1", "lithium-1.jar"),
+                ],
+            },
+        );
+
+        fs::remove_file(instance.join("mods").join("sodium-1.jar")).unwrap();
+        fs::remove_file(instance.join("mods").join("lithium-1.jar")).unwrap();
+        write_file(&instance.join("mods").join("sodium-2.jar"), b"sodium 2");
+        write_file(&instance.join("mods").join("lithium-2.jar"), b"lithium 2");
+        write_file(&instance.join("mods").join("extra.jar"), b"extra");
+        let current = Lockfile {
+            version: 2,
+            entries: vec![
+                mod_entry("sodium", "2", "sodium-2.jar"),
+                mod_entry("lithium", "2", "lithium-2.jar"),
+                mod_entry("extra", "1", "extra.jar"),
+            ],
+        };
+
+        let plan = plan_selective_restore(
+            &instance,
+            &snapshot,
+            &current,
+            &[
+                "modrinth:mods:sodium".to_string(),
+                "modrinth:mods:extra".to_string(),
+            ],
+            &[],
+        )
+        .expect("plan");
+        verify_selection_plan(&plan, &store).expect("verify");
+        assert_eq!(
+            apply_selection_plan(&instance, &plan, &store).expect("apply"),
+            (1, 2)
+        );
+        assert_eq!((plan.restored_entries, plan.removed_entries), (1, 1));
+
+        let mods = instance.join("mods");
+        assert_eq!(fs::read(mods.join("sodium-1.jar")).unwrap(), b"sodium 1");
+        assert!(!mods.join("sodium-2.jar").exists());
+        assert!(!mods.join("extra.jar").exists());
+        assert_eq!(fs::read(mods.join("lithium-2.jar")).unwrap(), b"lithium 2");
+        let versions = plan
+            .lock
+            .entries
+            .iter()
+            .map(|entry| (entry.project_id.as_str(), entry.version_number.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(versions, vec![("sodium", "1"), ("lithium", "2")]);
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn selective_restore_rejects_paths_outside_snapshots() {
+        let root = temp_root("reject");
+        let instance = root.join("instance");
+        let store = ContentStore::new(root.join("store"));
+        let snapshot = snapshot_of(&instance, &store, Lockfile::default());
+        let err = plan_selective_restore(
+            &instance,
+            &snapshot,
+            &Lockfile::default(),
+            &[],
+            &["../options.txt".to_string()],
+        )
+        .unwrap_err();
+        assert!(err.contains("not part of instance snapshots"));
+        let _ = fs::remove_dir_all(root);
+    }
+}
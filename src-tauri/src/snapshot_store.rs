@@ -0,0 +1,555 @@
+//! Content-addressed instance snapshots.
+//!
+//! A snapshot keeps `manifest.json` (relative path → SHA-512) next to its
+//! `lock.json`; file bodies live once in the shared content store, so files
+//! unchanged since an earlier snapshot cost nothing. Files whose size and
+//! modification time match the newest earlier manifest are not even re-read.
+//! Bodies are stored and restored as private copies, so editing a live file in
+//! place never changes what a snapshot holds.
+//! Legacy `content.zip` snapshots are migrated into this layout once.
+
+use crate::content_store::{BlobDigest, ContentStore};
+use crate::{
+    clear_snapshot_content_dirs, instance_dir_for_id, list_snapshots, read_index,
+    restore_instance_content_zip, snapshot_allowed_root, snapshot_content_zip_path, snapshots_dir,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::UNIX_EPOCH;
+
+pub(crate) const MANIFEST_FILE: &str = "manifest.json";
+/// Object store used when no launcher-wide content store is configured.
+const LOCAL_OBJECTS_DIR: &str = "objects";
+pub(crate) const MANIFEST_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct SnapshotManifest {
+    pub version: u32,
+    pub files: Vec<SnapshotFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct SnapshotFile {
+    pub path: String,
+    pub sha512: String,
+    pub size: u64,
+    /// Source modification time in Unix millis; 0 for migrated entries.
+    #[serde(default)]
+    pub modified_ms: u64,
+}
+
+pub(crate) fn snapshot_manifest_path(snapshot_dir: &Path) -> PathBuf {
+    snapshot_dir.join(MANIFEST_FILE)
+}
+
+pub(crate) fn read_manifest(snapshot_dir: &Path) -> Option<SnapshotManifest> {
+    let raw = fs::read_to_string(snapshot_manifest_path(snapshot_dir)).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+pub(crate) fn write_manifest(
+    snapshot_dir: &Path,
+    manifest: &SnapshotManifest,
+) -> Result<(), String> {
+    let raw = serde_json::to_string_pretty(manifest)
+        .map_err(|e| format!("serialize snapshot manifest failed: {e}"))?;
+    let path = snapshot_manifest_path(snapshot_dir);
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, raw).map_err(|e| format!("write snapshot manifest failed: {e}"))?;
+    fs::rename(&tmp, &path).map_err(|e| format!("commit snapshot manifest failed: {e}"))
+}
+
+/// Where snapshot file bodies live for `instance_dir`.
+pub(crate) fn object_store_for(instance_dir: &Path) -> ContentStore {
+    ContentStore::configured()
+        .unwrap_or_else(|| ContentStore::new(snapshots_dir(instance_dir).join(LOCAL_OBJECTS_DIR)))
+}
+
+/// Relative snapshot paths must stay inside the snapshotted content folders.
+pub(crate) fn snapshot_path_allowed(rel: &str) -> bool {
+    let parts = rel.split('/').collect::<Vec<_>>();
+    if parts
+        .iter()
+        .any(|part| part.trim().is_empty() || *part == "." || *part == ".." || part.contains('\\'))
+    {
+        return false;
+    }
+    if !snapshot_allowed_root(parts[0]) {
+        return false;
+    }
+    parts[0] != "saves" || (parts.len() >= 4 && parts[2] == "datapacks")
+}
+
+pub(crate) fn modified_ms(meta: &fs::Metadata) -> u64 {
+    meta.modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn collect_files(root: &Path, current: &Path, out: &mut Vec<(String, PathBuf, fs::Metadata)>) {
+    let Ok(entries) = fs::read_dir(current) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(meta) = fs::symlink_metadata(&path) else {
+            continue;
+        };
+        if meta.is_dir() {
+            collect_files(root, &path, out);
+        } else if meta.is_file() {
+            let Ok(rel) = path.strip_prefix(root) else {
+                continue;
+            };
+            let rel = rel.to_string_lossy().replace('\\', "/");
+            if snapshot_path_allowed(&rel) {
+                out.push((rel, path, meta));
+            }
+        }
+    }
+}
+
+/// Files a snapshot covers: mods, resource packs, shader packs and every
+/// world's datapacks.
+fn snapshot_source_files(instance_dir: &Path) -> Vec<(String, PathBuf, fs::Metadata)> {
+    let mut out = Vec::new();
+    for dir_name in ["mods", "resourcepacks", "shaderpacks"] {
+        collect_files(instance_dir, &instance_dir.join(dir_name), &mut out);
+    }
+    if let Ok(worlds) = fs::read_dir(instance_dir.join("saves")) {
+        for world in worlds.flatten() {
+            let datapacks = world.path().join("datapacks");
+            if datapacks.is_dir() {
+                collect_files(instance_dir, &datapacks, &mut out);
+            }
+        }
+    }
+    out.sort_by(|a, b| a.0.cmp(&b.0));
+    out
+}
+
+/// Newest earlier manifest, used to skip re-hashing unchanged files.
+fn latest_manifest(instance_dir: &Path, except: &Path) -> Option<SnapshotManifest> {
+    let root = snapshots_dir(instance_dir);
+    list_snapshots(instance_dir)
+        .ok()?
+        .into_iter()
+        .map(|meta| root.join(meta.id))
+        .filter(|dir| dir != except)
+        .find_map(|dir| read_manifest(&dir))
+}
+
+/// Lists the instance content with a hash per file. Files whose size and
+/// modification time match `previous` (and pass `reusable`) keep its hash;
+/// the rest go through `hash`.
+fn scan_content(
+    instance_dir: &Path,
+    previous: Option<&SnapshotManifest>,
+    reusable: impl Fn(&SnapshotFile) -> bool,
+    mut hash: impl FnMut(&Path) -> Result<String, String>,
+) -> Result<SnapshotManifest, String> {
+    let known = previous
+        .map(|manifest| {
+            manifest
+                .files
+                .iter()
+                .map(|file| (file.path.as_str(), file))
+                .collect::<HashMap<_, _>>()
+        })
+        .unwrap_or_default();
+    let mut manifest = SnapshotManifest {
+        version: MANIFEST_VERSION,
+        files: Vec::new(),
+    };
+    for (rel, path, meta) in snapshot_source_files(instance_dir) {
+        let modified = modified_ms(&meta);
+        let unchanged = known.get(rel.as_str()).filter(|file| {
+            file.size == meta.len()
+                && file.modified_ms != 0
+                && file.modified_ms == modified
+                && reusable(file)
+        });
+        let sha512 = match unchanged {
+            Some(file) => file.sha512.clone(),
+            None => hash(&path)?,
+        };
+        manifest.files.push(SnapshotFile {
+            path: rel,
+            sha512,
+            size: meta.len(),
+            modified_ms: modified,
+        });
+    }
+    Ok(manifest)
+}
+
+/// Records the instance content into `snapshot_dir` and returns the file count.
+pub(crate) fn write_content_manifest(
+    instance_dir: &Path,
+    snapshot_dir: &Path,
+    store: &ContentStore,
+    previous: Option<&SnapshotManifest>,
+) -> Result<usize, String> {
+    fs::create_dir_all(snapshot_dir).map_err(|e| format!("mkdir snapshot dir failed: {e}"))?;
+    let _store_use = store.begin_use();
+    let manifest = scan_content(
+        instance_dir,
+        previous,
+        |file| store.contains(&file.sha512),
+        |path| store.put_file_copy(path).map(|(digest, _)| digest.sha512),
+    )?;
+    write_manifest(snapshot_dir, &manifest)?;
+    Ok(manifest.files.len())
+}
+
+/// The instance's current content as a manifest, without storing anything.
+pub(crate) fn current_content_manifest(instance_dir: &Path) -> Result<SnapshotManifest, String> {
+    let previous = latest_manifest(instance_dir, &snapshots_dir(instance_dir));
+    scan_content(
+        instance_dir,
+        previous.as_ref(),
+        |_| true,
+        |path| BlobDigest::of_file(path).map(|digest| digest.sha512),
+    )
+}
+
+/// Snapshots the instance content using the shared object store.
+pub(crate) fn create_content_snapshot(
+    instance_dir: &Path,
+    snapshot_dir: &Path,
+) -> Result<usize, String> {
+    let previous = latest_manifest(instance_dir, snapshot_dir);
+    write_content_manifest(
+        instance_dir,
+        snapshot_dir,
+        &object_store_for(instance_dir),
+        previous.as_ref(),
+    )
+}
+
+/// Replaces the instance content folders with the manifest's files. Every
+/// object is verified before anything is cleared.
+pub(crate) fn restore_content_manifest(
+    snapshot_dir: &Path,
+    instance_dir: &Path,
+    store: &ContentStore,
+) -> Result<usize, String> {
+    let manifest =
+        read_manifest(snapshot_dir).ok_or_else(|| "read snapshot manifest failed".to_string())?;
+    // Objects may be shared with installed files, so re-check their bytes.
+    let mut checked = HashMap::new();
+    let missing = manifest
+        .files
+        .iter()
+        .filter(|file| {
+            !*checked
+                .entry(file.sha512.as_str())
+                .or_insert_with(|| store.verify(&file.sha512))
+        })
+        .map(|file| file.path.as_str())
+        .collect::<Vec<_>>();
+    if !missing.is_empty() {
+        return Err(format!(
+            "Snapshot is missing or has damaged {} stored file(s): {}",
+            missing.len(),
+            missing.join(", ")
+        ));
+    }
+    clear_snapshot_content_dirs(instance_dir)?;
+    let mut count = 0usize;
+    for file in &manifest.files {
+        if !snapshot_path_allowed(&file.path) {
+            continue;
+        }
+        let out_path = instance_dir.join(&file.path);
+        if let Some(parent) = out_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("mkdir restore parent failed: {e}"))?;
+        }
+        store.copy_out(&file.sha512, &out_path)?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// Serializes migration of one snapshot between the startup migration and a
+/// rollback that needs the same snapshot.
+fn migration_lock(snapshot_dir: &Path) -> Arc<Mutex<()>> {
+    static LOCKS: OnceLock<Mutex<HashMap<PathBuf, Arc<Mutex<()>>>>> = OnceLock::new();
+    let locks = LOCKS.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut guard = locks.lock().expect("snapshot migration lock registry");
+    guard
+        .entry(snapshot_dir.to_path_buf())
+        .or_insert_with(|| Arc::new(Mutex::new(())))
+        .clone()
+}
+
+/// Converts a legacy `content.zip` snapshot into a manifest. Returns false when
+/// there was nothing to migrate.
+pub(crate) fn migrate_legacy_snapshot(
+    snapshot_dir: &Path,
+    store: &ContentStore,
+) -> Result<bool, String> {
+    let lock = migration_lock(snapshot_dir);
+    let _guard = lock
+        .lock()
+        .map_err(|_| "snapshot migration lock poisoned".to_string())?;
+    let zip_path = snapshot_content_zip_path(snapshot_dir);
+    if !zip_path.exists() || snapshot_manifest_path(snapshot_dir).exists() {
+        return Ok(false);
+    }
+    let _store_use = store.begin_use();
+    let file = fs::File::open(&zip_path).map_err(|e| format!("open snapshot zip failed: {e}"))?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("read snapshot zip failed: {e}"))?;
+    let mut manifest = SnapshotManifest {
+        version: MANIFEST_VERSION,
+        files: Vec::new(),
+    };
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| format!("read snapshot zip entry failed: {e}"))?;
+        if entry.is_dir() {
+            continue;
+        }
+        let Some(name) = entry.enclosed_name() else {
+            continue;
+        };
+        let rel = name.to_string_lossy().replace('\\', "/");
+        if !snapshot_path_allowed(&rel) {
+            continue;
+        }
+        let mut bytes = Vec::new();
+        entry
+            .read_to_end(&mut bytes)
+            .map_err(|e| format!("read snapshot zip entry failed: {e}"))?;
+        let digest = store.put_bytes(&bytes)?;
+        manifest.files.push(SnapshotFile {
+            path: rel,
+            sha512: digest.sha512,
+            size: digest.len,
+            modified_ms: 0,
+        });
+    }
+    manifest.files.sort_by(|a, b| a.path.cmp(&b.path));
+    write_manifest(snapshot_dir, &manifest)?;
+    fs::remove_file(&zip_path).map_err(|e| format!("remove migrated snapshot zip failed: {e}"))?;
+    Ok(true)
+}
+
+/// Migrates every legacy snapshot of one instance; returns how many changed.
+pub(crate) fn migrate_instance_snapshots(instance_dir: &Path) -> Result<usize, String> {
+    let store = object_store_for(instance_dir);
+    let root = snapshots_dir(instance_dir);
+    let mut migrated = 0usize;
+    for meta in list_snapshots(instance_dir)? {
+        if migrate_legacy_snapshot(&root.join(&meta.id), &store)? {
+            migrated += 1;
+        }
+    }
+    Ok(migrated)
+}
+
+/// Migrates legacy snapshots of every instance; failures are left for the
+/// next start or for the rollback that needs them.
+pub(crate) fn migrate_all_snapshots(instances_dir: &Path) -> usize {
+    let Ok(index) = read_index(instances_dir) else {
+        return 0;
+    };
+    index
+        .instances
+        .iter()
+        .filter_map(|instance| instance_dir_for_id(instances_dir, &instance.id).ok())
+        .filter_map(|instance_dir| migrate_instance_snapshots(&instance_dir).ok())
+        .sum()
+}
+
+/// Restores a snapshot's content, falling back to the legacy zip when the
+/// snapshot could not be migrated. Nothing is cleared unless one of the two
+/// is actually there to restore from.
+pub(crate) fn restore_snapshot_content(
+    snapshot_dir: &Path,
+    instance_dir: &Path,
+) -> Result<usize, String> {
+    let store = object_store_for(instance_dir);
+    let migrated = migrate_legacy_snapshot(snapshot_dir, &store);
+    // Another migration may have finished while this one failed.
+    if snapshot_manifest_path(snapshot_dir).exists() {
+        return restore_content_manifest(snapshot_dir, instance_dir, &store);
+    }
+    let zip_path = snapshot_content_zip_path(snapshot_dir);
+    if zip_path.exists() {
+        return restore_instance_content_zip(&zip_path, instance_dir);
+    }
+    Err(match migrated {
+        Err(err) => err,
+        Ok(_) => "Snapshot has no recorded content to restore".to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{temp_root, write_file};
+    use crate::{create_instance_content_zip, write_snapshot_meta, SnapshotMeta};
+
+    fn register(instance_dir: &Path, id: &str, created_at: &str) -> PathBuf {
+        let dir = snapshots_dir(instance_dir).join(id);
+        fs::create_dir_all(&dir).expect("mkdir snapshot");
+        write_snapshot_meta(
+            &dir,
+            &SnapshotMeta {
+                id: id.to_string(),
+                created_at: created_at.to_string(),
+                reason: "test".to_string(),
+                label: None,
+                pinned: false,
+                auto_pin: None,
+                verified_at: None,
+                integrity_error: None,
+            },
+        )
+        .expect("write meta");
+        dir
+    }
+
+    #[test]
+    fn snapshots_share_objects_and_restore_exact_content() {
+        let root = temp_root("roundtrip");
+        let instance = root.join("instance");
+        let store = ContentStore::new(root.join("store"));
+        write_file(&instance.join("mods").join("a.jar"), b"mod a");
+        write_file(
+            &instance.join("shaderpacks").join("big.zip"),
+            b"large shaderpack",
+        );
+        write_file(
+            &instance
+                .join("saves")
+                .join("World")
+                .join("datapacks")
+                .join("dp.zip"),
+            b"datapack",
+        );
+        write_file(
+            &instance.join("saves").join("World").join("level.dat"),
+            b"world data",
+        );
+
+        let first = snapshots_dir(&instance).join("one");
+        assert_eq!(
+            write_content_manifest(&instance, &first, &store, None).unwrap(),
+            3
+        );
+        let first_manifest = read_manifest(&first).expect("first manifest");
+        // Rewriting the live file in place must not reach the stored copy.
+        fs::write(instance.join("mods").join("a.jar"), b"edited").unwrap();
+
+        write_file(&instance.join("mods").join("b.jar"), b"mod b");
+        let second = snapshots_dir(&instance).join("two");
+        assert_eq!(
+            write_content_manifest(&instance, &second, &store, Some(&first_manifest)).unwrap(),
+            4
+        );
+        // The shaderpack is stored once no matter how many snapshots list it;
+        // the edited mod is a new blob.
+        let blobs = fs::read_dir(root.join("store").join("sha512"))
+            .unwrap()
+            .flatten()
+            .map(|shard| fs::read_dir(shard.path()).unwrap().count())
+            .sum::<usize>();
+        assert_eq!(blobs, 5);
+
+        fs::remove_file(instance.join("mods").join("a.jar")).unwrap();
+        write_file(&instance.join("mods").join("stray.jar"), b"stray");
+        assert_eq!(
+            restore_content_manifest(&first, &instance, &store).unwrap(),
+            3
+        );
+        assert_eq!(
+            fs::read(instance.join("mods").join("a.jar")).unwrap(),
+            b"mod a"
+        );
+        assert!(!instance.join("mods").join("b.jar").exists());
+        assert!(!instance.join("mods").join("stray.jar").exists());
+        assert_eq!(
+            fs::read(instance.join("saves").join("World").join("level.dat")).unwrap(),
+            b"world data"
+        );
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn restore_refuses_when_objects_are_missing() {
+        let root = temp_root("missing");
+        let instance = root.join("instance");
+        let store = ContentStore::new(root.join("store"));
+        write_file(&instance.join("mods").join("a.jar"), b"mod a");
+        let snapshot = snapshots_dir(&instance).join("one");
+        write_content_manifest(&instance, &snapshot, &store, None).unwrap();
+        fs::remove_dir_all(root.join("store")).unwrap();
+        write_file(&instance.join("mods").join("current.jar"), b"current");
+
+        let err = restore_content_manifest(&snapshot, &instance, &store).unwrap_err();
+        assert!(err.contains("mods/a.jar"));
+        assert!(instance.join("mods").join("current.jar").exists());
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn legacy_zip_snapshots_migrate_to_manifests() {
+        let root = temp_root("migrate");
+        let instance = root.join("instance");
+        write_file(&instance.join("mods").join("a.jar"), b"mod a");
+        write_file(&instance.join("resourcepacks").join("pack.zip"), b"pack");
+        let snapshot = register(&instance, "legacy", "2026-01-01T00:00:00Z");
+        create_instance_content_zip(&instance, &snapshot_content_zip_path(&snapshot)).unwrap();
+
+        assert_eq!(migrate_instance_snapshots(&instance).unwrap(), 1);
+        assert_eq!(migrate_instance_snapshots(&instance).unwrap(), 0);
+        assert!(!snapshot_content_zip_path(&snapshot).exists());
+        let manifest = read_manifest(&snapshot).expect("manifest");
+        let paths = manifest
+            .files
+            .iter()
+            .map(|file| file.path.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(paths, vec!["mods/a.jar", "resourcepacks/pack.zip"]);
+
+        fs::remove_dir_all(instance.join("mods")).unwrap();
+        assert_eq!(restore_snapshot_content(&snapshot, &instance).unwrap(), 2);
+        assert_eq!(
+            fs::read(instance.join("mods").join("a.jar")).unwrap(),
+            b"mod a"
+        );
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn restore_without_manifest_or_zip_leaves_content_alone() {
+        let root = temp_root("empty");
+        let instance = root.join("instance");
+        write_file(&instance.join("mods").join("current.jar"), b"current");
+        let snapshot = register(&instance, "gone", "2026-01-01T00:00:00Z");
+
+        assert!(restore_snapshot_content(&snapshot, &instance).is_err());
+        assert!(instance.join("mods").join("current.jar").exists());
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn manifest_paths_cannot_escape_content_folders() {
+        assert!(snapshot_path_allowed("mods/a.jar"));
+        assert!(snapshot_path_allowed("saves/World/datapacks/dp.zip"));
+        assert!(!snapshot_path_allowed("saves/World/level.dat"));
+        assert!(!snapshot_path_allowed("mods/../options.txt"));
+        assert!(!snapshot_path_allowed("config/a.toml"));
+        assert!(!snapshot_path_allowed("/mods/a.jar"));
+    }
+}
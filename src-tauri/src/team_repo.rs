@@ -0,0 +1,762 @@
+//! Team repository provider: a directory or static HTTP location holding an
+//! `index.json` that lists internal projects, their versions and files.
+//!
+//! Lock entries use source `team`, project ids `team:<id>` and version ids
+//! `team_ver:<id>`. Download paths in the index are resolved against the
+//! index location unless they are absolute http(s) URLs.
+
+use crate::{
+    canonical_lock_entry_name, content_type_display_name, download_bytes_with_retry,
+    enforce_lock_entry_uniqueness, hash_bytes_hex, minecraft_version_matches_advertised,
+    normalize_discover_content_type, normalize_lock_content_type,
+    normalize_target_worlds_for_datapack, sanitize_filename, validate_lock_entry_uniqueness,
+    write_download_to_content_targets, ContentUpdateInfo, DiscoverSearchHit, DiscoverSearchResult,
+    Instance, LocalImportedProviderMatch, LockEntry, Lockfile, ProviderCandidate,
+    SearchDiscoverContentArgs,
+};
+use reqwest::blocking::Client;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Mutex, OnceLock, RwLock};
+use std::time::{Duration, Instant};
+
+pub(crate) const TEAM_SOURCE: &str = "team";
+pub(crate) const TEAM_REPOSITORY_ENV: &str = "MPM_TEAM_REPOSITORY";
+const INDEX_FILE_NAME: &str = "index.json";
+const INDEX_CACHE_TTL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct TeamRepoIndex {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub projects: Vec<TeamRepoProject>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct TeamRepoProject {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub author: String,
+    #[serde(default = "default_content_type")]
+    pub content_type: String,
+    #[serde(default)]
+    pub icon_url: Option<String>,
+    #[serde(default)]
+    pub categories: Vec<String>,
+    #[serde(default)]
+    pub versions: Vec<TeamRepoVersion>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct TeamRepoVersion {
+    pub id: String,
+    pub version_number: String,
+    #[serde(default)]
+    pub published_at: String,
+    #[serde(default)]
+    pub loaders: Vec<String>,
+    #[serde(default)]
+    pub game_versions: Vec<String>,
+    pub filename: String,
+    /// Relative to the index location, or an absolute http(s) URL.
+    pub path: String,
+    #[serde(default)]
+    pub hashes: HashMap<String, String>,
+}
+
+fn default_content_type() -> String {
+    "mods".to_string()
+}
+
+fn configured_location() -> &'static RwLock<Option<String>> {
+    static LOCATION: OnceLock<RwLock<Option<String>>> = OnceLock::new();
+    LOCATION.get_or_init(|| RwLock::new(None))
+}
+
+/// Mirrors the launcher setting so provider code without an app handle can
+/// reach the repository.
+pub(crate) fn set_configured_location(location: &str) {
+    let trimmed = location.trim();
+    if let Ok(mut guard) = configured_location().write() {
+        *guard = if trimmed.is_empty() {
+            None
+        } else {
+            Some(trimmed.to_string())
+        };
+    }
+}
+
+pub(crate) fn team_repository_location() -> Option<String> {
+    if let Ok(value) = std::env::var(TEAM_REPOSITORY_ENV) {
+        let trimmed = value.trim();
+        if !trimmed.is_empty() {
+            return Some(trimmed.to_string());
+        }
+    }
+    configured_location().read().ok().and_then(|v| v.clone())
+}
+
+pub(crate) fn team_project_key(id: &str) -> String {
+    format!("team:{}", id.trim())
+}
+
+pub(crate) fn team_version_key(id: &str) -> String {
+    format!("team_ver:{}", id.trim())
+}
+
+pub(crate) fn parse_team_project_id(raw: &str) -> Result<String, String> {
+    let trimmed = raw.trim();
+    let id = trimmed.strip_prefix("team:").unwrap_or(trimmed).trim();
+    if id.is_empty() {
+        return Err(format!("Invalid team repository project id '{raw}'"));
+    }
+    Ok(id.to_string())
+}
+
+fn parse_team_version_id(raw: &str) -> Option<String> {
+    let id = raw.trim().strip_prefix("team_ver:")?.trim();
+    if id.is_empty() {
+        None
+    } else {
+        Some(id.to_string())
+    }
+}
+
+fn is_http_location(location: &str) -> bool {
+    let lower = location.trim().to_ascii_lowercase();
+    lower.starts_with("http://") || lower.starts_with("https://")
+}
+
+fn index_url(location: &str) -> String {
+    let trimmed = location.trim();
+    if trimmed.to_ascii_lowercase().ends_with(".json") {
+        trimmed.to_string()
+    } else {
+        format!("{}/{INDEX_FILE_NAME}", trimmed.trim_end_matches('/'))
+    }
+}
+
+fn index_path(location: &str) -> PathBuf {
+    let path = PathBuf::from(location.trim());
+    if path.is_dir() {
+        path.join(INDEX_FILE_NAME)
+    } else {
+        path
+    }
+}
+
+pub(crate) fn parse_team_index(raw: &str) -> Result<TeamRepoIndex, String> {
+    serde_json::from_str(raw).map_err(|e| format!("parse team repository index failed: {e}"))
+}
+
+fn index_cache() -> &'static Mutex<Option<(String, Instant, TeamRepoIndex)>> {
+    static CACHE: OnceLock<Mutex<Option<(String, Instant, TeamRepoIndex)>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(None))
+}
+
+pub(crate) fn load_team_index(client: &Client, location: &str) -> Result<TeamRepoIndex, String> {
+    if let Ok(guard) = index_cache().lock() {
+        if let Some((cached_location, fetched_at, index)) = guard.as_ref() {
+            if cached_location == location && fetched_at.elapsed() < INDEX_CACHE_TTL {
+                return Ok(index.clone());
+            }
+        }
+    }
+    let raw = if is_http_location(location) {
+        let url = index_url(location);
+        let resp = client
+            .get(&url)
+            .header("Accept", "application/json")
+            .send()
+            .map_err(|e| format!("team repository index request failed: {e}"))?;
+        if !resp.status().is_success() {
+            return Err(format!(
+                "team repository index request failed with status {}",
+                resp.status()
+            ));
+        }
+        resp.text()
+            .map_err(|e| format!("read team repository index failed: {e}"))?
+    } else {
+        let path = index_path(location);
+        fs::read_to_string(&path).map_err(|e| {
+            format!(
+                "read team repository index '{}' failed: {e}",
+                path.display()
+            )
+        })?
+    };
+    let index = parse_team_index(&raw)?;
+    if let Ok(mut guard) = index_cache().lock() {
+        *guard = Some((location.to_string(), Instant::now(), index.clone()));
+    }
+    Ok(index)
+}
+
+/// Resolves a version's download path. Relative paths may not climb out of
+/// the repository root.
+pub(crate) fn resolve_team_download(location: &str, path: &str) -> Result<String, String> {
+    let trimmed = path.trim();
+    if is_http_location(trimmed) {
+        return Ok(trimmed.to_string());
+    }
+    let relative = Path::new(trimmed);
+    if trimmed.is_empty()
+        || relative
+            .components()
+            .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(format!("Team repository file path '{path}' is not allowed"));
+    }
+    if is_http_location(location) {
+        let base = index_url(location);
+        let parent = base.rsplit_once('/').map(|(head, _)| head).unwrap_or(&base);
+        return Ok(format!("{parent}/{}", trimmed.replace('\\', "/")));
+    }
+    let root = index_path(location)
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+    Ok(root.join(relative).display().to_string())
+}
+
+pub(crate) fn fetch_team_version_bytes(
+    client: &Client,
+    location: &str,
+    version: &TeamRepoVersion,
+) -> Result<Vec<u8>, String> {
+    let target = resolve_team_download(location, &version.path)?;
+    let bytes = if is_http_location(&target) {
+        download_bytes_with_retry(client, &target, &team_version_key(&version.id))?
+    } else {
+        fs::read(&target)
+            .map_err(|e| format!("read team repository file '{target}' failed: {e}"))?
+    };
+    if let Some((algorithm, expected)) = crate::preferred_verify_hash(&version.hashes) {
+        let actual = hash_bytes_hex(algorithm, &bytes);
+        if actual != expected {
+            return Err(format!(
+                "Team repository file '{}' failed {algorithm} verification",
+                version.filename
+            ));
+        }
+    }
+    Ok(bytes)
+}
+
+fn loader_matches(version: &TeamRepoVersion, loader: &str) -> bool {
+    version.loaders.is_empty()
+        || version
+            .loaders
+            .iter()
+            .any(|value| value.trim().eq_ignore_ascii_case(loader))
+}
+
+fn game_version_matches(version: &TeamRepoVersion, mc_version: &str, loose: bool) -> bool {
+    version.game_versions.is_empty()
+        || version
+            .game_versions
+            .iter()
+            .any(|gv| minecraft_version_matches_advertised(gv, &mc_version.to_lowercase(), loose))
+}
+
+pub(crate) fn pick_compatible_team_version<'a>(
+    project: &'a TeamRepoProject,
+    instance: &Instance,
+    content_type: &str,
+) -> Option<&'a TeamRepoVersion> {
+    let normalized = normalize_lock_content_type(content_type);
+    project
+        .versions
+        .iter()
+        .filter(|v| game_version_matches(v, &instance.mc_version, normalized != "mods"))
+        .filter(|v| normalized != "mods" || loader_matches(v, &instance.loader))
+        .max_by(|a, b| a.published_at.cmp(&b.published_at))
+}
+
+fn find_project<'a>(index: &'a TeamRepoIndex, project_id: &str) -> Option<&'a TeamRepoProject> {
+    let id = parse_team_project_id(project_id).ok()?;
+    index
+        .projects
+        .iter()
+        .find(|project| project.id.trim().eq_ignore_ascii_case(&id))
+}
+
+fn project_hit(project: &TeamRepoProject, repo_name: Option<&str>) -> DiscoverSearchHit {
+    let latest = project
+        .versions
+        .iter()
+        .max_by(|a, b| a.published_at.cmp(&b.published_at));
+    let mut versions = project
+        .versions
+        .iter()
+        .flat_map(|v| v.game_versions.iter().cloned())
+        .collect::<Vec<_>>();
+    versions.sort();
+    versions.dedup();
+    let mut categories = project.categories.clone();
+    for loader in project.versions.iter().flat_map(|v| v.loaders.iter()) {
+        if !categories.iter().any(|c| c.eq_ignore_ascii_case(loader)) {
+            categories.push(loader.to_ascii_lowercase());
+        }
+    }
+    DiscoverSearchHit {
+        source: TEAM_SOURCE.to_string(),
+        project_id: team_project_key(&project.id),
+        title: project.title.clone(),
+        description: project.description.clone(),
+        author: if project.author.trim().is_empty() {
+            repo_name.unwrap_or("Team repository").to_string()
+        } else {
+            project.author.clone()
+        },
+        downloads: 0,
+        follows: 0,
+        icon_url: project.icon_url.clone(),
+        categories,
+        versions,
+        date_modified: latest.map(|v| v.published_at.clone()).unwrap_or_default(),
+        content_type: normalize_discover_content_type(&project.content_type),
+        slug: Some(project.id.clone()),
+        external_url: None,
+        confidence: None,
+        reason: None,
+        install_state: None,
+        install_summary: None,
+    }
+}
+
+/// Filters the index by content type, query text, loaders and game version.
+pub(crate) fn search_team_index(
+    index: &TeamRepoIndex,
+    args: &SearchDiscoverContentArgs,
+) -> DiscoverSearchResult {
+    let content_type = normalize_discover_content_type(&args.content_type);
+    let query = args.query.trim().to_lowercase();
+    let hits = index
+        .projects
+        .iter()
+        .filter(|project| normalize_discover_content_type(&project.content_type) == content_type)
+        .filter(|project| {
+            query.is_empty()
+                || project.title.to_lowercase().contains(&query)
+                || project.id.to_lowercase().contains(&query)
+                || project.description.to_lowercase().contains(&query)
+        })
+        .filter(|project| {
+            project.versions.iter().any(|version| {
+                let loader_ok = args.loaders.is_empty()
+                    || args
+                        .loaders
+                        .iter()
+                        .any(|loader| loader_matches(version, loader));
+                let game_ok = args
+                    .game_version
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|value| !value.is_empty())
+                    .map(|value| game_version_matches(version, value, content_type != "mods"))
+                    .unwrap_or(true);
+                loader_ok && game_ok
+            })
+        })
+        .map(|project| project_hit(project, index.name.as_deref()))
+        .collect::<Vec<_>>();
+    let total_hits = hits.len();
+    DiscoverSearchResult {
+        hits: hits
+            .into_iter()
+            .skip(args.offset)
+            .take(args.limit)
+            .collect(),
+        offset: args.offset,
+        limit: args.limit,
+        total_hits,
+    }
+}
+
+pub(crate) fn search_team_discover(
+    client: &Client,
+    args: &SearchDiscoverContentArgs,
+) -> Result<DiscoverSearchResult, String> {
+    let Some(location) = team_repository_location() else {
+        return Ok(DiscoverSearchResult {
+            hits: vec![],
+            offset: args.offset,
+            limit: args.limit,
+            total_hits: 0,
+        });
+    };
+    let index = load_team_index(client, &location)?;
+    Ok(search_team_index(&index, args))
+}
+
+fn team_provider_candidate(
+    project: &TeamRepoProject,
+    version: &TeamRepoVersion,
+) -> ProviderCandidate {
+    ProviderCandidate {
+        source: TEAM_SOURCE.to_string(),
+        project_id: team_project_key(&project.id),
+        version_id: team_version_key(&version.id),
+        name: project.title.clone(),
+        version_number: version.version_number.clone(),
+        confidence: None,
+        reason: None,
+        verification_status: None,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn install_team_content_inner(
+    instance: &Instance,
+    instance_dir: &Path,
+    lock: &mut Lockfile,
+    client: &Client,
+    project_id: &str,
+    project_title: Option<&str>,
+    content_type: &str,
+    target_worlds: &[String],
+) -> Result<LockEntry, String> {
+    let normalized = normalize_lock_content_type(content_type);
+    if normalized == "modpacks" {
+        return Err(
+            "Modpack entries are template-only. Import as template in Modpacks & Presets."
+                .to_string(),
+        );
+    }
+    let location = team_repository_location()
+        .ok_or_else(|| "No team repository is configured in settings.".to_string())?;
+    let index = load_team_index(client, &location)?;
+    let project = find_project(&index, project_id)
+        .ok_or_else(|| format!("Team repository has no project '{project_id}'"))?;
+    let version =
+        pick_compatible_team_version(project, instance, &normalized).ok_or_else(|| {
+            format!(
+                "No compatible team repository {} version found for {} ({} + {})",
+                content_type_display_name(&normalized),
+                project.title,
+                instance.loader,
+                instance.mc_version
+            )
+        })?;
+    let safe_filename = sanitize_filename(&version.filename);
+    if safe_filename.is_empty() {
+        return Err("Resolved filename is invalid".to_string());
+    }
+    let bytes = fetch_team_version_bytes(client, &location, version)?;
+    let worlds = if normalized == "datapacks" {
+        normalize_target_worlds_for_datapack(instance_dir, target_worlds)?
+    } else {
+        vec![]
+    };
+    let resolved_title = project_title
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| project.title.clone());
+    let mut hashes = version.hashes.clone();
+    hashes
+        .entry("sha512".to_string())
+        .or_insert_with(|| crate::sha512_hex(&bytes));
+    let mut new_entry = LockEntry {
+        source: TEAM_SOURCE.to_string(),
+        project_id: team_project_key(&project.id),
+        version_id: team_version_key(&version.id),
+        name: canonical_lock_entry_name(&normalized, &safe_filename, &resolved_title),
+        version_number: version.version_number.clone(),
+        filename: safe_filename.clone(),
+        content_type: normalized.clone(),
+        target_scope: if normalized == "datapacks" {
+            "world".to_string()
+        } else {
+            "instance".to_string()
+        },
+        target_worlds: worlds.clone(),
+        pinned_version: None,
+        enabled: true,
+        hashes,
+        provider_candidates: vec![team_provider_candidate(project, version)],
+        local_analysis: None,
+        security_scan: None,
+        url_update: None,
+        github_channel: None,
+    };
+    validate_lock_entry_uniqueness(lock, &new_entry)?;
+    new_entry.security_scan = write_download_to_content_targets(
+        instance_dir,
+        &normalized,
+        &safe_filename,
+        &worlds,
+        &bytes,
+    )?;
+    enforce_lock_entry_uniqueness(lock, instance_dir, &mut new_entry)?;
+    lock.entries.push(new_entry.clone());
+    Ok(new_entry)
+}
+
+pub(crate) fn check_team_content_update(
+    client: &Client,
+    instance: &Instance,
+    entry: &LockEntry,
+    provider: &ProviderCandidate,
+    warnings: &mut Vec<String>,
+) -> Result<Option<ContentUpdateInfo>, String> {
+    let Some(location) = team_repository_location() else {
+        warnings.push(format!(
+            "Skipped team repository entry '{}': no team repository is configured.",
+            entry.name
+        ));
+        return Ok(None);
+    };
+    let content_type = normalize_lock_content_type(&entry.content_type);
+    let index = load_team_index(client, &location)?;
+    let Some(project) = find_project(&index, &provider.project_id) else {
+        warnings.push(format!(
+            "Team repository no longer lists '{}' ({})",
+            entry.name, provider.project_id
+        ));
+        return Ok(None);
+    };
+    let Some(latest) = pick_compatible_team_version(project, instance, &content_type) else {
+        warnings.push(format!(
+            "No compatible team repository update found for '{}' ({})",
+            entry.name, provider.project_id
+        ));
+        return Ok(None);
+    };
+    let current_version_id = if provider.version_id.trim().is_empty() {
+        entry.version_id.clone()
+    } else {
+        provider.version_id.clone()
+    };
+    let latest_version_id = team_version_key(&latest.id);
+    if latest_version_id == current_version_id {
+        return Ok(None);
+    }
+    Ok(Some(ContentUpdateInfo {
+        source: TEAM_SOURCE.to_string(),
+        content_type,
+        project_id: team_project_key(&project.id),
+        name: if provider.name.trim().is_empty() {
+            project.title.clone()
+        } else {
+            provider.name.clone()
+        },
+        current_version_id,
+        current_version_number: if provider.version_number.trim().is_empty() {
+            entry.version_number.clone()
+        } else {
+            provider.version_number.clone()
+        },
+        latest_version_id,
+        latest_version_number: latest.version_number.clone(),
+        enabled: entry.enabled,
+        target_worlds: entry.target_worlds.clone(),
+        latest_file_name: Some(latest.filename.clone()),
+        latest_download_url: resolve_team_download(&location, &latest.path).ok(),
+        latest_hashes: latest.hashes.clone(),
+        required_dependencies: vec![],
+        compatibility_status: Some("compatible".to_string()),
+        compatibility_notes: vec![],
+    }))
+}
+
+/// Exact hash lookup used when identifying local files.
+pub(crate) fn find_team_match_by_hashes(
+    index: &TeamRepoIndex,
+    sha512: &str,
+    sha256: &str,
+) -> Option<LocalImportedProviderMatch> {
+    for project in &index.projects {
+        for version in &project.versions {
+            let matched = version.hashes.iter().any(|(key, value)| {
+                let key = key.trim().replace('-', "").to_ascii_lowercase();
+                (key == "sha512" && value.trim().eq_ignore_ascii_case(sha512))
+                    || (key == "sha256" && value.trim().eq_ignore_ascii_case(sha256))
+            });
+            if !matched {
+                continue;
+            }
+            let mut hashes = version.hashes.clone();
+            hashes
+                .entry("sha512".to_string())
+                .or_insert_with(|| sha512.to_string());
+            return Some(LocalImportedProviderMatch {
+                source: TEAM_SOURCE.to_string(),
+                project_id: team_project_key(&project.id),
+                version_id: team_version_key(&version.id),
+                name: project.title.clone(),
+                version_number: version.version_number.clone(),
+                hashes,
+                confidence: "deterministic".to_string(),
+                reason: "Exact team repository hash match.".to_string(),
+                verification_status: "verified".to_string(),
+            });
+        }
+    }
+    None
+}
+
+pub(crate) fn detect_team_match(
+    client: &Client,
+    sha512: &str,
+    sha256: &str,
+) -> Option<LocalImportedProviderMatch> {
+    let location = team_repository_location()?;
+    let index = load_team_index(client, &location).ok()?;
+    find_team_match_by_hashes(&index, sha512, sha256)
+}
+
+/// Fetches the exact pinned version of a team entry, for rehydrate and Friend Link.
+pub(crate) fn download_team_version_bytes(
+    client: &Client,
+    project_id: &str,
+    version_id: &str,
+) -> Result<Option<Vec<u8>>, String> {
+    let Some(location) = team_repository_location() else {
+        return Ok(None);
+    };
+    let Some(version_key) = parse_team_version_id(version_id) else {
+        return Ok(None);
+    };
+    let index = load_team_index(client, &location)?;
+    let Some(version) = find_project(&index, project_id).and_then(|project| {
+        project
+            .versions
+            .iter()
+            .find(|v| v.id.trim().eq_ignore_ascii_case(&version_key))
+    }) else {
+        return Ok(None);
+    };
+    fetch_team_version_bytes(client, &location, version).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INDEX: &str = r#"{
+        "name": "Team",
+        "projects": [
+            {
+                "id": "better-fork",
+                "title": "Better Fork",
+                "description": "Internal fork",
+                "versions": [
+                    {
+                        "id": "1.0.0-fabric",
+                        "version_number": "1.0.0",
+                        "published_at": "2026-01-01T00:00:00Z",
+                        "loaders": ["fabric"],
+                        "game_versions": ["1.20.1"],
+                        "filename": "better-fork-1.0.0.jar",
+                        "path": "files/better-fork-1.0.0.jar",
+                        "hashes": { "sha256": "ABCDEF" }
+                    },
+                    {
+                        "id": "1.1.0-fabric",
+                        "version_number": "1.1.0",
+                        "published_at": "2026-02-01T00:00:00Z",
+                        "loaders": ["fabric"],
+                        "game_versions": ["1.20.1"],
+                        "filename": "better-fork-1.1.0.jar",
+                        "path": "files/better-fork-1.1.0.jar"
+                    },
+                    {
+                        "id": "1.2.0-forge",
+                        "version_number": "1.2.0",
+                        "published_at": "2026-03-01T00:00:00Z",
+                        "loaders": ["forge"],
+                        "game_versions": ["1.20.1"],
+                        "filename": "better-fork-1.2.0.jar",
+                        "path": "files/better-fork-1.2.0.jar"
+                    }
+                ]
+            },
+            {
+                "id": "team-pack",
+                "title": "Team Textures",
+                "content_type": "resourcepacks",
+                "versions": []
+            }
+        ]
+    }"#;
+
+    fn instance(loader: &str) -> Instance {
+        Instance {
+            id: "inst".to_string(),
+            name: "Inst".to_string(),
+            origin: "custom".to_string(),
+            folder_name: None,
+            mc_version: "1.20.1".to_string(),
+            loader: loader.to_string(),
+            created_at: "now".to_string(),
+            icon_path: None,
+            settings: Default::default(),
+        }
+    }
+
+    #[test]
+    fn picks_latest_version_for_instance_loader() {
+        let index = parse_team_index(INDEX).expect("parse index");
+        let project = &index.projects[0];
+        let fabric = pick_compatible_team_version(project, &instance("fabric"), "mods");
+        assert_eq!(fabric.map(|v| v.id.as_str()), Some("1.1.0-fabric"));
+        let forge = pick_compatible_team_version(project, &instance("forge"), "mods");
+        assert_eq!(forge.map(|v| v.id.as_str()), Some("1.2.0-forge"));
+        assert!(pick_compatible_team_version(project, &instance("quilt"), "mods").is_none());
+    }
+
+    #[test]
+    fn search_filters_by_content_type_query_and_loader() {
+        let index = parse_team_index(INDEX).expect("parse index");
+        let args: SearchDiscoverContentArgs = serde_json::from_value(serde_json::json!({
+            "query": "fork",
+            "loaders": ["forge"],
+            "index": "relevance",
+            "limit": 20,
+            "offset": 0,
+            "contentType": "mods"
+        }))
+        .expect("args");
+        let result = search_team_index(&index, &args);
+        assert_eq!(result.total_hits, 1);
+        assert_eq!(result.hits[0].project_id, "team:better-fork");
+        assert_eq!(result.hits[0].source, "team");
+
+        let mut packs = args.clone();
+        packs.query = String::new();
+        packs.loaders.clear();
+        packs.content_type = "resourcepacks".to_string();
+        assert_eq!(search_team_index(&index, &packs).total_hits, 0);
+    }
+
+    #[test]
+    fn resolves_downloads_relative_to_index_and_rejects_traversal() {
+        assert_eq!(
+            resolve_team_download("https://repo.example/team/index.json", "files/a.jar"),
+            Ok("https://repo.example/team/files/a.jar".to_string())
+        );
+        assert_eq!(
+            resolve_team_download("https://repo.example/team", "files/a.jar"),
+            Ok("https://repo.example/team/files/a.jar".to_string())
+        );
+        assert!(resolve_team_download("https://repo.example/team", "../secret.jar").is_err());
+        assert!(resolve_team_download("/srv/team", "/etc/passwd").is_err());
+    }
+
+    #[test]
+    fn identifies_files_by_recorded_hash() {
+        let index = parse_team_index(INDEX).expect("parse index");
+        let found = find_team_match_by_hashes(&index, "00", "abcdef").expect("hash match");
+        assert_eq!(found.project_id, "team:better-fork");
+        assert_eq!(found.version_id, "team_ver:1.0.0-fabric");
+        assert!(find_team_match_by_hashes(&index, "00", "11").is_none());
+    }
+}
@@ -139,3 +139,18 @@ pub(crate) fn token_keyring_delete_secret(service: &str, username: &str) -> Resu
     guard.remove(&(service.to_string(), username.to_string()));
     Ok(())
 }
+
+/// Fresh, empty folder under the system temp dir for one test.
+#[cfg(test)]
+pub(crate) fn temp_root(label: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("openjar-{label}-{}", Uuid::new_v4()));
+    fs::create_dir_all(&root).expect("create temp root");
+    root
+}
+
+/// Writes `bytes` to `path`, creating missing parent folders.
+#[cfg(test)]
+pub(crate) fn write_file(path: &Path, bytes: &[u8]) {
+    fs::create_dir_all(path.parent().expect("file has a parent")).expect("mkdir");
+    fs::write(path, bytes).expect("write file");
+}
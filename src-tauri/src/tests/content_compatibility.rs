@@ -24,6 +24,8 @@ fn make_cf_file(game_versions: Vec<&str>) -> CurseforgeFile {
         game_versions: game_versions.into_iter().map(str::to_string).collect(),
         hashes: vec![],
         dependencies: vec![],
+        file_fingerprint: None,
+        file_length: None,
     }
 }
 
@@ -62,6 +64,7 @@ fn make_cf_project(
         authors: vec![],
         categories,
         logo: None,
+        links: None,
     }
 }
 
@@ -115,6 +115,9 @@ fn effective_updatable_provider_allows_safe_local_github_candidate() {
             verification_status: Some("verified".to_string()),
         }],
         local_analysis: None,
+        security_scan: None,
+        url_update: None,
+        github_channel: None,
     };
     let effective = effective_updatable_provider_for_entry(&entry, UpdateScope::AllContent)
         .expect("effective provider");
@@ -151,6 +154,9 @@ fn effective_updatable_provider_blocks_weak_local_github_candidate() {
                 verification_status: Some("manual_unverified".to_string()),
             }],
             local_analysis: None,
+            security_scan: None,
+            url_update: None,
+            github_channel: None,
         };
     assert!(effective_updatable_provider_for_entry(&entry, UpdateScope::AllContent).is_none());
 }
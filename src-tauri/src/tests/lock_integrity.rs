@@ -0,0 +1,119 @@
+use crate::*;
+
+fn temp_path(label: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("openjar-lock-integrity-{label}-{}", Uuid::new_v4()))
+}
+
+fn make_mod_entry(filename: &str, hashes: &[(&str, String)]) -> LockEntry {
+    LockEntry {
+        source: "modrinth".to_string(),
+        project_id: format!("proj-{filename}"),
+        version_id: format!("ver-{filename}"),
+        name: filename.trim_end_matches(".jar").to_string(),
+        version_number: "1.0.0".to_string(),
+        filename: filename.to_string(),
+        content_type: "mods".to_string(),
+        target_scope: "instance".to_string(),
+        target_worlds: vec![],
+        pinned_version: None,
+        enabled: true,
+        hashes: hashes
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.clone()))
+            .collect(),
+        provider_candidates: vec![],
+        local_analysis: None,
+        security_scan: None,
+        url_update: None,
+        github_channel: None,
+    }
+}
+
+#[test]
+fn lockfile_verification_classifies_each_enabled_entry() {
+    let root = temp_path("statuses");
+    fs::create_dir_all(root.join("mods")).expect("create mods dir");
+    fs::write(root.join("mods").join("intact.jar"), b"intact").expect("write intact");
+    fs::write(root.join("mods").join("tampered.jar"), b"tampered").expect("write tampered");
+    fs::write(root.join("mods").join("unhashed.jar"), b"unhashed").expect("write unhashed");
+
+    let mut disabled = make_mod_entry("disabled.jar", &[("sha1", "00".to_string())]);
+    disabled.enabled = false;
+    let lock = Lockfile {
+        version: 2,
+        entries: vec![
+            make_mod_entry(
+                "intact.jar",
+                &[
+                    ("sha1", "ffff".to_string()),
+                    ("sha512", sha512_hex(b"intact").to_ascii_uppercase()),
+                ],
+            ),
+            make_mod_entry(
+                "tampered.jar",
+                &[("sha1", hash_bytes_hex("sha1", b"original"))],
+            ),
+            make_mod_entry("gone.jar", &[("sha512", sha512_hex(b"gone"))]),
+            make_mod_entry("unhashed.jar", &[("md5", "abc".to_string())]),
+            disabled,
+        ],
+    };
+
+    let report = verify_lockfile_integrity("inst", &root, &lock);
+    let status_of = |filename: &str| {
+        report
+            .items
+            .iter()
+            .find(|item| item.filename == filename)
+            .map(|item| item.status.as_str())
+    };
+
+    assert_eq!(report.checked_entries, 4);
+    assert_eq!(status_of("intact.jar"), Some("unchanged"));
+    assert_eq!(status_of("tampered.jar"), Some("modified"));
+    assert_eq!(status_of("gone.jar"), Some("missing"));
+    assert_eq!(status_of("unhashed.jar"), Some("no_hash"));
+    assert_eq!(status_of("disabled.jar"), None);
+    assert_eq!(
+        (
+            report.unchanged,
+            report.modified,
+            report.missing,
+            report.no_hash
+        ),
+        (1, 1, 1, 1)
+    );
+    let tampered = report
+        .items
+        .iter()
+        .find(|item| item.filename == "tampered.jar")
+        .expect("tampered item");
+    assert_eq!(tampered.algorithm.as_deref(), Some("sha1"));
+    assert_eq!(
+        tampered.actual_hash.as_deref(),
+        Some(hash_bytes_hex("sha1", b"tampered").as_str())
+    );
+    assert!(tampered.redownloadable);
+
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn expected_hash_prefers_strongest_recorded_algorithm() {
+    let entry = make_mod_entry(
+        "a.jar",
+        &[
+            ("sha1", "AA".to_string()),
+            ("sha-256", "BB".to_string()),
+            ("md5", "CC".to_string()),
+        ],
+    );
+    assert_eq!(
+        lock_entry_expected_hash(&entry),
+        Some(("sha256", "bb".to_string()))
+    );
+    assert_eq!(
+        lock_entry_expected_hash(&make_mod_entry("b.jar", &[("md5", "CC".to_string())])),
+        None
+    );
+}
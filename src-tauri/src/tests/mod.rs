@@ -4,8 +4,10 @@ mod github_provider;
 mod instance_health;
 mod local_provider_preference;
 mod lock_entry_names;
+mod lock_integrity;
 mod path_grants;
 mod runtime_and_playtime;
 mod storage_usage;
 mod token_storage;
+mod update_batching;
 mod update_check_resilience;
@@ -150,6 +150,7 @@ fn playtime_store_tracks_native_session_duration_and_summary() {
         "native_test",
         std::process::id(),
         false,
+        None,
     )
     .expect("register active play session");
     let mut active = read_active_play_sessions_store(&instance_dir);
@@ -176,6 +177,103 @@ fn playtime_store_tracks_native_session_duration_and_summary() {
     let _ = fs::remove_dir_all(&instances_dir);
 }
 
+#[test]
+fn successful_sessions_pin_their_launch_snapshot_as_last_known_good() {
+    let instances_dir = temp_path("last-known-good");
+    fs::create_dir_all(&instances_dir).expect("create instances root");
+    let instance = Instance {
+        id: "inst_known_good".to_string(),
+        name: "Known Good".to_string(),
+        origin: "custom".to_string(),
+        folder_name: Some("KnownGood".to_string()),
+        mc_version: "1.20.1".to_string(),
+        loader: "fabric".to_string(),
+        created_at: now_iso(),
+        icon_path: None,
+        settings: InstanceSettings::default(),
+    };
+    write_index(
+        &instances_dir,
+        &InstanceIndex {
+            instances: vec![instance.clone()],
+        },
+    )
+    .expect("write index");
+    let instance_dir = instance_dir_for_instance(&instances_dir, &instance);
+    fs::create_dir_all(instance_dir.join("mods")).expect("create mods dir");
+    fs::write(instance_dir.join("mods").join("a.jar"), b"mod a").expect("write mod");
+
+    let play = |launch_id: &str, exit_kind: &str| -> String {
+        let snapshot_id =
+            launch_content_snapshot(&instances_dir, &instance.id).expect("launch snapshot");
+        register_native_play_session_start(
+            &instances_dir,
+            &instance.id,
+            launch_id,
+            std::process::id(),
+            false,
+            Some(snapshot_id.clone()),
+        )
+        .expect("register session");
+        finalize_native_play_session(&instances_dir, &instance.id, launch_id, exit_kind, false)
+            .expect("finalize session");
+        snapshot_id
+    };
+    let meta_of = |id: &str| {
+        list_snapshots(&instance_dir)
+            .expect("list snapshots")
+            .into_iter()
+            .find(|meta| meta.id == id)
+            .expect("snapshot meta")
+    };
+
+    let good = play("native_1", "success");
+    assert!(meta_of(&good).pinned);
+    assert_eq!(
+        meta_of(&good).auto_pin.as_deref(),
+        Some(SNAPSHOT_PIN_LAST_KNOWN_GOOD)
+    );
+    // Unchanged content reuses the launch snapshot instead of taking another.
+    assert_eq!(play("native_2", "success"), good);
+    assert_eq!(list_snapshots(&instance_dir).unwrap().len(), 1);
+
+    fs::write(instance_dir.join("mods").join("b.jar"), b"mod b").expect("write mod b");
+    let crashed = play("native_3", "crashed");
+    assert_ne!(crashed, good);
+    assert!(!meta_of(&crashed).pinned);
+    assert!(meta_of(&good).pinned);
+
+    // Pinned snapshots survive retention and storage cleanup however old.
+    let mut old = meta_of(&good);
+    old.created_at = "2020-01-01T00:00:00Z".to_string();
+    write_snapshot_meta(&snapshots_dir(&instance_dir).join(&good), &old).expect("age snapshot");
+    prune_old_snapshots(&instance_dir, 1, 14).expect("prune");
+    assert!(snapshot_prune_targets(&instance_dir, 1, 14)
+        .expect("cleanup targets")
+        .is_empty());
+    assert_eq!(list_snapshots(&instance_dir).unwrap().len(), 2);
+
+    // A manual pin stays when the automatic pin moves on.
+    let labelled = update_snapshot_meta(&instance_dir, &good, Some(" Before b "), Some(true))
+        .expect("label and pin");
+    assert_eq!(labelled.label.as_deref(), Some("Before b"));
+    assert_eq!(labelled.auto_pin, None);
+    assert_eq!(play("native_4", "success"), crashed);
+    assert_eq!(
+        meta_of(&crashed).auto_pin.as_deref(),
+        Some(SNAPSHOT_PIN_LAST_KNOWN_GOOD)
+    );
+    assert!(meta_of(&good).pinned);
+
+    update_snapshot_meta(&instance_dir, &good, None, Some(false)).expect("unpin");
+    assert_eq!(
+        snapshot_prune_targets(&instance_dir, 5, 14).expect("cleanup targets"),
+        vec![snapshots_dir(&instance_dir).join(&good)]
+    );
+
+    let _ = fs::remove_dir_all(&instances_dir);
+}
+
 #[test]
 fn normalize_app_language_accepts_supported_aliases() {
     assert_eq!(normalize_app_language(""), "en-US");
@@ -186,3 +284,14 @@ fn normalize_app_language_accepts_supported_aliases() {
     assert_eq!(normalize_app_language("pt"), "pt-BR");
     assert_eq!(normalize_app_language("unknown"), "en-US");
 }
+
+#[test]
+fn world_quick_play_needs_minecraft_1_20() {
+    assert!(supports_world_quick_play("1.20"));
+    assert!(supports_world_quick_play("1.21.4"));
+    assert!(supports_world_quick_play("1.20-pre1"));
+    assert!(supports_world_quick_play("26.1"));
+    assert!(!supports_world_quick_play("1.19.4"));
+    assert!(!supports_world_quick_play("1.12.2"));
+    assert!(supports_world_quick_play("23w14a"));
+}
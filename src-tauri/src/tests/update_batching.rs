@@ -0,0 +1,332 @@
+use crate::update_batch::{batch_check_content_updates, UpdateBatchEndpoints};
+use crate::*;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Minimal stand-in for the Modrinth and CurseForge batch endpoints. Every
+/// request is counted; `fail_modrinth` answers the Modrinth batch with a 500.
+struct StandInApi {
+    base: String,
+    requests: Arc<AtomicUsize>,
+}
+
+impl StandInApi {
+    fn start(fail_modrinth: bool) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind stand-in api");
+        let base = format!("http://{}", listener.local_addr().expect("local addr"));
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else {
+                    continue;
+                };
+                counter.fetch_add(1, Ordering::SeqCst);
+                let (path, body) = read_request(&mut stream);
+                let (status, payload) = route(&path, &body, fail_modrinth);
+                let text = payload.to_string();
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{text}",
+                    text.len()
+                );
+            }
+        });
+        Self { base, requests }
+    }
+
+    fn endpoints(&self) -> UpdateBatchEndpoints {
+        UpdateBatchEndpoints {
+            modrinth_base: format!("{}/v2", self.base),
+            curseforge_base: format!("{}/v1", self.base),
+        }
+    }
+
+    fn request_count(&self) -> usize {
+        self.requests.load(Ordering::SeqCst)
+    }
+}
+
+fn read_request(stream: &mut std::net::TcpStream) -> (String, serde_json::Value) {
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).expect("request line");
+    let path = request_line
+        .split_whitespace()
+        .nth(1)
+        .unwrap_or_default()
+        .to_string();
+    let mut content_length = 0usize;
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).expect("header line");
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+    let mut body = vec![0u8; content_length];
+    reader.read_exact(&mut body).expect("request body");
+    (
+        path,
+        serde_json::from_slice(&body).unwrap_or(serde_json::Value::Null),
+    )
+}
+
+/// Hash `hash-N` belongs to Modrinth project `proj-N`; even projects have a
+/// newer version, odd ones are current. `hash-unknown` is not indexed.
+/// CurseForge mod `N` has a newer file `N * 10 + 1` for fabric 1.20.1.
+fn route(
+    path: &str,
+    body: &serde_json::Value,
+    fail_modrinth: bool,
+) -> (&'static str, serde_json::Value) {
+    match path {
+        "/v2/version_files/update" => {
+            if fail_modrinth {
+                return ("500 Internal Server Error", serde_json::json!({}));
+            }
+            let mut out = serde_json::Map::new();
+            for hash in body["hashes"].as_array().into_iter().flatten() {
+                let hash = hash.as_str().unwrap_or_default();
+                let Some(n) = hash
+                    .strip_prefix("hash-")
+                    .and_then(|v| v.parse::<u32>().ok())
+                else {
+                    continue;
+                };
+                let version_id = if n % 2 == 0 {
+                    format!("new-{n}")
+                } else {
+                    format!("cur-{n}")
+                };
+                out.insert(
+                    hash.to_string(),
+                    serde_json::json!({
+                        "project_id": format!("proj-{n}"),
+                        "id": version_id,
+                        "version_number": format!("{version_id}-fabric"),
+                        "game_versions": ["1.20.1"],
+                        "loaders": ["fabric"],
+                        "date_published": "2026-05-01T00:00:00Z",
+                        "files": [{
+                            "url": format!("https://cdn.example.invalid/{version_id}.jar"),
+                            "filename": format!("{version_id}.jar"),
+                            "primary": true,
+                            "hashes": { "sha512": format!("next-{n}") }
+                        }]
+                    }),
+                );
+            }
+            ("200 OK", serde_json::Value::Object(out))
+        }
+        "/v1/mods" => {
+            let data = body["modIds"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|id| id.as_i64())
+                .map(|id| {
+                    serde_json::json!({
+                        "id": id,
+                        "latestFilesIndexes": [
+                            { "gameVersion": "1.20.1", "fileId": id * 10 + 1, "modLoader": 4 },
+                            { "gameVersion": "1.20.1", "fileId": id * 10 + 2, "modLoader": 1 },
+                            { "gameVersion": "1.19.2", "fileId": id * 10 + 3, "modLoader": 4 }
+                        ]
+                    })
+                })
+                .collect::<Vec<_>>();
+            ("200 OK", serde_json::json!({ "data": data }))
+        }
+        "/v1/mods/files" => {
+            let data = body["fileIds"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|id| id.as_i64())
+                .map(|id| {
+                    serde_json::json!({
+                        "id": id,
+                        "modId": id / 10,
+                        "displayName": format!("file-{id}"),
+                        "fileName": format!("file-{id}.jar"),
+                        "fileDate": "2026-05-01T00:00:00Z",
+                        "downloadUrl": format!("https://edge.example.invalid/{id}.jar"),
+                        "gameVersions": ["1.20.1", if id % 10 == 2 { "Forge" } else { "Fabric" }],
+                    })
+                })
+                .collect::<Vec<_>>();
+            ("200 OK", serde_json::json!({ "data": data }))
+        }
+        _ => ("404 Not Found", serde_json::json!({})),
+    }
+}
+
+fn make_instance() -> Instance {
+    Instance {
+        id: "inst_batch".to_string(),
+        name: "Batch".to_string(),
+        origin: "custom".to_string(),
+        folder_name: None,
+        mc_version: "1.20.1".to_string(),
+        loader: "fabric".to_string(),
+        created_at: "now".to_string(),
+        icon_path: None,
+        settings: InstanceSettings::default(),
+    }
+}
+
+fn make_entry(source: &str, project_id: &str, version_id: &str, sha512: Option<&str>) -> LockEntry {
+    LockEntry {
+        source: source.to_string(),
+        project_id: project_id.to_string(),
+        version_id: version_id.to_string(),
+        name: project_id.to_string(),
+        version_number: "1.0.0".to_string(),
+        filename: format!("{}.jar", project_id.replace(':', "-")),
+        content_type: "mods".to_string(),
+        target_scope: "instance".to_string(),
+        target_worlds: vec![],
+        pinned_version: None,
+        enabled: true,
+        hashes: sha512
+            .map(|hash| HashMap::from([("sha512".to_string(), hash.to_string())]))
+            .unwrap_or_default(),
+        provider_candidates: vec![],
+        local_analysis: None,
+        security_scan: None,
+        url_update: None,
+        github_channel: None,
+    }
+}
+
+fn large_instance_entries() -> Vec<LockEntry> {
+    let mut entries = (0..200)
+        .map(|n| {
+            make_entry(
+                "modrinth",
+                &format!("proj-{n}"),
+                &format!("cur-{n}"),
+                Some(&format!("hash-{n}")),
+            )
+        })
+        .collect::<Vec<_>>();
+    entries.extend((1..=40).map(|n| {
+        make_entry(
+            "curseforge",
+            &format!("cf:{n}"),
+            &format!("cf_file:{}", n * 10),
+            None,
+        )
+    }));
+    entries
+}
+
+#[test]
+fn large_instance_update_check_uses_a_handful_of_batch_requests() {
+    let api = StandInApi::start(false);
+    let entries = large_instance_entries();
+    let client = Client::new();
+
+    let batched = batch_check_content_updates(
+        &client,
+        &make_instance(),
+        &entries,
+        UpdateScope::AllContent,
+        Some("test-key"),
+        &api.endpoints(),
+    );
+
+    // One Modrinth hash batch, one CurseForge mods batch, one files batch.
+    assert_eq!(api.request_count(), 3);
+    assert!(batched.remaining.is_empty());
+    assert_eq!(batched.resolved.len(), entries.len());
+    let updates = batched
+        .resolved
+        .iter()
+        .filter_map(|(_, update, _)| update.as_ref())
+        .collect::<Vec<_>>();
+    let modrinth_updates = updates.iter().filter(|u| u.source == "modrinth").count();
+    let curseforge_updates = updates.iter().filter(|u| u.source == "curseforge").count();
+    assert_eq!(modrinth_updates, 100);
+    assert_eq!(curseforge_updates, 40);
+    let cf_update = updates
+        .iter()
+        .find(|u| u.project_id == "cf:7")
+        .expect("cf:7 update");
+    assert_eq!(cf_update.latest_version_id, "cf_file:71");
+    let mr_update = updates
+        .iter()
+        .find(|u| u.project_id == "proj-4")
+        .expect("proj-4 update");
+    assert_eq!(mr_update.latest_version_id, "new-4");
+    assert_eq!(
+        mr_update.latest_hashes.get("sha512").map(String::as_str),
+        Some("next-4")
+    );
+}
+
+#[test]
+fn batch_hands_back_entries_it_cannot_settle() {
+    let api = StandInApi::start(false);
+    let mut pinned = make_entry("modrinth", "proj-2", "cur-2", Some("hash-2"));
+    pinned.pinned_version = Some("cur-2".to_string());
+    let entries = vec![
+        make_entry("modrinth", "proj-unknown", "cur-x", Some("hash-unknown")),
+        make_entry("modrinth", "proj-no-hash", "cur-y", None),
+        // Hash resolves to a different project than the lock entry claims.
+        make_entry("modrinth", "proj-other", "cur-3", Some("hash-3")),
+        pinned,
+        make_entry("modrinth", "proj-6", "cur-6", Some("hash-6")),
+    ];
+
+    let batched = batch_check_content_updates(
+        &Client::new(),
+        &make_instance(),
+        &entries,
+        UpdateScope::AllContent,
+        None,
+        &api.endpoints(),
+    );
+
+    assert_eq!(api.request_count(), 1);
+    assert_eq!(batched.remaining, vec![0, 1, 2, 3]);
+    assert_eq!(batched.resolved.len(), 1);
+    assert_eq!(batched.resolved[0].0, 4);
+}
+
+#[test]
+fn failed_batch_request_falls_back_to_per_entry_checks() {
+    let api = StandInApi::start(true);
+    let entries = (0..10)
+        .map(|n| {
+            make_entry(
+                "modrinth",
+                &format!("proj-{n}"),
+                &format!("cur-{n}"),
+                Some(&format!("hash-{n}")),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let batched = batch_check_content_updates(
+        &Client::new(),
+        &make_instance(),
+        &entries,
+        UpdateScope::AllContent,
+        None,
+        &api.endpoints(),
+    );
+
+    assert_eq!(api.request_count(), 1);
+    assert!(batched.resolved.is_empty());
+    assert_eq!(batched.remaining, (0..10).collect::<Vec<_>>());
+}
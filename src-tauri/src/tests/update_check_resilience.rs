@@ -80,6 +80,9 @@ fn make_github_lock_entry(name: &str, project_id: &str, version_id: &str) -> Loc
         hashes: HashMap::new(),
         provider_candidates: vec![],
         local_analysis: None,
+        security_scan: None,
+        url_update: None,
+        github_channel: None,
     }
 }
 
@@ -0,0 +1,371 @@
+//! Batched provider lookups for update checks.
+//!
+//! Modrinth mods are resolved by file hash through `version_files/update`, one
+//! request per hash algorithm and chunk. CurseForge entries are resolved with the
+//! bulk `mods` endpoint (latest file indexes) followed by the bulk `mods/files`
+//! endpoint. Anything a batch cannot settle — missing hashes, unknown files, no
+//! compatible candidate, failed requests — is handed back for the regular
+//! per-entry check, which also produces the detailed warnings.
+
+use crate::{
+    curseforge_update_from_files, effective_updatable_provider_for_entry,
+    file_looks_compatible_with_instance, minecraft_version_matches_advertised, modrinth_api_base,
+    modrinth_update_from_versions, parse_curseforge_project_id,
+    pick_compatible_version_for_content, update_check_target, ContentUpdateInfo, CurseforgeFile,
+    CurseforgeFilesResponse, Instance, LockEntry, ModrinthVersion, UpdateCheckTarget, UpdateScope,
+    CURSEFORGE_API_BASE,
+};
+use reqwest::blocking::Client;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+const MODRINTH_HASH_BATCH_SIZE: usize = 500;
+const CURSEFORGE_ID_BATCH_SIZE: usize = 500;
+
+pub(crate) struct UpdateBatchEndpoints {
+    pub modrinth_base: String,
+    pub curseforge_base: String,
+}
+
+impl UpdateBatchEndpoints {
+    pub(crate) fn from_environment() -> Self {
+        Self {
+            modrinth_base: modrinth_api_base(),
+            curseforge_base: CURSEFORGE_API_BASE.to_string(),
+        }
+    }
+}
+
+pub(crate) struct BatchedUpdateChecks {
+    /// Entry index, update (if any) and warnings for entries settled in bulk.
+    pub resolved: Vec<(usize, Option<ContentUpdateInfo>, Vec<String>)>,
+    /// Entry indexes that still need the per-entry check.
+    pub remaining: Vec<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CurseforgeBulkModsResponse {
+    #[serde(default)]
+    data: Vec<CurseforgeBulkMod>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CurseforgeBulkMod {
+    id: i64,
+    #[serde(default)]
+    #[serde(rename = "latestFilesIndexes")]
+    latest_files_indexes: Vec<CurseforgeFileIndex>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CurseforgeFileIndex {
+    #[serde(default)]
+    #[serde(rename = "gameVersion")]
+    game_version: String,
+    #[serde(rename = "fileId")]
+    file_id: i64,
+    #[serde(default)]
+    #[serde(rename = "modLoader")]
+    mod_loader: Option<i64>,
+}
+
+/// Preferred lock hash for a Modrinth lookup, strongest algorithm first.
+fn modrinth_lookup_hash(entry: &LockEntry) -> Option<(&'static str, String)> {
+    ["sha512", "sha1"].into_iter().find_map(|algorithm| {
+        entry
+            .hashes
+            .get(algorithm)
+            .map(|value| value.trim().to_ascii_lowercase())
+            .filter(|value| !value.is_empty())
+            .map(|value| (algorithm, value))
+    })
+}
+
+/// CurseForge `ModLoaderType` ids; `None` for loaders CurseForge does not index.
+fn curseforge_mod_loader_id(loader: &str) -> Option<i64> {
+    match loader.trim().to_ascii_lowercase().as_str() {
+        "forge" => Some(1),
+        "fabric" => Some(4),
+        "quilt" => Some(5),
+        "neoforge" => Some(6),
+        _ => None,
+    }
+}
+
+fn curseforge_index_matches_instance(
+    index: &CurseforgeFileIndex,
+    instance: &Instance,
+    content_type: &str,
+) -> bool {
+    let is_mod = content_type == "mods";
+    if !minecraft_version_matches_advertised(&index.game_version, &instance.mc_version, !is_mod) {
+        return false;
+    }
+    if !is_mod {
+        return true;
+    }
+    match index.mod_loader.filter(|id| *id > 0) {
+        None => true,
+        Some(id) => curseforge_mod_loader_id(&instance.loader) == Some(id),
+    }
+}
+
+fn post_json<T: for<'de> Deserialize<'de>>(
+    client: &Client,
+    url: &str,
+    api_key: Option<&str>,
+    body: &serde_json::Value,
+) -> Result<T, String> {
+    let mut request = client
+        .post(url)
+        .header("Accept", "application/json")
+        .json(body);
+    if let Some(key) = api_key {
+        request = request.header("x-api-key", key);
+    }
+    let response = request
+        .send()
+        .map_err(|e| format!("batch lookup {url} failed: {e}"))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "batch lookup {url} failed with status {}",
+            response.status()
+        ));
+    }
+    response
+        .json::<T>()
+        .map_err(|e| format!("parse batch lookup {url} failed: {e}"))
+}
+
+/// Settles as many entries as possible with bulk provider requests.
+pub(crate) fn batch_check_content_updates(
+    client: &Client,
+    instance: &Instance,
+    entries: &[LockEntry],
+    scope: UpdateScope,
+    cf_key: Option<&str>,
+    endpoints: &UpdateBatchEndpoints,
+) -> BatchedUpdateChecks {
+    let mut out = BatchedUpdateChecks {
+        resolved: Vec::new(),
+        remaining: Vec::new(),
+    };
+    // algorithm -> (index, target, hash)
+    let mut modrinth: BTreeMap<&'static str, Vec<(usize, UpdateCheckTarget, String)>> =
+        BTreeMap::new();
+    let mut curseforge: Vec<(usize, UpdateCheckTarget, i64)> = Vec::new();
+
+    for (idx, entry) in entries.iter().enumerate() {
+        let pinned = entry
+            .pinned_version
+            .as_ref()
+            .map(|value| !value.trim().is_empty())
+            .unwrap_or(false);
+        let Some(provider) = effective_updatable_provider_for_entry(entry, scope) else {
+            out.remaining.push(idx);
+            continue;
+        };
+        let target = update_check_target(entry, &provider);
+        if pinned {
+            out.remaining.push(idx);
+        } else if target.source == "modrinth" && target.content_type == "mods" {
+            match modrinth_lookup_hash(entry) {
+                Some((algorithm, hash)) => modrinth
+                    .entry(algorithm)
+                    .or_default()
+                    .push((idx, target, hash)),
+                None => out.remaining.push(idx),
+            }
+        } else if target.source == "curseforge" && cf_key.is_some() {
+            match parse_curseforge_project_id(&target.project_id) {
+                Ok(mod_id) => curseforge.push((idx, target, mod_id)),
+                Err(_) => out.remaining.push(idx),
+            }
+        } else {
+            out.remaining.push(idx);
+        }
+    }
+
+    for (algorithm, group) in modrinth {
+        for chunk in group.chunks(MODRINTH_HASH_BATCH_SIZE) {
+            batch_modrinth_chunk(
+                client, instance, entries, algorithm, chunk, endpoints, &mut out,
+            );
+        }
+    }
+    if let Some(api_key) = cf_key {
+        for chunk in curseforge.chunks(CURSEFORGE_ID_BATCH_SIZE) {
+            batch_curseforge_chunk(
+                client, api_key, instance, entries, chunk, endpoints, &mut out,
+            );
+        }
+    }
+    out.remaining.sort_unstable();
+    out
+}
+
+fn batch_modrinth_chunk(
+    client: &Client,
+    instance: &Instance,
+    entries: &[LockEntry],
+    algorithm: &str,
+    chunk: &[(usize, UpdateCheckTarget, String)],
+    endpoints: &UpdateBatchEndpoints,
+    out: &mut BatchedUpdateChecks,
+) {
+    let hashes = chunk
+        .iter()
+        .map(|(_, _, hash)| hash.clone())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+    let body = serde_json::json!({
+        "hashes": hashes,
+        "algorithm": algorithm,
+        "loaders": [instance.loader],
+        "game_versions": [instance.mc_version],
+    });
+    let url = format!("{}/version_files/update", endpoints.modrinth_base);
+    let Ok(latest_by_hash) =
+        post_json::<HashMap<String, ModrinthVersion>>(client, &url, None, &body)
+    else {
+        out.remaining.extend(chunk.iter().map(|(idx, _, _)| *idx));
+        return;
+    };
+    let latest_by_hash = latest_by_hash
+        .into_iter()
+        .map(|(hash, version)| (hash.to_ascii_lowercase(), version))
+        .collect::<HashMap<_, _>>();
+    for (idx, target, hash) in chunk {
+        let entry = &entries[*idx];
+        let latest = latest_by_hash.get(hash).filter(|version| {
+            version.project_id.trim() == target.project_id
+                && pick_compatible_version_for_content(
+                    vec![(*version).clone()],
+                    instance,
+                    &target.content_type,
+                )
+                .is_some()
+        });
+        let Some(latest) = latest else {
+            out.remaining.push(*idx);
+            continue;
+        };
+        let mut warnings = Vec::new();
+        let update = modrinth_update_from_versions(
+            instance,
+            entry,
+            target,
+            vec![latest.clone()],
+            &mut warnings,
+        );
+        out.resolved.push((*idx, update, warnings));
+    }
+}
+
+fn batch_curseforge_chunk(
+    client: &Client,
+    api_key: &str,
+    instance: &Instance,
+    entries: &[LockEntry],
+    chunk: &[(usize, UpdateCheckTarget, i64)],
+    endpoints: &UpdateBatchEndpoints,
+    out: &mut BatchedUpdateChecks,
+) {
+    let give_back = |out: &mut BatchedUpdateChecks| {
+        out.remaining.extend(chunk.iter().map(|(idx, _, _)| *idx));
+    };
+    let mod_ids = chunk
+        .iter()
+        .map(|(_, _, mod_id)| *mod_id)
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+    let Ok(mods) = post_json::<CurseforgeBulkModsResponse>(
+        client,
+        &format!("{}/mods", endpoints.curseforge_base),
+        Some(api_key),
+        &serde_json::json!({ "modIds": mod_ids }),
+    ) else {
+        give_back(out);
+        return;
+    };
+    let indexes_by_mod = mods
+        .data
+        .into_iter()
+        .map(|item| (item.id, item.latest_files_indexes))
+        .collect::<HashMap<_, _>>();
+
+    // Candidate files per chunk entry, from the latest-file indexes.
+    let mut candidate_ids: Vec<Vec<i64>> = Vec::with_capacity(chunk.len());
+    let mut all_file_ids: HashSet<i64> = HashSet::new();
+    for (_, target, mod_id) in chunk {
+        let mut ids = indexes_by_mod
+            .get(mod_id)
+            .map(|indexes| {
+                indexes
+                    .iter()
+                    .filter(|index| {
+                        curseforge_index_matches_instance(index, instance, &target.content_type)
+                    })
+                    .map(|index| index.file_id)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        ids.sort_unstable();
+        ids.dedup();
+        all_file_ids.extend(ids.iter().copied());
+        candidate_ids.push(ids);
+    }
+    if all_file_ids.is_empty() {
+        give_back(out);
+        return;
+    }
+    let mut file_ids = all_file_ids.into_iter().collect::<Vec<_>>();
+    file_ids.sort_unstable();
+    let Ok(files) = post_json::<CurseforgeFilesResponse>(
+        client,
+        &format!("{}/mods/files", endpoints.curseforge_base),
+        Some(api_key),
+        &serde_json::json!({ "fileIds": file_ids }),
+    ) else {
+        give_back(out);
+        return;
+    };
+    let files_by_id = files
+        .data
+        .into_iter()
+        .map(|file| (file.id, file))
+        .collect::<HashMap<i64, CurseforgeFile>>();
+
+    for ((idx, target, mod_id), ids) in chunk.iter().zip(candidate_ids) {
+        let entry = &entries[*idx];
+        let files = ids
+            .iter()
+            .filter_map(|id| files_by_id.get(id))
+            .filter(|file| file.mod_id == *mod_id || file.mod_id == 0)
+            .cloned()
+            .collect::<Vec<_>>();
+        let has_compatible = files.iter().any(|file| {
+            !file.file_name.trim().is_empty()
+                && file_looks_compatible_with_instance(file, instance, &target.content_type)
+        });
+        if !has_compatible {
+            out.remaining.push(*idx);
+            continue;
+        }
+        let mut warnings = Vec::new();
+        match curseforge_update_from_files(
+            client,
+            api_key,
+            instance,
+            entry,
+            target,
+            files,
+            &mut warnings,
+        ) {
+            Ok(update) => out.resolved.push((*idx, update, warnings)),
+            Err(_) => out.remaining.push(*idx),
+        }
+    }
+}
@@ -0,0 +1,694 @@
+//! Direct-URL content source for files only published on a website or a
+//! Maven repository.
+//!
+//! Lock entries use source `url` and project ids `url:<download url>`. The
+//! download URL may contain a `{version}` placeholder that is filled from the
+//! entry's version number, so Friend Link and redownloads can rebuild the exact
+//! file URL from the lock alone. Every entry records a SHA-256/512 pin that is
+//! enforced whenever the file is fetched again. An optional update template
+//! names a version-listing endpoint (JSON or `maven-metadata.xml`) polled by
+//! update checks.
+
+use crate::advisories::compare_loose_versions;
+use crate::{
+    canonical_lock_entry_name, download_bytes_with_retry, enforce_lock_entry_uniqueness,
+    hash_bytes_hex, normalize_lock_content_type, normalize_target_worlds_for_datapack,
+    sanitize_filename, sha256_bytes_hex, sha512_hex, validate_lock_entry_uniqueness,
+    write_download_to_content_targets, ContentUpdateInfo, InstallUrlContentArgs, LockEntry,
+    Lockfile, ProviderCandidate,
+};
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::path::Path;
+
+pub(crate) const URL_SOURCE: &str = "url";
+const PROJECT_PREFIX: &str = "url:";
+const VERSION_PREFIX: &str = "url_ver:";
+const HASH_VERSION_PREFIX: &str = "url_sha256:";
+const VERSION_PLACEHOLDER: &str = "{version}";
+const URL_PLACEHOLDER: &str = "{url}";
+
+/// How update checks discover newer versions of a direct-URL entry.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct UrlUpdateTemplate {
+    /// JSON endpoint listing versions: an array of strings, an array of objects
+    /// with a `version`, `version_number`, `tag_name` or `name` field, or an
+    /// object holding such an array under `versions`.
+    #[serde(
+        alias = "versionsUrl",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub versions_url: Option<String>,
+    /// `maven-metadata.xml` whose `<version>` elements are the versions.
+    #[serde(
+        alias = "mavenMetadataUrl",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub maven_metadata_url: Option<String>,
+    /// Only versions containing this text (for example `1.20.1`) are considered.
+    #[serde(
+        alias = "versionFilter",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub version_filter: Option<String>,
+    /// Checksum sidecar for a version, with `{url}` and/or `{version}`
+    /// placeholders, e.g. `{url}.sha256`. Updates are pinned to its digest.
+    #[serde(
+        alias = "checksumUrl",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub checksum_url: Option<String>,
+}
+
+fn non_empty(value: Option<&String>) -> Option<&str> {
+    value.map(|v| v.trim()).filter(|v| !v.is_empty())
+}
+
+pub(crate) fn require_https(url: &str, label: &str) -> Result<(), String> {
+    let trimmed = url.trim();
+    let valid = trimmed
+        .get(..8)
+        .map(|scheme| scheme.eq_ignore_ascii_case("https://"))
+        .unwrap_or(false)
+        && trimmed.len() > 8
+        && !trimmed.chars().any(char::is_whitespace);
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("{label} must be an https:// URL"))
+    }
+}
+
+pub(crate) fn url_project_key(url: &str) -> String {
+    format!("{PROJECT_PREFIX}{}", url.trim())
+}
+
+fn parse_url_project_id(project_id: &str) -> Result<String, String> {
+    project_id
+        .trim()
+        .strip_prefix(PROJECT_PREFIX)
+        .map(str::to_string)
+        .filter(|url| !url.trim().is_empty())
+        .ok_or_else(|| format!("'{project_id}' is not a direct-URL project id"))
+}
+
+fn url_version_id(version: &str, sha256: &str) -> String {
+    if version.trim().is_empty() {
+        format!("{HASH_VERSION_PREFIX}{}", &sha256[..sha256.len().min(16)])
+    } else {
+        format!("{VERSION_PREFIX}{}", version.trim())
+    }
+}
+
+/// Fills the `{version}` placeholder of an entry's download URL.
+pub(crate) fn resolve_url_download(
+    project_id: &str,
+    version_number: &str,
+) -> Result<String, String> {
+    let pattern = parse_url_project_id(project_id)?;
+    let url = if pattern.contains(VERSION_PLACEHOLDER) {
+        if version_number.trim().is_empty() {
+            return Err(format!(
+                "Download URL '{pattern}' needs a version but the entry has none"
+            ));
+        }
+        pattern.replace(VERSION_PLACEHOLDER, version_number.trim())
+    } else {
+        pattern
+    };
+    require_https(&url, "Download URL")?;
+    Ok(url)
+}
+
+/// Normalizes user-supplied pins, rejecting anything that is not a hex digest
+/// of the right length.
+pub(crate) fn normalize_pin(
+    sha256: Option<&str>,
+    sha512: Option<&str>,
+) -> Result<HashMap<String, String>, String> {
+    let mut pins = HashMap::new();
+    for (algorithm, value, len) in [("sha256", sha256, 64), ("sha512", sha512, 128)] {
+        let Some(value) = value.map(str::trim).filter(|v| !v.is_empty()) else {
+            continue;
+        };
+        let cleaned = value.to_ascii_lowercase();
+        if cleaned.len() != len || !cleaned.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("The {algorithm} pin must be {len} hex characters"));
+        }
+        pins.insert(algorithm.to_string(), cleaned);
+    }
+    Ok(pins)
+}
+
+/// Checks bytes against the strongest SHA-2 pin present. Returns an error when
+/// there is no pin at all, so entries can never be fetched unverified.
+pub(crate) fn verify_pinned_bytes(
+    bytes: &[u8],
+    hashes: &HashMap<String, String>,
+    label: &str,
+) -> Result<(), String> {
+    let pin = ["sha512", "sha256"].iter().find_map(|algorithm| {
+        hashes
+            .iter()
+            .find(|(key, value)| {
+                key.trim().replace('-', "").eq_ignore_ascii_case(algorithm)
+                    && !value.trim().is_empty()
+            })
+            .map(|(_, value)| (*algorithm, value.trim().to_ascii_lowercase()))
+    });
+    let Some((algorithm, expected)) = pin else {
+        return Err(format!("{label} has no SHA-256/512 pin"));
+    };
+    if hash_bytes_hex(algorithm, bytes) != expected {
+        return Err(format!("{label} does not match its pinned {algorithm}"));
+    }
+    Ok(())
+}
+
+/// `<version>` values from a `maven-metadata.xml` document, in file order.
+pub(crate) fn parse_maven_metadata_versions(xml: &str) -> Vec<String> {
+    let scope = xml
+        .find("<versions>")
+        .and_then(|start| {
+            xml[start..]
+                .find("</versions>")
+                .map(|end| &xml[start..start + end])
+        })
+        .unwrap_or(xml);
+    let mut out = Vec::new();
+    let mut rest = scope;
+    while let Some(start) = rest.find("<version>") {
+        rest = &rest[start + "<version>".len()..];
+        let Some(end) = rest.find("</version>") else {
+            break;
+        };
+        let value = rest[..end].trim();
+        if !value.is_empty() {
+            out.push(value.to_string());
+        }
+        rest = &rest[end..];
+    }
+    out
+}
+
+pub(crate) fn parse_version_listing(raw: &str) -> Result<Vec<String>, String> {
+    let value: serde_json::Value =
+        serde_json::from_str(raw).map_err(|e| format!("parse version listing failed: {e}"))?;
+    let items = match &value {
+        serde_json::Value::Array(items) => items,
+        serde_json::Value::Object(map) => match map.get("versions") {
+            Some(serde_json::Value::Array(items)) => items,
+            _ => return Err("Version listing has no 'versions' array".to_string()),
+        },
+        _ => return Err("Version listing must be a JSON array or object".to_string()),
+    };
+    Ok(items
+        .iter()
+        .filter_map(|item| match item {
+            serde_json::Value::String(value) => Some(value.clone()),
+            serde_json::Value::Object(map) => ["version", "version_number", "tag_name", "name"]
+                .iter()
+                .find_map(|key| map.get(*key).and_then(|v| v.as_str()))
+                .map(str::to_string),
+            _ => None,
+        })
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .collect())
+}
+
+pub(crate) fn pick_latest_version(versions: &[String], filter: Option<&str>) -> Option<String> {
+    versions
+        .iter()
+        .filter(|version| filter.map(|f| version.contains(f)).unwrap_or(true))
+        .max_by(|a, b| compare_loose_versions(a, b))
+        .cloned()
+}
+
+fn fetch_text(client: &Client, url: &str, label: &str) -> Result<String, String> {
+    require_https(url, label)?;
+    let bytes = download_bytes_with_retry(client, url, label)?;
+    String::from_utf8(bytes).map_err(|_| format!("{label} is not valid UTF-8"))
+}
+
+fn fetch_listed_versions(
+    client: &Client,
+    template: &UrlUpdateTemplate,
+) -> Result<Vec<String>, String> {
+    if let Some(url) = non_empty(template.maven_metadata_url.as_ref()) {
+        return Ok(parse_maven_metadata_versions(&fetch_text(
+            client,
+            url,
+            "Maven metadata",
+        )?));
+    }
+    if let Some(url) = non_empty(template.versions_url.as_ref()) {
+        return parse_version_listing(&fetch_text(client, url, "Version listing")?);
+    }
+    Err("Update template has no version listing or Maven metadata URL".to_string())
+}
+
+/// Reads a checksum sidecar (`<hex>` or `<hex>  filename`) and keys it by
+/// digest length.
+fn fetch_checksum_pin(
+    client: &Client,
+    template: &UrlUpdateTemplate,
+    download_url: &str,
+    version: &str,
+) -> Result<HashMap<String, String>, String> {
+    let Some(pattern) = non_empty(template.checksum_url.as_ref()) else {
+        return Ok(HashMap::new());
+    };
+    let url = pattern
+        .replace(URL_PLACEHOLDER, download_url)
+        .replace(VERSION_PLACEHOLDER, version);
+    let body = fetch_text(client, &url, "Checksum file")?;
+    let digest = body.split_whitespace().next().unwrap_or_default();
+    match digest.len() {
+        64 => normalize_pin(Some(digest), None),
+        128 => normalize_pin(None, Some(digest)),
+        _ => Err(format!(
+            "Checksum file '{url}' does not hold a SHA-256/512 digest"
+        )),
+    }
+}
+
+fn validate_template(url: &str, template: &UrlUpdateTemplate) -> Result<(), String> {
+    if !url.contains(VERSION_PLACEHOLDER) {
+        return Err(format!(
+            "An update template needs a download URL containing {VERSION_PLACEHOLDER}"
+        ));
+    }
+    let maven = non_empty(template.maven_metadata_url.as_ref());
+    let listing = non_empty(template.versions_url.as_ref());
+    if maven.is_none() && listing.is_none() {
+        return Err("Update template has no version listing or Maven metadata URL".to_string());
+    }
+    for url in [maven, listing].into_iter().flatten() {
+        require_https(url, "Update template URL")?;
+    }
+    Ok(())
+}
+
+fn filename_from_url(url: &str) -> String {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    sanitize_filename(path.rsplit('/').next().unwrap_or_default())
+}
+
+fn recorded_hashes(bytes: &[u8]) -> HashMap<String, String> {
+    HashMap::from([
+        ("sha256".to_string(), sha256_bytes_hex(bytes)),
+        ("sha512".to_string(), sha512_hex(bytes)),
+    ])
+}
+
+fn url_provider_candidate(entry: &LockEntry) -> ProviderCandidate {
+    ProviderCandidate {
+        source: URL_SOURCE.to_string(),
+        project_id: entry.project_id.clone(),
+        version_id: entry.version_id.clone(),
+        name: entry.name.clone(),
+        version_number: entry.version_number.clone(),
+        confidence: None,
+        reason: None,
+        verification_status: Some("verified".to_string()),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn write_url_entry(
+    instance_dir: &Path,
+    lock: &mut Lockfile,
+    project_id: String,
+    version: &str,
+    display_name: &str,
+    filename: &str,
+    content_type: &str,
+    target_worlds: &[String],
+    enabled: bool,
+    pinned_version: Option<String>,
+    update: Option<UrlUpdateTemplate>,
+    bytes: &[u8],
+) -> Result<LockEntry, String> {
+    let safe_filename = sanitize_filename(filename);
+    if safe_filename.is_empty() {
+        return Err("Resolved filename is invalid".to_string());
+    }
+    let worlds = if content_type == "datapacks" {
+        normalize_target_worlds_for_datapack(instance_dir, target_worlds)?
+    } else {
+        vec![]
+    };
+    let hashes = recorded_hashes(bytes);
+    let sha256 = hashes.get("sha256").cloned().unwrap_or_default();
+    let mut new_entry = LockEntry {
+        source: URL_SOURCE.to_string(),
+        project_id,
+        version_id: url_version_id(version, &sha256),
+        name: canonical_lock_entry_name(content_type, &safe_filename, display_name),
+        version_number: if version.trim().is_empty() {
+            sha256[..sha256.len().min(12)].to_string()
+        } else {
+            version.trim().to_string()
+        },
+        filename: safe_filename.clone(),
+        content_type: content_type.to_string(),
+        target_scope: if content_type == "datapacks" {
+            "world".to_string()
+        } else {
+            "instance".to_string()
+        },
+        target_worlds: worlds.clone(),
+        pinned_version,
+        enabled,
+        hashes,
+        provider_candidates: vec![],
+        local_analysis: None,
+        security_scan: None,
+        url_update: update,
+        github_channel: None,
+    };
+    new_entry.provider_candidates = vec![url_provider_candidate(&new_entry)];
+    validate_lock_entry_uniqueness(lock, &new_entry)?;
+    new_entry.security_scan = write_download_to_content_targets(
+        instance_dir,
+        content_type,
+        &safe_filename,
+        &worlds,
+        bytes,
+    )?;
+    if content_type == "mods" && !enabled {
+        crate::disable_mod_file(instance_dir, &new_entry.filename)?;
+    }
+    enforce_lock_entry_uniqueness(lock, instance_dir, &mut new_entry)?;
+    lock.entries.push(new_entry.clone());
+    Ok(new_entry)
+}
+
+pub(crate) fn install_url_content_inner(
+    instance_dir: &Path,
+    lock: &mut Lockfile,
+    client: &Client,
+    args: &InstallUrlContentArgs,
+) -> Result<LockEntry, String> {
+    let normalized = normalize_lock_content_type(&args.content_type);
+    if normalized == "modpacks" {
+        return Err(
+            "Modpack entries are template-only. Import as template in Modpacks & Presets."
+                .to_string(),
+        );
+    }
+    let url = args.url.trim().to_string();
+    require_https(&url.replace(VERSION_PLACEHOLDER, "0"), "Download URL")?;
+    if let Some(template) = args.update_template.as_ref() {
+        validate_template(&url, template)?;
+    }
+    let mut version = args
+        .version
+        .as_deref()
+        .map(str::trim)
+        .unwrap_or_default()
+        .to_string();
+    if url.contains(VERSION_PLACEHOLDER) && version.is_empty() {
+        let template = args.update_template.as_ref().ok_or_else(|| {
+            format!("A download URL containing {VERSION_PLACEHOLDER} needs a version")
+        })?;
+        let filter = non_empty(template.version_filter.as_ref());
+        version = pick_latest_version(&fetch_listed_versions(client, template)?, filter)
+            .ok_or_else(|| "The version listing has no matching versions".to_string())?;
+    }
+    let project_id = url_project_key(&url);
+    let download_url = resolve_url_download(&project_id, &version)?;
+
+    let mut pin = normalize_pin(args.sha256.as_deref(), args.sha512.as_deref())?;
+    if pin.is_empty() {
+        if let Some(template) = args.update_template.as_ref() {
+            pin = fetch_checksum_pin(client, template, &download_url, &version)?;
+        }
+    }
+    let bytes = download_bytes_with_retry(client, &download_url, &download_url)?;
+    if pin.is_empty() {
+        // Trust on first use: this download's digests become the pin that
+        // every later fetch of the entry is checked against.
+        pin = recorded_hashes(&bytes);
+    }
+    verify_pinned_bytes(
+        &bytes,
+        &pin,
+        &format!("Downloaded file from {download_url}"),
+    )?;
+
+    let filename = non_empty(args.filename.as_ref())
+        .map(str::to_string)
+        .unwrap_or_else(|| filename_from_url(&download_url));
+    let display_name = non_empty(args.name.as_ref())
+        .map(str::to_string)
+        .unwrap_or_else(|| filename.clone());
+    write_url_entry(
+        instance_dir,
+        lock,
+        project_id,
+        &version,
+        &display_name,
+        &filename,
+        &normalized,
+        &args.target_worlds,
+        true,
+        None,
+        args.update_template.clone(),
+        &bytes,
+    )
+}
+
+pub(crate) fn check_url_content_update(
+    client: &Client,
+    entry: &LockEntry,
+    provider: &ProviderCandidate,
+    warnings: &mut Vec<String>,
+) -> Result<Option<ContentUpdateInfo>, String> {
+    let Some(template) = entry.url_update.as_ref() else {
+        return Ok(None);
+    };
+    let versions = match fetch_listed_versions(client, template) {
+        Ok(versions) => versions,
+        Err(err) => {
+            warnings.push(format!(
+                "Could not list versions for '{}' ({}): {err}",
+                entry.name, entry.project_id
+            ));
+            return Ok(None);
+        }
+    };
+    let Some(latest) = pick_latest_version(&versions, non_empty(template.version_filter.as_ref()))
+    else {
+        warnings.push(format!(
+            "No matching versions listed for '{}' ({})",
+            entry.name, entry.project_id
+        ));
+        return Ok(None);
+    };
+    if compare_loose_versions(&latest, &entry.version_number) != Ordering::Greater {
+        return Ok(None);
+    }
+    let checked = resolve_url_download(&entry.project_id, &latest).and_then(|download_url| {
+        let hashes = fetch_checksum_pin(client, template, &download_url, &latest)?;
+        Ok((download_url, hashes))
+    });
+    let (download_url, latest_hashes) = match checked {
+        Ok(checked) => checked,
+        Err(err) => {
+            warnings.push(format!(
+                "Could not check version {latest} of '{}' ({}): {err}",
+                entry.name, entry.project_id
+            ));
+            return Ok(None);
+        }
+    };
+    Ok(Some(ContentUpdateInfo {
+        source: URL_SOURCE.to_string(),
+        content_type: normalize_lock_content_type(&entry.content_type),
+        project_id: entry.project_id.clone(),
+        name: entry.name.clone(),
+        current_version_id: if provider.version_id.trim().is_empty() {
+            entry.version_id.clone()
+        } else {
+            provider.version_id.clone()
+        },
+        current_version_number: entry.version_number.clone(),
+        latest_version_id: format!("{VERSION_PREFIX}{latest}"),
+        latest_version_number: latest.clone(),
+        enabled: entry.enabled,
+        target_worlds: entry.target_worlds.clone(),
+        latest_file_name: Some(filename_from_url(&download_url)),
+        latest_download_url: Some(download_url),
+        latest_hashes,
+        required_dependencies: vec![],
+        compatibility_status: None,
+        compatibility_notes: if template.checksum_url.is_some() {
+            vec![]
+        } else {
+            vec![
+                "No checksum source configured; add a checksum URL to the update template to install this update."
+                    .to_string(),
+            ]
+        },
+    }))
+}
+
+/// Applies an update found by [`check_url_content_update`], carrying the
+/// entry's update template forward.
+pub(crate) fn install_url_content_update(
+    instance_dir: &Path,
+    lock: &mut Lockfile,
+    client: &Client,
+    update: &ContentUpdateInfo,
+    carried_pin: Option<String>,
+) -> Result<LockEntry, String> {
+    let normalized = normalize_lock_content_type(&update.content_type);
+    let template = lock
+        .entries
+        .iter()
+        .find(|entry| {
+            entry.source.eq_ignore_ascii_case(URL_SOURCE)
+                && entry.project_id.trim() == update.project_id.trim()
+        })
+        .and_then(|entry| entry.url_update.clone());
+    if update.latest_hashes.is_empty() {
+        return Err(format!(
+            "'{}' {} has no published checksum to verify against; add a checksum URL to its update template",
+            update.name, update.latest_version_number
+        ));
+    }
+    let download_url = resolve_url_download(&update.project_id, &update.latest_version_number)?;
+    let bytes = download_bytes_with_retry(client, &download_url, &download_url)?;
+    verify_pinned_bytes(
+        &bytes,
+        &update.latest_hashes,
+        &format!("Downloaded file from {download_url}"),
+    )?;
+    let filename = update
+        .latest_file_name
+        .clone()
+        .unwrap_or_else(|| filename_from_url(&download_url));
+    write_url_entry(
+        instance_dir,
+        lock,
+        update.project_id.clone(),
+        &update.latest_version_number,
+        &update.name,
+        &filename,
+        &normalized,
+        &update.target_worlds,
+        update.enabled,
+        carried_pin,
+        template,
+        &bytes,
+    )
+}
+
+/// Fetches the exact pinned file of a direct-URL entry, for redownloads and
+/// Friend Link. Fails when the file no longer matches its pin.
+pub(crate) fn download_url_entry_bytes(
+    client: &Client,
+    project_id: &str,
+    version_number: &str,
+    hashes: &HashMap<String, String>,
+) -> Result<Vec<u8>, String> {
+    let download_url = resolve_url_download(project_id, version_number)?;
+    let bytes = download_bytes_with_retry(client, &download_url, &download_url)?;
+    verify_pinned_bytes(
+        &bytes,
+        hashes,
+        &format!("Downloaded file from {download_url}"),
+    )?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_version_placeholder_and_requires_https() {
+        let project = url_project_key("https://maven.example/mod/{version}/mod-{version}.jar");
+        assert_eq!(
+            resolve_url_download(&project, "1.2.0"),
+            Ok("https://maven.example/mod/1.2.0/mod-1.2.0.jar".to_string())
+        );
+        assert!(resolve_url_download(&project, "").is_err());
+        assert!(resolve_url_download("url:http://insecure.example/a.jar", "").is_err());
+        assert!(resolve_url_download("team:thing", "").is_err());
+    }
+
+    #[test]
+    fn reads_maven_metadata_and_json_listings() {
+        let xml = r#"<metadata>
+            <versioning>
+                <latest>2.0.0+1.21</latest>
+                <versions>
+                    <version>1.0.0+1.20.1</version>
+                    <version>1.4.0+1.20.1</version>
+                    <version>2.0.0+1.21</version>
+                </versions>
+            </versioning>
+        </metadata>"#;
+        let versions = parse_maven_metadata_versions(xml);
+        assert_eq!(versions.len(), 3);
+        assert_eq!(
+            pick_latest_version(&versions, Some("1.20.1")),
+            Some("1.4.0+1.20.1".to_string())
+        );
+        assert_eq!(
+            pick_latest_version(&versions, None),
+            Some("2.0.0+1.21".to_string())
+        );
+
+        assert_eq!(
+            parse_version_listing(r#"["1.0", "1.10", "1.9"]"#)
+                .map(|v| pick_latest_version(&v, None)),
+            Ok(Some("1.10".to_string()))
+        );
+        assert_eq!(
+            parse_version_listing(r#"{"versions": [{"tag_name": "v3"}, {"name": "v4"}]}"#),
+            Ok(vec!["v3".to_string(), "v4".to_string()])
+        );
+        assert!(parse_version_listing("42").is_err());
+    }
+
+    #[test]
+    fn enforces_sha2_pins() {
+        let bytes = b"mod bytes";
+        let pins =
+            normalize_pin(Some(&sha256_bytes_hex(bytes).to_uppercase()), None).expect("valid pin");
+        assert!(verify_pinned_bytes(bytes, &pins, "file").is_ok());
+        assert!(verify_pinned_bytes(b"tampered", &pins, "file").is_err());
+        assert!(verify_pinned_bytes(bytes, &HashMap::new(), "file").is_err());
+        assert!(normalize_pin(Some("abc"), None).is_err());
+        assert!(normalize_pin(None, Some(&"g".repeat(128))).is_err());
+    }
+
+    #[test]
+    fn template_requires_placeholder_and_listing() {
+        let template = UrlUpdateTemplate {
+            maven_metadata_url: Some("https://maven.example/mod/maven-metadata.xml".to_string()),
+            ..Default::default()
+        };
+        assert!(validate_template("https://maven.example/mod/{version}.jar", &template).is_ok());
+        assert!(validate_template("https://maven.example/mod/1.0.jar", &template).is_err());
+        assert!(validate_template(
+            "https://maven.example/mod/{version}.jar",
+            &UrlUpdateTemplate::default()
+        )
+        .is_err());
+        assert_eq!(
+            filename_from_url("https://cdn.example/files/mod-1.0.jar?token=abc"),
+            "mod-1.0.jar"
+        );
+    }
+}
@@ -0,0 +1,474 @@
+//! Portable world archives for sharing saves between instances and players.
+//!
+//! An export zip holds the world under a single top folder plus an
+//! `openjar-world.json` manifest at the root naming the game version, loader
+//! and (optionally) the mods the world was played with. Imports accept that
+//! layout and plain world zips from elsewhere: the folder holding the
+//! shallowest `level.dat` is taken as the world root and everything outside
+//! it is ignored.
+
+use crate::world_backup_store::{collect_world_files, world_path_allowed};
+use crate::{normalize_lock_content_type, Lockfile};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use zip::write::FileOptions;
+use zip::ZipArchive;
+
+pub(crate) const WORLD_ARCHIVE_MANIFEST: &str = "openjar-world.json";
+pub(crate) const WORLD_ARCHIVE_FORMAT: u32 = 1;
+/// Largest manifest read from an archive; real ones are a few kilobytes.
+const MAX_MANIFEST_BYTES: u64 = 4 * 1024 * 1024;
+const MAX_WORLD_NAME_CHARS: usize = 80;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct WorldArchiveMod {
+    pub name: String,
+    pub source: String,
+    pub project_id: String,
+    #[serde(default)]
+    pub version_number: String,
+    pub filename: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct WorldArchiveManifest {
+    #[serde(default = "default_world_archive_format")]
+    pub format: u32,
+    pub world_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mc_version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub loader: Option<String>,
+    pub exported_at: String,
+    #[serde(default)]
+    pub includes_datapacks: bool,
+    /// Enabled mods of the exporting instance; empty when the exporter left
+    /// the mod list out.
+    #[serde(default)]
+    pub required_mods: Vec<WorldArchiveMod>,
+}
+
+fn default_world_archive_format() -> u32 {
+    WORLD_ARCHIVE_FORMAT
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct WorldExportResult {
+    pub output_path: String,
+    pub world_id: String,
+    pub files_count: usize,
+    pub required_mods: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct WorldImportResult {
+    pub world_id: String,
+    pub files_count: usize,
+    /// The requested name was taken, so the world got a numbered one.
+    pub renamed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub manifest: Option<WorldArchiveManifest>,
+    /// Mods from the manifest that this instance does not have enabled.
+    pub missing_mods: Vec<WorldArchiveMod>,
+}
+
+/// Enabled mods of `lock`, for the export manifest.
+pub(crate) fn required_mods_from_lock(lock: &Lockfile) -> Vec<WorldArchiveMod> {
+    let mut out = lock
+        .entries
+        .iter()
+        .filter(|entry| entry.enabled && normalize_lock_content_type(&entry.content_type) == "mods")
+        .map(|entry| WorldArchiveMod {
+            name: entry.name.clone(),
+            source: entry.source.clone(),
+            project_id: entry.project_id.clone(),
+            version_number: entry.version_number.clone(),
+            filename: entry.filename.clone(),
+        })
+        .collect::<Vec<_>>();
+    out.sort_by_key(|m| m.name.to_lowercase());
+    out
+}
+
+/// Mods in `required` with no enabled counterpart in `lock`. A mod counts as
+/// present when the same project is installed from the same source, or when
+/// a local file has the same name.
+pub(crate) fn missing_required_mods(
+    required: &[WorldArchiveMod],
+    lock: &Lockfile,
+) -> Vec<WorldArchiveMod> {
+    let installed = lock
+        .entries
+        .iter()
+        .filter(|entry| entry.enabled && normalize_lock_content_type(&entry.content_type) == "mods")
+        .collect::<Vec<_>>();
+    required
+        .iter()
+        .filter(|wanted| {
+            !installed.iter().any(|entry| {
+                (!wanted.project_id.trim().is_empty()
+                    && entry.source.eq_ignore_ascii_case(&wanted.source)
+                    && entry.project_id == wanted.project_id)
+                    || entry.filename.eq_ignore_ascii_case(&wanted.filename)
+            })
+        })
+        .cloned()
+        .collect()
+}
+
+/// Turns an archive or user supplied name into a safe save folder name.
+pub(crate) fn world_folder_name(raw: &str) -> String {
+    let cleaned = raw
+        .chars()
+        .map(|ch| {
+            if ch.is_control() || matches!(ch, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|')
+            {
+                '_'
+            } else {
+                ch
+            }
+        })
+        .take(MAX_WORLD_NAME_CHARS)
+        .collect::<String>();
+    let trimmed = cleaned.trim().trim_matches('.').trim();
+    if trimmed.is_empty() {
+        "world".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// First free save folder name: `name`, then `name (2)`, `name (3)`, ...
+fn unique_world_name(saves_dir: &Path, name: &str) -> String {
+    if !saves_dir.join(name).exists() {
+        return name.to_string();
+    }
+    (2..)
+        .map(|n| format!("{name} ({n})"))
+        .find(|candidate| !saves_dir.join(candidate).exists())
+        .unwrap_or_else(|| name.to_string())
+}
+
+/// Zips `world_dir` under a top folder named after the world. `datapacks/`
+/// is skipped unless `include_datapacks`; `session.lock` is always skipped.
+pub(crate) fn export_world_archive(
+    world_dir: &Path,
+    output: &Path,
+    include_datapacks: bool,
+    manifest: &WorldArchiveManifest,
+) -> Result<usize, String> {
+    if !world_dir.is_dir() {
+        return Err("World folder does not exist".to_string());
+    }
+    let top = world_folder_name(&manifest.world_name);
+    let mut files = Vec::new();
+    collect_world_files(world_dir, world_dir, &mut files);
+    files.retain(|(rel, _, _)| include_datapacks || !rel.starts_with("datapacks/"));
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("mkdir export directory failed: {e}"))?;
+    }
+    let file = File::create(output).map_err(|e| format!("create zip failed: {e}"))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    let manifest_raw = serde_json::to_vec_pretty(manifest)
+        .map_err(|e| format!("serialize world manifest failed: {e}"))?;
+    zip.start_file(WORLD_ARCHIVE_MANIFEST, options)
+        .map_err(|e| format!("zip write header failed: {e}"))?;
+    zip.write_all(&manifest_raw)
+        .map_err(|e| format!("zip write manifest failed: {e}"))?;
+
+    for (rel, path, _) in &files {
+        let mut src = File::open(path).map_err(|e| format!("open '{}' failed: {e}", rel))?;
+        zip.start_file(format!("{top}/{rel}"), options)
+            .map_err(|e| format!("zip write header failed: {e}"))?;
+        std::io::copy(&mut src, &mut zip)
+            .map_err(|e| format!("zip write '{}' failed: {e}", rel))?;
+    }
+    zip.finish()
+        .map_err(|e| format!("finalize zip failed: {e}"))?;
+    Ok(files.len())
+}
+
+/// Archive path split into parts, or `None` for entries that would escape
+/// the extraction folder.
+fn safe_entry_parts(entry: &zip::read::ZipFile<'_>) -> Option<Vec<String>> {
+    let name = entry.enclosed_name()?;
+    let rel = name.to_string_lossy().replace('\\', "/");
+    let parts = rel
+        .split('/')
+        .filter(|part| !part.trim().is_empty())
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    if parts.is_empty() || parts.iter().any(|part| part == "__MACOSX") {
+        return None;
+    }
+    world_path_allowed(&parts.join("/")).then_some(parts)
+}
+
+fn read_archive_manifest(archive: &mut ZipArchive<File>) -> Option<WorldArchiveManifest> {
+    let entry = archive.by_name(WORLD_ARCHIVE_MANIFEST).ok()?;
+    if entry.size() > MAX_MANIFEST_BYTES {
+        return None;
+    }
+    let mut raw = Vec::new();
+    entry.take(MAX_MANIFEST_BYTES).read_to_end(&mut raw).ok()?;
+    serde_json::from_slice(&raw).ok()
+}
+
+/// Extracts a world zip into `saves_dir`. The world lands in a staging folder
+/// first and is renamed into place once every file is written, so a failed
+/// import leaves no half-written save behind. Returns the result without
+/// `missing_mods`, which needs the instance lockfile.
+pub(crate) fn import_world_archive(
+    archive_path: &Path,
+    saves_dir: &Path,
+    requested_name: Option<&str>,
+) -> Result<WorldImportResult, String> {
+    let file = File::open(archive_path).map_err(|e| format!("open world zip failed: {e}"))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("read world zip failed: {e}"))?;
+    let manifest = read_archive_manifest(&mut archive);
+
+    let mut root: Option<Vec<String>> = None;
+    for i in 0..archive.len() {
+        let entry = archive
+            .by_index(i)
+            .map_err(|e| format!("read world zip entry failed: {e}"))?;
+        if entry.is_dir() {
+            continue;
+        }
+        let Some(mut parts) = safe_entry_parts(&entry) else {
+            continue;
+        };
+        if parts.last().map(String::as_str) != Some("level.dat") {
+            continue;
+        }
+        parts.pop();
+        if root
+            .as_ref()
+            .map_or(true, |current| parts.len() < current.len())
+        {
+            root = Some(parts);
+        }
+    }
+    let root = root.ok_or_else(|| {
+        "The archive has no level.dat, so it is not a Minecraft world.".to_string()
+    })?;
+
+    let base_name = world_folder_name(
+        requested_name
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .or(root.last().map(String::as_str))
+            .or(manifest.as_ref().map(|m| m.world_name.as_str()))
+            .unwrap_or("world"),
+    );
+    fs::create_dir_all(saves_dir).map_err(|e| format!("mkdir saves failed: {e}"))?;
+    let world_id = unique_world_name(saves_dir, &base_name);
+    let staging = saves_dir.join(format!(".{world_id}.importing"));
+    if staging.exists() {
+        fs::remove_dir_all(&staging).map_err(|e| format!("clear import staging failed: {e}"))?;
+    }
+    fs::create_dir_all(&staging).map_err(|e| format!("mkdir import staging failed: {e}"))?;
+
+    let extracted = extract_world_entries(&mut archive, &root, &staging);
+    let files_count = match extracted {
+        Ok(count) => count,
+        Err(err) => {
+            let _ = fs::remove_dir_all(&staging);
+            return Err(err);
+        }
+    };
+    let target = saves_dir.join(&world_id);
+    if let Err(err) = fs::rename(&staging, &target) {
+        let _ = fs::remove_dir_all(&staging);
+        return Err(format!("move imported world into saves failed: {err}"));
+    }
+    Ok(WorldImportResult {
+        renamed: world_id != base_name,
+        world_id,
+        files_count,
+        manifest,
+        missing_mods: Vec::new(),
+    })
+}
+
+fn extract_world_entries(
+    archive: &mut ZipArchive<File>,
+    root: &[String],
+    out_dir: &Path,
+) -> Result<usize, String> {
+    let mut count = 0usize;
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| format!("read world zip entry failed: {e}"))?;
+        if entry.is_dir() {
+            continue;
+        }
+        let Some(parts) = safe_entry_parts(&entry) else {
+            continue;
+        };
+        if parts.len() <= root.len() || parts[..root.len()] != *root {
+            continue;
+        }
+        let rel = &parts[root.len()..];
+        if rel
+            .last()
+            .is_some_and(|name| name.eq_ignore_ascii_case("session.lock"))
+        {
+            continue;
+        }
+        let out_path = rel
+            .iter()
+            .fold(PathBuf::from(out_dir), |path, part| path.join(part));
+        if let Some(parent) = out_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("mkdir world import parent failed: {e}"))?;
+        }
+        let mut out =
+            File::create(&out_path).map_err(|e| format!("import world file failed: {e}"))?;
+        std::io::copy(&mut entry, &mut out)
+            .map_err(|e| format!("import world copy failed: {e}"))?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{temp_root, write_file};
+    use crate::LockEntry;
+
+    fn mod_entry(source: &str, project_id: &str, filename: &str, enabled: bool) -> LockEntry {
+        serde_json::from_value(serde_json::json!({
+            "source": source,
+            "project_id": project_id,
+            "version_id": "v1",
+            "name": filename.trim_end_matches(".jar"),
+            "version_number": "1.0.0",
+            "filename": filename,
+            "content_type": "mods",
+            "enabled": enabled,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn export_then_import_round_trips_and_reports_missing_mods() {
+        let root = temp_root("world-archive");
+        let world = root.join("saves").join("Castle");
+        write_file(&world.join("level.dat"), b"level");
+        write_file(&world.join("region/r.0.0.mca"), b"region");
+        write_file(&world.join("datapacks/extra.zip"), b"pack");
+        write_file(&world.join("session.lock"), b"lock");
+
+        let exporting = Lockfile {
+            version: 2,
+            entries: vec![
+                mod_entry("modrinth", "AANobbMI", "sodium.jar", true),
+                mod_entry("local", "", "custom.jar", true),
+                mod_entry("modrinth", "gone", "disabled.jar", false),
+            ],
+        };
+        let manifest = WorldArchiveManifest {
+            format: WORLD_ARCHIVE_FORMAT,
+            world_name: "Castle".to_string(),
+            mc_version: Some("1.20.1".to_string()),
+            loader: Some("fabric".to_string()),
+            exported_at: "2026-01-01T00:00:00Z".to_string(),
+            includes_datapacks: false,
+            required_mods: required_mods_from_lock(&exporting),
+        };
+        assert_eq!(manifest.required_mods.len(), 2);
+        let zip_path = root.join("castle.zip");
+        assert_eq!(
+            export_world_archive(&world, &zip_path, false, &manifest).unwrap(),
+            2
+        );
+
+        let saves = root.join("other").join("saves");
+        write_file(&saves.join("Castle").join("level.dat"), b"existing");
+        let imported = import_world_archive(&zip_path, &saves, None).unwrap();
+        assert_eq!(imported.world_id, "Castle (2)");
+        assert!(imported.renamed);
+        assert_eq!(imported.files_count, 2);
+        assert_eq!(imported.manifest.as_ref(), Some(&manifest));
+        let dest = saves.join("Castle (2)");
+        assert_eq!(fs::read(dest.join("region/r.0.0.mca")).unwrap(), b"region");
+        assert!(!dest.join("datapacks").exists());
+        assert!(!dest.join("session.lock").exists());
+        assert_eq!(
+            fs::read(saves.join("Castle/level.dat")).unwrap(),
+            b"existing"
+        );
+
+        let importing = Lockfile {
+            version: 2,
+            entries: vec![mod_entry("modrinth", "AANobbMI", "sodium-0.5.jar", true)],
+        };
+        let missing = missing_required_mods(&manifest.required_mods, &importing);
+        assert_eq!(
+            missing
+                .iter()
+                .map(|m| m.filename.as_str())
+                .collect::<Vec<_>>(),
+            vec!["custom.jar"]
+        );
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn import_finds_nested_world_root_and_skips_escaping_entries() {
+        let root = temp_root("world-archive-unsafe");
+        let zip_path = root.join("shared.zip");
+        let mut zip = zip::ZipWriter::new(File::create(&zip_path).unwrap());
+        let options = FileOptions::default();
+        for (name, body) in [
+            ("download/My World/level.dat", b"level".as_slice()),
+            ("download/My World/region/r.0.0.mca", b"region"),
+            ("download/My World/backup/level.dat", b"nested"),
+            ("download/readme.txt", b"outside"),
+            ("__MACOSX/download/My World/._level.dat", b"junk"),
+            ("../escape.txt", b"evil"),
+            ("download/My World/../../escape.txt", b"evil"),
+        ] {
+            zip.start_file(name, options).unwrap();
+            zip.write_all(body).unwrap();
+        }
+        zip.finish().unwrap();
+
+        let saves = root.join("saves");
+        let imported = import_world_archive(&zip_path, &saves, Some("  ")).unwrap();
+        assert_eq!(imported.world_id, "My World");
+        assert!(!imported.renamed);
+        assert!(imported.manifest.is_none());
+        assert_eq!(imported.files_count, 3);
+        let world = saves.join("My World");
+        assert_eq!(fs::read(world.join("level.dat")).unwrap(), b"level");
+        assert_eq!(fs::read(world.join("backup/level.dat")).unwrap(), b"nested");
+        assert!(!world.join("readme.txt").exists());
+        assert!(!root.join("escape.txt").exists());
+        assert!(!saves.join("escape.txt").exists());
+        assert_eq!(
+            fs::read_dir(&saves).unwrap().count(),
+            1,
+            "staging folder should be gone"
+        );
+
+        let renamed = import_world_archive(&zip_path, &saves, Some("a/b:c")).unwrap();
+        assert_eq!(renamed.world_id, "a_b_c");
+
+        let empty = root.join("empty.zip");
+        let mut zip = zip::ZipWriter::new(File::create(&empty).unwrap());
+        zip.start_file("notes.txt", options).unwrap();
+        zip.finish().unwrap();
+        assert!(import_world_archive(&empty, &saves, None).is_err());
+        let _ = fs::remove_dir_all(root);
+    }
+}
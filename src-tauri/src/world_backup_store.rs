@@ -0,0 +1,946 @@
+//! Incremental, deduplicated world backups.
+//!
+//! A backup keeps `manifest.json` (world-relative path → SHA-512) next to its
+//! `meta.json`. File bodies — region files, `level.dat`, playerdata and the
+//! rest — live once in `world_backups/objects`, so each backup only copies
+//! files whose hash changed since the previous backup of that world. Every
+//! manifest lists the whole world, so any backup restores the exact state it
+//! was taken at. Blobs are private copies rather than hardlinks because the
+//! game rewrites region files in place. Legacy `world.zip` backups stay
+//! restorable.
+//!
+//! Backups can also be copied to destinations outside the instance, such as
+//! a NAS mount or a second disk. A destination holds
+//! `<path>/<instance id>/world_backups` in the same layout, so listing,
+//! pruning and restoring work the same way against either root.
+
+use crate::content_store::{BlobCheck, BlobDigest, ContentStore, StoreGcReport};
+use crate::snapshot_store::{
+    modified_ms, read_manifest, write_manifest, SnapshotFile, SnapshotManifest, MANIFEST_VERSION,
+};
+use crate::{
+    created_at_sort_key, list_world_backups, read_world_backup_meta, restore_world_backup_zip,
+    world_backup_zip_path, world_backups_dir, write_world_backup_meta, InstanceSettings,
+    WorldBackupMeta,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+pub(crate) const WORLD_BACKUP_FORMAT_ZIP: &str = "zip";
+pub(crate) const WORLD_BACKUP_FORMAT_INCREMENTAL: &str = "incremental";
+const OBJECTS_DIR: &str = "objects";
+pub(crate) const DESTINATION_MODE_MIRROR: &str = "mirror";
+pub(crate) const DESTINATION_MODE_MOVE: &str = "move";
+/// Location name of the backups kept inside the instance folder.
+pub(crate) const LOCAL_LOCATION: &str = "local";
+
+/// Which backups of a world survive pruning: the newest `keep_recent`, plus
+/// the newest backup in each of the last `hourly` hours, `daily` days and
+/// `weekly` weeks that have one (grandfather-father-son). Buckets are UTC
+/// aligned. `keep_recent == 0` disables pruning.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct WorldBackupRetention {
+    pub keep_recent: usize,
+    pub hourly: usize,
+    pub daily: usize,
+    pub weekly: usize,
+}
+
+impl WorldBackupRetention {
+    pub(crate) fn from_settings(settings: &InstanceSettings) -> Self {
+        Self {
+            keep_recent: settings.world_backup_retention_count as usize,
+            hourly: settings.world_backup_keep_hourly as usize,
+            daily: settings.world_backup_keep_daily as usize,
+            weekly: settings.world_backup_keep_weekly as usize,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct IncrementalBackupStats {
+    pub files_count: usize,
+    pub total_bytes: u64,
+    /// Bytes copied into the object store by this backup.
+    pub new_bytes: u64,
+}
+
+/// Held while world backups of one instance are created, pruned or garbage
+/// collected, so collection never runs between a backup storing its blobs and
+/// writing the manifest that references them.
+pub(crate) fn world_backup_lock(instance_dir: &Path) -> Arc<Mutex<()>> {
+    static LOCKS: OnceLock<Mutex<HashMap<PathBuf, Arc<Mutex<()>>>>> = OnceLock::new();
+    let locks = LOCKS.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut guard = locks.lock().expect("world backup lock registry");
+    guard
+        .entry(world_backups_dir(instance_dir))
+        .or_insert_with(|| Arc::new(Mutex::new(())))
+        .clone()
+}
+
+pub(crate) fn world_backup_objects(instance_dir: &Path) -> ContentStore {
+    ContentStore::new(world_backups_dir(instance_dir).join(OBJECTS_DIR))
+}
+
+/// World-relative backup paths must stay inside the world folder.
+pub(crate) fn world_path_allowed(rel: &str) -> bool {
+    !rel.is_empty()
+        && rel.split('/').all(|part| {
+            !part.trim().is_empty() && part != "." && part != ".." && !part.contains('\\')
+        })
+}
+
+pub(crate) fn collect_world_files(
+    root: &Path,
+    current: &Path,
+    out: &mut Vec<(String, PathBuf, fs::Metadata)>,
+) {
+    let Ok(entries) = fs::read_dir(current) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(meta) = fs::symlink_metadata(&path) else {
+            continue;
+        };
+        if meta.is_dir() {
+            collect_world_files(root, &path, out);
+            continue;
+        }
+        if !meta.is_file()
+            || entry
+                .file_name()
+                .to_string_lossy()
+                .eq_ignore_ascii_case("session.lock")
+        {
+            continue;
+        }
+        let Ok(rel) = path.strip_prefix(root) else {
+            continue;
+        };
+        let rel = rel.to_string_lossy().replace('\\', "/");
+        if world_path_allowed(&rel) {
+            out.push((rel, path, meta));
+        }
+    }
+}
+
+/// Newest intact incremental manifest of `world_id`, used to skip unchanged
+/// files. Backups that failed verification are never used as a base.
+fn latest_world_manifest(instance_dir: &Path, world_id: &str) -> Option<SnapshotManifest> {
+    let root = world_backups_dir(instance_dir);
+    list_world_backups(instance_dir)
+        .ok()?
+        .into_iter()
+        .filter(|meta| meta.world_id == world_id && meta.integrity_error.is_none())
+        .find_map(|meta| read_manifest(&root.join(meta.id)))
+}
+
+/// Writes an incremental backup of `world_dir` into `backup_dir`.
+pub(crate) fn create_incremental_world_backup(
+    instance_dir: &Path,
+    world_id: &str,
+    world_dir: &Path,
+    backup_dir: &Path,
+) -> Result<IncrementalBackupStats, String> {
+    let store = world_backup_objects(instance_dir);
+    let previous = latest_world_manifest(instance_dir, world_id);
+    let known = previous
+        .as_ref()
+        .map(|manifest| {
+            manifest
+                .files
+                .iter()
+                .map(|file| (file.path.as_str(), file))
+                .collect::<HashMap<_, _>>()
+        })
+        .unwrap_or_default();
+    let mut files = Vec::new();
+    collect_world_files(world_dir, world_dir, &mut files);
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut stats = IncrementalBackupStats::default();
+    let mut manifest = SnapshotManifest {
+        version: MANIFEST_VERSION,
+        files: Vec::new(),
+    };
+    // Reused blobs are re-hashed, so a damaged one is stored afresh instead
+    // of being carried into every later backup.
+    let mut intact = HashMap::new();
+    for (rel, path, meta) in files {
+        let modified = modified_ms(&meta);
+        let unchanged = known.get(rel.as_str()).filter(|file| {
+            file.size == meta.len()
+                && file.modified_ms != 0
+                && file.modified_ms == modified
+                && *intact
+                    .entry(file.sha512.clone())
+                    .or_insert_with(|| store.check(&file.sha512) == BlobCheck::Intact)
+        });
+        let (sha512, size) = match unchanged {
+            Some(file) => (file.sha512.clone(), file.size),
+            None => {
+                let (digest, fresh) = match store.put_file_copy(&path) {
+                    Ok(stored) => stored,
+                    // Files the game removed mid-backup are simply skipped.
+                    Err(_) if !path.exists() => continue,
+                    Err(err) => return Err(err),
+                };
+                if fresh {
+                    stats.new_bytes = stats.new_bytes.saturating_add(digest.len);
+                }
+                (digest.sha512, digest.len)
+            }
+        };
+        stats.files_count += 1;
+        stats.total_bytes = stats.total_bytes.saturating_add(size);
+        manifest.files.push(SnapshotFile {
+            path: rel,
+            sha512,
+            size,
+            modified_ms: modified,
+        });
+    }
+    fs::create_dir_all(backup_dir).map_err(|e| format!("mkdir world backup failed: {e}"))?;
+    write_manifest(backup_dir, &manifest)?;
+    Ok(stats)
+}
+
+/// Replaces `world_dir` with the backup's files after verifying every blob.
+fn restore_incremental_world_backup(
+    instance_dir: &Path,
+    backup_dir: &Path,
+    world_dir: &Path,
+) -> Result<usize, String> {
+    let manifest =
+        read_manifest(backup_dir).ok_or_else(|| "read world backup manifest failed".to_string())?;
+    let store = world_backup_objects(instance_dir);
+    let mut checked = HashMap::new();
+    let damaged = manifest
+        .files
+        .iter()
+        .filter(|file| {
+            !world_path_allowed(&file.path)
+                || !*checked
+                    .entry(file.sha512.as_str())
+                    .or_insert_with(|| store.verify(&file.sha512))
+        })
+        .map(|file| file.path.as_str())
+        .collect::<Vec<_>>();
+    if !damaged.is_empty() {
+        return Err(format!(
+            "World backup is missing or has damaged {} file(s): {}",
+            damaged.len(),
+            damaged.join(", ")
+        ));
+    }
+    if world_dir.exists() {
+        fs::remove_dir_all(world_dir).map_err(|e| format!("clear world dir failed: {e}"))?;
+    }
+    fs::create_dir_all(world_dir).map_err(|e| format!("mkdir world dir failed: {e}"))?;
+    for file in &manifest.files {
+        let out_path = world_dir.join(&file.path);
+        if let Some(parent) = out_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("mkdir world restore parent failed: {e}"))?;
+        }
+        store.copy_out(&file.sha512, &out_path)?;
+    }
+    Ok(manifest.files.len())
+}
+
+/// Restores a world backup of either format into `world_dir`.
+pub(crate) fn restore_world_backup(
+    instance_dir: &Path,
+    backup_dir: &Path,
+    world_dir: &Path,
+) -> Result<usize, String> {
+    if read_manifest(backup_dir).is_some() {
+        restore_incremental_world_backup(instance_dir, backup_dir, world_dir)
+    } else {
+        restore_world_backup_zip(&world_backup_zip_path(backup_dir), world_dir)
+    }
+}
+
+/// Newest intact backup of `world_id` taken at or before `at` (RFC 3339).
+pub(crate) fn world_backup_at(
+    backups: Vec<WorldBackupMeta>,
+    world_id: &str,
+    at: &str,
+) -> Result<WorldBackupMeta, String> {
+    let cutoff = created_at_sort_key(at);
+    if cutoff <= 0 {
+        return Err(format!("Invalid restore time '{at}'"));
+    }
+    backups
+        .into_iter()
+        .filter(|meta| meta.world_id == world_id && meta.integrity_error.is_none())
+        .find(|meta| {
+            let created = created_at_sort_key(&meta.created_at);
+            created > 0 && created <= cutoff
+        })
+        .ok_or_else(|| format!("No backup of '{world_id}' exists from before {at}"))
+}
+
+/// Ids of the backups the retention policy keeps. `metas` must be newest first.
+pub(crate) fn retained_world_backups(
+    metas: &[WorldBackupMeta],
+    retention: WorldBackupRetention,
+) -> HashSet<String> {
+    let mut by_world: HashMap<&str, Vec<&WorldBackupMeta>> = HashMap::new();
+    for meta in metas {
+        by_world
+            .entry(meta.world_id.as_str())
+            .or_default()
+            .push(meta);
+    }
+    let mut keep = HashSet::new();
+    for backups in by_world.values() {
+        if retention.keep_recent == 0 {
+            keep.extend(backups.iter().map(|meta| meta.id.clone()));
+            continue;
+        }
+        keep.extend(
+            backups
+                .iter()
+                .take(retention.keep_recent)
+                .map(|meta| meta.id.clone()),
+        );
+        for (span, count) in [
+            (3_600i64, retention.hourly),
+            (86_400, retention.daily),
+            (604_800, retention.weekly),
+        ] {
+            let mut buckets = HashSet::new();
+            for meta in backups {
+                if buckets.len() >= count {
+                    break;
+                }
+                let created = created_at_sort_key(&meta.created_at);
+                if created > 0 && buckets.insert(created.div_euclid(span)) {
+                    keep.insert(meta.id.clone());
+                }
+            }
+        }
+    }
+    keep
+}
+
+/// Backup folders the retention policy would remove, optionally for one world.
+pub(crate) fn world_backup_prune_targets(
+    instance_dir: &Path,
+    world_id: Option<&str>,
+    retention: WorldBackupRetention,
+) -> Result<Vec<PathBuf>, String> {
+    let metas = list_world_backups(instance_dir)?;
+    let keep = retained_world_backups(&metas, retention);
+    let root = world_backups_dir(instance_dir);
+    Ok(metas
+        .into_iter()
+        .filter(|meta| world_id.map_or(true, |world| meta.world_id == world))
+        .filter(|meta| !keep.contains(&meta.id))
+        .map(|meta| root.join(meta.id))
+        .filter(|dir| dir.exists())
+        .collect())
+}
+
+fn live_world_objects(instance_dir: &Path, excluding: &[PathBuf]) -> HashMap<String, usize> {
+    let mut live = HashMap::new();
+    let Ok(entries) = fs::read_dir(world_backups_dir(instance_dir)) else {
+        return live;
+    };
+    for entry in entries.flatten() {
+        let dir = entry.path();
+        if excluding.contains(&dir) {
+            continue;
+        }
+        if let Some(manifest) = read_manifest(&dir) {
+            for file in manifest.files {
+                *live.entry(file.sha512).or_insert(0) += 1;
+            }
+        }
+    }
+    live
+}
+
+/// Object bytes that only the backups in `targets` reference.
+pub(crate) fn exclusive_object_bytes(instance_dir: &Path, targets: &[PathBuf]) -> u64 {
+    let live = live_world_objects(instance_dir, targets);
+    let store = world_backup_objects(instance_dir);
+    let mut seen = HashSet::new();
+    targets
+        .iter()
+        .filter_map(|dir| read_manifest(dir))
+        .flat_map(|manifest| manifest.files)
+        .filter(|file| !live.contains_key(&file.sha512) && seen.insert(file.sha512.clone()))
+        .filter_map(|file| store.blob_len(&file.sha512))
+        .sum()
+}
+
+/// Drops objects no remaining world backup references. Callers hold
+/// [`world_backup_lock`].
+pub(crate) fn collect_world_backup_objects(instance_dir: &Path) -> StoreGcReport {
+    let live = live_world_objects(instance_dir, &[]);
+    world_backup_objects(instance_dir).remove_unreferenced(&live)
+}
+
+/// Folder outside the instance that world backups are copied to.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub(crate) struct WorldBackupDestination {
+    pub path: String,
+    /// `mirror` keeps the local backup as well; `move` keeps only the newest
+    /// local backup of each world, as the base for the next incremental one,
+    /// and drops older ones once they have a verified copy here.
+    #[serde(default = "default_destination_mode")]
+    pub mode: String,
+    #[serde(default)]
+    pub keep_recent: u32,
+    #[serde(default)]
+    pub keep_hourly: u32,
+    #[serde(default)]
+    pub keep_daily: u32,
+    #[serde(default)]
+    pub keep_weekly: u32,
+}
+
+fn default_destination_mode() -> String {
+    DESTINATION_MODE_MIRROR.to_string()
+}
+
+impl WorldBackupDestination {
+    fn retention(&self) -> WorldBackupRetention {
+        WorldBackupRetention {
+            keep_recent: self.keep_recent as usize,
+            hourly: self.keep_hourly as usize,
+            daily: self.keep_daily as usize,
+            weekly: self.keep_weekly as usize,
+        }
+    }
+
+    /// Stands in for the instance folder at this destination.
+    fn instance_root(&self, instance_id: &str) -> PathBuf {
+        Path::new(self.path.trim()).join(instance_id)
+    }
+
+    /// Destinations are never created; an unmounted drive must not turn into
+    /// a folder on the local disk.
+    fn available(&self) -> bool {
+        Path::new(self.path.trim()).is_dir()
+    }
+}
+
+/// Trims paths, drops blanks and duplicates, and clamps retention.
+pub(crate) fn normalize_world_backup_destinations(
+    destinations: Vec<WorldBackupDestination>,
+) -> Vec<WorldBackupDestination> {
+    let mut seen = HashSet::new();
+    destinations
+        .into_iter()
+        .filter_map(|dest| {
+            let path = dest.path.trim().to_string();
+            if path.is_empty() || !seen.insert(path.clone()) {
+                return None;
+            }
+            let mode = if dest.mode.trim().eq_ignore_ascii_case(DESTINATION_MODE_MOVE) {
+                DESTINATION_MODE_MOVE
+            } else {
+                DESTINATION_MODE_MIRROR
+            };
+            Some(WorldBackupDestination {
+                path,
+                mode: mode.to_string(),
+                keep_recent: dest.keep_recent.min(500),
+                keep_hourly: dest.keep_hourly.min(48),
+                keep_daily: dest.keep_daily.min(31),
+                keep_weekly: dest.keep_weekly.min(12),
+            })
+        })
+        .collect()
+}
+
+/// Instance destinations followed by the launcher-wide ones; an instance
+/// entry wins over a global one with the same path.
+pub(crate) fn world_backup_destinations_for(
+    launcher: &[WorldBackupDestination],
+    instance: &[WorldBackupDestination],
+) -> Vec<WorldBackupDestination> {
+    normalize_world_backup_destinations(instance.iter().chain(launcher).cloned().collect())
+}
+
+/// Copies one local backup to `destination`. Blobs are hashed after landing
+/// and the backup only becomes visible there once everything checked out.
+fn copy_world_backup_to_destination(
+    instance_dir: &Path,
+    instance_id: &str,
+    backup_id: &str,
+    destination: &WorldBackupDestination,
+) -> Result<(), String> {
+    if !destination.available() {
+        return Err(format!(
+            "Backup destination '{}' is not available",
+            destination.path
+        ));
+    }
+    let source_dir = world_backups_dir(instance_dir).join(backup_id);
+    let meta = read_world_backup_meta(&source_dir)?;
+    let root = destination.instance_root(instance_id);
+    let target_dir = world_backups_dir(&root).join(backup_id);
+    if read_world_backup_meta(&target_dir).is_ok() {
+        return Ok(());
+    }
+    let staging = world_backups_dir(&root).join(format!(".{backup_id}.partial"));
+    if staging.exists() {
+        fs::remove_dir_all(&staging)
+            .map_err(|e| format!("clear partial destination backup failed: {e}"))?;
+    }
+    fs::create_dir_all(&staging).map_err(|e| format!("mkdir destination backup failed: {e}"))?;
+    if let Some(manifest) = read_manifest(&source_dir) {
+        let local = world_backup_objects(instance_dir);
+        let remote = world_backup_objects(&root);
+        for file in &manifest.files {
+            remote.copy_blob_from(&local, &file.sha512)?;
+        }
+        write_manifest(&staging, &manifest)?;
+    } else {
+        let source_zip = world_backup_zip_path(&source_dir);
+        let target_zip = world_backup_zip_path(&staging);
+        fs::copy(&source_zip, &target_zip)
+            .map_err(|e| format!("copy world backup archive failed: {e}"))?;
+        if BlobDigest::of_file(&source_zip)?.sha512 != BlobDigest::of_file(&target_zip)?.sha512 {
+            return Err("copied world backup archive failed verification".to_string());
+        }
+    }
+    write_world_backup_meta(&staging, &meta)?;
+    fs::rename(&staging, &target_dir)
+        .map_err(|e| format!("finalize destination backup failed: {e}"))
+}
+
+fn prune_destination(
+    destination: &WorldBackupDestination,
+    instance_id: &str,
+    world_id: &str,
+) -> Result<(), String> {
+    let root = destination.instance_root(instance_id);
+    let targets = world_backup_prune_targets(&root, Some(world_id), destination.retention())?;
+    if targets.is_empty() {
+        return Ok(());
+    }
+    for dir in targets {
+        fs::remove_dir_all(&dir).map_err(|e| format!("remove old world backup failed: {e}"))?;
+    }
+    collect_world_backup_objects(&root);
+    Ok(())
+}
+
+/// Copies a new local backup to every destination and applies each
+/// destination's retention. When `move` destinations are configured, older
+/// local backups of the world that every one of them holds are dropped; the
+/// new one stays so the next backup can reuse its unchanged files. Returns
+/// one message per destination that failed.
+pub(crate) fn mirror_world_backup(
+    instance_dir: &Path,
+    instance_id: &str,
+    meta: &WorldBackupMeta,
+    destinations: &[WorldBackupDestination],
+) -> Vec<String> {
+    let mut errors = Vec::new();
+    for destination in destinations {
+        let result =
+            copy_world_backup_to_destination(instance_dir, instance_id, &meta.id, destination)
+                .and_then(|_| prune_destination(destination, instance_id, &meta.world_id));
+        if let Err(err) = result {
+            errors.push(format!("{}: {err}", destination.path));
+        }
+    }
+    let move_destinations = destinations
+        .iter()
+        .filter(|dest| dest.mode == DESTINATION_MODE_MOVE)
+        .collect::<Vec<_>>();
+    if move_destinations.is_empty() || !move_destinations.iter().all(|dest| dest.available()) {
+        return errors;
+    }
+    let move_roots = move_destinations
+        .iter()
+        .map(|dest| world_backups_dir(&dest.instance_root(instance_id)))
+        .collect::<Vec<_>>();
+    let root = world_backups_dir(instance_dir);
+    let mut removed = false;
+    for older in list_world_backups(instance_dir).unwrap_or_default() {
+        if older.world_id != meta.world_id || older.id == meta.id {
+            continue;
+        }
+        let held_everywhere = move_roots
+            .iter()
+            .all(|dest| read_world_backup_meta(&dest.join(&older.id)).is_ok());
+        if held_everywhere && fs::remove_dir_all(root.join(&older.id)).is_ok() {
+            removed = true;
+        }
+    }
+    if removed {
+        collect_world_backup_objects(instance_dir);
+    }
+    errors
+}
+
+/// A backup together with every location that holds it.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct WorldBackupListing {
+    #[serde(flatten)]
+    pub meta: WorldBackupMeta,
+    /// `local` and/or destination paths holding an intact copy.
+    pub locations: Vec<String>,
+    /// Locations whose copy failed its last integrity check.
+    pub corrupt_locations: Vec<String>,
+}
+
+impl WorldBackupListing {
+    /// Whether some location still holds a copy that passed verification.
+    pub(crate) fn restorable(&self) -> bool {
+        !self.locations.is_empty()
+    }
+}
+
+pub(crate) fn backup_roots(
+    instance_dir: &Path,
+    instance_id: &str,
+    destinations: &[WorldBackupDestination],
+) -> Vec<(String, PathBuf)> {
+    let mut roots = vec![(LOCAL_LOCATION.to_string(), instance_dir.to_path_buf())];
+    for destination in destinations.iter().filter(|dest| dest.available()) {
+        roots.push((
+            destination.path.clone(),
+            destination.instance_root(instance_id),
+        ));
+    }
+    roots
+}
+
+/// Backups from the instance folder and every reachable destination, newest
+/// first. Unreachable destinations are left out.
+pub(crate) fn list_world_backups_with_locations(
+    instance_dir: &Path,
+    instance_id: &str,
+    destinations: &[WorldBackupDestination],
+) -> Vec<WorldBackupListing> {
+    let mut listings: Vec<WorldBackupListing> = Vec::new();
+    for (location, root) in backup_roots(instance_dir, instance_id, destinations) {
+        for meta in list_world_backups(&root).unwrap_or_default() {
+            let intact = meta.integrity_error.is_none();
+            let index = match listings.iter().position(|entry| entry.meta.id == meta.id) {
+                Some(index) => index,
+                None => {
+                    listings.push(WorldBackupListing {
+                        meta: meta.clone(),
+                        locations: Vec::new(),
+                        corrupt_locations: Vec::new(),
+                    });
+                    listings.len() - 1
+                }
+            };
+            let entry = &mut listings[index];
+            if intact {
+                if !entry.restorable() {
+                    entry.meta = meta;
+                }
+                entry.locations.push(location.clone());
+            } else {
+                entry.corrupt_locations.push(location.clone());
+            }
+        }
+    }
+    listings.sort_by(|a, b| {
+        created_at_sort_key(&b.meta.created_at).cmp(&created_at_sort_key(&a.meta.created_at))
+    });
+    listings
+}
+
+/// Root holding `backup_id`, taken from `location` when given and otherwise
+/// the first location with an intact copy, local first.
+pub(crate) fn locate_world_backup(
+    instance_dir: &Path,
+    instance_id: &str,
+    destinations: &[WorldBackupDestination],
+    backup_id: &str,
+    location: Option<&str>,
+) -> Result<PathBuf, String> {
+    let candidates = backup_roots(instance_dir, instance_id, destinations)
+        .into_iter()
+        .filter(|(name, _)| location.map_or(true, |wanted| wanted == name))
+        .filter_map(|(_, root)| {
+            let meta = read_world_backup_meta(&world_backups_dir(&root).join(backup_id)).ok()?;
+            Some((root, meta.integrity_error.is_none()))
+        })
+        .collect::<Vec<_>>();
+    candidates
+        .iter()
+        .find(|(_, intact)| *intact)
+        .or_else(|| candidates.first())
+        .map(|(root, _)| root.clone())
+        .ok_or_else(|| match location {
+            Some(wanted) => format!("World backup is not available at '{wanted}'"),
+            None => "World backup not found".to_string(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{temp_root, write_file};
+    use crate::write_world_backup_meta;
+
+    fn meta(id: &str, world: &str, created_at: &str) -> WorldBackupMeta {
+        WorldBackupMeta {
+            id: id.to_string(),
+            world_id: world.to_string(),
+            created_at: created_at.to_string(),
+            reason: "test".to_string(),
+            files_count: 0,
+            total_bytes: 0,
+            format: WORLD_BACKUP_FORMAT_INCREMENTAL.to_string(),
+            new_bytes: 0,
+            verified_at: None,
+            integrity_error: None,
+        }
+    }
+
+    fn backup(instance: &Path, world: &Path, id: &str, created_at: &str) -> IncrementalBackupStats {
+        let dir = world_backups_dir(instance).join(id);
+        let stats =
+            create_incremental_world_backup(instance, "World", world, &dir).expect("backup");
+        write_world_backup_meta(&dir, &meta(id, "World", created_at)).expect("meta");
+        stats
+    }
+
+    #[test]
+    fn unchanged_regions_are_stored_once_and_restores_are_point_in_time() {
+        let root = temp_root("incremental");
+        let instance = root.join("instance");
+        let world = instance.join("saves").join("World");
+        write_file(&world.join("level.dat"), b"level v1");
+        write_file(&world.join("region").join("r.0.0.mca"), &[7u8; 4096]);
+        write_file(&world.join("region").join("r.0.1.mca"), &[8u8; 4096]);
+        write_file(&world.join("session.lock"), b"lock");
+
+        let first = backup(&instance, &world, "wb_1", "2026-01-01T10:00:00Z");
+        assert_eq!(first.files_count, 3);
+        assert_eq!(first.new_bytes, 8 + 8192);
+
+        write_file(&world.join("level.dat"), b"level two");
+        write_file(&world.join("playerdata").join("p.dat"), b"player");
+        let second = backup(&instance, &world, "wb_2", "2026-01-01T11:00:00Z");
+        assert_eq!(second.files_count, 4);
+        // Only level.dat and the new player file were copied.
+        assert_eq!(second.new_bytes, 9 + 6);
+
+        write_file(&world.join("region").join("r.0.0.mca"), &[9u8; 4096]);
+        assert_eq!(
+            restore_world_backup(
+                &instance,
+                &world_backups_dir(&instance).join("wb_1"),
+                &world
+            )
+            .expect("restore"),
+            3
+        );
+        assert_eq!(fs::read(world.join("level.dat")).unwrap(), b"level v1");
+        assert_eq!(
+            fs::read(world.join("region").join("r.0.0.mca")).unwrap(),
+            vec![7u8; 4096]
+        );
+        assert!(!world.join("playerdata").exists());
+        assert!(!world.join("session.lock").exists());
+
+        // Restored files are private copies; editing them leaves backups intact.
+        fs::write(world.join("level.dat"), b"edited").unwrap();
+        restore_world_backup(
+            &instance,
+            &world_backups_dir(&instance).join("wb_2"),
+            &world,
+        )
+        .expect("restore second");
+        assert_eq!(fs::read(world.join("level.dat")).unwrap(), b"level two");
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn damaged_objects_are_not_reused_by_later_backups() {
+        use sha2::Digest as _;
+        let root = temp_root("damaged-base");
+        let instance = root.join("instance");
+        let world = instance.join("saves").join("World");
+        write_file(&world.join("region").join("r.0.0.mca"), &[3u8; 2048]);
+        backup(&instance, &world, "wb_1", "2026-01-01T10:00:00Z");
+
+        let hash = format!("{:x}", sha2::Sha512::digest([3u8; 2048]));
+        let blob = world_backups_dir(&instance)
+            .join(OBJECTS_DIR)
+            .join("sha512")
+            .join(&hash[..2])
+            .join(&hash);
+        fs::write(&blob, b"bit rot").unwrap();
+
+        let second = backup(&instance, &world, "wb_2", "2026-01-01T11:00:00Z");
+        assert_eq!(second.new_bytes, 2048);
+        fs::remove_dir_all(&world).unwrap();
+        restore_world_backup(
+            &instance,
+            &world_backups_dir(&instance).join("wb_2"),
+            &world,
+        )
+        .expect("second backup restores");
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn pruning_collects_only_unreferenced_objects() {
+        let root = temp_root("gc");
+        let instance = root.join("instance");
+        let world = instance.join("saves").join("World");
+        write_file(&world.join("region").join("r.0.0.mca"), &[1u8; 1024]);
+        write_file(&world.join("level.dat"), b"one");
+        backup(&instance, &world, "wb_1", "2026-01-01T10:00:00Z");
+        write_file(&world.join("level.dat"), b"second");
+        backup(&instance, &world, "wb_2", "2026-01-01T11:00:00Z");
+
+        let retention = WorldBackupRetention {
+            keep_recent: 1,
+            ..WorldBackupRetention::default()
+        };
+        let targets = world_backup_prune_targets(&instance, Some("World"), retention).unwrap();
+        assert_eq!(targets, vec![world_backups_dir(&instance).join("wb_1")]);
+        assert_eq!(exclusive_object_bytes(&instance, &targets), 3);
+        for target in &targets {
+            fs::remove_dir_all(target).unwrap();
+        }
+        let report = collect_world_backup_objects(&instance);
+        assert_eq!((report.removed_blobs, report.reclaimed_bytes), (1, 3));
+        restore_world_backup(
+            &instance,
+            &world_backups_dir(&instance).join("wb_2"),
+            &world,
+        )
+        .expect("remaining backup restores");
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn gfs_retention_keeps_one_backup_per_bucket() {
+        let metas = [
+            meta("h12", "W", "2026-03-10T12:40:00Z"),
+            meta("h12b", "W", "2026-03-10T12:10:00Z"),
+            meta("h11", "W", "2026-03-10T11:30:00Z"),
+            meta("d09", "W", "2026-03-09T18:00:00Z"),
+            meta("d08", "W", "2026-03-08T18:00:00Z"),
+            meta("w01", "W", "2026-03-01T18:00:00Z"),
+            meta("w00", "W", "2026-02-20T18:00:00Z"),
+            meta("other", "Other", "2026-01-01T00:00:00Z"),
+        ];
+        let keep = retained_world_backups(
+            &metas,
+            WorldBackupRetention {
+                keep_recent: 1,
+                hourly: 2,
+                daily: 2,
+                weekly: 3,
+            },
+        );
+        let mut kept = keep.iter().map(String::as_str).collect::<Vec<_>>();
+        kept.sort();
+        assert_eq!(kept, vec!["d09", "h11", "h12", "other", "w00", "w01"]);
+
+        let all = retained_world_backups(&metas, WorldBackupRetention::default());
+        assert_eq!(all.len(), metas.len());
+    }
+
+    #[test]
+    fn point_in_time_picks_the_newest_backup_before_the_cutoff() {
+        let metas = vec![
+            meta("b3", "W", "2026-03-10T12:00:00Z"),
+            meta("b2", "W", "2026-03-10T11:00:00Z"),
+            meta("b1", "W", "2026-03-10T10:00:00Z"),
+        ];
+        let picked = world_backup_at(metas.clone(), "W", "2026-03-10T11:30:00Z").unwrap();
+        assert_eq!(picked.id, "b2");
+        assert!(world_backup_at(metas, "W", "2026-03-10T09:00:00Z").is_err());
+    }
+
+    fn destination(path: &Path, mode: &str) -> WorldBackupDestination {
+        WorldBackupDestination {
+            path: path.display().to_string(),
+            mode: mode.to_string(),
+            keep_recent: 0,
+            keep_hourly: 0,
+            keep_daily: 0,
+            keep_weekly: 0,
+        }
+    }
+
+    #[test]
+    fn destinations_hold_verified_copies_that_restore_on_their_own() {
+        let root = temp_root("destinations");
+        let instance = root.join("instance");
+        let world = instance.join("saves").join("World");
+        let nas = root.join("nas");
+        let second_disk = root.join("second-disk");
+        let unmounted = root.join("unmounted");
+        fs::create_dir_all(&nas).unwrap();
+        fs::create_dir_all(&second_disk).unwrap();
+        write_file(&world.join("level.dat"), b"level v1");
+        write_file(&world.join("region").join("r.0.0.mca"), &[3u8; 2048]);
+
+        backup(&instance, &world, "wb_1", "2026-02-01T10:00:00Z");
+        let first = read_world_backup_meta(&world_backups_dir(&instance).join("wb_1")).unwrap();
+        let mirrors = vec![
+            destination(&nas, DESTINATION_MODE_MIRROR),
+            destination(&unmounted, DESTINATION_MODE_MIRROR),
+        ];
+        let errors = mirror_world_backup(&instance, "inst", &first, &mirrors);
+        assert_eq!(errors.len(), 1);
+        assert!(!unmounted.exists());
+        let listed = list_world_backups_with_locations(&instance, "inst", &mirrors);
+        assert_eq!(
+            listed[0].locations,
+            vec![LOCAL_LOCATION.to_string(), nas.display().to_string()]
+        );
+
+        write_file(&world.join("level.dat"), b"level two");
+        backup(&instance, &world, "wb_2", "2026-02-01T11:00:00Z");
+        let second = read_world_backup_meta(&world_backups_dir(&instance).join("wb_2")).unwrap();
+        let moved = vec![destination(&second_disk, DESTINATION_MODE_MOVE)];
+        assert!(mirror_world_backup(&instance, "inst", &second, &moved).is_empty());
+        // The newest backup stays local as the base of the next one.
+        assert!(world_backups_dir(&instance).join("wb_2").exists());
+
+        write_file(&world.join("level.dat"), b"level three");
+        let third_stats = backup(&instance, &world, "wb_3", "2026-02-01T12:00:00Z");
+        assert_eq!(third_stats.new_bytes, b"level three".len() as u64);
+        let third = read_world_backup_meta(&world_backups_dir(&instance).join("wb_3")).unwrap();
+        assert!(mirror_world_backup(&instance, "inst", &third, &moved).is_empty());
+        assert!(!world_backups_dir(&instance).join("wb_2").exists());
+        assert!(world_backups_dir(&instance).join("wb_3").exists());
+
+        let everywhere = [mirrors, moved].concat();
+        let listed = list_world_backups_with_locations(&instance, "inst", &everywhere);
+        assert_eq!(listed[1].meta.id, "wb_2");
+        assert_eq!(listed[1].locations, vec![second_disk.display().to_string()]);
+
+        // The local object store lost nothing wb_1 still needs.
+        fs::remove_dir_all(&world).unwrap();
+        let local = locate_world_backup(&instance, "inst", &everywhere, "wb_1", None).unwrap();
+        assert_eq!(local, instance);
+        restore_world_backup(&local, &world_backups_dir(&local).join("wb_1"), &world).unwrap();
+        assert_eq!(fs::read(world.join("level.dat")).unwrap(), b"level v1");
+
+        let remote = locate_world_backup(&instance, "inst", &everywhere, "wb_2", None).unwrap();
+        restore_world_backup(&remote, &world_backups_dir(&remote).join("wb_2"), &world).unwrap();
+        assert_eq!(fs::read(world.join("level.dat")).unwrap(), b"level two");
+        assert!(
+            locate_world_backup(&instance, "inst", &everywhere, "wb_2", Some(LOCAL_LOCATION))
+                .is_err()
+        );
+        let _ = fs::remove_dir_all(root);
+    }
+}
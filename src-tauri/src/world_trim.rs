@@ -0,0 +1,557 @@
+//! Removes chunks players barely spent time in from a world's region files.
+//!
+//! A chunk is dropped when its `InhabitedTime` is below the threshold and it
+//! lies outside the protected radius around the world spawn and every
+//! player's position and respawn point in that dimension. The game
+//! regenerates dropped chunks from the seed when someone walks back there.
+//! The matching chunk is also dropped from `entities/` and `poi/` so no
+//! orphaned mobs or villager workstations reappear. Chunks that cannot be
+//! read are always kept.
+
+use crate::anvil::{read_region, region_coords, RegionFile};
+use crate::nbt::{read_nbt, NbtTag};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub(crate) const DEFAULT_MIN_INHABITED_TICKS: i64 = 20 * 60;
+pub(crate) const DEFAULT_PROTECT_RADIUS_CHUNKS: i32 = 8;
+pub(crate) const MAX_PROTECT_RADIUS_CHUNKS: i32 = 256;
+const OVERWORLD: &str = "minecraft:overworld";
+/// Folders next to `region/` holding per-chunk data keyed the same way.
+const COMPANION_DIRS: [&str; 2] = ["entities", "poi"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct WorldTrimOptions {
+    pub min_inhabited_ticks: i64,
+    pub protect_radius_chunks: i32,
+    /// Only count what would be removed; nothing is written.
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub(crate) struct WorldTrimReport {
+    pub world_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_id: Option<String>,
+    pub dry_run: bool,
+    pub regions_scanned: usize,
+    pub regions_rewritten: usize,
+    pub regions_removed: usize,
+    pub chunks_scanned: usize,
+    pub chunks_removed: usize,
+    /// Chunks kept because their data could not be read.
+    pub chunks_unreadable: usize,
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+    pub bytes_reclaimed: u64,
+}
+
+/// Protected chunk positions per dimension id.
+/// Chunk coordinates are kept as `i64`: positions come from save data and
+/// region file names, so nothing bounds them to the playable area.
+type Protected = HashMap<String, Vec<(i64, i64)>>;
+
+fn dimension_id(tag: &NbtTag) -> Option<String> {
+    if let Some(name) = tag.as_str() {
+        let name = name.trim();
+        if name.is_empty() {
+            return None;
+        }
+        return Some(if name.contains(':') {
+            name.to_string()
+        } else {
+            format!("minecraft:{name}")
+        });
+    }
+    match tag.as_i64()? {
+        0 => Some(OVERWORLD.to_string()),
+        -1 => Some("minecraft:the_nether".to_string()),
+        1 => Some("minecraft:the_end".to_string()),
+        _ => None,
+    }
+}
+
+fn block_to_chunk(block: i64) -> i64 {
+    block >> 4
+}
+
+/// Chunk of an `[x, y, z]` block position stored as an int array.
+fn int_pos_chunk(tag: &NbtTag) -> Option<(i64, i64)> {
+    match tag {
+        NbtTag::IntArray(values) if values.len() == 3 => Some((
+            block_to_chunk(i64::from(values[0])),
+            block_to_chunk(i64::from(values[2])),
+        )),
+        _ => None,
+    }
+}
+
+/// Adds a player's position and respawn point, covering both the older
+/// `SpawnX`/`SpawnDimension` fields and the newer `respawn` compound.
+fn protect_player(player: &NbtTag, protected: &mut Protected) {
+    let dimension = player
+        .get("Dimension")
+        .and_then(dimension_id)
+        .unwrap_or_else(|| OVERWORLD.to_string());
+    if let Some([NbtTag::Double(x), _, NbtTag::Double(z)]) =
+        player.get("Pos").and_then(NbtTag::as_list)
+    {
+        if x.is_finite() && z.is_finite() {
+            protected.entry(dimension).or_default().push((
+                block_to_chunk(x.floor() as i64),
+                block_to_chunk(z.floor() as i64),
+            ));
+        }
+    }
+    if let (Some(x), Some(z)) = (
+        player.get("SpawnX").and_then(NbtTag::as_i64),
+        player.get("SpawnZ").and_then(NbtTag::as_i64),
+    ) {
+        let dimension = player
+            .get("SpawnDimension")
+            .and_then(dimension_id)
+            .unwrap_or_else(|| OVERWORLD.to_string());
+        protected
+            .entry(dimension)
+            .or_default()
+            .push((block_to_chunk(x), block_to_chunk(z)));
+    }
+    if let Some(respawn) = player.get("respawn") {
+        if let Some(chunk) = respawn.get("pos").and_then(int_pos_chunk) {
+            let dimension = respawn
+                .get("dimension")
+                .and_then(dimension_id)
+                .unwrap_or_else(|| OVERWORLD.to_string());
+            protected.entry(dimension).or_default().push(chunk);
+        }
+    }
+}
+
+fn protected_positions(world_dir: &Path) -> Result<Protected, String> {
+    let mut protected = Protected::new();
+    let level = fs::read(world_dir.join("level.dat"))
+        .map_err(|e| format!("read level.dat failed: {e}"))
+        .and_then(|bytes| read_nbt(&bytes))
+        .map_err(|err| format!("Cannot find the world spawn: {err}"))?
+        .1;
+    let data = level
+        .get("Data")
+        .ok_or_else(|| "Cannot find the world spawn: level.dat has no Data".to_string())?;
+    // Newer releases keep the spawn in a `spawn` compound.
+    if let Some(chunk) = data.path(&["spawn", "pos"]).and_then(int_pos_chunk) {
+        let dimension = data
+            .path(&["spawn", "dimension"])
+            .and_then(dimension_id)
+            .unwrap_or_else(|| OVERWORLD.to_string());
+        protected.entry(dimension).or_default().push(chunk);
+    } else if let (Some(x), Some(z)) = (
+        data.get("SpawnX").and_then(NbtTag::as_i64),
+        data.get("SpawnZ").and_then(NbtTag::as_i64),
+    ) {
+        protected
+            .entry(OVERWORLD.to_string())
+            .or_default()
+            .push((block_to_chunk(x), block_to_chunk(z)));
+    }
+    if let Some(player) = data.get("Player") {
+        protect_player(player, &mut protected);
+    }
+    for dir in ["playerdata", "players"] {
+        let Ok(entries) = fs::read_dir(world_dir.join(dir)) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("dat") {
+                continue;
+            }
+            if let Ok((_, player)) = fs::read(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|bytes| read_nbt(&bytes))
+            {
+                protect_player(&player, &mut protected);
+            }
+        }
+    }
+    Ok(protected)
+}
+
+/// Dimension folders that hold a `region/` directory, with their ids.
+fn dimension_roots(world_dir: &Path) -> Vec<(String, PathBuf)> {
+    fn custom(dir: &Path, id_parts: &mut Vec<String>, out: &mut Vec<(String, PathBuf)>) {
+        if id_parts.len() >= 2 && dir.join("region").is_dir() {
+            let id = format!("{}:{}", id_parts[0], id_parts[1..].join("/"));
+            out.push((id, dir.to_path_buf()));
+        }
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let is_dir = fs::symlink_metadata(entry.path()).is_ok_and(|meta| meta.is_dir());
+            if !is_dir
+                || COMPANION_DIRS.contains(&name.as_str())
+                || name == "region"
+                || name == "data"
+            {
+                continue;
+            }
+            id_parts.push(name);
+            custom(&entry.path(), id_parts, out);
+            id_parts.pop();
+        }
+    }
+
+    let mut out = vec![
+        (OVERWORLD.to_string(), world_dir.to_path_buf()),
+        ("minecraft:the_nether".to_string(), world_dir.join("DIM-1")),
+        ("minecraft:the_end".to_string(), world_dir.join("DIM1")),
+    ];
+    custom(&world_dir.join("dimensions"), &mut Vec::new(), &mut out);
+    out.retain(|(_, dir)| dir.join("region").is_dir());
+    out
+}
+
+fn inhabited_ticks(chunk: &NbtTag) -> Option<i64> {
+    chunk
+        .get("InhabitedTime")
+        .or_else(|| chunk.path(&["Level", "InhabitedTime"]))
+        .and_then(NbtTag::as_i64)
+}
+
+fn file_len(path: &Path) -> u64 {
+    fs::metadata(path).map(|meta| meta.len()).unwrap_or(0)
+}
+
+/// Writes `region` over `path` through a temporary file, or deletes the
+/// file once no chunk is left. Returns the new size.
+fn replace_region(path: &Path, region: &RegionFile, dry_run: bool) -> Result<u64, String> {
+    if region.chunks.is_empty() {
+        if !dry_run {
+            fs::remove_file(path)
+                .map_err(|e| format!("remove '{}' failed: {e}", path.display()))?;
+        }
+        return Ok(0);
+    }
+    let bytes = region.to_bytes()?;
+    if !dry_run {
+        let tmp = path.with_extension("mca.trim");
+        fs::write(&tmp, &bytes).map_err(|e| format!("write '{}' failed: {e}", tmp.display()))?;
+        fs::rename(&tmp, path).map_err(|e| {
+            let _ = fs::remove_file(&tmp);
+            format!("replace '{}' failed: {e}", path.display())
+        })?;
+    }
+    Ok(bytes.len() as u64)
+}
+
+/// Drops `indices` from a companion region file, if it exists.
+fn trim_companion(
+    path: &Path,
+    indices: &HashSet<usize>,
+    dry_run: bool,
+    report: &mut WorldTrimReport,
+) -> Result<(), String> {
+    if !path.is_file() {
+        return Ok(());
+    }
+    let before = file_len(path);
+    report.bytes_before += before;
+    let bytes = fs::read(path).map_err(|e| format!("read '{}' failed: {e}", path.display()))?;
+    let Ok(mut region) = read_region(&bytes) else {
+        report.bytes_after += before;
+        return Ok(());
+    };
+    let count = region.chunks.len();
+    region
+        .chunks
+        .retain(|chunk| !indices.contains(&chunk.index));
+    if region.chunks.len() == count {
+        report.bytes_after += before;
+        return Ok(());
+    }
+    report.bytes_after += replace_region(path, &region, dry_run)?;
+    Ok(())
+}
+
+/// Trims one world folder. The caller takes the backup and fills in
+/// `world_id` and `backup_id`.
+pub(crate) fn trim_world(
+    world_dir: &Path,
+    options: &WorldTrimOptions,
+) -> Result<WorldTrimReport, String> {
+    let protected = protected_positions(world_dir)?;
+    let radius = options
+        .protect_radius_chunks
+        .clamp(0, MAX_PROTECT_RADIUS_CHUNKS);
+    let mut report = WorldTrimReport {
+        dry_run: options.dry_run,
+        ..WorldTrimReport::default()
+    };
+
+    for (dimension, root) in dimension_roots(world_dir) {
+        let anchors = protected.get(&dimension).map(Vec::as_slice).unwrap_or(&[]);
+        let radius = radius.unsigned_abs() as u64;
+        let is_protected = |x: i64, z: i64| {
+            anchors
+                .iter()
+                .any(|(px, pz)| x.abs_diff(*px) <= radius && z.abs_diff(*pz) <= radius)
+        };
+        let Ok(entries) = fs::read_dir(root.join("region")) else {
+            continue;
+        };
+        let mut regions = entries
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                region_coords(&name).map(|coords| (name, coords, entry.path()))
+            })
+            .collect::<Vec<_>>();
+        regions.sort_by(|a, b| a.0.cmp(&b.0));
+
+        for (name, (region_x, region_z), path) in regions {
+            report.regions_scanned += 1;
+            let before = file_len(&path);
+            report.bytes_before += before;
+            let bytes =
+                fs::read(&path).map_err(|e| format!("read '{}' failed: {e}", path.display()))?;
+            let Ok(mut region) = read_region(&bytes) else {
+                report.bytes_after += before;
+                continue;
+            };
+            report.chunks_scanned += region.chunks.len();
+            let mut removed = HashSet::new();
+            for chunk in &region.chunks {
+                let (local_x, local_z) = chunk.local_pos();
+                // Oversized chunks kept in `.mcc` files are left alone.
+                if chunk.is_external()
+                    || is_protected(
+                        i64::from(region_x) * 32 + i64::from(local_x),
+                        i64::from(region_z) * 32 + i64::from(local_z),
+                    )
+                {
+                    continue;
+                }
+                match chunk.nbt().ok().as_ref().and_then(inhabited_ticks) {
+                    Some(ticks) if ticks < options.min_inhabited_ticks => {
+                        removed.insert(chunk.index);
+                    }
+                    Some(_) => {}
+                    None => report.chunks_unreadable += 1,
+                }
+            }
+            if removed.is_empty() {
+                report.bytes_after += before;
+                continue;
+            }
+            report.chunks_removed += removed.len();
+            region
+                .chunks
+                .retain(|chunk| !removed.contains(&chunk.index));
+            if region.chunks.is_empty() {
+                report.regions_removed += 1;
+            } else {
+                report.regions_rewritten += 1;
+            }
+            report.bytes_after += replace_region(&path, &region, options.dry_run)?;
+            for companion in COMPANION_DIRS {
+                trim_companion(
+                    &root.join(companion).join(&name),
+                    &removed,
+                    options.dry_run,
+                    &mut report,
+                )?;
+            }
+        }
+    }
+    report.bytes_reclaimed = report.bytes_before.saturating_sub(report.bytes_after);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::anvil::encode::{chunk, region};
+    use crate::nbt::encode::{compound, document, gzip};
+    use crate::test_support::{temp_root, write_file};
+
+    fn world() -> PathBuf {
+        let dir = temp_root("trim");
+        write_file(
+            &dir.join("level.dat"),
+            &gzip(&document(
+                "",
+                &compound(vec![(
+                    "Data",
+                    compound(vec![("SpawnX", NbtTag::Int(8)), ("SpawnZ", NbtTag::Int(8))]),
+                )]),
+            )),
+        );
+        // A player standing in the nether at chunk (-20, 40).
+        write_file(
+            &dir.join("playerdata/player.dat"),
+            &gzip(&document(
+                "",
+                &compound(vec![
+                    (
+                        "Dimension",
+                        NbtTag::String("minecraft:the_nether".to_string()),
+                    ),
+                    (
+                        "Pos",
+                        NbtTag::List(vec![
+                            NbtTag::Double(-312.5),
+                            NbtTag::Double(64.0),
+                            NbtTag::Double(650.0),
+                        ]),
+                    ),
+                ]),
+            )),
+        );
+        dir
+    }
+
+    #[test]
+    fn removes_unvisited_chunks_outside_protected_areas() {
+        let dir = world();
+        // Spawn chunk (0, 0) and its neighbour stay; far chunks go unless
+        // someone spent time there.
+        write_file(
+            &dir.join("region/r.0.0.mca"),
+            &region(vec![
+                chunk(0, 0, 0),
+                chunk(2, 0, 0),
+                chunk(20, 20, 0),
+                chunk(21, 20, 5_000),
+            ]),
+        );
+        write_file(
+            &dir.join("entities/r.0.0.mca"),
+            &region(vec![chunk(20, 20, 0)]),
+        );
+        write_file(
+            &dir.join("region/r.1.1.mca"),
+            &region(vec![chunk(0, 0, 10)]),
+        );
+        // Nether region -1.1 covers chunks x -32..-1, z 32..63.
+        write_file(
+            &dir.join("DIM-1/region/r.-1.1.mca"),
+            &region(vec![chunk(12, 8, 0), chunk(0, 0, 0)]),
+        );
+        let mut unreadable = chunk(5, 5, 0);
+        unreadable.compression = 4;
+        write_file(&dir.join("region/r.-1.-1.mca"), &region(vec![unreadable]));
+
+        let options = WorldTrimOptions {
+            min_inhabited_ticks: 1_200,
+            protect_radius_chunks: 2,
+            dry_run: true,
+        };
+        let before = fs::read(dir.join("region/r.0.0.mca")).unwrap();
+        let preview = trim_world(&dir, &options).unwrap();
+        assert_eq!(fs::read(dir.join("region/r.0.0.mca")).unwrap(), before);
+        assert!(preview.bytes_reclaimed > 0);
+
+        let report = trim_world(
+            &dir,
+            &WorldTrimOptions {
+                dry_run: false,
+                ..options
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            WorldTrimReport {
+                dry_run: true,
+                ..report.clone()
+            },
+            preview
+        );
+        assert_eq!(report.regions_scanned, 4);
+        assert_eq!(report.chunks_scanned, 8);
+        assert_eq!(report.chunks_removed, 3);
+        assert_eq!(report.chunks_unreadable, 1);
+        assert_eq!(report.regions_removed, 1);
+        assert_eq!(report.regions_rewritten, 2);
+        assert_eq!(
+            report.bytes_reclaimed,
+            report.bytes_before - report.bytes_after
+        );
+
+        let kept = |path: &str| {
+            read_region(&fs::read(dir.join(path)).unwrap())
+                .unwrap()
+                .chunks
+                .iter()
+                .map(|chunk| chunk.local_pos())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(kept("region/r.0.0.mca"), vec![(0, 0), (2, 0), (21, 20)]);
+        assert!(!dir.join("region/r.1.1.mca").exists());
+        assert!(!dir.join("entities/r.0.0.mca").exists());
+        assert_eq!(kept("DIM-1/region/r.-1.1.mca"), vec![(12, 8)]);
+        assert_eq!(kept("region/r.-1.-1.mca"), vec![(5, 5)]);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn finds_custom_dimensions_and_requires_level_dat() {
+        let dir = world();
+        write_file(
+            &dir.join("dimensions/mymod/deep/caves/region/r.0.0.mca"),
+            &region(vec![chunk(9, 9, 0)]),
+        );
+        let roots = dimension_roots(&dir)
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>();
+        assert_eq!(roots, vec!["mymod:deep/caves"]);
+        fs::remove_file(dir.join("level.dat")).unwrap();
+        assert!(trim_world(
+            &dir,
+            &WorldTrimOptions {
+                min_inhabited_ticks: 1,
+                protect_radius_chunks: 0,
+                dry_run: true,
+            }
+        )
+        .is_err());
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn extreme_coordinates_do_not_overflow() {
+        let dir = world();
+        write_file(
+            &dir.join("playerdata/far.dat"),
+            &gzip(&document(
+                "",
+                &compound(vec![(
+                    "Pos",
+                    NbtTag::List(vec![
+                        NbtTag::Double(1e300),
+                        NbtTag::Double(64.0),
+                        NbtTag::Double(-1e300),
+                    ]),
+                )]),
+            )),
+        );
+        write_file(
+            &dir.join("region/r.2147483647.-2147483648.mca"),
+            &region(vec![chunk(31, 0, 0)]),
+        );
+        let report = trim_world(
+            &dir,
+            &WorldTrimOptions {
+                min_inhabited_ticks: 1_200,
+                protect_radius_chunks: MAX_PROTECT_RADIUS_CHUNKS,
+                dry_run: true,
+            },
+        )
+        .unwrap();
+        assert_eq!(report.chunks_removed, 1);
+        let _ = fs::remove_dir_all(dir);
+    }
+}
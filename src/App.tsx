@@ -103,6 +103,7 @@ import {
   exportPresetsJson,
   exportInstanceModsZip,
   exportInstanceSupportBundle,
+  exportInstanceWorld,
   getDevModeState,
   getCurseforgeApiStatus,
   getGithubTokenPoolStatus,
@@ -119,6 +120,7 @@ import {
   importPresetsJson,
   importLocalModFile,
   importInstanceFromLauncher,
+  importInstanceWorld,
   installCurseforgeMod,
   installDiscoverContent,
   installModrinthMod,
@@ -137,6 +139,7 @@ import {
   logoutMicrosoftAccount,
   rollbackInstance,
   rollbackInstanceWorldBackup,
+  trimInstanceWorld,
   reconcileFriendLink,
   resolveFriendLinkConflicts,
   getFriendLinkStatus,
@@ -1016,6 +1019,10 @@ function defaultInstanceSettings(): InstanceSettings {
     force_vsync: false,
     world_backup_interval_minutes: 10,
     world_backup_retention_count: 1,
+    world_backup_keep_hourly: 0,
+    world_backup_keep_daily: 0,
+    world_backup_keep_weekly: 0,
+    world_backup_destinations: [],
     snapshot_retention_count: 5,
     snapshot_max_age_days: 14,
   };
@@ -1076,6 +1083,8 @@ function normalizeInstanceSettings(input?: Partial<InstanceSettings> | null): In
   const backupRetention = Number.isFinite(Number(merged.world_backup_retention_count))
     ? Math.max(1, Math.min(2, Math.round(Number(merged.world_backup_retention_count))))
     : 1;
+  const keepBuckets = (value: unknown, max: number) =>
+    Number.isFinite(Number(value)) ? Math.max(0, Math.min(max, Math.round(Number(value)))) : 0;
   const snapshotRetention = Number.isFinite(Number(merged.snapshot_retention_count))
     ? Math.max(1, Math.min(20, Math.round(Number(merged.snapshot_retention_count))))
     : 5;
@@ -1105,6 +1114,12 @@ function normalizeInstanceSettings(input?: Partial<InstanceSettings> | null): In
     memory_mb: normalizedMemory,
     world_backup_interval_minutes: backupInterval,
     world_backup_retention_count: backupRetention,
+    world_backup_keep_hourly: keepBuckets(merged.world_backup_keep_hourly, 48),
+    world_backup_keep_daily: keepBuckets(merged.world_backup_keep_daily, 31),
+    world_backup_keep_weekly: keepBuckets(merged.world_backup_keep_weekly, 12),
+    world_backup_destinations: Array.isArray(merged.world_backup_destinations)
+      ? merged.world_backup_destinations.filter((dest) => String(dest?.path ?? "").trim().length > 0)
+      : [],
     snapshot_retention_count: snapshotRetention,
     snapshot_max_age_days: snapshotMaxAgeDays,
   };
@@ -1939,6 +1954,13 @@ function parseReleaseParts(id: string) {
   return id.split(".").map((n) => parseInt(n, 10));
 }
 
+/** Opening a world from the launcher uses Quick Play, which Minecraft added in 1.20. */
+function supportsWorldQuickPlay(mcVersion: string) {
+  const parts = parseReleaseParts(mcVersion.trim().split("-")[0] ?? "");
+  if (!parts) return true;
+  return (parts[0] ?? 0) > 1 || (parts[1] ?? 0) >= 20;
+}
+
 function sameRunningInstances(a: RunningInstance[], b: RunningInstance[]) {
   if (a === b) return true;
   if (a.length !== b.length) return false;
@@ -2310,10 +2332,11 @@ function formatSnapshotOptionLabel(
 ) {
   const reason = formatSnapshotReason(snapshot.reason, resolveProjectLabel);
   const created = formatDateTime(snapshot.created_at, "Unknown time");
+  const damaged = snapshot.integrity_error ? " • Damaged" : "";
   if (created === "Unknown time") {
-    return `${reason} • ${snapshot.id}`;
+    return `${reason} • ${snapshot.id}${damaged}`;
   }
-  return `${reason} • ${created}`;
+  return `${reason} • ${created}${damaged}`;
 }
 
 function launchCompatibilityFingerprint(report: LaunchCompatibilityReport) {
@@ -5874,6 +5897,7 @@ export default function App() {
   const [rollbackBusy, setRollbackBusy] = useState(false);
   const [rollbackSnapshotId, setRollbackSnapshotId] = useState<string | null>(null);
   const [worldRollbackBusyById, setWorldRollbackBusyById] = useState<Record<string, boolean>>({});
+  const [worldTrimBusyById, setWorldTrimBusyById] = useState<Record<string, boolean>>({});
   const [presetIoBusy, setPresetIoBusy] = useState(false);
   const scopedInstalledMods = useMemo(
     () => (selectedId && installedModsInstanceId === selectedId ? installedMods : []),
@@ -9180,6 +9204,51 @@ export default function App() {
     }
   }
 
+  async function onTrimWorld(inst: Instance, world: InstanceWorld) {
+    const worldId = String(world.id ?? "").trim();
+    if (!worldId) return;
+    if (runningInstances.some((run) => run.instance_id === inst.id)) {
+      setInstallNotice("Stop all running sessions for this instance before trimming a world.");
+      return;
+    }
+    setWorldTrimBusyById((prev) => ({ ...prev, [worldId]: true }));
+    setError(null);
+    setInstallNotice(`Scanning "${world.name}" for unvisited chunks…`);
+    try {
+      const preview = await trimInstanceWorld({ instanceId: inst.id, worldId, dryRun: true });
+      if (preview.chunks_removed === 0) {
+        setInstallNotice(`Nothing to trim in "${world.name}".`);
+        return;
+      }
+      const confirmed = window.confirm(
+        `Trim "${world.name}"?\n\n${preview.chunks_removed} of ${preview.chunks_scanned} chunks were visited for under a minute and lie away from spawn and players. Removing them frees about ${formatFileSize(
+          preview.bytes_reclaimed
+        )}; Minecraft regenerates them if you go back.\n\nA world backup is taken first.`
+      );
+      if (!confirmed) {
+        setInstallNotice(null);
+        return;
+      }
+      setInstallNotice(`Backing up and trimming "${world.name}"…`);
+      const out = await trimInstanceWorld({ instanceId: inst.id, worldId });
+      const worlds = await listInstanceWorlds({ instanceId: inst.id }).catch(() => [] as InstanceWorld[]);
+      setInstanceWorlds(worlds);
+      setInstallNotice(
+        `Trimmed "${world.name}": removed ${out.chunks_removed} chunk(s) and reclaimed ${formatFileSize(
+          out.bytes_reclaimed
+        )}.`
+      );
+    } catch (e: any) {
+      setError(e?.toString?.() ?? String(e));
+    } finally {
+      setWorldTrimBusyById((prev) => {
+        const next = { ...prev };
+        delete next[worldId];
+        return next;
+      });
+    }
+  }
+
   async function onCreatePresetFromInstance(inst: Instance) {
     setPresetBusy(true);
     setError(null);
@@ -9755,12 +9824,22 @@ export default function App() {
     }
   }
 
-  async function onPlayInstance(inst: Instance, method?: LaunchMethod) {
+  async function onPlayInstance(inst: Instance, method?: LaunchMethod, world?: InstanceWorld) {
     const requestedMethod = method ?? launchMethodPick;
     if (launchBusyInstanceIds.includes(inst.id)) {
       await onCancelPendingLaunch(inst);
       return;
     }
+    const backupWorldFirst = Boolean(world?.newer_than_instance);
+    if (
+      world &&
+      backupWorldFirst &&
+      !window.confirm(
+        `"${world.name}" was last saved by Minecraft ${world.metadata?.version_name ?? "a newer version"}, newer than this instance's ${inst.mc_version}. Opening it here can corrupt it.\n\nTake a world backup first and open it anyway?`
+      )
+    ) {
+      return;
+    }
     const runningForInstance = runningByInstanceId.get(inst.id) ?? [];
     const hasNativeRunningForInstance = runningForInstance.some(
       (run) => String(run.method ?? "").toLowerCase() === "native"
@@ -9899,6 +9978,8 @@ export default function App() {
       const res: LaunchResult = await launchInstance({
         instanceId: inst.id,
         method: requestedMethod,
+        quickPlayWorld: world?.id,
+        backupWorldFirst,
       });
       if (res.method === "prism" && res.prism_instance_id) {
         setInstallNotice(`${res.message} (Prism instance: ${res.prism_instance_id})`);
@@ -9997,6 +10078,62 @@ export default function App() {
     }
   }
 
+  async function onExportWorld(inst: Instance, world: InstanceWorld) {
+    setLauncherErr(null);
+    setInstallNotice(null);
+    try {
+      const saveTarget = await pickExternalSavePathGrant({
+        purpose: "world_archive_export",
+        suggestedName: `${world.name.replace(/\s+/g, "-") || "world"}.zip`,
+      });
+      if (!saveTarget) return;
+      const out = await exportInstanceWorld({
+        instanceId: inst.id,
+        worldId: world.id,
+        grantId: saveTarget.grantId,
+        includeDatapacks: true,
+        includeModList: true,
+      });
+      setInstallNotice(
+        `Exported world "${out.world_id}" (${out.files_count} file(s), ${out.required_mods} mod(s) listed) to ${out.output_path}`
+      );
+    } catch (e: any) {
+      setLauncherErr(e?.toString?.() ?? String(e));
+    }
+  }
+
+  async function onImportWorld(inst: Instance) {
+    setLauncherErr(null);
+    setInstallNotice(null);
+    try {
+      const picked = await pickExternalOpenPathGrants({
+        purpose: "world_archive_import",
+        multiple: false,
+      });
+      const selected = picked[0] ?? null;
+      if (!selected) return;
+      const out = await importInstanceWorld({ instanceId: inst.id, grantId: selected.grantId });
+      const worlds = await listInstanceWorlds({ instanceId: inst.id }).catch(() => [] as InstanceWorld[]);
+      setInstanceWorlds(worlds);
+      const missing = out.missing_mods.map((mod) => mod.name || mod.filename);
+      setInstallNotice(
+        [
+          `Imported world "${out.world_id}" (${out.files_count} file(s)).`,
+          out.renamed ? "A world with that name already existed, so it was renamed." : null,
+          missing.length > 0
+            ? `Missing ${missing.length} mod(s) it was played with: ${missing.slice(0, 5).join(", ")}${
+                missing.length > 5 ? "…" : ""
+              }.`
+            : null,
+        ]
+          .filter(Boolean)
+          .join(" ")
+      );
+    } catch (e: any) {
+      setLauncherErr(e?.toString?.() ?? String(e));
+    }
+  }
+
   async function onOpenInstancePath(
     inst: Instance,
     target: "instance" | "mods" | "resourcepacks" | "shaderpacks" | "saves" | "launch-log" | "crash-log"
@@ -16352,6 +16489,9 @@ export default function App() {
                         >
                           Refresh
                         </button>
+                        <button className="btn" onClick={() => void onImportWorld(inst)}>
+                          Import world
+                        </button>
                       </div>
                     </div>
                     {instanceWorlds.length === 0 ? (
@@ -16389,18 +16529,70 @@ export default function App() {
                                   </span>
                                 </div>
                                 <div className="muted">{world.path}</div>
+                                {world.metadata ? (
+                                  <div className="muted">
+                                    {[
+                                      world.metadata.level_name && world.metadata.level_name !== world.name
+                                        ? world.metadata.level_name
+                                        : null,
+                                      world.metadata.hardcore ? "Hardcore" : world.metadata.game_mode,
+                                      world.metadata.version_name,
+                                      world.metadata.last_played_at
+                                        ? `Played ${formatDateTime(world.metadata.last_played_at)}`
+                                        : null,
+                                    ]
+                                      .filter(Boolean)
+                                      .join(" • ")}
+                                  </div>
+                                ) : null}
                                 <div className="instanceWorldBackupMeta">
                                   {hasBackup
                                     ? `Latest backup: ${formatDateTime(world.latest_backup_at)}`
                                     : `Auto backup runs every ${instSettings.world_backup_interval_minutes} minutes while Minecraft is running.`}
                                 </div>
+                                {world.newer_than_instance ? (
+                                  <div className="muted">
+                                    Saved by a newer Minecraft than {inst.mc_version}; Play takes a backup first.
+                                  </div>
+                                ) : null}
                                 <div className="instanceWorldActions">
+                                  <button
+                                    className="btn"
+                                    onClick={() => void onPlayInstance(inst, launchMethodPick, world)}
+                                    disabled={
+                                      instanceRunning ||
+                                      launchBusyInstanceIds.includes(inst.id) ||
+                                      !supportsWorldQuickPlay(inst.mc_version)
+                                    }
+                                    title={
+                                      supportsWorldQuickPlay(inst.mc_version)
+                                        ? "Launch straight into this world with Quick Play."
+                                        : `Opening a world directly needs Minecraft 1.20 or newer; this instance runs ${inst.mc_version}.`
+                                    }
+                                  >
+                                    Play
+                                  </button>
                                   <button className="btn" onClick={() => onOpenInstancePath(inst, "saves")}>
                                     <span className="btnIcon">
                                       <Icon name="folder" size={15} />
                                     </span>
                                     Open saves
                                   </button>
+                                  <button
+                                    className="btn"
+                                    onClick={() => void onExportWorld(inst, world)}
+                                    title="Save this world, its datapacks and the mod list to a zip."
+                                  >
+                                    Export
+                                  </button>
+                                  <button
+                                    className="btn"
+                                    onClick={() => void onTrimWorld(inst, world)}
+                                    disabled={instanceRunning || Boolean(worldTrimBusyById[world.id])}
+                                    title="Remove chunks nobody spent time in, after taking a backup."
+                                  >
+                                    {worldTrimBusyById[world.id] ? "Trimming…" : "Trim"}
+                                  </button>
                                   <button
                                     className="btn primary"
                                     onClick={() => void onRollbackWorldBackup(inst, world)}
@@ -1,6 +1,9 @@
 import { invoke } from "@tauri-apps/api/tauri";
 import type {
   AccountDiagnostics,
+  AdvisoryScanReport,
+  AdvisoryStatus,
+  BackupVerifyReport,
   BeginMicrosoftLoginResult,
   CreateInstanceFromModpackFileResult,
   CreatorPreset,
@@ -22,6 +25,10 @@ import type {
   InstallPlanPreview,
   Instance,
   InstanceHistoryEvent,
+  InstanceSecurityScanReport,
+  LockEntryRedownloadResult,
+  InstanceRehydrateReport,
+  LockfileIntegrityReport,
   JavaRuntimeCandidate,
   LauncherImportSource,
   InstalledMod,
@@ -44,11 +51,13 @@ import type {
   PresetsJsonIoResult,
   QuickPlayServerEntry,
   RollbackResult,
+  SelectiveRestoreResult,
   ResetConfigFilesResult,
   ReadInstanceLogsResult,
   RunningInstance,
   SeedDevResult,
   SpecIoResult,
+  SnapshotDiff,
   SnapshotMeta,
   ModpackSpec,
   StorageCleanupResult,
@@ -84,7 +93,16 @@ import type {
   SupportPerfAction,
   GrantedImagePathResult,
   GrantedPathResult,
+  WorldBackupDestination,
+  WorldBackupListing,
+  WorldExportResult,
+  WorldImportResult,
+  WorldTrimReport,
   WorldRollbackResult,
+  UrlUpdateTemplate,
+  CurseforgeHandoffItem,
+  CurseforgeHandoffScanResult,
+  GithubChannel,
 } from "./types";
 
 export function listInstances(): Promise<Instance[]> {
@@ -157,6 +175,8 @@ export function pickExternalOpenPathGrants(input: {
     | "modpack_local_jar_import"
     | "presets_import"
     | "modpack_spec_import"
+    | "advisory_bundle_import"
+    | "world_archive_import"
     | string;
   contentType?: string;
   multiple?: boolean;
@@ -171,6 +191,8 @@ export function grantExternalOpenPaths(input: {
     | "modpack_local_jar_import"
     | "presets_import"
     | "modpack_spec_import"
+    | "advisory_bundle_import"
+    | "world_archive_import"
     | string;
   paths: string[];
   contentType?: string;
@@ -184,6 +206,7 @@ export function pickExternalSavePathGrant(input: {
     | "modpack_spec_export"
     | "instance_mods_export"
     | "support_bundle_export"
+    | "world_archive_export"
     | string;
   suggestedName?: string;
 }): Promise<GrantedPathResult | null> {
@@ -238,6 +261,79 @@ export function setInstalledModPin(input: {
   return invoke("set_installed_mod_pin", { args: input });
 }
 
+export function setInstalledModGithubChannel(input: {
+  instanceId: string;
+  versionId: string;
+  contentType?: string;
+  filename?: string;
+  channel?: GithubChannel | null;
+}): Promise<InstalledMod> {
+  return invoke("set_installed_mod_github_channel", { args: input });
+}
+
+export function acknowledgeInstalledModSecurity(input: {
+  instanceId: string;
+  versionId: string;
+  contentType?: string;
+  filename?: string;
+}): Promise<InstalledMod> {
+  return invoke("acknowledge_installed_mod_security", { args: input });
+}
+
+export function scanInstanceContentSecurity(input: {
+  instanceId: string;
+}): Promise<InstanceSecurityScanReport> {
+  return invoke("scan_instance_content_security", { args: input });
+}
+
+export function verifyInstanceLockfile(input: {
+  instanceId: string;
+}): Promise<LockfileIntegrityReport> {
+  return invoke("verify_instance_lockfile", { args: input });
+}
+
+export function redownloadModifiedLockEntries(input: {
+  instanceId: string;
+  versionIds?: string[];
+}): Promise<LockEntryRedownloadResult> {
+  return invoke("redownload_modified_lock_entries", { args: input });
+}
+
+export function rehydrateInstanceContent(input: {
+  instanceId: string;
+  offline?: boolean;
+}): Promise<InstanceRehydrateReport> {
+  return invoke("rehydrate_instance_content", { args: input });
+}
+
+export function listCurseforgeHandoffItems(input: {
+  instanceId?: string;
+} = {}): Promise<CurseforgeHandoffItem[]> {
+  return invoke("list_curseforge_handoff_items", { args: input });
+}
+
+export function scanCurseforgeHandoffFolder(): Promise<CurseforgeHandoffScanResult> {
+  return invoke("scan_curseforge_handoff_folder");
+}
+
+export function dismissCurseforgeHandoffItem(input: {
+  instanceId: string;
+  projectId: string;
+}): Promise<boolean> {
+  return invoke("dismiss_curseforge_handoff_item", { args: input });
+}
+
+export function setInstanceContentWatcher(input: {
+  instanceId: string;
+  enabled: boolean;
+}): Promise<string[]> {
+  return invoke("set_instance_content_watcher", { args: input });
+}
+
+export function listContentWatchers(): Promise<string[]> {
+  return invoke("list_content_watchers");
+}
+
 export function setInstalledModProvider(input: {
   instanceId: string;
   versionId: string;
@@ -349,6 +445,8 @@ export function launchInstance(input: {
   method?: LaunchMethod;
   quickPlayHost?: string;
   quickPlayPort?: number;
+  quickPlayWorld?: string;
+  backupWorldFirst?: boolean;
 }): Promise<LaunchResult> {
   return invoke("launch_instance", { args: input });
 }
@@ -419,6 +517,11 @@ export function setLauncherSettings(input: {
   autoTriggerMicPermissionPrompt?: boolean;
   discordPresenceEnabled?: boolean;
   discordPresenceDetailLevel?: "minimal" | "expanded";
+  verifyLockHashesBeforeLaunch?: boolean;
+  teamRepository?: string;
+  curseforgeHandoffDir?: string;
+  worldBackupDestinations?: WorldBackupDestination[];
+  backupVerifyIntervalHours?: number;
 }): Promise<LauncherSettings> {
   return invoke("set_launcher_settings", { args: input });
 }
@@ -515,6 +618,34 @@ export function exportInstanceModsZip(input: {
   return invoke("export_instance_mods_zip", { args: input });
 }
 
+export function exportInstanceWorld(input: {
+  instanceId: string;
+  worldId: string;
+  grantId: string;
+  includeDatapacks?: boolean;
+  includeModList?: boolean;
+}): Promise<WorldExportResult> {
+  return invoke("export_instance_world", { args: input });
+}
+
+export function importInstanceWorld(input: {
+  instanceId: string;
+  grantId: string;
+  name?: string;
+}): Promise<WorldImportResult> {
+  return invoke("import_instance_world", { args: input });
+}
+
+export function trimInstanceWorld(input: {
+  instanceId: string;
+  worldId: string;
+  minInhabitedSeconds?: number;
+  protectRadiusChunks?: number;
+  dryRun?: boolean;
+}): Promise<WorldTrimReport> {
+  return invoke("trim_instance_world", { args: input });
+}
+
 export function exportInstanceSupportBundle(input: {
   instanceId: string;
   grantId: string;
@@ -583,14 +714,57 @@ export function rollbackInstance(input: {
   return invoke("rollback_instance", { args: input });
 }
 
+export function updateInstanceSnapshot(input: {
+  instanceId: string;
+  snapshotId: string;
+  label?: string;
+  pinned?: boolean;
+}): Promise<SnapshotMeta> {
+  return invoke("update_instance_snapshot", { args: input });
+}
+
+export function diffInstanceSnapshots(input: {
+  instanceId: string;
+  fromSnapshotId: string;
+  toSnapshotId?: string;
+}): Promise<SnapshotDiff> {
+  return invoke("diff_instance_snapshots", { args: input });
+}
+
+export function restoreInstanceSnapshotSelection(input: {
+  instanceId: string;
+  snapshotId: string;
+  entryKeys?: string[];
+  paths?: string[];
+}): Promise<SelectiveRestoreResult> {
+  return invoke("restore_instance_snapshot_selection", { args: input });
+}
+
 export function rollbackInstanceWorldBackup(input: {
   instanceId: string;
   worldId: string;
   backupId?: string;
+  at?: string;
+  location?: string;
 }): Promise<WorldRollbackResult> {
   return invoke("rollback_instance_world_backup", { args: input });
 }
 
+export function verifyBackups(input: { instanceId?: string } = {}): Promise<BackupVerifyReport> {
+  return invoke("verify_backups", { args: input });
+}
+
+export function getBackupVerifyReport(): Promise<BackupVerifyReport | null> {
+  return invoke("get_backup_verify_report");
+}
+
+export function listInstanceWorldBackups(input: {
+  instanceId: string;
+  worldId?: string;
+}): Promise<WorldBackupListing[]> {
+  return invoke("list_instance_world_backups", { args: input });
+}
+
 export function listInstanceWorlds(input: {
   instanceId: string;
 }): Promise<InstanceWorld[]> {
@@ -729,7 +903,7 @@ export function restoreInstanceConfigFileBackup(input: {
 
 export function installDiscoverContent(input: {
   instanceId: string;
-  source: DiscoverSource | "modrinth" | "curseforge" | "github";
+  source: DiscoverSource | "modrinth" | "curseforge" | "github" | "team";
   projectId: string;
   projectTitle?: string;
   contentType: DiscoverContentType;
@@ -738,6 +912,34 @@ export function installDiscoverContent(input: {
   return invoke("install_discover_content", { args: input });
 }
 
+export function installUrlContent(input: {
+  instanceId: string;
+  /** https:// download URL; may contain a {version} placeholder. */
+  url: string;
+  contentType?: DiscoverContentType;
+  name?: string;
+  version?: string;
+  filename?: string;
+  sha256?: string;
+  sha512?: string;
+  targetWorlds?: string[];
+  updateTemplate?: UrlUpdateTemplate;
+}): Promise<InstalledMod> {
+  return invoke("install_url_content", { args: input });
+}
+
+export function installMavenContent(input: {
+  instanceId: string;
+  repositoryUrl: string;
+  /** group:artifact[:version[:classifier]]; omit the version to take the newest match. */
+  coordinate: string;
+  contentType?: DiscoverContentType;
+  name?: string;
+  targetWorlds?: string[];
+}): Promise<InstalledMod> {
+  return invoke("install_maven_content", { args: input });
+}
+
 export function previewPresetApply(input: {
   instanceId: string;
   preset: CreatorPreset;
@@ -773,6 +975,20 @@ export function importPresetsJson(input: {
   return invoke("import_presets_json", { args: input });
 }
 
+export function getSecurityAdvisoryStatus(): Promise<AdvisoryStatus> {
+  return invoke("get_security_advisory_status");
+}
+
+export function runSecurityAdvisoryScan(): Promise<AdvisoryScanReport> {
+  return invoke("run_security_advisory_scan");
+}
+
+export function importSecurityAdvisoryBundle(input: {
+  grantId: string;
+}): Promise<AdvisoryStatus> {
+  return invoke("import_security_advisory_bundle", { args: input });
+}
+
 export function listModpackSpecs(): Promise<ModpackSpec[]> {
   return invoke("list_modpack_specs");
 }
@@ -30,12 +30,16 @@ export type InstanceSettings = {
   force_vsync: boolean;
   world_backup_interval_minutes: number;
   world_backup_retention_count: number;
+  world_backup_keep_hourly: number;
+  world_backup_keep_daily: number;
+  world_backup_keep_weekly: number;
+  world_backup_destinations?: WorldBackupDestination[];
   snapshot_retention_count: number;
   snapshot_max_age_days: number;
 };
 
 export type InstalledMod = {
-  source: "modrinth" | "curseforge" | "github" | string;
+  source: "modrinth" | "curseforge" | "github" | "team" | "url" | "maven" | string;
   project_id: string;
   version_id: string;
   name: string;
@@ -51,6 +55,133 @@ export type InstalledMod = {
   hashes?: Record<string, string>;
   provider_candidates?: ProviderCandidate[];
   local_analysis?: LocalModAnalysis | null;
+  security_scan?: JarSecurityScan | null;
+  github_channel?: GithubChannel | null;
+};
+
+export type GithubChannel = {
+  kind: "stable" | "prerelease" | "artifact" | string;
+  branch?: string | null;
+  workflow?: string | null;
+};
+
+export type MalwareFinding = {
+  rule: string;
+  severity: "critical" | "high" | "medium" | string;
+  title: string;
+  detail: string;
+  location: string;
+};
+
+export type JarSecurityScan = {
+  scanned_at: string;
+  findings: MalwareFinding[];
+  acknowledged_at?: string | null;
+};
+
+export type InstanceSecurityScanItem = {
+  filename: string;
+  name: string;
+  enabled: boolean;
+  tracked: boolean;
+  acknowledged: boolean;
+  findings: MalwareFinding[];
+};
+
+export type InstanceSecurityScanReport = {
+  instance_id: string;
+  scanned_at: string;
+  scanned_files: number;
+  unacknowledged_count: number;
+  flagged: InstanceSecurityScanItem[];
+  /** Files that could not be read, with the reason. */
+  unscanned: string[];
+};
+
+export type LockEntryIntegrityItem = {
+  project_id: string;
+  version_id: string;
+  name: string;
+  filename: string;
+  content_type: string;
+  status: "unchanged" | "modified" | "missing" | "no_hash" | string;
+  algorithm?: string;
+  expected_hash?: string;
+  actual_hash?: string;
+  path?: string;
+  redownloadable: boolean;
+};
+
+export type LockfileIntegrityReport = {
+  instance_id: string;
+  checked_at: string;
+  checked_entries: number;
+  unchanged: number;
+  modified: number;
+  missing: number;
+  no_hash: number;
+  items: LockEntryIntegrityItem[];
+};
+
+export type CurseforgeHandoffItem = {
+  instance_id: string;
+  project_id: string;
+  file_id: number;
+  name: string;
+  filename: string;
+  version_number: string;
+  content_type: string;
+  target_worlds: string[];
+  enabled: boolean;
+  fingerprint?: number | null;
+  file_length?: number | null;
+  hashes: Record<string, string>;
+  project_url: string;
+  download_page_url?: string | null;
+  queued_at: string;
+};
+
+export type CurseforgeHandoffScanResult = {
+  folder?: string | null;
+  completed: string[];
+  remaining: number;
+  warnings: string[];
+};
+
+export type UrlUpdateTemplate = {
+  versionsUrl?: string;
+  mavenMetadataUrl?: string;
+  versionFilter?: string;
+  /** Checksum sidecar with {url}/{version} placeholders, e.g. "{url}.sha256". */
+  checksumUrl?: string;
+};
+
+export type LockEntryRedownloadResult = {
+  instance_id: string;
+  repaired: string[];
+  warnings: string[];
+};
+
+export type RehydrateEntryOutcome = {
+  project_id: string;
+  version_id: string;
+  name: string;
+  filename: string;
+  content_type: string;
+  status: "present" | "restored" | "needs_network" | "unavailable" | "failed";
+  origin?: "content_store" | "snapshot" | "provider";
+  message?: string;
+};
+
+export type InstanceRehydrateReport = {
+  instance_id: string;
+  offline: boolean;
+  present: number;
+  restored: number;
+  needs_network: number;
+  unavailable: number;
+  failed: number;
+  entries: RehydrateEntryOutcome[];
 };
 
 export type LocalModAnalysis = {
@@ -63,7 +194,7 @@ export type LocalModAnalysis = {
 };
 
 export type ProviderCandidate = {
-  source: "modrinth" | "curseforge" | "github" | string;
+  source: "modrinth" | "curseforge" | "github" | "team" | "url" | "maven" | string;
   project_id: string;
   version_id: string;
   name: string;
@@ -117,7 +248,7 @@ export type UpdateAllResult = {
 };
 
 export type ContentUpdateInfo = {
-  source: "modrinth" | "curseforge" | "github" | string;
+  source: "modrinth" | "curseforge" | "github" | "team" | "url" | "maven" | string;
   content_type: "mods" | "shaderpacks" | "resourcepacks" | "datapacks" | string;
   project_id: string;
   name: string;
@@ -140,6 +271,7 @@ export type ContentUpdateCheckResult = {
   update_count: number;
   updates: ContentUpdateInfo[];
   warnings: string[];
+  advisories?: RunFinding[];
 };
 
 export type UpdateAllContentResult = {
@@ -203,6 +335,17 @@ export type LaunchCompatibilityReport = {
   items: LaunchCompatibilityItem[];
   permissions?: LaunchPermissionChecklistItem[];
   mic_requirement?: LaunchMicRequirementSummary | null;
+  mixin_conflicts?: MixinConflict[];
+};
+
+export type MixinConflict = {
+  class_name: string;
+  method?: string | null;
+  risk: "high" | "medium" | "low" | string;
+  risk_score: number;
+  mods: string[];
+  kinds?: string[];
+  mixins?: string[];
 };
 
 export type LocalResolverMatch = {
@@ -345,6 +488,11 @@ export type LauncherSettings = {
   auto_trigger_mic_permission_prompt?: boolean;
   discord_presence_enabled?: boolean;
   discord_presence_detail_level?: "minimal" | "expanded" | string;
+  verify_lock_hashes_before_launch?: boolean;
+  team_repository?: string;
+  curseforge_handoff_dir?: string;
+  world_backup_destinations?: WorldBackupDestination[];
+  backup_verify_interval_hours?: number;
 };
 
 export type QuickPlayServerEntry = {
@@ -528,6 +676,8 @@ export type StorageUsageOverview = {
   app_bytes: number;
   shared_cache_bytes: number;
   instances_bytes: number;
+  /** Bytes shared through hardlinks, counted once in total_bytes. */
+  hardlinked_bytes: number;
   reclaimable_bytes: number;
   app_breakdown: StorageBucketTotal[];
   instance_summaries: StorageInstanceSummary[];
@@ -584,6 +734,75 @@ export type InstanceWorld = {
   latest_backup_id?: string | null;
   latest_backup_at?: string | null;
   backup_count?: number;
+  /** Read from level.dat; absent when it is missing or unreadable. */
+  metadata?: WorldMetadata;
+  /** Last saved by a newer game version than the instance runs. */
+  newer_than_instance?: boolean;
+};
+
+export type WorldArchiveMod = {
+  name: string;
+  source: string;
+  project_id: string;
+  version_number?: string;
+  filename: string;
+};
+
+export type WorldArchiveManifest = {
+  format: number;
+  world_name: string;
+  mc_version?: string;
+  loader?: string;
+  exported_at: string;
+  includes_datapacks: boolean;
+  required_mods: WorldArchiveMod[];
+};
+
+export type WorldExportResult = {
+  output_path: string;
+  world_id: string;
+  files_count: number;
+  required_mods: number;
+};
+
+export type WorldImportResult = {
+  world_id: string;
+  files_count: number;
+  /** The archive's name was taken, so the world got a numbered one. */
+  renamed: boolean;
+  manifest?: WorldArchiveManifest;
+  /** Mods from the manifest that this instance does not have enabled. */
+  missing_mods: WorldArchiveMod[];
+};
+
+export type WorldTrimReport = {
+  world_id: string;
+  backup_id?: string;
+  dry_run: boolean;
+  regions_scanned: number;
+  regions_rewritten: number;
+  regions_removed: number;
+  chunks_scanned: number;
+  chunks_removed: number;
+  /** Chunks kept because their data could not be read. */
+  chunks_unreadable: number;
+  bytes_before: number;
+  bytes_after: number;
+  bytes_reclaimed: number;
+};
+
+export type WorldMetadata = {
+  level_name?: string;
+  seed?: number;
+  game_mode?: "survival" | "creative" | "adventure" | "spectator";
+  hardcore: boolean;
+  difficulty?: "peaceful" | "easy" | "normal" | "hard" | string;
+  data_version?: number;
+  version_name?: string;
+  last_played_at?: string;
+  enabled_datapacks: string[];
+  disabled_datapacks: string[];
+  enabled_features: string[];
 };
 
 export type InstanceLastRunMetadata = {
@@ -606,6 +825,29 @@ export type RunArtifactRef = {
   exists: boolean;
 };
 
+export type InstanceAdvisoryFindings = {
+  instance_id: string;
+  instance_name: string;
+  findings: RunFinding[];
+};
+
+export type AdvisoryScanReport = {
+  bundle_revision: number;
+  checked_at: string;
+  trigger: "startup" | "update_check" | "manual" | "import" | string;
+  total_findings: number;
+  instances: InstanceAdvisoryFindings[];
+};
+
+export type AdvisoryStatus = {
+  bundled_revision: number;
+  active_revision: number;
+  active_source: "bundled" | "imported" | string;
+  advisory_count: number;
+  published_at?: string | null;
+  last_report?: AdvisoryScanReport | null;
+};
+
 export type RunFinding = {
   id: string;
   category: string;
@@ -865,6 +1107,13 @@ export type SnapshotMeta = {
   id: string;
   created_at: string;
   reason: string;
+  label?: string;
+  /** Pinned snapshots are exempt from retention and storage cleanup. */
+  pinned: boolean;
+  auto_pin?: "last_known_good";
+  verified_at?: string;
+  /** Problem found by the last integrity check; never picked by "restore latest". */
+  integrity_error?: string;
 };
 
 export type RollbackResult = {
@@ -874,6 +1123,92 @@ export type RollbackResult = {
   message: string;
 };
 
+export type SnapshotLockEntrySide = {
+  version_id: string;
+  version_number: string;
+  filename: string;
+  enabled: boolean;
+};
+
+export type SnapshotLockChange = {
+  key: string;
+  name: string;
+  content_type: string;
+  change: "added" | "removed" | "changed";
+  before?: SnapshotLockEntrySide;
+  after?: SnapshotLockEntrySide;
+};
+
+export type SnapshotFileChange = {
+  path: string;
+  change: "added" | "removed" | "modified";
+  before_size?: number;
+  after_size?: number;
+};
+
+export type SnapshotDiff = {
+  instance_id: string;
+  from: string;
+  /** Snapshot id, or "current" for the live instance. */
+  to: string;
+  lock_changes: SnapshotLockChange[];
+  file_changes: SnapshotFileChange[];
+};
+
+export type SelectiveRestoreResult = {
+  snapshot_id: string;
+  undo_snapshot_id: string;
+  restored_entries: number;
+  removed_entries: number;
+  restored_files: number;
+  removed_files: number;
+  message: string;
+};
+
+export type WorldBackupDestination = {
+  path: string;
+  /** "mirror" keeps every local backup too; "move" keeps only the newest one locally. */
+  mode: "mirror" | "move" | string;
+  keep_recent: number;
+  keep_hourly: number;
+  keep_daily: number;
+  keep_weekly: number;
+};
+
+export type WorldBackupListing = {
+  id: string;
+  world_id: string;
+  created_at: string;
+  reason: string;
+  files_count: number;
+  total_bytes: number;
+  format: "zip" | "incremental" | string;
+  new_bytes: number;
+  verified_at?: string;
+  integrity_error?: string;
+  /** "local" and/or destination paths holding an intact copy. */
+  locations: string[];
+  /** Locations whose copy failed its last integrity check. */
+  corrupt_locations: string[];
+};
+
+export type CorruptBackup = {
+  instance_id: string;
+  instance_name: string;
+  kind: "snapshot" | "world_backup" | string;
+  id: string;
+  location: string;
+  problem: string;
+};
+
+export type BackupVerifyReport = {
+  checked_at: string;
+  trigger: string;
+  snapshots_checked: number;
+  world_backups_checked: number;
+  corrupt: CorruptBackup[];
+};
+
 export type WorldRollbackResult = {
   world_id: string;
   backup_id: string;
@@ -882,12 +1217,12 @@ export type WorldRollbackResult = {
   message: string;
 };
 
-export type DiscoverSource = "modrinth" | "curseforge" | "github" | "all";
+export type DiscoverSource = "modrinth" | "curseforge" | "github" | "team" | "all";
 export type DiscoverContentType = "mods" | "shaderpacks" | "resourcepacks" | "datapacks" | "modpacks";
 export type GithubInstallState = "ready" | "checking" | "unsupported" | string;
 
 export type DiscoverSearchHit = {
-  source: "modrinth" | "curseforge" | "github" | string;
+  source: "modrinth" | "curseforge" | "github" | "team" | "url" | "maven" | string;
   project_id: string;
   title: string;
   description: string;
@@ -990,7 +1325,7 @@ export type PresetsJsonIoResult = {
 };
 
 export type CreatorPresetEntry = {
-  source: "modrinth" | "curseforge" | "github" | string;
+  source: "modrinth" | "curseforge" | "github" | "team" | "url" | "maven" | string;
   project_id: string;
   title: string;
   content_type: "mods" | "shaderpacks" | "resourcepacks" | "datapacks" | "modpacks" | string;
@@ -1038,13 +1373,13 @@ export type PresetApplyResult = {
 };
 
 export type EntryKey = {
-  provider: "modrinth" | "curseforge" | "github" | string;
+  provider: "modrinth" | "curseforge" | "github" | "team" | "url" | "maven" | string;
   project_id: string;
   content_type: "mods" | "shaderpacks" | "resourcepacks" | "datapacks" | string;
 };
 
 export type ModEntry = {
-  provider: "modrinth" | "curseforge" | "github" | string;
+  provider: "modrinth" | "curseforge" | "github" | "team" | "url" | "maven" | string;
   project_id: string;
   slug?: string | null;
   content_type: "mods" | "shaderpacks" | "resourcepacks" | "datapacks" | string;
@@ -1125,7 +1460,7 @@ export type TargetInstanceSnapshot = {
 };
 
 export type ResolvedMod = {
-  source: "modrinth" | "curseforge" | "github" | string;
+  source: "modrinth" | "curseforge" | "github" | "team" | "url" | "maven" | string;
   content_type: "mods" | "shaderpacks" | "resourcepacks" | "datapacks" | string;
   project_id: string;
   name: string;
@@ -1143,7 +1478,7 @@ export type ResolvedMod = {
 };
 
 export type FailedMod = {
-  source: "modrinth" | "curseforge" | "github" | string;
+  source: "modrinth" | "curseforge" | "github" | "team" | "url" | "maven" | string;
   content_type: "mods" | "shaderpacks" | "resourcepacks" | "datapacks" | string;
   project_id: string;
   name: string;
@@ -1187,7 +1522,7 @@ export type ResolutionPlan = {
 };
 
 export type LockSnapshotEntry = {
-  source: "modrinth" | "curseforge" | "github" | string;
+  source: "modrinth" | "curseforge" | "github" | "team" | "url" | "maven" | string;
   content_type: "mods" | "shaderpacks" | "resourcepacks" | "datapacks" | string;
   project_id: string;
   name: string;
@@ -1218,7 +1553,7 @@ export type InstanceModpackLinkState = {
 };
 
 export type DriftItem = {
-  source: "modrinth" | "curseforge" | "github" | string;
+  source: "modrinth" | "curseforge" | "github" | "team" | "url" | "maven" | string;
   content_type: "mods" | "shaderpacks" | "resourcepacks" | "datapacks" | string;
   project_id: string;
   name: string;